@@ -0,0 +1,103 @@
+//! Сравнивает количество выделений памяти при чтении потока записей со строками с буфером,
+//! заданным методом `Deserializer::with_scratch`, и без него.
+//!
+//! Запуск: `cargo bench --bench scratch`
+
+extern crate serde;
+extern crate serde_pod;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde_pod::LEDeserializer;
+
+/// Распределитель памяти, подсчитывающий количество выделений
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    System.alloc(layout)
+  }
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Тип предмета, записанный в потоке своим названием
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind { Sword, Shield, Potion }
+
+impl<'de> Deserialize<'de> for Kind {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct KindVisitor;
+    impl<'de> Visitor<'de> for KindVisitor {
+      type Value = Kind;
+
+      fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("an item kind")
+      }
+      fn visit_str<E: de::Error>(self, v: &str) -> Result<Kind, E> {
+        match v {
+          "sword"  => Ok(Kind::Sword),
+          "shield" => Ok(Kind::Shield),
+          "potion" => Ok(Kind::Potion),
+          _ => Err(E::unknown_variant(v, &["sword", "shield", "potion"])),
+        }
+      }
+    }
+    deserializer.deserialize_str(KindVisitor)
+  }
+}
+
+const RECORDS: usize = 100_000;
+
+/// Формирует поток записей: длина названия типа `u8`, затем само название
+fn records() -> Vec<u8> {
+  let names: [&[u8]; 3] = [b"sword", b"shield", b"potion"];
+  let mut data = Vec::new();
+  for i in 0..RECORDS {
+    let name = names[i % names.len()];
+    data.push(name.len() as u8);
+    data.extend_from_slice(name);
+  }
+  data
+}
+
+/// Читает все записи и возвращает количество выделений памяти и затраченное время
+fn run(data: &[u8], scratch: bool) -> (usize, f64) {
+  let mut de = LEDeserializer::new(data);
+  if scratch {
+    de = de.with_scratch(Vec::with_capacity(16));
+  }
+  let start = Instant::now();
+  let before = ALLOCATIONS.load(Ordering::Relaxed);
+  let mut potions = 0;
+  for _ in 0..RECORDS {
+    let len = u8::deserialize(&mut de).unwrap();
+    if de.bounded::<Kind>(len.into()).unwrap() == Kind::Potion {
+      potions += 1;
+    }
+  }
+  let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+  assert_eq!(potions, RECORDS / 3);
+  (allocations, start.elapsed().as_secs_f64() * 1000.0)
+}
+
+fn main() {
+  let data = records();
+  for &scratch in &[false, true] {
+    let (allocations, ms) = run(&data, scratch);
+    println!(
+      "{:>15}: {} records, {} allocations, {:.2} ms",
+      if scratch { "with scratch" } else { "without scratch" }, RECORDS, allocations, ms
+    );
+  }
+}
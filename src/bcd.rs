@@ -0,0 +1,167 @@
+//! Содержит типы для чтения и записи чисел в двоично-десятичном коде (BCD), в котором каждая
+//! десятичная цифра занимает полубайт. Такое представление используется для телефонных номеров,
+//! дат и счетчиков в телекоммуникационных форматах и встраиваемых системах.
+//!
+//! Числа хранятся в виде строки цифр, т.к. ведущие нули значимы (например, в телефонных номерах).
+//! Если цифр меньше, чем помещается в поле, недостающие полубайты заполняются значением `0xF`.
+//! Представление не зависит от порядка байт сериализатора.
+
+use std::fmt;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer};
+use serde::ser::{self, Serialize, Serializer};
+
+use raw::{Raw, RawBuf};
+
+/// Значение полубайта, заполняющего поле после последней цифры
+const FILLER: u8 = 0xF;
+
+/// Кодирует строку цифр в `len` байт. Если `low_first`, первая цифра каждого байта записывается
+/// в младший полубайт, иначе в старший
+fn encode(digits: &str, len: usize, low_first: bool) -> Result<Vec<u8>, String> {
+  if digits.len() > len * 2 {
+    return Err(format!("{} digits do not fit into {} BCD bytes", digits.len(), len));
+  }
+  let mut nibbles = Vec::with_capacity(len * 2);
+  for c in digits.chars() {
+    let digit = c.to_digit(10).ok_or_else(|| format!("{:?} is not a decimal digit", c))?;
+    nibbles.push(digit as u8);
+  }
+  nibbles.resize(len * 2, FILLER);
+  Ok(nibbles.chunks(2).map(|pair| {
+    if low_first { pair[1] << 4 | pair[0] } else { pair[0] << 4 | pair[1] }
+  }).collect())
+}
+
+/// Декодирует байты в строку цифр. Если `low_first`, первая цифра каждого байта находится в
+/// младшем полубайте, иначе в старшем. Полубайты `0xF` допускаются только после всех цифр
+fn decode(bytes: &[u8], low_first: bool) -> Result<String, String> {
+  let mut digits = String::with_capacity(bytes.len() * 2);
+  let mut filler = false;
+  for &b in bytes {
+    let (first, second) = if low_first { (b & 0x0F, b >> 4) } else { (b >> 4, b & 0x0F) };
+    for &nibble in &[first, second] {
+      match nibble {
+        FILLER => filler = true,
+        0..=9 if !filler => digits.push((b'0' + nibble) as char),
+        0..=9 => return Err(format!("BCD digit {} after filler", nibble)),
+        _ => return Err(format!("invalid BCD nibble {:#X}", nibble)),
+      }
+    }
+  }
+  Ok(digits)
+}
+
+/// Макрос, объявляющий тип числа в двоично-десятичном коде с указанным порядком полубайт
+macro_rules! bcd_type {
+  ($(#[$meta:meta])* $name:ident, $low_first:expr) => {
+    $(#[$meta])*
+    #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct $name<const N: usize> {
+      /// Десятичные цифры числа
+      digits: String,
+    }
+
+    impl<const N: usize> $name<N> {
+      /// Оборачивает строку десятичных цифр. Строка проверяется при сериализации
+      #[inline]
+      pub fn new<S: Into<String>>(digits: S) -> Self {
+        $name { digits: digits.into() }
+      }
+      /// Возвращает десятичные цифры числа
+      #[inline]
+      pub fn as_str(&self) -> &str { &self.digits }
+      /// Возвращает десятичные цифры числа
+      #[inline]
+      pub fn into_inner(self) -> String { self.digits }
+    }
+    impl<const N: usize> From<String> for $name<N> {
+      #[inline]
+      fn from(digits: String) -> Self { Self::new(digits) }
+    }
+    impl<const N: usize> fmt::Display for $name<N> {
+      fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(&self.digits)
+      }
+    }
+
+    impl<const N: usize> Serialize for $name<N> {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = encode(&self.digits, N, $low_first).map_err(ser::Error::custom)?;
+        Raw(&bytes).serialize(serializer)
+      }
+    }
+    impl<'de, const N: usize> Deserialize<'de> for $name<N> {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = RawBuf(N).deserialize(deserializer)?;
+        decode(&bytes, $low_first).map($name::new).map_err(de::Error::custom)
+      }
+    }
+  }
+}
+
+bcd_type!(
+  /// Число в двоично-десятичном коде, занимающее `N` байт, в котором первая цифра каждого байта
+  /// записывается в старший полубайт: число `1234` записывается, как `0x12 0x34`.
+  Bcd, false
+);
+bcd_type!(
+  /// Число в двоично-десятичном коде, занимающее `N` байт, в котором первая цифра каждого байта
+  /// записывается в младший полубайт (TBCD, как в GSM): число `1234` записывается, как `0x21 0x43`.
+  ///
+  /// # Пример
+  /// ```rust
+  /// # extern crate byteorder;
+  /// # extern crate serde_pod;
+  /// # use byteorder::LE;
+  /// # use serde_pod::{from_bytes, to_vec};
+  /// # use serde_pod::bcd::BcdLe;
+  /// # fn main() -> serde_pod::Result<()> {
+  /// assert_eq!(to_vec::<LE, _>(&BcdLe::<3>::new("12345"))?, [0x21, 0x43, 0xF5]);
+  /// assert_eq!(from_bytes::<LE, BcdLe<3>>(&[0x21, 0x43, 0xF5])?.as_str(), "12345");
+  /// # Ok(())
+  /// # }
+  /// ```
+  BcdLe, true
+);
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::{Bcd, BcdLe};
+
+  #[test]
+  fn test_bcd() {
+    assert_eq!(to_vec::<BE,_>(&Bcd::<2>::new("1234")).unwrap(), [0x12, 0x34]);
+    assert_eq!(to_vec::<LE,_>(&Bcd::<2>::new("0123")).unwrap(), [0x01, 0x23]);
+    assert_eq!(to_vec::<BE,_>(&Bcd::<2>::new("123")).unwrap(), [0x12, 0x3F]);
+    assert_eq!(from_bytes::<BE, Bcd<2>>(&[0x01, 0x23]).unwrap(), Bcd::new("0123"));
+    assert_eq!(from_bytes::<BE, Bcd<2>>(&[0x12, 0x3F]).unwrap(), Bcd::new("123"));
+  }
+  /// Номер телефона с нечетным количеством цифр в кодировке TBCD
+  #[test]
+  fn test_tbcd() {
+    let number = BcdLe::<6>::new("79161234567");
+    let data = [0x97, 0x61, 0x21, 0x43, 0x65, 0xF7];
+    assert_eq!(to_vec::<BE,_>(&number).unwrap(), data);
+    assert_eq!(to_vec::<LE,_>(&number).unwrap(), data);
+    assert_eq!(from_bytes::<BE, BcdLe<6>>(&data).unwrap(), number);
+    assert_eq!(from_bytes::<LE, BcdLe<6>>(&data).unwrap(), number);
+
+    assert_eq!(to_vec::<BE,_>(&BcdLe::<2>::new("1")).unwrap(), [0xF1, 0xFF]);
+    assert_eq!(from_bytes::<BE, BcdLe<2>>(&[0xFF, 0xFF]).unwrap(), BcdLe::new(""));
+  }
+  #[test]
+  fn test_invalid() {
+    assert!(to_vec::<BE,_>(&BcdLe::<1>::new("123")).is_err());
+    assert!(to_vec::<BE,_>(&BcdLe::<2>::new("12a")).is_err());
+    // Полубайты 0xA-0xE не являются цифрами
+    assert!(from_bytes::<BE, BcdLe<1>>(&[0x1A]).is_err());
+    assert!(from_bytes::<BE, Bcd<1>>(&[0xA1]).is_err());
+    // Цифры после заполнителя
+    assert!(from_bytes::<BE, BcdLe<2>>(&[0xF1, 0x32]).is_err());
+  }
+}
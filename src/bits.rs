@@ -0,0 +1,516 @@
+//! Содержит описания упаковки нескольких небольших полей (флагов и коротких целых) в один байт,
+//! наборы битовых флагов и упакованные битовые массивы.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::result;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeTuple, Serializer};
+
+use error::{Error, Result};
+use framing::{checked_len, Length};
+use raw::{Raw, RawBuf};
+
+/// Описание поля упакованного байта
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BitField {
+  /// Имя поля
+  name: &'static str,
+  /// Номер младшего бита поля
+  shift: u8,
+  /// Ширина поля в битах
+  width: u8,
+}
+impl BitField {
+  /// Маска значения поля до сдвига
+  #[inline]
+  fn mask(&self) -> u8 { (((1u16 << self.width) - 1) & 0xFF) as u8 }
+}
+
+/// Описание байта, в который упакованы несколько полей заданной ширины в битах, например,
+/// пара флагов и 4-х битное число. Поля располагаются в порядке объявления, начиная с младшего
+/// бита байта; неиспользуемые старшие биты записываются нулями и игнорируются при чтении.
+///
+/// Описание создается с помощью [`Packed8::builder`], а упаковка и распаковка значений
+/// выполняются в реализациях `Serialize` и `Deserialize` типа, который содержит поля:
+/// упакованный байт записывается и читается, как обычный `u8`.
+///
+/// # Пример
+/// ```rust
+/// # extern crate serde_pod;
+/// # use serde_pod::bits::Packed8;
+/// # fn main() -> serde_pod::Result<()> {
+/// let layout = Packed8::builder()
+///   .field("visible", 1)
+///   .field("enabled", 1)
+///   .field("level", 4)
+///   .build()?;
+///
+/// let byte = layout.pack(&[1, 0, 0b1010])?;
+/// assert_eq!(byte, 0b0010_1001);
+/// assert_eq!(layout.unpack(byte), [1, 0, 0b1010]);
+/// assert_eq!(layout.get(byte, "level"), Some(0b1010));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Packed8::builder`]: #method.builder
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packed8 {
+  /// Поля в порядке объявления
+  fields: Vec<BitField>,
+}
+
+impl Packed8 {
+  /// Создает построитель описания упакованного байта без полей
+  #[inline]
+  pub fn builder() -> Packed8Builder {
+    Packed8Builder { fields: Vec::new() }
+  }
+  /// Возвращает количество полей
+  #[inline]
+  pub fn len(&self) -> usize { self.fields.len() }
+  /// Возвращает `true`, если описание не содержит полей
+  #[inline]
+  pub fn is_empty(&self) -> bool { self.fields.is_empty() }
+
+  /// Упаковывает значения полей в байт.
+  ///
+  /// # Параметры
+  /// - `values`: Значения полей в порядке их объявления. Флаги передаются как `0` или `1`
+  ///
+  /// # Ошибки
+  /// - [`Error::InvalidLength`]: количество значений не совпадает с количеством полей
+  /// - [`Error::Unknown`]: значение не помещается в ширину своего поля
+  ///
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  /// [`Error::Unknown`]: ../error/enum.Error.html#variant.Unknown
+  pub fn pack(&self, values: &[u8]) -> Result<u8> {
+    if values.len() != self.fields.len() {
+      return Err(Error::InvalidLength(format!(
+        "expected {} packed field values, but got {}", self.fields.len(), values.len()
+      )));
+    }
+    let mut byte = 0;
+    for (field, &value) in self.fields.iter().zip(values) {
+      if value & !field.mask() != 0 {
+        return Err(Error::Unknown(format!(
+          "value {} of field `{}` does not fit into {} bits", value, field.name, field.width
+        )));
+      }
+      byte |= value << field.shift;
+    }
+    Ok(byte)
+  }
+  /// Распаковывает значения всех полей из байта в порядке их объявления
+  pub fn unpack(&self, byte: u8) -> Vec<u8> {
+    self.fields.iter().map(|f| (byte >> f.shift) & f.mask()).collect()
+  }
+  /// Возвращает значение поля с указанным именем, распакованное из байта, или `None`,
+  /// если такого поля нет
+  pub fn get(&self, byte: u8, name: &str) -> Option<u8> {
+    self.fields.iter().find(|f| f.name == name).map(|f| (byte >> f.shift) & f.mask())
+  }
+}
+
+/// Построитель описания упакованного байта [`Packed8`]
+///
+/// [`Packed8`]: struct.Packed8.html
+#[derive(Clone, Debug)]
+pub struct Packed8Builder {
+  /// Объявленные поля: имя и ширина в битах
+  fields: Vec<(&'static str, u8)>,
+}
+
+impl Packed8Builder {
+  /// Добавляет поле, располагающееся в битах, следующих за ранее объявленными полями
+  ///
+  /// # Параметры
+  /// - `name`: Имя поля
+  /// - `width`: Ширина поля в битах (1 для флагов)
+  #[inline]
+  pub fn field(mut self, name: &'static str, width: u8) -> Self {
+    self.fields.push((name, width));
+    self
+  }
+  /// Проверяет объявленные поля и создает описание упакованного байта
+  ///
+  /// # Ошибки
+  /// [`Error::InvalidLength`]: поле имеет нулевую ширину, или суммарная ширина полей
+  /// превышает 8 бит, или имена полей повторяются
+  ///
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  pub fn build(self) -> Result<Packed8> {
+    let mut fields: Vec<BitField> = Vec::with_capacity(self.fields.len());
+    let mut shift = 0u8;
+    for (name, width) in self.fields {
+      if width == 0 || u16::from(shift) + u16::from(width) > 8 {
+        return Err(Error::InvalidLength(format!(
+          "field `{}` of {} bits does not fit into a byte after {} bits", name, width, shift
+        )));
+      }
+      if fields.iter().any(|f| f.name == name) {
+        return Err(Error::InvalidLength(format!("field `{}` is declared twice", name)));
+      }
+      fields.push(BitField { name, shift, width });
+      shift += width;
+    }
+    Ok(Packed8 { fields })
+  }
+}
+
+/// Целый беззнаковый тип, в биты которого упаковываются флаги [`BitFlags`]
+///
+/// [`BitFlags`]: struct.BitFlags.html
+pub trait FlagsInt: Copy + Default + PartialEq {
+  /// Количество бит в типе
+  const BITS: u32;
+  /// Расширяет значение до 64 бит
+  fn to_u64(self) -> u64;
+  /// Отбрасывает старшие биты 64-битного значения, не помещающиеся в тип
+  fn truncate(value: u64) -> Self;
+}
+
+macro_rules! impl_flags_int {
+  ($($type:ty),*) => {
+    $(
+      impl FlagsInt for $type {
+        const BITS: u32 = <$type>::BITS;
+        #[inline]
+        fn to_u64(self) -> u64 { self.into() }
+        #[inline]
+        fn truncate(value: u64) -> Self { value as $type }
+      }
+    )*
+  };
+}
+impl_flags_int!(u8, u16, u32, u64);
+
+/// Набор булевых флагов, упакованных в биты целого числа типа `T`. Записывается и читается,
+/// как число `T` в порядке байт сериализатора. Бит с номером `0` -- младший бит числа.
+///
+/// Для доступа к флагам по имени удобно объявить константы с номерами бит.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::bits::BitFlags;
+/// const READ: u32 = 0;
+/// const WRITE: u32 = 1;
+/// const HIDDEN: u32 = 15;
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut flags = BitFlags::<u16>::default();
+/// flags.set(READ, true);
+/// flags.set(HIDDEN, true);
+/// assert_eq!(to_vec::<BE, _>(&flags)?, [0x80, 0x01]);
+///
+/// let flags = from_bytes::<BE, BitFlags<u16>>(&[0x00, 0x02])?;
+/// assert!(flags.get(WRITE));
+/// assert!(!flags.get(READ));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitFlags<T>(pub T);
+
+impl<T: FlagsInt> BitFlags<T> {
+  /// Создает набор флагов из числа, в которое они упакованы
+  #[inline]
+  pub fn new(bits: T) -> Self { BitFlags(bits) }
+  /// Возвращает число, в которое упакованы флаги
+  #[inline]
+  pub fn bits(&self) -> T { self.0 }
+  /// Возвращает состояние флага с указанным номером
+  ///
+  /// # Паники
+  /// Если номер бита не меньше количества бит в типе `T`
+  #[inline]
+  pub fn get(&self, bit: u32) -> bool {
+    self.0.to_u64() & Self::mask(bit) != 0
+  }
+  /// Устанавливает или сбрасывает флаг с указанным номером
+  ///
+  /// # Паники
+  /// Если номер бита не меньше количества бит в типе `T`
+  #[inline]
+  pub fn set(&mut self, bit: u32, value: bool) {
+    let mask = Self::mask(bit);
+    let bits = self.0.to_u64();
+    self.0 = T::truncate(if value { bits | mask } else { bits & !mask });
+  }
+  /// Возвращает набор с установленным флагом с указанным номером
+  ///
+  /// # Паники
+  /// Если номер бита не меньше количества бит в типе `T`
+  #[inline]
+  pub fn with(mut self, bit: u32) -> Self {
+    self.set(bit, true);
+    self
+  }
+  /// Возвращает итератор по номерам установленных флагов в порядке возрастания
+  pub fn iter(&self) -> impl Iterator<Item = u32> {
+    let bits = self.0.to_u64();
+    (0..T::BITS).filter(move |&bit| bits & (1 << bit) != 0)
+  }
+  /// Маска бита с указанным номером
+  #[inline]
+  fn mask(bit: u32) -> u64 {
+    assert!(bit < T::BITS, "bit {} is out of range for {}-bit flags", bit, T::BITS);
+    1 << bit
+  }
+}
+impl<T: FlagsInt> From<T> for BitFlags<T> {
+  #[inline]
+  fn from(bits: T) -> Self { BitFlags(bits) }
+}
+
+impl<T: Serialize> Serialize for BitFlags<T> {
+  #[inline]
+  fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+    self.0.serialize(serializer)
+  }
+}
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for BitFlags<T> {
+  #[inline]
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<Self, D::Error> {
+    T::deserialize(deserializer).map(BitFlags)
+  }
+}
+
+/// Массив флагов произвольной длины, упакованный по 8 флагов в байт. Сначала в поток записывается
+/// количество флагов в виде числа типа `L` в порядке байт сериализатора, затем `ceil(count / 8)`
+/// байт с флагами, начиная со старшего бита первого байта.
+///
+/// Неиспользуемые младшие биты последнего байта записываются нулями и игнорируются при чтении.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается количество флагов
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitVec<L> {
+  /// Флаги
+  bits: Vec<bool>,
+  /// Тип количества флагов
+  _marker: PhantomData<L>,
+}
+
+impl<L> BitVec<L> {
+  /// Оборачивает указанный массив флагов
+  #[inline]
+  pub fn new(bits: Vec<bool>) -> Self {
+    BitVec { bits, _marker: PhantomData }
+  }
+  /// Возвращает обернутые флаги
+  #[inline]
+  pub fn as_slice(&self) -> &[bool] { &self.bits }
+  /// Возвращает обернутые флаги
+  #[inline]
+  pub fn into_inner(self) -> Vec<bool> { self.bits }
+}
+impl<L> From<Vec<bool>> for BitVec<L> {
+  #[inline]
+  fn from(bits: Vec<bool>) -> Self { Self::new(bits) }
+}
+
+impl<L: Length> Serialize for BitVec<L> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+    let count = L::from_usize(self.bits.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "bit vector of {} bits is too long for its length prefix", self.bits.len()
+    )))?;
+    let mut bytes = vec![0u8; self.bits.len().div_ceil(8)];
+    for (i, _) in self.bits.iter().enumerate().filter(|&(_, &bit)| bit) {
+      bytes[i / 8] |= 0x80 >> (i % 8);
+    }
+
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&count)?;
+    tuple.serialize_element(&Raw(&bytes))?;
+    tuple.end()
+  }
+}
+impl<'de, L: Length> Deserialize<'de> for BitVec<L> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> result::Result<Self, D::Error> {
+    deserializer.deserialize_tuple(2, BitVecVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий количество флагов, а затем упакованные флаги
+struct BitVecVisitor<L>(PhantomData<L>);
+impl<'de, L: Length> Visitor<'de> for BitVecVisitor<L> {
+  type Value = BitVec<L>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a count-prefixed bit vector")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> result::Result<Self::Value, A::Error> {
+    let count: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let count = checked_len(count)?;
+    let bytes = seq.next_element_seed(RawBuf(count.div_ceil(8)))?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+    let bits = (0..count).map(|i| bytes[i / 8] & (0x80 >> (i % 8)) != 0).collect();
+    Ok(BitVec::new(bits))
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use serde::de::{Deserialize, Deserializer};
+  use serde::ser::{self, Serialize, Serializer};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::Packed8;
+
+  #[derive(Debug, PartialEq)]
+  struct Flags {
+    visible: bool,
+    enabled: bool,
+    level: u8,
+  }
+  impl Flags {
+    fn layout() -> Packed8 {
+      Packed8::builder()
+        .field("visible", 1)
+        .field("enabled", 1)
+        .field("level", 4)
+        .build()
+        .unwrap()
+    }
+  }
+  impl Serialize for Flags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+      let values = [u8::from(self.visible), u8::from(self.enabled), self.level];
+      let byte = Flags::layout().pack(&values).map_err(ser::Error::custom)?;
+      serializer.serialize_u8(byte)
+    }
+  }
+  impl<'de> Deserialize<'de> for Flags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+      let values = Flags::layout().unpack(u8::deserialize(deserializer)?);
+      Ok(Flags { visible: values[0] != 0, enabled: values[1] != 0, level: values[2] })
+    }
+  }
+
+  /// Два флага и 4-х битное число занимают один байт, поля идут от младшего бита
+  #[test]
+  fn test_two_bools_and_nibble() {
+    let test = Flags { visible: true, enabled: false, level: 0xB };
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0b0010_1101]);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), [0b0010_1101]);
+    assert_eq!(from_bytes::<BE, Flags>(&[0b0010_1101]).unwrap(), test);
+    // Неиспользуемые старшие биты игнорируются
+    assert_eq!(from_bytes::<LE, Flags>(&[0b1110_1101]).unwrap(), test);
+  }
+  #[test]
+  fn test_value_too_wide() {
+    let test = Flags { visible: true, enabled: false, level: 0x10 };
+    assert!(to_vec::<BE,_>(&test).is_err());
+  }
+  #[test]
+  fn test_invalid_layout() {
+    assert!(Packed8::builder().field("a", 4).field("b", 5).build().is_err());
+    assert!(Packed8::builder().field("a", 0).build().is_err());
+    assert!(Packed8::builder().field("a", 1).field("a", 1).build().is_err());
+    assert!(Packed8::builder().field("a", 8).build().is_ok());
+  }
+  #[test]
+  fn test_get() {
+    let layout = Flags::layout();
+    assert_eq!(layout.get(0b0010_1101, "visible"), Some(1));
+    assert_eq!(layout.get(0b0010_1101, "enabled"), Some(0));
+    assert_eq!(layout.get(0b0010_1101, "level"), Some(0xB));
+    assert_eq!(layout.get(0b0010_1101, "unknown"), None);
+  }
+}
+
+#[cfg(test)]
+mod flags {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::BitFlags;
+
+  #[test]
+  fn test_u8() {
+    let flags = BitFlags::<u8>::default().with(0).with(2).with(7);
+    assert_eq!(to_vec::<BE,_>(&flags).unwrap(), [0b1000_0101]);
+    assert_eq!(from_bytes::<LE, BitFlags<u8>>(&[0b1000_0101]).unwrap(), flags);
+    assert_eq!(flags.iter().collect::<Vec<_>>(), [0, 2, 7]);
+  }
+  /// Флаги записываются в порядке байт сериализатора
+  #[test]
+  fn test_u32() {
+    let flags = BitFlags::<u32>::default().with(1).with(8).with(31);
+    assert_eq!(to_vec::<BE,_>(&flags).unwrap(), [0x80, 0x00, 0x01, 0x02]);
+    assert_eq!(to_vec::<LE,_>(&flags).unwrap(), [0x02, 0x01, 0x00, 0x80]);
+    assert_eq!(from_bytes::<LE, BitFlags<u32>>(&[0x02, 0x01, 0x00, 0x80]).unwrap(), flags);
+    assert_eq!(flags.bits(), 0x8000_0102);
+  }
+  #[test]
+  fn test_get_set() {
+    let mut flags = BitFlags::new(0u16);
+    assert!(!flags.get(9));
+    flags.set(9, true);
+    assert!(flags.get(9));
+    assert_eq!(to_vec::<BE,_>(&flags).unwrap(), [0x02, 0x00]);
+    flags.set(9, false);
+    assert_eq!(flags, BitFlags(0));
+    assert_eq!(to_vec::<BE,_>(&BitFlags(u16::MAX)).unwrap(), [0xFF, 0xFF]);
+  }
+  #[test]
+  #[should_panic]
+  fn test_out_of_range() {
+    BitFlags::<u8>::default().set(8, true);
+  }
+}
+
+#[cfg(test)]
+mod bit_vec {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::BitVec;
+
+  fn bits(pattern: &str) -> BitVec<u16> {
+    BitVec::new(pattern.chars().map(|c| c == '1').collect())
+  }
+
+  #[test]
+  fn test_empty() {
+    let test = bits("");
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x00, 0x00]);
+    assert_eq!(from_bytes::<BE, BitVec<u16>>(&[0x00, 0x00]).unwrap(), test);
+  }
+  /// Неиспользуемые биты записываются нулями
+  #[test]
+  fn test_7() {
+    let test = bits("1011001");
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x00, 0x07,   0b1011_0010]);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), [0x07, 0x00,   0b1011_0010]);
+    assert_eq!(from_bytes::<BE, BitVec<u16>>(&[0x00, 0x07,   0b1011_0010]).unwrap(), test);
+    // Неиспользуемые биты игнорируются при чтении
+    assert_eq!(from_bytes::<LE, BitVec<u16>>(&[0x07, 0x00,   0b1011_0011]).unwrap(), test);
+  }
+  #[test]
+  fn test_8() {
+    let test = bits("10110011");
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x00, 0x08,   0b1011_0011]);
+    assert_eq!(from_bytes::<BE, BitVec<u16>>(&[0x00, 0x08,   0b1011_0011]).unwrap(), test);
+  }
+  #[test]
+  fn test_9() {
+    let test = bits("101100111");
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x00, 0x09,   0b1011_0011, 0b1000_0000]);
+    assert_eq!(from_bytes::<BE, BitVec<u16>>(&[0x00, 0x09,   0b1011_0011, 0b1111_1111]).unwrap(), test);
+  }
+  #[test]
+  #[should_panic]
+  fn test_no_data() {
+    from_bytes::<BE, BitVec<u16>>(&[0x00, 0x09,   0b1011_0011]).unwrap();
+  }
+}
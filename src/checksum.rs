@@ -0,0 +1,352 @@
+//! Содержит средства вычисления и проверки контрольных сумм сериализованных данных.
+
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+use std::marker::PhantomData;
+use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use magic;
+
+/// Таблица остатков для побайтового вычисления CRC-32
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// Строит таблицу остатков CRC-32 для отраженного полинома `0xEDB88320`
+const fn crc32_table() -> [u32; 256] {
+  let mut table = [0u32; 256];
+  let mut i = 0;
+  while i < 256 {
+    let mut crc = i as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+      bit += 1;
+    }
+    table[i] = crc;
+    i += 1;
+  }
+  table
+}
+
+/// Вычислитель контрольной суммы CRC-32 (IEEE 802.3, как в zlib и PNG) по частям данных
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Crc32 {
+  /// Текущее состояние (инвертированное значение контрольной суммы)
+  state: u32,
+}
+impl Crc32 {
+  /// Создает вычислитель контрольной суммы пустых данных
+  #[inline]
+  pub fn new() -> Self {
+    Crc32 { state: 0xFFFF_FFFF }
+  }
+  /// Добавляет данные к вычисляемой контрольной сумме
+  pub fn update(&mut self, data: &[u8]) {
+    for &b in data {
+      self.state = CRC32_TABLE[((self.state ^ u32::from(b)) & 0xFF) as usize] ^ (self.state >> 8);
+    }
+  }
+  /// Возвращает контрольную сумму всех добавленных данных
+  #[inline]
+  pub fn finish(&self) -> u32 {
+    !self.state
+  }
+}
+impl Default for Crc32 {
+  fn default() -> Self { Self::new() }
+}
+
+/// Вычисляет контрольную сумму CRC-32 (IEEE 802.3) указанных данных
+#[inline]
+pub fn crc32(data: &[u8]) -> u32 {
+  let mut crc = Crc32::new();
+  crc.update(data);
+  crc.finish()
+}
+
+/// Поток, передающий прочитанные из нижележащего потока байты и вычисляющий по ним контрольную
+/// сумму CRC-32. Реализует [`BufRead`], поэтому может использоваться в качестве потока
+/// [`Deserializer`], чтобы после чтения данных сравнить их контрольную сумму с записанной,
+/// например, в трейлере файла. Учитываются только байты, действительно поглощенные читателем,
+/// а не заполнившие буфер.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde::Deserialize;
+/// # use serde_pod::checksum::{crc32, ChecksumReader};
+/// # use serde_pod::de::Deserializer;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut data = b"\x02\x01data".to_vec();
+/// data.extend_from_slice(&crc32(&data).to_le_bytes());
+///
+/// let mut de = Deserializer::<LE, _>::new(ChecksumReader::new(&data[..]));
+/// let value = <(u16, [u8; 4])>::deserialize(&mut de)?;
+/// let reader = de.into_inner();
+/// let digest = reader.digest();
+/// let stored = reader.into_inner();
+/// assert_eq!(value, (0x0102, *b"data"));
+/// assert_eq!(digest.to_le_bytes(), stored);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`BufRead`]: https://doc.rust-lang.org/std/io/trait.BufRead.html
+/// [`Deserializer`]: ../de/struct.Deserializer.html
+#[derive(Debug)]
+pub struct ChecksumReader<R> {
+  /// Нижележащий поток
+  inner: R,
+  /// Контрольная сумма прочитанных данных
+  crc: Crc32,
+}
+impl<R> ChecksumReader<R> {
+  /// Оборачивает поток
+  #[inline]
+  pub fn new(inner: R) -> Self {
+    ChecksumReader { inner, crc: Crc32::new() }
+  }
+  /// Возвращает контрольную сумму прочитанных на данный момент данных
+  #[inline]
+  pub fn digest(&self) -> u32 { self.crc.finish() }
+  /// Возвращает ссылку на нижележащий поток
+  #[inline]
+  pub fn get_ref(&self) -> &R { &self.inner }
+  /// Возвращает нижележащий поток
+  #[inline]
+  pub fn into_inner(self) -> R { self.inner }
+}
+impl<R: Read> Read for ChecksumReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let len = self.inner.read(buf)?;
+    self.crc.update(&buf[..len]);
+    Ok(len)
+  }
+}
+impl<R: BufRead> BufRead for ChecksumReader<R> {
+  #[inline]
+  fn fill_buf(&mut self) -> io::Result<&[u8]> { self.inner.fill_buf() }
+  fn consume(&mut self, amt: usize) {
+    // Поглощаемые байты уже находятся в буфере, поэтому его получение не приводит к чтению
+    if amt > 0 {
+      if let Ok(buf) = self.inner.fill_buf() {
+        self.crc.update(&buf[..amt.min(buf.len())]);
+      }
+    }
+    self.inner.consume(amt)
+  }
+}
+
+/// Поток, передающий записываемые байты в нижележащий поток и вычисляющий по ним контрольную
+/// сумму CRC-32. Может использоваться в качестве потока [`Serializer`], чтобы после записи
+/// данных дописать их контрольную сумму в конец, например, в трейлер файла.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde::Serialize;
+/// # use serde_pod::checksum::{crc32, ChecksumWriter};
+/// # use serde_pod::ser::Serializer;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut writer = ChecksumWriter::new(Vec::new());
+/// {
+///   let mut ser = Serializer::<LE, _>::new(&mut writer);
+///   (0x0102u16, *b"data").serialize(&mut ser)?;
+/// }
+/// let crc = writer.finish();
+/// let mut data = writer.into_inner();
+/// assert_eq!(crc, crc32(&data));
+/// data.extend_from_slice(&crc.to_le_bytes());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Serializer`]: ../ser/struct.Serializer.html
+#[derive(Debug)]
+pub struct ChecksumWriter<W> {
+  /// Нижележащий поток
+  inner: W,
+  /// Контрольная сумма записанных данных
+  crc: Crc32,
+}
+impl<W> ChecksumWriter<W> {
+  /// Оборачивает поток
+  #[inline]
+  pub fn new(inner: W) -> Self {
+    ChecksumWriter { inner, crc: Crc32::new() }
+  }
+  /// Возвращает контрольную сумму данных, успешно записанных в нижележащий поток. Запись
+  /// может быть продолжена, например, самой контрольной суммой, но она уже будет учтена
+  #[inline]
+  pub fn finish(&self) -> u32 { self.crc.finish() }
+  /// Возвращает ссылку на нижележащий поток
+  #[inline]
+  pub fn get_ref(&self) -> &W { &self.inner }
+  /// Возвращает нижележащий поток
+  #[inline]
+  pub fn into_inner(self) -> W { self.inner }
+}
+impl<W: Write> Write for ChecksumWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let len = self.inner.write(buf)?;
+    self.crc.update(&buf[..len]);
+    Ok(len)
+  }
+  #[inline]
+  fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+/// Значение, за которым в потоке следует его контрольная сумма CRC-32 в виде `u32` в порядке
+/// байт сериализатора. Контрольная сумма вычисляется по байтам, которые занимает значение.
+///
+/// При десериализации контрольная сумма прочитанных байт сравнивается с записанной, и при
+/// несовпадении возвращается ошибка [`Error::ChecksumMismatch`]. Другие форматы видят этот тип,
+/// как newtype-структуру, и контрольную сумму не записывают.
+///
+/// [`Error::ChecksumMismatch`]: ../error/enum.Error.html#variant.ChecksumMismatch
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Crc32Wrapped<T>(pub T);
+
+impl<T> Crc32Wrapped<T> {
+  /// Возвращает обернутое значение
+  #[inline]
+  pub fn into_inner(self) -> T { self.0 }
+}
+impl<T: Serialize> Serialize for Crc32Wrapped<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(magic::CRC32, &self.0)
+  }
+}
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Crc32Wrapped<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_newtype_struct(magic::CRC32, Crc32Visitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий значение, защищенное контрольной суммой
+struct Crc32Visitor<T>(PhantomData<T>);
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Crc32Visitor<T> {
+  type Value = Crc32Wrapped<T>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a value followed by its CRC-32")
+  }
+  fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    T::deserialize(deserializer).map(Crc32Wrapped)
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use error::Error;
+  use ser::to_vec;
+  use serde::Serialize;
+  use ser::Serializer;
+  use serde::Deserialize;
+  use de::Deserializer;
+  use super::{crc32, ChecksumReader, ChecksumWriter, Crc32, Crc32Wrapped};
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Payload {
+    id: u16,
+    name: [u8; 4],
+  }
+
+  #[test]
+  fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+    let mut crc = Crc32::new();
+    crc.update(b"1234");
+    crc.update(b"56789");
+    assert_eq!(crc.finish(), 0xCBF4_3926);
+  }
+  /// Контрольная сумма вычисляется по байтам значения и записывается в порядке байт сериализатора
+  #[test]
+  fn test_roundtrip() {
+    let test = Crc32Wrapped(Payload { id: 0x3132, name: *b"3456" });
+    let mut be = b"123456".to_vec();
+    be.extend_from_slice(&[0x09, 0x72, 0xD3, 0x61]);
+    let mut le = b"213456".to_vec();
+    le.extend_from_slice(&crc32(b"213456").to_le_bytes());
+
+    assert_eq!(crc32(b"123456"), 0x0972_D361);
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Crc32Wrapped<Payload>>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Crc32Wrapped<Payload>>(&le).unwrap(), test);
+  }
+  /// Испорченные данные обнаруживаются
+  #[test]
+  fn test_corrupted() {
+    let mut data = to_vec::<BE,_>(&Crc32Wrapped(Payload { id: 0x3132, name: *b"3456" })).unwrap();
+    data[3] ^= 0x01;
+    match from_bytes::<BE, Crc32Wrapped<Payload>>(&data) {
+      Err(Error::ChecksumMismatch { stored, computed }) => {
+        assert_eq!(stored, 0x0972_D361);
+        assert_eq!(computed, crc32(&data[..6]));
+      }
+      result => panic!("expected checksum mismatch, but got {:?}", result),
+    }
+  }
+  /// Контрольная сумма вычисляется по байтам, записанным сериализатором
+  #[test]
+  fn test_writer() {
+    let test = Payload { id: 0x3132, name: *b"3456" };
+    let mut writer = ChecksumWriter::new(Vec::new());
+    {
+      let mut ser = Serializer::<BE, _>::new(&mut writer);
+      test.serialize(&mut ser).unwrap();
+      b"789".serialize(&mut ser).unwrap();
+    }
+    assert_eq!(writer.finish(), 0xCBF4_3926);
+    assert_eq!(writer.into_inner(), b"123456789");
+
+    let mut writer = ChecksumWriter::new(Vec::new());
+    test.serialize(&mut Serializer::<BE, _>::new(&mut writer)).unwrap();
+    assert_eq!(writer.finish(), 0x0972_D361);
+    assert_eq!(ChecksumWriter::new(Vec::<u8>::new()).finish(), 0);
+  }
+  /// Контрольная сумма вычисляется по байтам, прочитанным десериализатором
+  #[test]
+  fn test_reader() {
+    let data = b"123456789";
+    let mut de = Deserializer::<BE, _>::new(ChecksumReader::new(&data[..]));
+    assert_eq!(Payload::deserialize(&mut de).unwrap(), Payload { id: 0x3132, name: *b"3456" });
+    assert_eq!(de.into_inner().digest(), 0x0972_D361);
+
+    let mut de = Deserializer::<BE, _>::new(ChecksumReader::new(&data[..]));
+    assert_eq!(<Vec<u8>>::deserialize(&mut de).unwrap(), data);
+    assert_eq!(de.into_inner().digest(), 0xCBF4_3926);
+  }
+  /// Испорченный байт приводит к несовпадению контрольной суммы после чтения
+  #[test]
+  fn test_reader_tampered() {
+    let mut data = to_vec::<LE,_>(&Payload { id: 0x3132, name: *b"3456" }).unwrap();
+    let stored = crc32(&data);
+    data[4] ^= 0x80;
+
+    let mut de = Deserializer::<LE, _>::new(ChecksumReader::new(&data[..]));
+    let payload = Payload::deserialize(&mut de).unwrap();
+    assert_eq!(payload.name, *b"34\xB56");
+    let reader = de.into_inner();
+    assert_ne!(reader.digest(), stored);
+    assert_eq!(reader.digest(), crc32(&data));
+  }
+  #[test]
+  #[should_panic]
+  fn test_no_checksum() {
+    from_bytes::<BE, Crc32Wrapped<Payload>>(b"123456").unwrap();
+  }
+}
@@ -0,0 +1,232 @@
+//! Содержит обертки для записи и чтения значений, хранящихся в потоке в сжатом виде, как
+//! сжатые секции архивов. Алгоритмы сжатия доступны при включении соответствующих функций
+//! крейта: `flate2` для [`Deflate`] и `zstd` для [`Zstd`].
+//!
+//! [`Deflate`]: struct.Deflate.html
+//! [`Zstd`]: struct.Zstd.html
+
+use std::fmt;
+use std::io;
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+/// Максимальный размер несжатых данных в байтах. Несжатый размер, прочитанный из потока,
+/// проверяется перед распаковкой, а распаковка останавливается по достижении этого размера,
+/// поэтому поврежденные или специально подготовленные данные не приводят к исчерпанию памяти
+pub const MAX_UNCOMPRESSED_LEN: u32 = 64 << 20;
+
+/// Функции сжатия и распаковки данных алгоритмом, выбранным по имени newtype-структуры
+pub(crate) struct Codec {
+  /// Имя newtype-структуры, которым обозначаются значения, сжатые этим алгоритмом
+  name: &'static str,
+  /// Сжимает данные
+  pub compress: fn(&[u8]) -> io::Result<Vec<u8>>,
+  /// Распаковывает данные, несжатый размер которых должен быть равен указанному
+  pub decompress: fn(&[u8], usize) -> io::Result<Vec<u8>>,
+}
+
+/// Поддерживаемые алгоритмы сжатия
+const CODECS: &[Codec] = &[
+  #[cfg(feature = "flate2")]
+  Codec { name: Deflate::NAME, compress: deflate, decompress: inflate },
+  #[cfg(feature = "zstd")]
+  Codec { name: Zstd::NAME, compress: zstd_compress, decompress: zstd_decompress },
+];
+
+/// Возвращает алгоритм сжатия, значения которого обозначаются newtype-структурой `name`
+pub(crate) fn codec(name: &str) -> Option<&'static Codec> {
+  CODECS.iter().find(|c| c.name == name)
+}
+
+/// Читает из потока распаковки ровно `len` байт и проверяет, что больше данных в нем нет
+#[cfg(any(feature = "flate2", feature = "zstd"))]
+fn read_unpacked<R: Read>(reader: R, len: usize) -> io::Result<Vec<u8>> {
+  let mut buf = Vec::with_capacity(len);
+  reader.take(len as u64 + 1).read_to_end(&mut buf)?;
+  if buf.len() != len {
+    return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+      "decompressed data size does not match the stored size {}", len
+    )));
+  }
+  Ok(buf)
+}
+
+#[cfg(feature = "flate2")]
+fn deflate(data: &[u8]) -> io::Result<Vec<u8>> {
+  let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+  encoder.write_all(data)?;
+  encoder.finish()
+}
+#[cfg(feature = "flate2")]
+fn inflate(data: &[u8], len: usize) -> io::Result<Vec<u8>> {
+  read_unpacked(flate2::read::DeflateDecoder::new(data), len)
+}
+#[cfg(feature = "zstd")]
+fn zstd_compress(data: &[u8]) -> io::Result<Vec<u8>> {
+  let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), 0)?;
+  encoder.write_all(data)?;
+  encoder.finish()
+}
+#[cfg(feature = "zstd")]
+fn zstd_decompress(data: &[u8], len: usize) -> io::Result<Vec<u8>> {
+  read_unpacked(zstd::stream::read::Decoder::new(data)?, len)
+}
+
+/// Закрывает типаж [`Compression`] от реализации вне крейта
+///
+/// [`Compression`]: trait.Compression.html
+mod private {
+  pub trait Sealed {}
+}
+
+/// Алгоритм сжатия данных, используемый оберткой [`Compressed`]
+///
+/// [`Compressed`]: struct.Compressed.html
+pub trait Compression: private::Sealed {
+  /// Имя newtype-структуры, по которому сериализатор и десериализатор выбирают алгоритм
+  #[doc(hidden)]
+  const NAME: &'static str;
+}
+
+/// Сжатие алгоритмом DEFLATE (RFC 1951) без заголовков zlib или gzip
+#[cfg(feature = "flate2")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Deflate;
+#[cfg(feature = "flate2")]
+impl private::Sealed for Deflate {}
+#[cfg(feature = "flate2")]
+impl Compression for Deflate {
+  const NAME: &'static str = "$serde_pod::Compressed::Deflate";
+}
+
+/// Сжатие алгоритмом Zstandard (RFC 8878) с уровнем сжатия по умолчанию
+#[cfg(feature = "zstd")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Zstd;
+#[cfg(feature = "zstd")]
+impl private::Sealed for Zstd {}
+#[cfg(feature = "zstd")]
+impl Compression for Zstd {
+  const NAME: &'static str = "$serde_pod::Compressed::Zstd";
+}
+
+/// Значение, хранящееся в потоке в сжатом виде. Значение сериализуется с настройками
+/// сериализатора, полученные байты сжимаются алгоритмом `C`, после чего в поток записываются
+/// несжатый размер в виде `u32`, сжатый размер в виде `u32` (оба в порядке байт сериализатора)
+/// и сжатые данные.
+///
+/// При десериализации сжатые данные распаковываются, и значение читается из них; значение
+/// должно занимать все распакованные данные. Несжатый размер не может превышать
+/// [`MAX_UNCOMPRESSED_LEN`]. Другие форматы видят этот тип, как newtype-структуру, и записывают
+/// значение без сжатия.
+///
+/// # Параметры типа
+/// - `T`: тип сжимаемого значения
+/// - `C`: алгоритм сжатия
+///
+/// [`MAX_UNCOMPRESSED_LEN`]: constant.MAX_UNCOMPRESSED_LEN.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Compressed<T, C> {
+  /// Сжимаемое значение
+  value: T,
+  /// Алгоритм сжатия
+  _marker: PhantomData<C>,
+}
+
+impl<T, C> Compressed<T, C> {
+  /// Оборачивает указанное значение
+  #[inline]
+  pub fn new(value: T) -> Self {
+    Compressed { value, _marker: PhantomData }
+  }
+  /// Возвращает ссылку на обернутое значение
+  #[inline]
+  pub fn get(&self) -> &T { &self.value }
+  /// Возвращает обернутое значение
+  #[inline]
+  pub fn into_inner(self) -> T { self.value }
+}
+
+impl<T: Serialize, C: Compression> Serialize for Compressed<T, C> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(C::NAME, &self.value)
+  }
+}
+impl<'de, T: Deserialize<'de>, C: Compression> Deserialize<'de> for Compressed<T, C> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_newtype_struct(C::NAME, CompressedVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий распакованное значение
+struct CompressedVisitor<T, C>(PhantomData<(T, C)>);
+impl<'de, T: Deserialize<'de>, C> Visitor<'de> for CompressedVisitor<T, C> {
+  type Value = Compressed<T, C>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a compressed value")
+  }
+  fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    T::deserialize(deserializer).map(Compressed::new)
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(test, any(feature = "flate2", feature = "zstd")))]
+mod tests {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use wrappers::Array;
+  use super::{Compressed, Compression};
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Table {
+    id: u32,
+    rows: Array<u16, 512>,
+  }
+
+  fn table() -> Table {
+    let mut rows = [0u16; 512];
+    for (i, r) in rows.iter_mut().enumerate() {
+      *r = (i % 4) as u16;
+    }
+    Table { id: 0x1234_5678, rows: Array(rows) }
+  }
+
+  fn roundtrip<C: Compression>() {
+    let test = Compressed::<_, C>::new(table());
+    let be = to_vec::<BE,_>(&test).unwrap();
+    assert_eq!(&be[..4], [0x00, 0x00, 0x04, 0x04]);
+    assert!(be.len() < 1028 / 4, "{} bytes is too much", be.len());
+    assert_eq!(be.len() - 8, u32::from_be_bytes([be[4], be[5], be[6], be[7]]) as usize);
+    assert_eq!(from_bytes::<BE, Compressed<Table, C>>(&be).unwrap().into_inner(), table());
+
+    let le = to_vec::<LE,_>(&test).unwrap();
+    assert_eq!(&le[..4], [0x04, 0x04, 0x00, 0x00]);
+    assert_eq!(from_bytes::<LE, Compressed<Table, C>>(&le).unwrap().into_inner(), table());
+
+    // Несжатый размер не совпадает с записанным
+    let mut bad = be.clone();
+    bad[3] = 0x03;
+    assert!(from_bytes::<BE, Compressed<Table, C>>(&bad).is_err());
+    // Слишком большой несжатый размер отвергается до распаковки
+    bad[0] = 0xFF;
+    assert!(from_bytes::<BE, Compressed<Table, C>>(&bad).is_err());
+  }
+
+  #[cfg(feature = "flate2")]
+  #[test]
+  fn test_deflate() {
+    roundtrip::<super::Deflate>();
+  }
+  #[cfg(feature = "zstd")]
+  #[test]
+  fn test_zstd() {
+    roundtrip::<super::Zstd>();
+  }
+}
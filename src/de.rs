@@ -1,13 +1,31 @@
 //! Содержит тип, реализующий простую десериализацию данных, как POD типов.
 
-use std::io::BufRead;
+use std::convert::TryFrom;
+#[cfg(feature = "memmap2")]
+use std::fs::File;
+use std::error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
 use std::marker::PhantomData;
+#[cfg(feature = "memmap2")]
+use std::path::Path;
 use std::str;
 use std::string::String;
-use byteorder::{ByteOrder, ReadBytesExt};
-use serde::de::{self, Deserialize, DeserializeSeed, SeqAccess, Visitor};
+use std::sync::Arc;
+use byteorder::{BE, ByteOrder, LE, ReadBytesExt};
+use serde::de::{self, Deserialize, DeserializeSeed, EnumAccess, IntoDeserializer, SeqAccess, Unexpected, VariantAccess, Visitor};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "memmap2")]
+use memmap2::Mmap;
 
-use error::{Error, Result};
+use checksum::ChecksumReader;
+use compress;
+use encoding::{CustomEncoding, Encoding};
+use error::{Error, ErrorKind, Result};
+use framing::Length;
+use magic::{self, COLUMNAR_ERROR};
+use raw::capped_capacity;
+use {EnumTag, Endian, TagWidth};
 
 /// Структура для десериализации потока байт, практически из значений, как они хранятся
 /// в памяти, в значения Rust.
@@ -28,13 +46,18 @@ use error::{Error, Result};
 /// именно тип он должен обернуть возвращенные данные, таким образом маркер типа даже не требуется.
 ///
 /// При десериализации строк байты интерпретируются в кодировке UTF-8, являющейся нативной для Rust.
-/// В случае, если требуется читать строки в других кодировках, оберните их в структуры, для которых
-/// будет реализован типах [`Deserialize`], выполняющий чтение массива байт из потока и конвертирующий
-/// его в строку с помощью требуемой кодировки, например, используя крейт [encoding]. Чтение строки
+/// В случае, если требуется читать строки в других кодировках, задайте кодировку методом
+/// [`with_encoding`], либо оберните их в структуры, для которых будет реализован типаж [`Deserialize`],
+/// выполняющий чтение массива байт из потока и конвертирующий его в строку с помощью требуемой
+/// кодировки, например, используя крейт [encoding]. Чтение строки
 /// продолжается до конца потока, т.к. десериализатор не способен самостоятельно определить длину
 /// строки. В случае, если поток содержит некорректные UTF-8 данные, то возвращается ошибка
 /// [`Error::Encoding`].
 ///
+/// Значения типа `bool` читаются, как 1 байт: `0` означает `false`, любое другое значение -- `true`.
+/// Если включена строгая проверка методом [`with_strict_bool`], значения, отличные от `0` и `1`,
+/// считаются ошибкой.
+///
 /// При десериализации элемента типа `char` из потока читается требуемое количество байт (от 1 до 4-х)
 /// его UTF-8 представления; если в процессе чтения выясняется, что байты не составляют корректно
 /// кодированное значение символа в UTF-8, возвращается ошибка [`Error::Encoding`].
@@ -44,7 +67,13 @@ use error::{Error, Result};
 /// ни разделители между элементами, ни какой-либо маркер конца последовательности не читаются. В случае,
 /// если они требуются для корректной десериализации, они должны быть добавлены в сериализуемые структуры
 /// вручную. Для последовательностей с известной длиной (например, массивы) читается запрошенное количество
-/// данных.
+/// данных. При чтении недоверенных данных ограничьте размер таких последовательностей, а также строк и
+/// массивов байт, методом [`with_limit`].
+///
+/// Последовательность кортежей, например, `Vec<(K, V)>`, читается как упорядоченный набор пар ключ-значение:
+/// пары читаются одна за другой до конца потока, их порядок в результате совпадает с порядком в потоке.
+/// Конец потока проверяется только перед началом очередной пары; если поток заканчивается посреди пары,
+/// возвращается ошибка. Такой тип можно использовать вместо отображений, которые не поддерживаются.
 ///
 /// # Неподдерживаемые методы
 /// Для некоторых типов [модели serde] десериализация не поддержана, попытка их десериализации приводит
@@ -52,25 +81,23 @@ use error::{Error, Result};
 /// к десериализатору: не все, что может быть закодировано, может быть раскодировано.
 ///
 /// К неподдерживаемым типам модели относятся:
-/// - Оба варианта [`Option`] -- десериализатор не способен самостоятельно их различить. При необходимости
-///   десериализации типа [`Option`] можно реализовать собственную структуру, для которой реализовать
-///   типаж [`Deserialize`] и выполнить чтение маркера типа и данных `Some` варианта, если в потоке записан
-///   `Some` вариант
-/// - Перечисления. Также как и в предыдущем случае, десериализатор не способен самостоятельно определить,
-///   какой из вариантов записан в потоке. Стоит отметить, что данное ограничение применимо только к
-///   [варианту десериализации][enum] перечислений в externally tagged виде (с внешней пометкой), который
-///   является вариантом сериализации перечислений в serde по умолчанию. В остальных случаях serde десериализует
-///   перечисления, как структуры, что уже поддерживается десериализатором.
-/// - Тип `bool` также не поддерживается ввиду того, что десериализатор не знает, сколько байт читать и как
-///   их интерпретировать. Так как обычно булевы значения записываются в виде числа, не должно возникнуть
-///   проблем использовать вместо типа `bool` число, соответствующее его представлению в сериализованных данных.
+/// - Оба варианта [`Option`], если не включен байт-маркер методом [`with_option_marker`] -- без него
+///   десериализатор не способен самостоятельно их различить. Также можно реализовать собственную
+///   структуру, для которой реализовать типаж [`Deserialize`] и выполнить чтение маркера типа и данных
+///   `Some` варианта, если в потоке записан `Some` вариант
+/// - Перечисления, если их представление не задано методом [`with_enum_tag`]. Также как и в предыдущем
+///   случае, десериализатор не способен самостоятельно определить, какой из вариантов записан в потоке.
+///   Стоит отметить, что данное ограничение применимо только к [варианту десериализации][enum] перечислений
+///   в externally tagged виде (с внешней пометкой), который является вариантом сериализации перечислений
+///   в serde по умолчанию. В остальных случаях serde десериализует перечисления, как структуры, что уже
+///   поддерживается десериализатором.
 /// - Десериализация произвольных данных и отображений (map) также не поддерживается. Отображения обычно будут
 ///   записаны в потоке, как список пар ключ-значение, поэтому не должно возникнуть проблем десериализовывать
 ///   именно такие структуры, а затем приводить их в требуемый вид.
 ///
 /// # Параметры типа
 /// - `BO`: определяет порядок байт, в котором будут записаны примитивные числовые типы:
-///         `u16`, `u32`, `u64`, `u128`, `i16`, `i32`, `i64`, `i128`, `f32` и `f64`.
+///   `u16`, `u32`, `u64`, `u128`, `i16`, `i32`, `i64`, `i128`, `f32` и `f64`.
 /// - `W`: определяет тип, обеспечивающих сохранение сериализуемых данных в хранилище
 ///
 /// [`()`]: https://doc.rust-lang.org/std/primitive.unit.html
@@ -84,11 +111,62 @@ use error::{Error, Result};
 /// [сериализатор]: ../ser/struct.Serializer.html
 /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
 /// [enum]: https://serde.rs/enum-representations.html
+/// [`with_enum_tag`]: #method.with_enum_tag
+/// [`with_strict_bool`]: #method.with_strict_bool
+/// [`with_option_marker`]: #method.with_option_marker
+/// [`with_limit`]: #method.with_limit
+/// [`with_encoding`]: #method.with_encoding
 pub struct Deserializer<BO, R> {
   /// Источник данных для десериализации
-  reader: R,
+  reader: Tracked<R>,
   /// Порядок байт, используемый при чтении чисел
   _byteorder: PhantomData<BO>,
+  /// Функция перемещения по потоку, если поток поддерживает перемещение и десериализатор
+  /// создан методом [`seekable`](#method.seekable)
+  seek: Option<fn(&mut R, SeekFrom) -> io::Result<u64>>,
+  /// Настройки чтения данных
+  options: Options,
+  /// Буфер, повторно используемый для чтения строк и массивов байт до конца потока, если он
+  /// задан методом [`with_scratch`](#method.with_scratch)
+  scratch: Option<Vec<u8>>,
+}
+
+/// Настройки десериализатора, общие для него и десериализаторов, создаваемых им для чтения
+/// частей данных
+#[derive(Clone, Debug, Default)]
+struct Options {
+  /// Кодировка строк, если она отличается от UTF-8. Разделяется с десериализаторами частей
+  /// данных, поэтому хранится в `Arc`, см. [`Encoding`]
+  ///
+  /// [`Encoding`]: ../encoding/trait.Encoding.html
+  encoding: Option<Arc<dyn Encoding>>,
+  /// Представление перечислений, если их варианты записаны в потоке
+  enum_tag: Option<EnumTag>,
+  /// Считать ли ошибкой значения `bool`, отличные от `0` и `1`
+  strict_bool: bool,
+  /// Записан ли перед значением `Option` байт-маркер его наличия
+  option_marker: bool,
+  /// Ограничения на размер данных, читаемых до конца потока
+  limit: Limit,
+  /// Читается ли поле последовательности, записанной по столбцам. Сериализатор не знает,
+  /// где в потоке окажутся поля столбцов, поэтому выравнивание в них не поддерживается
+  columnar: bool,
+}
+
+/// Ограничения на размер данных, читаемых до конца потока: последовательностей без определенной
+/// длины, строк и массивов байт. Без ограничений поврежденный или специально подготовленный файл
+/// может заставить десериализатор выделить огромный объем памяти. Ограничения задаются методом
+/// [`Deserializer::with_limit`] и действуют на каждое такое значение отдельно, в том числе на
+/// вложенные в другие значения. Значение `None` означает отсутствие ограничения.
+///
+/// [`Deserializer::with_limit`]: struct.Deserializer.html#method.with_limit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Limit {
+  /// Максимальное количество байт, занимаемых в потоке последовательностью, строкой или
+  /// массивом байт
+  pub max_bytes: Option<u64>,
+  /// Максимальное количество элементов последовательности
+  pub max_elements: Option<usize>,
 }
 
 impl<BO, R> Deserializer<BO, R>
@@ -106,13 +184,549 @@ impl<BO, R> Deserializer<BO, R>
   /// # Возвращаемое значение
   /// Десериализатор для чтения данных из указанного потока и кодированием строк в UTF-8
   pub fn new(reader: R) -> Self {
-    Deserializer { reader, _byteorder: PhantomData }
+    Deserializer {
+      reader: Tracked { inner: reader, position: 0 },
+      _byteorder: PhantomData,
+      seek: None,
+      options: Options::default(),
+      scratch: None,
+    }
+  }
+  /// Устанавливает кодировку строк и символов вместо UTF-8. Символ читается, как количество
+  /// байт, определяемое кодировкой по его первому байту, которые должны декодироваться ровно
+  /// в один символ
+  ///
+  /// # Параметры
+  /// - `encoding`: Кодировка, используемая для чтения строк и символов
+  pub fn with_encoding<E: Encoding + 'static>(mut self, encoding: E) -> Self {
+    self.options.encoding = Some(Arc::new(encoding));
+    self
+  }
+  /// Устанавливает однобайтовую кодировку строк с произвольной таблицей символов вместо UTF-8.
+  /// В такой кодировке каждый байт представляет один символ, поэтому декодирование всегда успешно
+  ///
+  /// # Параметры
+  /// - `encoding`: Кодировка, используемая для чтения строк и символов
+  pub fn with_custom_encoding(self, encoding: CustomEncoding) -> Self {
+    self.with_encoding(encoding)
+  }
+  /// Включает поддержку перечислений: перед данными варианта читается его индекс и, если
+  /// требуется, размер данных. Представление должно совпадать с тем, которое использовалось
+  /// при сериализации
+  ///
+  /// # Параметры
+  /// - `tag`: Представление перечислений
+  pub fn with_enum_tag(mut self, tag: EnumTag) -> Self {
+    self.options.enum_tag = Some(tag);
+    self
+  }
+  /// Включает строгую проверку значений типа `bool`: байты, отличные от `0` и `1`, считаются
+  /// ошибкой, а не значением `true`
+  pub fn with_strict_bool(mut self) -> Self {
+    self.options.strict_bool = true;
+    self
+  }
+  /// Включает поддержку [`Option`]: перед значением читается байт-маркер, `0` означает `None`,
+  /// `1` -- `Some`, за которым следует само значение. Другие значения маркера считаются ошибкой.
+  /// Такое представление записывает сериализатор, для которого вызван метод [`with_option_marker`]
+  ///
+  /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+  /// [`with_option_marker`]: ../ser/struct.Serializer.html#method.with_option_marker
+  pub fn with_option_marker(mut self) -> Self {
+    self.options.option_marker = true;
+    self
+  }
+  /// Ограничивает размер данных, читаемых до конца потока. Если последовательность, строка или
+  /// массив байт превышает ограничение, чтение прерывается с ошибкой [`Error::InvalidLength`]
+  /// до того, как будет выделена память под лишние данные
+  ///
+  /// # Параметры
+  /// - `limit`: Ограничения на количество элементов и байт
+  ///
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  pub fn with_limit(mut self, limit: Limit) -> Self {
+    self.options.limit = limit;
+    self
+  }
+  /// Задает буфер, повторно используемый при чтении строк и массивов байт до конца потока, чтобы
+  /// не выделять память для каждого из них. Если буфер задан, методы `deserialize_str` и
+  /// `deserialize_bytes` заполняют его и передают посетителю заимствованный срез методами
+  /// `visit_str` и `visit_bytes`. Срез действителен только во время вызова посетителя, поэтому
+  /// посетитель, которому нужны данные после возврата, должен их скопировать. Методы
+  /// `deserialize_string` и `deserialize_byte_buf` (используемые, например, типами `String` и
+  /// `Vec<u8>`) по-прежнему передают посетителю владеющий буфер, так как иначе данные все равно
+  /// пришлось бы скопировать.
+  ///
+  /// Кроме самого десериализатора, буфер используется при чтении значений ограниченной длины
+  /// методами [`bounded`](#method.bounded) и [`read_size_prefixed`](#method.read_size_prefixed),
+  /// что позволяет читать поток записей со строками без выделения памяти для каждой из них.
+  /// Остальные десериализаторы, создаваемые для чтения частей данных, выделяют память как обычно.
+  /// Вернуть буфер, например, чтобы использовать его с другим десериализатором, можно методом
+  /// [`take_scratch`](#method.take_scratch).
+  ///
+  /// # Параметры
+  /// - `scratch`: Буфер; его содержимое не важно, используется только выделенная им память
+  pub fn with_scratch(mut self, scratch: Vec<u8>) -> Self {
+    self.scratch = Some(scratch);
+    self
+  }
+  /// Забирает у десериализатора буфер, заданный методом [`with_scratch`](#method.with_scratch).
+  /// После этого строки и массивы байт снова читаются в новые буферы
+  #[inline]
+  pub fn take_scratch(&mut self) -> Option<Vec<u8>> { self.scratch.take() }
+  /// Возвращает поток, из которого читаются данные, например, чтобы продолжить чтение файла
+  /// после заголовка собственными средствами. Десериализатор не хранит прочитанные из потока,
+  /// но еще не использованные данные, поэтому поток продолжается сразу после последнего
+  /// прочитанного значения
+  #[inline]
+  pub fn into_inner(self) -> R { self.reader.inner }
+  /// Возвращает количество байт, прочитанных из потока с момента создания десериализатора.
+  /// Помогает найти место в файле, при чтении которого произошла ошибка. Значения, прочитанные
+  /// по смещению (например, методом `read_at` или типом [`Offset`]), не учитываются: во время
+  /// их чтения позиция указывает на читаемое значение, а после -- восстанавливается так же,
+  /// как позиция в потоке
+  ///
+  /// [`Offset`]: ../wrappers/struct.Offset.html
+  #[inline]
+  pub fn position(&self) -> u64 { self.reader.position }
+  /// Добавляет к ошибке чтения или декодирования текущую позицию в потоке
+  fn locate(&self, err: Error) -> Error {
+    match err {
+      Error::Io(_) | Error::Eof(_) | Error::Encoding(_) => Error::At { offset: self.position(), source: Box::new(err) },
+      err => err,
+    }
+  }
+  /// Создает десериализатор для чтения части данных из другого потока с теми же настройками.
+  /// Данные другого потока располагаются в потоке этого десериализатора с позиции `position`,
+  /// от которой и продолжается отсчет позиции, например, для выравнивания значений
+  fn nested<R2: BufRead>(&self, reader: R2, position: u64) -> Deserializer<BO, R2> {
+    let mut de = Deserializer::new(reader);
+    de.reader.position = position;
+    de.options = self.options.clone();
+    de
+  }
+  /// Читает из потока одно значение типа `T`. Это сокращение для `T::deserialize(&mut *self)`,
+  /// удобное при ручном чтении структур, набор полей которых зависит от ранее прочитанных данных.
+  ///
+  /// # Пример
+  /// ```rust
+  /// # extern crate byteorder;
+  /// # extern crate serde_pod;
+  /// # use std::io::BufRead;
+  /// # use byteorder::{ByteOrder, BE};
+  /// # use serde_pod::de::Deserializer;
+  /// # use serde_pod::Result;
+  /// /// Фигура, количество полей которой зависит от ее вида
+  /// #[derive(Debug, PartialEq)]
+  /// enum Shape {
+  ///   Circle { radius: u16 },
+  ///   Rect { width: u16, height: u16 },
+  /// }
+  /// impl Shape {
+  ///   fn read<BO: ByteOrder, R: BufRead>(de: &mut Deserializer<BO, R>) -> Result<Self> {
+  ///     match de.read_field::<u8>()? {
+  ///       1 => Ok(Shape::Circle { radius: de.read_field()? }),
+  ///       _ => Ok(Shape::Rect { width: de.read_field()?, height: de.read_field()? }),
+  ///     }
+  ///   }
+  /// }
+  ///
+  /// # fn main() -> Result<()> {
+  /// let data = [0x01, 0x00, 0x05,   0x02, 0x00, 0x03, 0x00, 0x04];
+  /// let mut de = Deserializer::<BE, _>::new(&data[..]);
+  /// assert_eq!(Shape::read(&mut de)?, Shape::Circle { radius: 5 });
+  /// assert_eq!(Shape::read(&mut de)?, Shape::Rect { width: 3, height: 4 });
+  /// # Ok(())
+  /// # }
+  /// ```
+  #[inline]
+  pub fn read_field<T>(&mut self) -> Result<T>
+    where T: DeserializeOwned,
+  {
+    T::deserialize(&mut *self)
+  }
+  /// Читает из потока заголовок типа `H`, а затем передает его и десериализатор в функцию `f`,
+  /// которая читает тело, тип или формат которого определяется заголовком.
+  ///
+  /// # Параметры
+  /// - `f`: Функция, читающая тело. Заголовок к моменту ее вызова уже прочитан
+  ///
+  /// # Возвращаемое значение
+  /// Прочитанный заголовок и значение, возвращенное функцией `f`
+  ///
+  /// # Ошибки
+  /// Ошибка чтения заголовка или ошибка, возвращенная функцией `f`
+  ///
+  /// # Пример
+  /// ```rust
+  /// # extern crate byteorder;
+  /// # #[macro_use]
+  /// # extern crate serde_derive;
+  /// # extern crate serde;
+  /// # extern crate serde_pod;
+  /// # use byteorder::LE;
+  /// # use serde::Deserialize;
+  /// # use serde_pod::de::Deserializer;
+  /// # use serde_pod::Error;
+  /// #[derive(Deserialize)]
+  /// struct Header { kind: u8, version: u8 }
+  ///
+  /// #[derive(Debug, PartialEq)]
+  /// enum Body { Text([u8; 2]), Number(u32) }
+  ///
+  /// # fn main() -> serde_pod::Result<()> {
+  /// let data = [0x02, 0x01,   0x2A, 0x00, 0x00, 0x00];
+  /// let mut de = Deserializer::<LE, _>::new(&data[..]);
+  /// let (header, body) = de.read_header_then(|header: &Header, de| match header.kind {
+  ///   1 => Ok(Body::Text(Deserialize::deserialize(de)?)),
+  ///   2 => Ok(Body::Number(Deserialize::deserialize(de)?)),
+  ///   kind => Err(Error::Unknown(format!("unknown body kind {}", kind))),
+  /// })?;
+  /// assert_eq!(header.version, 1);
+  /// assert_eq!(body, Body::Number(42));
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn read_header_then<H, T, F>(&mut self, f: F) -> Result<(H, T)>
+    where H: DeserializeOwned,
+          F: FnOnce(&H, &mut Self) -> Result<T>,
+  {
+    let header = H::deserialize(&mut *self)?;
+    let body = f(&header, self)?;
+    Ok((header, body))
+  }
+  /// Читает из потока элементы типа `T` до его окончания и передает каждый из них в функцию `f`.
+  /// В отличие от десериализации [`Vec<T>`] элементы не накапливаются в памяти, что позволяет
+  /// обрабатывать списки произвольного размера. Чтобы прочитать не весь поток, а только его часть,
+  /// ограничьте поток, например, с помощью [`Read::take`].
+  ///
+  /// # Параметры
+  /// - `f`: Функция, вызываемая для каждого прочитанного элемента. Если она возвращает ошибку,
+  ///   чтение прекращается и ошибка возвращается из метода
+  ///
+  /// # Ошибки
+  /// Ошибка чтения элемента или первая ошибка, возвращенная функцией `f`
+  ///
+  /// [`Vec<T>`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+  /// [`Read::take`]: https://doc.rust-lang.org/std/io/trait.Read.html#method.take
+  pub fn for_each_element<T, F>(&mut self, mut f: F) -> Result<()>
+    where T: DeserializeOwned,
+          F: FnMut(T) -> Result<()>,
+  {
+    while !self.is_eof()? {
+      f(T::deserialize(&mut *self)?)?;
+    }
+    Ok(())
+  }
+  /// Читает из потока элементы типа `T` до его окончания или до первой ошибки чтения элемента.
+  /// В отличие от десериализации [`Vec<T>`] ошибка не отменяет результат целиком: возвращаются
+  /// все элементы, прочитанные до нее, что позволяет восстановить данные из поврежденного или
+  /// обрезанного потока, например, журнала записей.
+  ///
+  /// Элемент, при чтении которого произошла ошибка, мог быть прочитан из потока частично,
+  /// поэтому продолжать чтение после ошибки, как правило, бессмысленно.
+  ///
+  /// # Возвращаемое значение
+  /// Прочитанные элементы и ошибку, прервавшую чтение, или `None`, если поток был прочитан
+  /// до конца
+  ///
+  /// [`Vec<T>`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+  pub fn read_vec_partial<T>(&mut self) -> (Vec<T>, Option<Error>)
+    where T: DeserializeOwned,
+  {
+    let mut vec = Vec::new();
+    loop {
+      match self.is_eof() {
+        Ok(true) => return (vec, None),
+        Ok(false) => {},
+        Err(e) => return (vec, Some(e)),
+      }
+      match T::deserialize(&mut *self) {
+        Ok(elem) => vec.push(elem),
+        Err(e) => return (vec, Some(e)),
+      }
+    }
+  }
+  /// Читает `count` элементов типа `T`, порядок байт которых меняется от элемента к элементу
+  /// согласно расписанию `orders`: элемент с индексом `i` читается в порядке `orders[i % orders.len()]`.
+  /// Порядок байт десериализатора при этом не используется.
+  ///
+  /// # Параметры
+  /// - `count`: Количество читаемых элементов
+  /// - `orders`: Расписание порядков байт элементов, повторяемое циклически
+  ///
+  /// # Ошибки
+  /// Ошибка чтения элемента или [`Error::Unsupported`], если расписание пустое, а `count` не равен 0
+  ///
+  /// [`Error::Unsupported`]: ../error/enum.Error.html#variant.Unsupported
+  pub fn read_alternating_seq<T>(&mut self, count: usize, orders: &[Endian]) -> Result<Vec<T>>
+    where T: DeserializeOwned,
+  {
+    if orders.is_empty() && count > 0 {
+      return Err(Error::Unsupported("byte order schedule is empty"));
+    }
+    let mut seq = Alternating { reader: &mut self.reader, options: &self.options, orders, index: 0, count };
+    let mut vec = Vec::with_capacity(capped_capacity(count));
+    while let Some(elem) = seq.next_element()? {
+      vec.push(elem);
+    }
+    Ok(vec)
+  }
+  /// Читает `count` элементов типа `T`, идущих в потоке друг за другом без префикса длины.
+  /// Используется, когда количество элементов известно из ранее прочитанных данных, например,
+  /// упаковано в битовое поле байта типа, см. [`CountInBits`].
+  ///
+  /// # Параметры
+  /// - `count`: Количество читаемых элементов
+  ///
+  /// # Ошибки
+  /// Ошибка чтения элемента
+  ///
+  /// [`CountInBits`]: ../framing/struct.CountInBits.html
+  pub fn read_counted_by<T>(&mut self, count: usize) -> Result<Vec<T>>
+    where T: DeserializeOwned,
+  {
+    let mut seq = Tuple { de: self, count };
+    let mut vec = Vec::with_capacity(capped_capacity(count));
+    while let Some(elem) = seq.next_element()? {
+      vec.push(elem);
+    }
+    Ok(vec)
+  }
+  /// Читает все оставшиеся в потоке данные, как последовательность элементов фиксированного
+  /// размера. Размер элемента определяется при чтении первого из них, а количество элементов
+  /// вычисляется, как частное от деления размера оставшихся данных на размер элемента. Чтобы
+  /// прочитать не весь поток, а только его часть, используйте [`bounded`](#method.bounded).
+  ///
+  /// # Ошибки
+  /// - [`Error::InvalidLength`]: размер оставшихся данных не кратен размеру элемента или
+  ///   элементы имеют разный размер
+  /// - ошибка чтения элемента
+  ///
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  pub fn read_rest_as_vec<T>(&mut self) -> Result<Vec<T>>
+    where T: DeserializeOwned,
+  {
+    let start = self.position();
+    let buf = self.read_to_end()?;
+    if buf.is_empty() {
+      return Ok(Vec::new());
+    }
+    let mut de = self.nested(&buf[..], start);
+
+    let first = T::deserialize(&mut de)?;
+    let size = buf.len() - de.reader.inner.len();
+    if size == 0 || buf.len() % size != 0 {
+      return Err(Error::InvalidLength(format!(
+        "{} remaining bytes are not a multiple of the element size {}", buf.len(), size
+      )));
+    }
+    let mut vec = Vec::with_capacity(buf.len() / size);
+    vec.push(first);
+    while !de.reader.inner.is_empty() {
+      let rest = de.reader.inner.len();
+      vec.push(T::deserialize(&mut de)?);
+      if rest - de.reader.inner.len() != size {
+        return Err(Error::InvalidLength("elements have different sizes".into()));
+      }
+    }
+    Ok(vec)
+  }
+  /// Читает значение типа `T`, занимающее в потоке ровно `len` байт, например, если его длина
+  /// задается ранее прочитанным полем. Значение не может прочитать больше `len` байт: для него
+  /// поток заканчивается на этой границе, поэтому последовательности и строки внутри значения
+  /// читаются до границы, а не до конца потока. Если значение заняло меньше `len` байт, остаток
+  /// пропускается.
+  ///
+  /// # Параметры
+  /// - `len`: Количество байт, занимаемых значением в потоке
+  ///
+  /// # Ошибки
+  /// Ошибка чтения значения или [`Error::Eof`], если поток закончился раньше, чем было прочитано
+  /// `len` байт
+  ///
+  /// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+  pub fn bounded<T>(&mut self, len: u64) -> Result<T>
+    where T: DeserializeOwned,
+  {
+    self.bounded_seed(len, PhantomData)
+  }
+  /// Создает десериализатор для чтения блока данных длиной `len` байт, например, области,
+  /// размер которой задан в заголовке файла. Для созданного десериализатора поток заканчивается
+  /// на границе блока, поэтому последовательности и строки читаются до нее, а попытка прочитать
+  /// поле за границей блока завершается ошибкой. В отличие от метода [`bounded`](#method.bounded),
+  /// из блока можно читать несколько значений, в том числе решая по ходу чтения, что читать дальше.
+  ///
+  /// Чтобы продолжить чтение после блока, вызовите метод [`finish`], который пропускает
+  /// непрочитанный остаток блока. Созданный десериализатор использует настройки данного.
+  ///
+  /// # Параметры
+  /// - `len`: Количество байт, занимаемых блоком в потоке
+  ///
+  /// # Пример
+  /// ```rust
+  /// # extern crate byteorder;
+  /// # extern crate serde_pod;
+  /// # use byteorder::LE;
+  /// # use serde_pod::de::Deserializer;
+  /// # fn main() -> serde_pod::Result<()> {
+  /// let data = [0x04,   0x01, 0x00, 0x02, 0x00,   0xFF];
+  /// let mut de = Deserializer::<LE, _>::new(&data[..]);
+  /// let len: u8 = de.read_field()?;
+  /// let mut block = de.scoped(len.into());
+  /// let values: Vec<u16> = block.read_field()?;
+  /// block.finish()?;
+  /// assert_eq!(values, [1, 2]);
+  /// assert_eq!(de.read_field::<u8>()?, 0xFF);
+  /// # Ok(())
+  /// # }
+  /// ```
+  ///
+  /// [`finish`]: #method.finish
+  pub fn scoped(&mut self, len: u64) -> ScopedDeserializer<'_, BO, R> {
+    let position = self.reader.position;
+    let options = self.options.clone();
+    let mut sub = Deserializer::new(Scoped { inner: (&mut self.reader).take(len) });
+    sub.reader.position = position;
+    sub.options = options;
+    sub
+  }
+  /// Читает элементы типа `T`, занимающие в потоке ровно `len` байт. В отличие от обычных
+  /// последовательностей, которые читаются до конца потока, последовательность заканчивается
+  /// на границе `len` байт, поэтому после нее в потоке могут располагаться другие данные,
+  /// например, следующие разделы составного файла. Чтение продолжается сразу за границей.
+  ///
+  /// Последовательность читается из блока, созданного методом [`scoped`], поэтому элементы,
+  /// которые сами читаются до конца потока, например, строки, заканчиваются на границе.
+  ///
+  /// # Параметры
+  /// - `len`: Количество байт, занимаемых последовательностью в потоке
+  ///
+  /// # Ошибки
+  /// Ошибка чтения элемента, в том числе [`Error::Eof`], если последний элемент пересекает
+  /// границу или поток закончился раньше нее
+  ///
+  /// # Пример
+  /// ```rust
+  /// # extern crate byteorder;
+  /// # extern crate serde_pod;
+  /// # use byteorder::BE;
+  /// # use serde_pod::de::Deserializer;
+  /// # fn main() -> serde_pod::Result<()> {
+  /// let data = [0x00, 0x01, 0x00, 0x02,   0xFF];
+  /// let mut de = Deserializer::<BE, _>::new(&data[..]);
+  /// assert_eq!(de.seq_within::<u16>(4)?, [1, 2]);
+  /// assert_eq!(de.read_field::<u8>()?, 0xFF);
+  /// # Ok(())
+  /// # }
+  /// ```
+  ///
+  /// [`scoped`]: #method.scoped
+  /// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+  pub fn seq_within<T>(&mut self, len: u64) -> Result<Vec<T>>
+    where T: DeserializeOwned,
+  {
+    let mut block = self.scoped(len);
+    let vec = block.read_field()?;
+    block.finish()?;
+    Ok(vec)
+  }
+  /// Читает `count` элементов типа `T` или, если `count` равен `None`, элементы до конца потока.
+  /// Перед каждым элементом в потоке записан его размер в байтах в виде числа типа `L`. Элемент
+  /// читается так же, как методом [`bounded`](#method.bounded): он не может выйти за границу,
+  /// заданную размером, а непрочитанный остаток пропускается. Это позволяет новым версиям формата
+  /// добавлять в конец элементов поля, неизвестные читающей программе.
+  ///
+  /// # Параметры
+  /// - `count`: Количество читаемых элементов или `None`, чтобы читать до конца потока
+  ///
+  /// # Ошибки
+  /// Ошибка чтения размера или элемента, или [`Error::Eof`], если поток закончился раньше,
+  /// чем был прочитан элемент с заявленным размером
+  ///
+  /// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+  pub fn read_size_prefixed<L, T>(&mut self, count: Option<usize>) -> Result<Vec<T>>
+    where L: Length,
+          T: DeserializeOwned,
+  {
+    let mut seq = SizePrefixedElements { de: self, count, _length: PhantomData::<L> };
+    let mut vec = Vec::with_capacity(capped_capacity(count.unwrap_or(0)));
+    while let Some(elem) = seq.next_element()? {
+      vec.push(elem);
+    }
+    Ok(vec)
+  }
+  /// Читает значение, занимающее в потоке ровно `len` байт, с помощью `seed`
+  fn bounded_seed<'de, S>(&mut self, len: u64, seed: S) -> Result<S::Value>
+    where S: DeserializeSeed<'de>,
+  {
+    let position = self.reader.position;
+    let options = self.options.clone();
+    let mut sub = Deserializer::<BO, _>::new((&mut self.reader).take(len));
+    sub.reader.position = position;
+    sub.options = options;
+    sub.scratch = self.scratch.take();
+    let value = seed.deserialize(&mut sub);
+    self.scratch = sub.scratch.take();
+    let value = value?;
+    io::copy(&mut sub.reader, &mut io::sink())?;
+    if sub.reader.inner.limit() > 0 {
+      return Err(Error::Eof(None));
+    }
+    Ok(value)
+  }
+  /// Проверяет, закончились ли данные в потоке
+  #[inline]
+  pub(crate) fn is_eof(&mut self) -> Result<bool> {
+    Ok(self.reader.fill_buf()?.is_empty())
+  }
+  /// Проверяет, остались ли в потоке данные, например, чтобы читать записи, пока они не
+  /// закончатся. Конец потока определяется так же, как при чтении последовательностей без
+  /// определенной длины: при необходимости данные читаются в буфер потока, но не потребляются
+  ///
+  /// # Ошибки
+  /// Ошибка чтения из потока
+  #[inline]
+  pub fn has_remaining(&mut self) -> Result<bool> {
+    Ok(!self.is_eof()?)
+  }
+  /// Пропускает `len` байт потока, например, зарезервированные поля или выравнивание при ручной
+  /// реализации [`Deserialize`]. Пропущенные байты учитываются [`position`]. Для полей
+  /// структур удобнее использовать тип [`Pad`]
+  ///
+  /// # Параметры
+  /// - `len`: Количество пропускаемых байт
+  ///
+  /// # Ошибки
+  /// Ошибка чтения из потока или [`Error::Eof`], если в потоке меньше `len` байт
+  ///
+  /// [`Deserialize`]: https://docs.serde.rs/serde/trait.Deserialize.html
+  /// [`position`]: #method.position
+  /// [`Pad`]: ../layout/struct.Pad.html
+  /// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+  pub fn skip(&mut self, len: usize) -> Result<()> {
+    let skipped = io::copy(&mut (&mut self.reader).take(len as u64), &mut io::sink())?;
+    if skipped < len as u64 {
+      return Err(Error::Eof(Some(len)));
+    }
+    Ok(())
   }
   /// Читает все данные из потока в вектор и возвращает его
   #[inline]
   fn read_to_end(&mut self) -> Result<Vec<u8>> {
     let mut buf = Vec::new();
-    self.reader.read_to_end(&mut buf)?;
+    read_to_end(&mut self.reader, &self.options.limit, &mut buf)?;
+    Ok(buf)
+  }
+  /// Читает из потока ровно `len` байт и возвращает их. Память под буфер выделяется по мере
+  /// чтения данных, поэтому большая длина, прочитанная из поврежденного потока, не приводит
+  /// к выделению большого объема памяти, если данных в потоке недостаточно
+  fn read_exactly(&mut self, len: usize) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    (&mut self.reader).take(len as u64).read_to_end(&mut buf)?;
+    if buf.len() < len {
+      return Err(Error::Eof(None));
+    }
     Ok(buf)
   }
   /// Читает из потока один символ в кодировке UTF-8 (т.е. 1-4 байта для его формирования) и
@@ -152,13 +766,86 @@ impl<BO, R> Deserializer<BO, R>
   }
 }
 
+impl<BO, R> Deserializer<BO, BufReader<R>>
+  where R: Read,
+        BO: ByteOrder,
+{
+  /// Создает десериализатор с настройками по умолчанию для небуферизованного потока, например,
+  /// файла. Поток оборачивается во внутренний [`BufReader`], поэтому, в отличие от
+  /// [`ReadDeserializer`], поддерживается чтение последовательностей до конца потока
+  ///
+  /// # Параметры
+  /// - `reader`: Поток, из которого будут читаться данные
+  ///
+  /// [`BufReader`]: https://doc.rust-lang.org/std/io/struct.BufReader.html
+  /// [`ReadDeserializer`]: type.ReadDeserializer.html
+  pub fn from_read(reader: R) -> Self {
+    Self::new(BufReader::new(reader))
+  }
+}
+
+impl<BO, R> Deserializer<BO, R>
+  where R: BufRead + Seek,
+        BO: ByteOrder,
+{
+  /// Создает десериализатор для потока, поддерживающего перемещение. В отличие от
+  /// десериализатора, созданного методом [`new`], такой десериализатор может читать значения,
+  /// расположенные по смещению, например, [`Offset`].
+  ///
+  /// # Параметры
+  /// - `reader`: Поток, из которого будут читаться данные
+  ///
+  /// [`new`]: #method.new
+  /// [`Offset`]: ../wrappers/struct.Offset.html
+  pub fn seekable(reader: R) -> Self {
+    let mut de = Self::new(reader);
+    de.seek = Some(<R as Seek>::seek);
+    de
+  }
+  /// Читает значение типа `T`, расположенное по абсолютному смещению `offset` от начала потока,
+  /// например, по смещению, указанному в заголовке файла. После чтения позиция в потоке
+  /// восстанавливается, поэтому можно прочитать несколько значений по смещениям из одного
+  /// заголовка, а затем продолжить последовательное чтение.
+  ///
+  /// # Параметры
+  /// - `offset`: Смещение значения от начала потока
+  ///
+  /// # Ошибки
+  /// Ошибка перемещения по потоку или чтения значения. Позиция в потоке восстанавливается
+  /// и в случае ошибки чтения значения
+  pub fn read_at<T>(&mut self, offset: u64) -> Result<T>
+    where T: DeserializeOwned,
+  {
+    let position = self.reader.position;
+    let pos = self.reader.inner.stream_position()?;
+    self.reader.inner.seek(SeekFrom::Start(offset))?;
+    // Позиция значения отсчитывается от создания десериализатора, как и текущая позиция
+    self.reader.position = (position + offset).saturating_sub(pos);
+    let value = T::deserialize(&mut *self);
+    self.reader.inner.seek(SeekFrom::Start(pos))?;
+    self.reader.position = position;
+    value
+  }
+}
+
+/// Преобразует ошибку чтения значения размером `size` байт. Если поток закончился, в ошибке
+/// [`Error::Eof`] указывается размер значения
+///
+/// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+fn read_error(err: io::Error, size: usize) -> Error {
+  match err.kind() {
+    io::ErrorKind::UnexpectedEof => Error::Eof(Some(size)),
+    _ => err.into(),
+  }
+}
+
 /// Макрос, генерирующий код десериализации числовых типов
 macro_rules! impl_numbers {
-  ($dser_method:ident, $visitor_method:ident, $reader_method:ident) => {
+  ($dser_method:ident, $visitor_method:ident, $reader_method:ident, $size:expr) => {
     fn $dser_method<V>(self, visitor: V) -> Result<V::Value>
       where V: de::Visitor<'de>,
     {
-      visitor.$visitor_method(self.reader.$reader_method::<BO>()?)
+      visitor.$visitor_method(self.reader.$reader_method::<BO>().map_err(|e| read_error(e, $size))?)
     }
   }
 }
@@ -178,66 +865,122 @@ macro_rules! unsupported {
   }
 }
 
-impl<'de, 'a, BO, R> de::Deserializer<'de> for &'a mut Deserializer<BO, R>
+impl<'de, BO, R> de::Deserializer<'de> for &mut Deserializer<BO, R>
   where R: BufRead,
         BO: ByteOrder,
 {
   type Error = Error;
 
+  /// Читает из потока 1 байт: `0` означает `false`, остальные значения -- `true`, либо ошибку,
+  /// если включена строгая проверка и значение не равно `1`
+  fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    match self.reader.read_u8().map_err(|e| read_error(e, 1))? {
+      0 => visitor.visit_bool(false),
+      1 => visitor.visit_bool(true),
+      b if self.options.strict_bool => Err(de::Error::invalid_value(Unexpected::Unsigned(b.into()), &"0 or 1")),
+      _ => visitor.visit_bool(true),
+    }
+  }
   /// Читает из потока 1 байт, интерпретируя его, как число со знаком
   fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
-    visitor.visit_i8(self.reader.read_i8()?)
+    visitor.visit_i8(self.reader.read_i8().map_err(|e| read_error(e, 1))?)
   }
   /// Читает из потока 1 байт, интерпретируя его, как беззнаковое число
   fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
-    visitor.visit_u8(self.reader.read_u8()?)
+    visitor.visit_u8(self.reader.read_u8().map_err(|e| read_error(e, 1))?)
   }
-  impl_numbers!(deserialize_i16, visit_i16, read_i16);
-  impl_numbers!(deserialize_u16, visit_u16, read_u16);
-  impl_numbers!(deserialize_i32, visit_i32, read_i32);
-  impl_numbers!(deserialize_u32, visit_u32, read_u32);
-  impl_numbers!(deserialize_i64, visit_i64, read_i64);
-  impl_numbers!(deserialize_u64, visit_u64, read_u64);
-  impl_numbers!(deserialize_i128, visit_i128, read_i128);
-  impl_numbers!(deserialize_u128, visit_u128, read_u128);
-  impl_numbers!(deserialize_f32, visit_f32, read_f32);
-  impl_numbers!(deserialize_f64, visit_f64, read_f64);
+  impl_numbers!(deserialize_i16, visit_i16, read_i16, 2);
+  impl_numbers!(deserialize_u16, visit_u16, read_u16, 2);
+  impl_numbers!(deserialize_i32, visit_i32, read_i32, 4);
+  impl_numbers!(deserialize_u32, visit_u32, read_u32, 4);
+  impl_numbers!(deserialize_i64, visit_i64, read_i64, 8);
+  impl_numbers!(deserialize_u64, visit_u64, read_u64, 8);
+  impl_numbers!(deserialize_i128, visit_i128, read_i128, 16);
+  impl_numbers!(deserialize_u128, visit_u128, read_u128, 16);
+  impl_numbers!(deserialize_f32, visit_f32, read_f32, 4);
+  impl_numbers!(deserialize_f64, visit_f64, read_f64, 8);
 
   fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
-    visitor.visit_char(self.read_char()?)
+    let encoding = match self.options.encoding {
+      Some(ref encoding) => encoding.clone(),
+      None => return visitor.visit_char(self.read_char()?),
+    };
+    let first = self.reader.read_u8()?;
+    let mut buf = vec![first];
+    buf.resize(encoding.char_width(first).max(1), 0);
+    self.reader.read_exact(&mut buf[1..])?;
+    let s = encoding.decode(&buf)?;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+      (Some(c), None) => visitor.visit_char(c),
+      _ => Err(Error::Unknown(format!("bytes {:?} are not decoded as a single character", buf))),
+    }
   }
   #[inline]
+  /// Читает байты до конца потока так же, как [`deserialize_string`], но, если задан буфер
+  /// методом [`with_scratch`], читает их в него и передает посетителю заимствованную строку
+  ///
+  /// [`deserialize_string`]: #method.deserialize_string
+  /// [`with_scratch`]: struct.Deserializer.html#method.with_scratch
   fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
-    self.deserialize_string(visitor)
+    let Deserializer { ref mut reader, ref mut scratch, ref options, .. } = *self;
+    match *scratch {
+      Some(ref mut buf) => {
+        buf.clear();
+        read_to_end(reader, &options.limit, buf)?;
+        match options.encoding {
+          Some(ref encoding) => visitor.visit_string(encoding.decode(buf)?),
+          None => visitor.visit_str(str::from_utf8(buf)?),
+        }
+      }
+      None => self.deserialize_string(visitor),
+    }
   }
   /// Читает байты до конца потока, возвращает их посетителю в виде владеющего буфера.
   /// Так как десериализатор сам не может определить, где заканчиваются данные, то для
   /// десериализации сложных структур внешний код должен ограничить размер буфера концом
   /// строки.
   ///
-  /// Прочитанные байт интерпретируются, как строка в кодировке UTF-8, в случае, если это не так,
-  /// возвращается ошибка [`Error::Encoding`]
+  /// Прочитанные байт интерпретируются, как строка в кодировке десериализатора (по умолчанию
+  /// UTF-8), в случае, если это не так, возвращается ошибка [`Error::Encoding`]
   ///
   /// [`Error::Encoding`]: ../error/enum.Error.html#variant.Encoding
   fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
     let buf = self.read_to_end()?;
-    visitor.visit_string(String::from_utf8(buf)?)
+    match self.options.encoding {
+      Some(ref encoding) => visitor.visit_string(encoding.decode(&buf)?),
+      None => visitor.visit_string(String::from_utf8(buf)?),
+    }
   }
-  #[inline]
+  /// Читает байты до конца потока так же, как [`deserialize_byte_buf`], но, если задан буфер
+  /// методом [`with_scratch`], читает их в него и передает посетителю заимствованный срез
+  ///
+  /// [`deserialize_byte_buf`]: #method.deserialize_byte_buf
+  /// [`with_scratch`]: struct.Deserializer.html#method.with_scratch
   fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
-    self.deserialize_byte_buf(visitor)
+    let Deserializer { ref mut reader, ref mut scratch, ref options, .. } = *self;
+    match *scratch {
+      Some(ref mut buf) => {
+        buf.clear();
+        read_to_end(reader, &options.limit, buf)?;
+        visitor.visit_bytes(buf)
+      }
+      None => self.deserialize_byte_buf(visitor),
+    }
   }
   fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
@@ -260,12 +1003,85 @@ impl<'de, 'a, BO, R> de::Deserializer<'de> for &'a mut Deserializer<BO, R>
   {
     visitor.visit_unit()
   }
-  /// Безусловно вызывает [`Visitor::visit_newtype_struct`]. Аргумент `_name` игнорируется
+  /// Безусловно вызывает [`Visitor::visit_newtype_struct`]. Аргумент `name` используется только
+  /// для распознавания типов-оберток этого крейта:
+  /// - для значений, расположенных по смещению, читается смещение типа `u32` относительно его
+  ///   собственной позиции, значение читается по этому смещению, после чего чтение продолжается
+  ///   сразу за смещением. Для этого десериализатор должен быть создан методом
+  ///   [`Deserializer::seekable`]
+  /// - для значений, защищенных контрольной суммой, после значения читается контрольная сумма
+  ///   CRC-32 типа `u32` и сравнивается с вычисленной по прочитанным байтам значения
+  /// - для перечислений с меткой варианта значение читается так, как будто представление
+  ///   перечислений задано методом [`with_enum_tag`](struct.Deserializer.html#method.with_enum_tag)
+  /// - для сжатых значений читаются несжатый и сжатый размеры типа `u32` и сжатые данные,
+  ///   значение читается из распакованных данных
+  /// - для выровненных значений пропускаются байты до границы выравнивания, отсчитываемой
+  ///   от начала чтения (см. [`position`](struct.Deserializer.html#method.position)), в том
+  ///   числе внутри значений, прочитанных по смещению или из отдельного буфера
   ///
   /// [`Visitor::visit_newtype_struct`]: https://docs.serde.rs/serde/de/trait.Visitor.html#method.visit_newtype_struct
-  fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+  /// [`Deserializer::seekable`]: struct.Deserializer.html#method.seekable
+  fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
+    if name == magic::OFFSET {
+      let seek = self.seek.ok_or(Error::Unsupported("offsets require a seekable stream"))?;
+      let pos = seek(&mut self.reader.inner, SeekFrom::Current(0))?;
+      let position = self.reader.position;
+      let offset = self.reader.read_u32::<BO>()?;
+      seek(&mut self.reader.inner, SeekFrom::Start(pos + u64::from(offset)))?;
+      self.reader.position = position + u64::from(offset);
+      let value = visitor.visit_newtype_struct(&mut *self);
+      seek(&mut self.reader.inner, SeekFrom::Start(pos + 4))?;
+      self.reader.position = position + 4;
+      return value;
+    }
+    if let Some(width) = magic::tagged_enum_width(name) {
+      let outer = self.options.enum_tag.replace(EnumTag { width, length_delimited: false });
+      let value = visitor.visit_newtype_struct(&mut *self);
+      self.options.enum_tag = outer;
+      return value;
+    }
+    if name == magic::CRC32 {
+      let position = self.reader.position;
+      let options = self.options.clone();
+      let mut sub = Deserializer::<BO, _>::new(ChecksumReader::new(&mut self.reader));
+      sub.reader.position = position;
+      sub.options = options;
+      let value = visitor.visit_newtype_struct(&mut sub)?;
+      let computed = sub.reader.inner.digest();
+      let stored = self.reader.read_u32::<BO>()?;
+      if stored != computed {
+        return Err(Error::ChecksumMismatch { stored, computed });
+      }
+      return Ok(value);
+    }
+    if let Some(codec) = compress::codec(name) {
+      let start = self.position();
+      let len = self.reader.read_u32::<BO>()?;
+      if len > compress::MAX_UNCOMPRESSED_LEN {
+        return Err(Error::InvalidLength(format!("uncompressed size {} is too big", len)));
+      }
+      let packed = self.reader.read_u32::<BO>()?;
+      let packed = usize::try_from(packed).map_err(|_| Error::Overflow(packed.into()))?;
+      let data = self.read_exactly(packed)?;
+      let buf = (codec.decompress)(&data, len as usize)?;
+      // Позиция распакованных данных отсчитывается от начала сжатого значения
+      let mut sub = self.nested(&buf[..], start);
+      let value = visitor.visit_newtype_struct(&mut sub)?;
+      if !sub.reader.inner.is_empty() {
+        return Err(Error::InvalidLength(format!("{} decompressed bytes are left unread", sub.reader.inner.len())));
+      }
+      return Ok(value);
+    }
+    if let Some(align) = magic::aligned_width(name) {
+      if self.options.columnar {
+        return Err(Error::Unsupported("alignment is not supported in columnar layout"));
+      }
+      let padding = magic::padding(self.position(), align);
+      self.skip(padding)?;
+      return visitor.visit_newtype_struct(self);
+    }
     visitor.visit_newtype_struct(self)
   }
   /// Десериализует последовательность, последовательно вычитывая ее элементы, пока не кончатся
@@ -277,7 +1093,8 @@ impl<'de, 'a, BO, R> de::Deserializer<'de> for &'a mut Deserializer<BO, R>
   fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
-    visitor.visit_seq(self)
+    let start = self.position();
+    visitor.visit_seq(Unbounded { de: self, count: 0, start })
   }
   /// Десериализует кортеж, как последовательность его полей: безусловно вызывает
   /// [`Visitor::visit_seq`].
@@ -289,13 +1106,57 @@ impl<'de, 'a, BO, R> de::Deserializer<'de> for &'a mut Deserializer<BO, R>
     visitor.visit_seq(Tuple { de: self, count: len })
   }
   /// Десериализует кортеж, как последовательность его полей: безусловно вызывает
-  /// [`Visitor::visit_seq`]. Аргумент `_name` игнорируется
+  /// [`Visitor::visit_seq`]. Аргумент `name` используется только для распознавания
+  /// типов-оберток этого крейта:
+  /// - для типов, чье представление зависит от порядка байт, читается `len` байт, которые
+  ///   передаются в [`Visitor::visit_byte_buf`] в порядке `Big-Endian`
+  /// - для массивов байт, записанных как есть, читается `len` байт, которые передаются
+  ///   в [`Visitor::visit_byte_buf`]
+  /// - для массивов примитивных чисел все `len` элементов читаются из потока за одну операцию
+  /// - для последовательностей структур, записанных по столбцам, каждый из `len` элементов
+  ///   собирается из полей, прочитанных из разных столбцов
+  /// - для последовательностей с количеством в конце читается весь поток, из последних `len`
+  ///   байт которого читается первый элемент, а из остальных -- все последующие
   ///
   /// [`Visitor::visit_seq`]: https://docs.serde.rs/serde/de/trait.Visitor.html#method.visit_seq
-  #[inline]
-  fn deserialize_tuple_struct<V>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value>
+  /// [`Visitor::visit_byte_buf`]: https://docs.serde.rs/serde/de/trait.Visitor.html#method.visit_byte_buf
+  fn deserialize_tuple_struct<V>(self, name: &'static str, len: usize, visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
+    if name == magic::BE_BYTES {
+      let mut buf = self.read_exactly(len)?;
+      if !magic::is_big_endian::<BO>() {
+        buf.reverse();
+      }
+      return visitor.visit_byte_buf(buf);
+    }
+    if name == magic::RAW_BYTES {
+      return visitor.visit_byte_buf(self.read_exactly(len)?);
+    }
+    if let Some(width) = magic::bulk_array_width(name) {
+      let size = len.checked_mul(width).ok_or(Error::Unsupported("array is too large"))?;
+      let start = self.position();
+      let buf = self.read_exactly(size)?;
+      let mut de = self.nested(&buf[..], start);
+      return visitor.visit_seq(Tuple { de: &mut de, count: len });
+    }
+    if name == magic::COLUMNAR {
+      let mut columns = Columns { de: self, count: len, index: 0, buf: Vec::new(), layout: Vec::new() };
+      let value = visitor.visit_seq(&mut columns)?;
+      // Дочитываем столбцы, если последние элементы не были запрошены посетителем
+      let end = columns.layout.last().map_or(0, |&(start, size)| start + size * len);
+      columns.fill(end)?;
+      return Ok(value);
+    }
+    if name == magic::TRAILER_COUNTED {
+      let start = self.position();
+      let buf = self.read_to_end()?;
+      let split = buf.len().checked_sub(len)
+        .ok_or(Error::Eof(Some(len)))?;
+      let (data, count) = buf.split_at(split);
+      let data = self.nested(data, start);
+      return visitor.visit_seq(Trailer { count: Some(Deserializer::new(count)), data });
+    }
     self.deserialize_tuple(len, visitor)
   }
   /// Десериализует структуру, как последовательность ее полей: безусловно вызывает
@@ -311,326 +1172,2725 @@ impl<'de, 'a, BO, R> de::Deserializer<'de> for &'a mut Deserializer<BO, R>
 
   unsupported!(deserialize_any);
   unsupported!(deserialize_map);
-  unsupported!(deserialize_bool);
-  unsupported!(deserialize_option);
+  /// Читает байт-маркер и, если он равен `1`, значение. Поддерживается, только если маркер
+  /// включен методом [`with_option_marker`]
+  ///
+  /// [`with_option_marker`]: struct.Deserializer.html#method.with_option_marker
+  fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    if !self.options.option_marker {
+      return Err(Error::Unsupported("`deserialize_option` is not supported without option marker"));
+    }
+    match self.reader.read_u8()? {
+      0 => visitor.visit_none(),
+      1 => visitor.visit_some(self),
+      b => Err(de::Error::invalid_value(Unexpected::Unsigned(b.into()), &"0 or 1")),
+    }
+  }
   unsupported!(deserialize_identifier);
-  unsupported!(deserialize_ignored_any);
-  fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], _visitor: V) -> Result<V::Value>
+  /// Пропускает все данные до конца потока и вызывает [`Visitor::visit_unit`]. Так как длина
+  /// произвольного значения неизвестна, пропускается весь остаток потока, поэтому значение,
+  /// которое нужно проигнорировать, например, [`IgnoredAny`], должно быть последним полем
+  /// структуры или ограничено, например, методом [`bounded`]. Данные, следующие за ним,
+  /// прочитать будет невозможно.
+  ///
+  /// [`Visitor::visit_unit`]: https://docs.serde.rs/serde/de/trait.Visitor.html#method.visit_unit
+  /// [`IgnoredAny`]: https://docs.serde.rs/serde/de/struct.IgnoredAny.html
+  /// [`bounded`]: struct.Deserializer.html#method.bounded
+  fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    io::copy(&mut self.reader, &mut io::sink())?;
+    visitor.visit_unit()
+  }
+  /// Читает метку варианта и, если требуется, размер его данных. Поддерживается, только если
+  /// представление перечислений задано методом [`with_enum_tag`]. Аргументы `_name` и `_variants`
+  /// игнорируются
+  ///
+  /// [`with_enum_tag`]: struct.Deserializer.html#method.with_enum_tag
+  fn deserialize_enum<V>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value>
     where V: Visitor<'de>,
   {
-    Err(Error::Unsupported("`deserialize_enum` is not supported"))
+    let tag = self.options.enum_tag.ok_or(Error::Unsupported("`deserialize_enum` is not supported without an enum tag"))?;
+    let index = match tag.width {
+      TagWidth::U8  => u32::from(self.reader.read_u8()?),
+      TagWidth::U16 => u32::from(self.reader.read_u16::<BO>()?),
+      TagWidth::U32 => self.reader.read_u32::<BO>()?,
+    };
+    let data = if tag.length_delimited {
+      let len = self.reader.read_u32::<BO>()?;
+      let len = usize::try_from(len).map_err(|_| Error::Overflow(len.into()))?;
+      Some(self.read_exactly(len)?)
+    } else {
+      None
+    };
+    let start = self.position() - data.as_ref().map_or(0, |data| data.len() as u64);
+    visitor.visit_enum(Enum { de: self, index, data, start })
   }
 }
 
-/// Структура, используемая для чтения ограниченных по количеству последовательностей,
-/// таких, как массивы, структуры и кортежи
-struct Tuple<'a, BO, R> {
-  /// Объект, используемый для чтения и десериализации элементов
+/// Доступ к варианту перечисления, метка которого уже прочитана
+struct Enum<'a, BO, R> {
+  /// Десериализатор, из которого читаются данные варианта
   de: &'a mut Deserializer<BO, R>,
-  /// Количество элементов, которое осталось прочитать
-  count: usize,
+  /// Индекс варианта
+  index: u32,
+  /// Данные варианта, если они предварялись их размером и уже прочитаны из потока
+  data: Option<Vec<u8>>,
+  /// Позиция данных варианта в потоке
+  start: u64,
 }
-impl<'a, 'de, BO, R> SeqAccess<'de> for Tuple<'a, BO, R>
+impl<'a, 'de, BO, R> EnumAccess<'de> for Enum<'a, BO, R>
   where R: BufRead,
         BO: ByteOrder,
 {
   type Error = Error;
+  type Variant = Self;
 
-  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
-    where T: DeserializeSeed<'de>,
+  fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where V: DeserializeSeed<'de>,
   {
-    // Если еще есть элементы для чтения, вытаскиваем их
-    if self.count > 0 {
-      self.count -= 1;
-      return seed.deserialize(&mut *self.de).map(Some);
-    }
-    return Ok(None);
+    let variant = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(self.index))?;
+    Ok((variant, self))
   }
-
-  fn size_hint(&self) -> Option<usize> { Some(self.count) }
 }
-
-impl<'a, 'de, BO, R> SeqAccess<'de> for &'a mut Deserializer<BO, R>
+impl<'a, 'de, BO, R> VariantAccess<'de> for Enum<'a, BO, R>
   where R: BufRead,
         BO: ByteOrder,
 {
   type Error = Error;
 
-  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+  /// Ничего не читает из потока. Данные варианта, если они были, пропускаются
+  fn unit_variant(self) -> Result<()> { Ok(()) }
+  fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
     where T: DeserializeSeed<'de>,
   {
-    // Если данные закончились, прекращаем итерации
-    if self.reader.fill_buf()?.is_empty() {
-      return Ok(None);
+    match self.data {
+      Some(ref data) => seed.deserialize(&mut self.de.nested(&data[..], self.start)),
+      None => seed.deserialize(self.de),
+    }
+  }
+  fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    match self.data {
+      Some(ref data) => de::Deserializer::deserialize_tuple(&mut self.de.nested(&data[..], self.start), len, visitor),
+      None => de::Deserializer::deserialize_tuple(self.de, len, visitor),
+    }
+  }
+  fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    match self.data {
+      Some(ref data) => de::Deserializer::deserialize_struct(&mut self.de.nested(&data[..], self.start), "", fields, visitor),
+      None => de::Deserializer::deserialize_struct(self.de, "", fields, visitor),
     }
-    seed.deserialize(&mut **self).map(Some)
   }
 }
 
-/// Десериализует значение заданного типа из массива байт.
-///
-/// # Параметры
-/// - `storage`: Массив байт, содержащий сериализованное значение
-///
-/// # Параметры типа
-/// - `BO`: Порядок байт, в котором читать данные из потока
-/// - `T`: Десериализуемый тип
+/// Десериализатор, порядок байт которого выбирается во время выполнения программы значением
+/// [`Endian`], а не параметром типа, например, если он определяется сигнатурой файла. Порядок байт
+/// можно изменить в любой момент методом [`set_byteorder`], поэтому один заголовок может
+/// содержать флаг, задающий порядок байт следующих за ним полей.
 ///
-/// # Возвращаемое значение
-/// Прочитанное значение
+/// Каждое значение, запрошенное непосредственно у этого десериализатора, читается обычным
+/// [`Deserializer`] с текущим порядком байт, поэтому правила десериализации совпадают. Порядок байт
+/// значения, уже начатого десериализацией, не изменяется.
 ///
-/// # Ошибки
-/// Возможны 2 причины, по которым данный метод вернет ошибку:
-/// - Реализация `Deserialize` для типа `T` вернет ошибку
-/// - [`Error::Encoding`]: Десериализуемый тип содержит [строки], и в десериализуемых
-///   данных они не содержат корректных UTF-8 последовательностей
+/// # Пример
+/// ```rust
+/// # extern crate serde;
+/// # extern crate serde_pod;
+/// # use serde::Deserialize;
+/// # use serde_pod::Endian;
+/// # use serde_pod::de::DynDeserializer;
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [b'I', 0x78, 0x56, 0x34, 0x12];
+/// let mut de = DynDeserializer::new(&data[..], Endian::Big);
+/// if u8::deserialize(&mut de)? == b'I' {
+///   de.set_byteorder(Endian::Little);
+/// }
+/// assert_eq!(u32::deserialize(&mut de)?, 0x1234_5678);
+/// # Ok(())
+/// # }
+/// ```
 ///
-/// [`Error::Encoding`]: ../error/enum.Error.html#variant.Encoding
-/// [строки]: https://doc.rust-lang.org/std/string/struct.String.html
-pub fn from_bytes<'a, BO, T>(storage: &'a [u8]) -> Result<T>
-  where T: Deserialize<'a>,
+/// [`Endian`]: ../enum.Endian.html
+/// [`set_byteorder`]: #method.set_byteorder
+/// [`Deserializer`]: struct.Deserializer.html
+#[derive(Debug)]
+pub struct DynDeserializer<R> {
+  /// Источник данных для десериализации
+  reader: Tracked<R>,
+  /// Функция перемещения по потоку, передаваемая десериализаторам с конкретным порядком байт.
+  /// Задается, если десериализатор создан методом [`seekable`](#method.seekable)
+  seek: Option<TrackedSeek<R>>,
+  /// Текущий порядок байт
+  endian: Endian,
+  /// Настройки чтения данных
+  options: Options,
+}
+
+impl<R: BufRead> DynDeserializer<R> {
+  /// Создает десериализатор с указанным начальным порядком байт. Строки кодируются в UTF-8
+  ///
+  /// # Параметры
+  /// - `reader`: Поток, из которого будут читаться данные
+  /// - `endian`: Порядок байт, в котором читаются числа, пока он не будет изменен
+  pub fn new(reader: R, endian: Endian) -> Self {
+    DynDeserializer {
+      reader: Tracked { inner: reader, position: 0 },
+      seek: None,
+      endian,
+      options: Options::default(),
+    }
+  }
+  /// Устанавливает кодировку строк и символов вместо UTF-8, см. [`Deserializer::with_encoding`]
+  ///
+  /// [`Deserializer::with_encoding`]: struct.Deserializer.html#method.with_encoding
+  pub fn with_encoding<E: Encoding + 'static>(mut self, encoding: E) -> Self {
+    self.options.encoding = Some(Arc::new(encoding));
+    self
+  }
+  /// Устанавливает однобайтовую кодировку строк с произвольной таблицей символов вместо UTF-8
+  pub fn with_custom_encoding(self, encoding: CustomEncoding) -> Self {
+    self.with_encoding(encoding)
+  }
+  /// Включает поддержку перечислений, см. [`Deserializer::with_enum_tag`]
+  ///
+  /// [`Deserializer::with_enum_tag`]: struct.Deserializer.html#method.with_enum_tag
+  pub fn with_enum_tag(mut self, tag: EnumTag) -> Self {
+    self.options.enum_tag = Some(tag);
+    self
+  }
+  /// Включает строгую проверку значений типа `bool`, см. [`Deserializer::with_strict_bool`]
+  ///
+  /// [`Deserializer::with_strict_bool`]: struct.Deserializer.html#method.with_strict_bool
+  pub fn with_strict_bool(mut self) -> Self {
+    self.options.strict_bool = true;
+    self
+  }
+  /// Включает поддержку [`Option`] с байтом-маркером, см. [`Deserializer::with_option_marker`]
+  ///
+  /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+  /// [`Deserializer::with_option_marker`]: struct.Deserializer.html#method.with_option_marker
+  pub fn with_option_marker(mut self) -> Self {
+    self.options.option_marker = true;
+    self
+  }
+  /// Ограничивает размер данных, читаемых до конца потока, см. [`Deserializer::with_limit`]
+  ///
+  /// [`Deserializer::with_limit`]: struct.Deserializer.html#method.with_limit
+  pub fn with_limit(mut self, limit: Limit) -> Self {
+    self.options.limit = limit;
+    self
+  }
+  /// Возвращает текущий порядок байт
+  #[inline]
+  pub fn byteorder(&self) -> Endian { self.endian }
+  /// Изменяет порядок байт, в котором будут читаться последующие значения
+  #[inline]
+  pub fn set_byteorder(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+  /// Возвращает количество байт, прочитанных из потока с момента создания десериализатора,
+  /// см. [`Deserializer::position`]
+  ///
+  /// [`Deserializer::position`]: struct.Deserializer.html#method.position
+  #[inline]
+  pub fn position(&self) -> u64 { self.reader.position }
+  /// Возвращает поток, из которого читаются данные
+  #[inline]
+  pub fn into_inner(self) -> R { self.reader.inner }
+  /// Создает десериализатор с порядком байт `BO`, читающий из потока этого десериализатора.
+  /// Позиция продолжает отсчитываться от создания этого десериализатора
+  fn typed<BO: ByteOrder>(&mut self) -> Deserializer<BO, &mut Tracked<R>> {
+    let position = self.reader.position;
+    let mut de = Deserializer::new(&mut self.reader);
+    de.reader.position = position;
+    de.seek = self.seek;
+    de.options = self.options.clone();
+    de
+  }
+}
+
+impl<R: BufRead + Seek> DynDeserializer<R> {
+  /// Создает десериализатор с указанным начальным порядком байт для потока, поддерживающего
+  /// перемещение, который может читать значения, расположенные по смещению, например,
+  /// [`Offset`], см. [`Deserializer::seekable`]
+  ///
+  /// # Параметры
+  /// - `reader`: Поток, из которого будут читаться данные
+  /// - `endian`: Порядок байт, в котором читаются числа, пока он не будет изменен
+  ///
+  /// [`Offset`]: ../wrappers/struct.Offset.html
+  /// [`Deserializer::seekable`]: struct.Deserializer.html#method.seekable
+  pub fn seekable(reader: R, endian: Endian) -> Self {
+    let mut de = Self::new(reader, endian);
+    de.seek = Some(seek_tracked::<R>);
+    de
+  }
+}
+
+/// Функция перемещения по потоку десериализатора [`DynDeserializer`], которую используют
+/// созданные им десериализаторы с конкретным порядком байт
+///
+/// [`DynDeserializer`]: struct.DynDeserializer.html
+type TrackedSeek<R> = for<'a> fn(&mut &'a mut Tracked<R>, SeekFrom) -> io::Result<u64>;
+
+/// Перемещается по потоку десериализатора [`DynDeserializer`], сдвигая его позицию на столько же
+/// байт, чтобы после возврата к месту смещения она снова соответствовала прочитанным данным
+///
+/// [`DynDeserializer`]: struct.DynDeserializer.html
+fn seek_tracked<R: Seek>(reader: &mut &mut Tracked<R>, pos: SeekFrom) -> io::Result<u64> {
+  let current = reader.inner.stream_position()?;
+  let new = reader.inner.seek(pos)?;
+  reader.position = reader.position.wrapping_add(new.wrapping_sub(current));
+  Ok(new)
+}
+
+/// Реализует метод десериализатора с порядком байт, выбираемым во время выполнения, передавая
+/// вызов десериализатору с соответствующим порядком байт
+macro_rules! dispatch {
+  ($dser_method:ident($($arg:ident: $type:ty),*)) => {
+    fn $dser_method<V>(self, $($arg: $type,)* visitor: V) -> Result<V::Value>
+      where V: Visitor<'de>,
+    {
+      match self.endian {
+        Endian::Big    => (&mut self.typed::<BE>()).$dser_method($($arg,)* visitor),
+        Endian::Little => (&mut self.typed::<LE>()).$dser_method($($arg,)* visitor),
+      }
+    }
+  };
+  ($($dser_method:ident),*) => {
+    $(dispatch!($dser_method());)*
+  };
+}
+
+impl<'de, R: BufRead> de::Deserializer<'de> for &mut DynDeserializer<R> {
+  type Error = Error;
+
+  dispatch!(
+    deserialize_any, deserialize_bool,
+    deserialize_i8, deserialize_i16, deserialize_i32, deserialize_i64, deserialize_i128,
+    deserialize_u8, deserialize_u16, deserialize_u32, deserialize_u64, deserialize_u128,
+    deserialize_f32, deserialize_f64, deserialize_char, deserialize_str, deserialize_string,
+    deserialize_bytes, deserialize_byte_buf, deserialize_option, deserialize_unit,
+    deserialize_seq, deserialize_map, deserialize_identifier, deserialize_ignored_any
+  );
+  dispatch!(deserialize_unit_struct(name: &'static str));
+  dispatch!(deserialize_newtype_struct(name: &'static str));
+  dispatch!(deserialize_tuple(len: usize));
+  dispatch!(deserialize_tuple_struct(name: &'static str, len: usize));
+  dispatch!(deserialize_struct(name: &'static str, fields: &'static [&'static str]));
+  dispatch!(deserialize_enum(name: &'static str, variants: &'static [&'static str]));
+
+  /// Возвращает `false`
+  fn is_human_readable(&self) -> bool { false }
+}
+
+/// Десериализатор, читающий данные из потока, не поддерживающего буферизацию (реализующего только
+/// [`Read`]), например, непосредственно из [`TcpStream`]. Такой десериализатор не способен определить
+/// конец потока, поэтому поддерживает только типы фиксированного размера: числа, символы, структуры,
+/// кортежи и массивы.
+///
+/// Десериализация последовательностей без определенной длины (таких, как [вектор]), строк и массивов
+/// байт, которые читаются до конца потока, в том числе вложенных в структуры, завершается ошибкой
+/// [`Error::Unsupported`]. Для их чтения оберните поток в [`BufReader`] и используйте [`Deserializer`].
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde;
+/// # extern crate serde_pod;
+/// # use serde::Deserialize;
+/// # use serde_pod::de::{ReadDeserializer, Unbuffered};
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct Section {
+///   offset: u32,
+///   count: u32,
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// # let stream = std::io::Cursor::new([0x38, 0, 0, 0, 0x0F, 0, 0, 0]);
+/// let mut de: ReadDeserializer<byteorder::LE, _> = ReadDeserializer::new(Unbuffered::new(stream));
+/// let section = Section::deserialize(&mut de)?;
+///
+/// assert_eq!(section, Section { offset: 0x38, count: 15 });
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
+/// [`TcpStream`]: https://doc.rust-lang.org/std/net/struct.TcpStream.html
+/// [вектор]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+/// [`Error::Unsupported`]: ../error/enum.Error.html#variant.Unsupported
+/// [`BufReader`]: https://doc.rust-lang.org/std/io/struct.BufReader.html
+/// [`Deserializer`]: struct.Deserializer.html
+pub type ReadDeserializer<BO, R> = Deserializer<BO, Unbuffered<R>>;
+
+/// Адаптер, позволяющий использовать поток, реализующий только [`Read`], в качестве источника
+/// данных для [`ReadDeserializer`]. Все операции, требующие определения конца потока, завершаются
+/// ошибкой.
+///
+/// [`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
+/// [`ReadDeserializer`]: type.ReadDeserializer.html
+#[derive(Debug)]
+pub struct Unbuffered<R>(R);
+
+impl<R: Read> Unbuffered<R> {
+  /// Оборачивает указанный поток
+  #[inline]
+  pub fn new(reader: R) -> Self { Unbuffered(reader) }
+  /// Возвращает обернутый поток
+  #[inline]
+  pub fn into_inner(self) -> R { self.0 }
+}
+impl<R: Read> Read for Unbuffered<R> {
+  #[inline]
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
+  #[inline]
+  fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> { self.0.read_exact(buf) }
+  /// Всегда возвращает ошибку, т.к. чтение до конца потока не поддерживается
+  fn read_to_end(&mut self, _buf: &mut Vec<u8>) -> io::Result<usize> { Err(NeedsBuffer.into()) }
+  /// Всегда возвращает ошибку, т.к. чтение до конца потока не поддерживается
+  fn read_to_string(&mut self, _buf: &mut String) -> io::Result<usize> { Err(NeedsBuffer.into()) }
+}
+impl<R: Read> BufRead for Unbuffered<R> {
+  /// Всегда возвращает ошибку, т.к. определение конца потока не поддерживается
+  fn fill_buf(&mut self) -> io::Result<&[u8]> { Err(NeedsBuffer.into()) }
+  fn consume(&mut self, _amt: usize) {}
+}
+
+/// Десериализатор блока данных известной длины, созданный методом [`Deserializer::scoped`]
+///
+/// [`Deserializer::scoped`]: struct.Deserializer.html#method.scoped
+pub type ScopedDeserializer<'a, BO, R> = Deserializer<BO, Scoped<'a, R>>;
+
+/// Поток, заканчивающийся на границе блока данных, см. [`Deserializer::scoped`]
+///
+/// [`Deserializer::scoped`]: struct.Deserializer.html#method.scoped
+#[derive(Debug)]
+pub struct Scoped<'a, R: 'a> {
+  /// Часть потока, ограниченная блоком
+  inner: io::Take<&'a mut Tracked<R>>,
+}
+impl<'a, R: Read> Read for Scoped<'a, R> {
+  #[inline]
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.inner.read(buf) }
+}
+impl<'a, R: BufRead> BufRead for Scoped<'a, R> {
+  #[inline]
+  fn fill_buf(&mut self) -> io::Result<&[u8]> { self.inner.fill_buf() }
+  #[inline]
+  fn consume(&mut self, amt: usize) { self.inner.consume(amt) }
+}
+
+impl<'a, BO, R> Deserializer<BO, Scoped<'a, R>>
+  where R: BufRead,
         BO: ByteOrder,
 {
-  let mut deserializer: Deserializer<BO, _> = Deserializer::new(storage);
-  T::deserialize(&mut deserializer)
+  /// Возвращает количество еще не прочитанных байт блока
+  #[inline]
+  pub fn remaining_len(&self) -> u64 { self.reader.inner.inner.limit() }
+  /// Пропускает непрочитанный остаток блока, после чего чтение можно продолжить десериализатором,
+  /// создавшим этот
+  ///
+  /// # Ошибки
+  /// [`Error::Eof`], если поток закончился раньше, чем закончился блок
+  ///
+  /// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+  pub fn finish(mut self) -> Result<()> {
+    io::copy(&mut self.reader, &mut io::sink())?;
+    if self.remaining_len() > 0 {
+      return Err(Error::Eof(None));
+    }
+    Ok(())
+  }
+}
+
+/// Ошибка, возникающая при попытке определить конец потока, не поддерживающего буферизацию
+#[derive(Debug)]
+pub(crate) struct NeedsBuffer;
+impl NeedsBuffer {
+  /// Текст ошибки
+  pub const MESSAGE: &'static str = "reading until the end of stream requires a buffered reader";
+}
+impl fmt::Display for NeedsBuffer {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str(Self::MESSAGE)
+  }
+}
+impl error::Error for NeedsBuffer {}
+impl From<NeedsBuffer> for io::Error {
+  fn from(err: NeedsBuffer) -> Self {
+    io::Error::new(io::ErrorKind::Unsupported, err)
+  }
+}
+
+/// Структура, используемая для чтения ограниченных по количеству последовательностей,
+/// таких, как массивы, структуры и кортежи
+struct Tuple<'a, BO, R> {
+  /// Объект, используемый для чтения и десериализации элементов
+  de: &'a mut Deserializer<BO, R>,
+  /// Количество элементов, которое осталось прочитать
+  count: usize,
+}
+impl<'a, 'de, BO, R> SeqAccess<'de> for Tuple<'a, BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    // Если еще есть элементы для чтения, вытаскиваем их
+    if self.count > 0 {
+      self.count -= 1;
+      return seed.deserialize(&mut *self.de).map(Some);
+    }
+    Ok(None)
+  }
+
+  fn size_hint(&self) -> Option<usize> { Some(self.count) }
+}
+
+/// Последовательность, элементы которой читаются до конца потока. Проверяет ограничения,
+/// заданные методом [`Deserializer::with_limit`]
+///
+/// [`Deserializer::with_limit`]: struct.Deserializer.html#method.with_limit
+struct Unbounded<'a, BO, R> {
+  /// Объект, используемый для чтения и десериализации элементов
+  de: &'a mut Deserializer<BO, R>,
+  /// Количество уже прочитанных элементов
+  count: usize,
+  /// Позиция в потоке, с которой начинается последовательность
+  start: u64,
+}
+impl<'a, 'de, BO, R> SeqAccess<'de> for Unbounded<'a, BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    let limit = self.de.options.limit;
+    if let Some(max) = limit.max_bytes {
+      let len = self.de.position() - self.start;
+      if len > max {
+        return Err(Error::InvalidLength(format!("sequence is longer than {} bytes", max)));
+      }
+    }
+    if self.de.is_eof()? {
+      return Ok(None);
+    }
+    if limit.max_elements == Some(self.count) {
+      return Err(Error::InvalidLength(format!("sequence has more than {} elements", self.count)));
+    }
+    self.count += 1;
+    seed.deserialize(&mut *self.de).map(Some)
+  }
+}
+
+/// Последовательность структур, записанная по столбцам. Размеры полей определяются при чтении
+/// первого элемента и должны быть одинаковыми у всех элементов, поэтому начало каждого столбца
+/// известно после чтения первого элемента. Данные читаются из потока по мере необходимости
+struct Columns<'a, BO, R> {
+  /// Десериализатор, из потока которого читаются столбцы
+  de: &'a mut Deserializer<BO, R>,
+  /// Количество элементов последовательности
+  count: usize,
+  /// Индекс следующего элемента
+  index: usize,
+  /// Прочитанные из потока байты столбцов
+  buf: Vec<u8>,
+  /// Смещение начала в `buf` и размер поля каждого известного столбца
+  layout: Vec<(usize, usize)>,
+}
+impl<'a, BO, R> Columns<'a, BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  /// Дочитывает данные из потока, пока в буфере не окажется `len` байт
+  fn fill(&mut self, len: usize) -> Result<()> {
+    if self.buf.len() < len {
+      let rest = self.de.read_exactly(len - self.buf.len())?;
+      self.buf.extend_from_slice(&rest);
+    }
+    Ok(())
+  }
+  /// Читает поле с индексом `field` текущего элемента
+  fn next_field<'de, T>(&mut self, field: usize, seed: T) -> Result<T::Value>
+    where T: DeserializeSeed<'de>,
+  {
+    if let Some(&(start, size)) = self.layout.get(field) {
+      let pos = start + size * self.index;
+      self.fill(pos + size)?;
+      let start = self.de.position() - self.buf.len() as u64;
+      let mut de = self.de.nested(&self.buf[pos..pos + size], start + pos as u64);
+      de.options.columnar = true;
+      let value = seed.deserialize(&mut de)?;
+      if !de.reader.inner.is_empty() {
+        return Err(Error::InvalidLength("columnar fields must have the same size in all elements".into()));
+      }
+      return Ok(value);
+    }
+    if self.index > 0 || field > self.layout.len() {
+      return Err(Error::InvalidLength("columnar elements must have the same fields".into()));
+    }
+    // Первое поле нового столбца: читаем его прямо из потока, запоминая прочитанные байты
+    let start = self.layout.last().map_or(0, |&(start, size)| start + size * self.count);
+    self.fill(start)?;
+    let options = self.de.options.clone();
+    let mut de = Deserializer::<BO, _>::new(Recorder { inner: &mut self.de.reader, buf: &mut self.buf });
+    de.options = options;
+    de.options.columnar = true;
+    let value = seed.deserialize(&mut de)?;
+    self.layout.push((start, self.buf.len() - start));
+    Ok(value)
+  }
+}
+impl<'a, 'b, 'de, BO, R> SeqAccess<'de> for &'b mut Columns<'a, BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    if self.index == self.count {
+      return Ok(None);
+    }
+    let value = seed.deserialize(Row { columns: &mut **self, field: 0 })?;
+    self.index += 1;
+    Ok(Some(value))
+  }
+
+  fn size_hint(&self) -> Option<usize> { Some(self.count - self.index) }
+}
+
+/// Элемент последовательности, записанной по столбцам. Десериализуется, как структура или кортеж,
+/// поля которого читаются из соответствующих столбцов
+struct Row<'a, 'b, BO, R> {
+  /// Последовательность, которой принадлежит элемент
+  columns: &'b mut Columns<'a, BO, R>,
+  /// Индекс следующего поля
+  field: usize,
+}
+impl<'a, 'b, 'de, BO, R> de::Deserializer<'de> for Row<'a, 'b, BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  type Error = Error;
+
+  fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    Err(Error::Unsupported(COLUMNAR_ERROR))
+  }
+  fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_seq(self)
+  }
+  fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_seq(self)
+  }
+  fn deserialize_struct<V>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_seq(self)
+  }
+  fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_newtype_struct(self)
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+    bytes byte_buf option unit unit_struct seq map enum identifier ignored_any
+  }
+}
+impl<'a, 'b, 'de, BO, R> SeqAccess<'de> for Row<'a, 'b, BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    let value = self.columns.next_field(self.field, seed)?;
+    self.field += 1;
+    Ok(Some(value))
+  }
+}
+
+/// Поток, запоминающий все прочитанные из него байты
+struct Recorder<'a, R> {
+  /// Нижележащий поток
+  inner: &'a mut R,
+  /// Буфер, в который дописываются прочитанные байты
+  buf: &'a mut Vec<u8>,
+}
+impl<'a, R: Read> Read for Recorder<'a, R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let len = self.inner.read(buf)?;
+    self.buf.extend_from_slice(&buf[..len]);
+    Ok(len)
+  }
+}
+impl<'a, R: BufRead> BufRead for Recorder<'a, R> {
+  #[inline]
+  fn fill_buf(&mut self) -> io::Result<&[u8]> { self.inner.fill_buf() }
+  fn consume(&mut self, amt: usize) {
+    // Поглощаемые байты уже находятся в буфере, поэтому его получение не приводит к чтению
+    if amt > 0 {
+      if let Ok(buf) = self.inner.fill_buf() {
+        self.buf.extend_from_slice(&buf[..amt.min(buf.len())]);
+      }
+    }
+    self.inner.consume(amt)
+  }
+}
+
+/// Последовательность, первым элементом которой является количество, записанное в конце потока,
+/// а остальными -- элементы, записанные перед ним
+struct Trailer<'a, BO> {
+  /// Десериализатор количества элементов, если оно еще не прочитано
+  count: Option<Deserializer<BO, &'a [u8]>>,
+  /// Десериализатор элементов
+  data: Deserializer<BO, &'a [u8]>,
+}
+impl<'a, 'de, BO> SeqAccess<'de> for Trailer<'a, BO>
+  where BO: ByteOrder,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    if let Some(mut count) = self.count.take() {
+      return seed.deserialize(&mut count).map(Some);
+    }
+    if self.data.reader.inner.is_empty() {
+      return Ok(None);
+    }
+    seed.deserialize(&mut self.data).map(Some)
+  }
+}
+
+/// Поток, подсчитывающий количество прочитанных из него байт
+#[derive(Debug)]
+struct Tracked<R> {
+  /// Поток, из которого читаются данные
+  inner: R,
+  /// Количество прочитанных байт
+  position: u64,
+}
+impl<R: Read> Read for Tracked<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let len = self.inner.read(buf)?;
+    self.position += len as u64;
+    Ok(len)
+  }
+  // Передаются потоку, т.к. он может не поддерживать чтение до конца, см. `Unbuffered`
+  fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let len = self.inner.read_to_end(buf)?;
+    self.position += len as u64;
+    Ok(len)
+  }
+  fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+    let len = self.inner.read_to_string(buf)?;
+    self.position += len as u64;
+    Ok(len)
+  }
+}
+impl<R: BufRead> BufRead for Tracked<R> {
+  #[inline]
+  fn fill_buf(&mut self) -> io::Result<&[u8]> { self.inner.fill_buf() }
+  fn consume(&mut self, amt: usize) {
+    self.inner.consume(amt);
+    self.position += amt as u64;
+  }
+}
+
+/// Последовательность элементов, порядок байт которых меняется от элемента к элементу
+struct Alternating<'a, R> {
+  /// Поток, из которого читаются элементы
+  reader: &'a mut Tracked<R>,
+  /// Настройки десериализатора, которые получает каждый элемент
+  options: &'a Options,
+  /// Расписание порядков байт элементов
+  orders: &'a [Endian],
+  /// Индекс следующего элемента
+  index: usize,
+  /// Количество элементов, которое осталось прочитать
+  count: usize,
+}
+impl<'a, 'de, R> SeqAccess<'de> for Alternating<'a, R>
+  where R: BufRead,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    if self.count == 0 {
+      return Ok(None);
+    }
+    let order = self.orders[self.index % self.orders.len()];
+    self.count -= 1;
+    self.index += 1;
+    let position = self.reader.position;
+    match order {
+      Endian::Big => {
+        let mut de = Deserializer::<BE, _>::new(&mut *self.reader);
+        de.reader.position = position;
+        de.options = self.options.clone();
+        seed.deserialize(&mut de).map(Some)
+      },
+      Endian::Little => {
+        let mut de = Deserializer::<LE, _>::new(&mut *self.reader);
+        de.reader.position = position;
+        de.options = self.options.clone();
+        seed.deserialize(&mut de).map(Some)
+      },
+    }
+  }
+
+  fn size_hint(&self) -> Option<usize> { Some(self.count) }
+}
+
+/// Последовательность элементов, перед каждым из которых записан его размер в байтах
+struct SizePrefixedElements<'a, BO, R, L> {
+  /// Десериализатор, из которого читаются элементы
+  de: &'a mut Deserializer<BO, R>,
+  /// Количество еще не прочитанных элементов или `None`, если они читаются до конца потока
+  count: Option<usize>,
+  /// Тип размера элемента
+  _length: PhantomData<L>,
+}
+
+impl<'a, 'de, BO, R, L> SeqAccess<'de> for SizePrefixedElements<'a, BO, R, L>
+  where R: BufRead,
+        BO: ByteOrder,
+        L: Length,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    match self.count {
+      Some(0) => return Ok(None),
+      Some(ref mut count) => *count -= 1,
+      None => if self.de.is_eof()? { return Ok(None); },
+    }
+    let len = L::deserialize(&mut *self.de)?;
+    self.de.bounded_seed(len.into(), seed).map(Some)
+  }
+
+  fn size_hint(&self) -> Option<usize> { self.count }
+}
+
+impl<'de, BO, R> SeqAccess<'de> for &mut Deserializer<BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    // Если данные закончились, прекращаем итерации
+    if self.reader.fill_buf()?.is_empty() {
+      return Ok(None);
+    }
+    seed.deserialize(&mut **self).map(Some)
+  }
+}
+
+/// Дописывает все данные из потока в буфер. Если задано ограничение на количество байт,
+/// читается не больше, чем на один байт сверх него, чтобы обнаружить превышение
+fn read_to_end<R: Read>(mut reader: R, limit: &Limit, buf: &mut Vec<u8>) -> Result<()> {
+  match limit.max_bytes {
+    Some(max) => {
+      let len = reader.take(max.saturating_add(1)).read_to_end(buf)?;
+      if len as u64 > max {
+        return Err(Error::InvalidLength(format!("data to the end of stream is longer than {} bytes", max)));
+      }
+    }
+    None => { reader.read_to_end(buf)?; }
+  }
+  Ok(())
+}
+
+/// Десериализует значение заданного типа из массива байт.
+///
+/// # Параметры
+/// - `storage`: Массив байт, содержащий сериализованное значение
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором читать данные из потока
+/// - `T`: Десериализуемый тип
+///
+/// # Возвращаемое значение
+/// Прочитанное значение
+///
+/// # Ошибки
+/// Возможны 2 причины, по которым данный метод вернет ошибку:
+/// - Реализация `Deserialize` для типа `T` вернет ошибку
+/// - [`Error::Encoding`]: Десериализуемый тип содержит [строки], и в десериализуемых
+///   данных они не содержат корректных UTF-8 последовательностей
+///
+/// Ошибки чтения ([`Error::Eof`], если данные закончились раньше времени) и
+/// [`Error::Encoding`] возвращаются обернутыми в [`Error::At`] со смещением, на котором
+/// они произошли.
+///
+/// [`Error::Encoding`]: ../error/enum.Error.html#variant.Encoding
+/// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+/// [`Error::At`]: ../error/enum.Error.html#variant.At
+/// [строки]: https://doc.rust-lang.org/std/string/struct.String.html
+pub fn from_bytes<'a, BO, T>(storage: &'a [u8]) -> Result<T>
+  where T: Deserialize<'a>,
+        BO: ByteOrder,
+{
+  let mut deserializer: Deserializer<BO, _> = Deserializer::new(storage);
+  T::deserialize(&mut deserializer).map_err(|e| deserializer.locate(e))
+}
+
+/// Десериализует значение заданного типа из массива байт и проверяет, что оно заняло ровно
+/// `expected_len` байт. Позволяет обнаружить несоответствие ширины полей типа и формата,
+/// например, поле `u16` там, где формат хранит `u32`.
+///
+/// # Параметры
+/// - `storage`: Массив байт, содержащий сериализованное значение
+/// - `expected_len`: Ожидаемое количество байт, занимаемых значением
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором читать данные из потока
+/// - `T`: Десериализуемый тип
+///
+/// # Возвращаемое значение
+/// Прочитанное значение
+///
+/// # Ошибки
+/// Помимо ошибок [`from_bytes`], возвращает ошибку [`Error::InvalidLength`], если значение
+/// заняло больше или меньше `expected_len` байт
+///
+/// [`from_bytes`]: fn.from_bytes.html
+/// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+pub fn from_bytes_sized<'a, BO, T>(storage: &'a [u8], expected_len: usize) -> Result<T>
+  where T: Deserialize<'a>,
+        BO: ByteOrder,
+{
+  let mut deserializer: Deserializer<BO, _> = Deserializer::new(storage);
+  let value = T::deserialize(&mut deserializer).map_err(|e| deserializer.locate(e))?;
+  let consumed = storage.len() - deserializer.reader.inner.len();
+  if consumed != expected_len {
+    return Err(Error::InvalidLength(format!(
+      "value takes {} bytes, but {} bytes expected", consumed, expected_len
+    )));
+  }
+  Ok(value)
+}
+
+/// Десериализует значение заданного типа из массива байт и проверяет, что значение заняло
+/// массив целиком. Позволяет сразу обнаружить ошибки в заголовках, например, количество
+/// элементов [`LengthPrefixed`], не соответствующее размеру данных.
+///
+/// # Параметры
+/// - `storage`: Массив байт, содержащий сериализованное значение
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором читать данные из потока
+/// - `T`: Десериализуемый тип
+///
+/// # Возвращаемое значение
+/// Прочитанное значение
+///
+/// # Ошибки
+/// Помимо ошибок [`from_bytes`], возвращает ошибку [`Error::InvalidLength`], если массив
+/// закончился раньше, чем было прочитано значение, или после значения в нем остались байты
+///
+/// [`LengthPrefixed`]: ../framing/struct.LengthPrefixed.html
+/// [`from_bytes`]: fn.from_bytes.html
+/// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+pub fn from_bytes_exact<'a, BO, T>(storage: &'a [u8]) -> Result<T>
+  where T: Deserialize<'a>,
+        BO: ByteOrder,
+{
+  match from_bytes_rest::<BO, T>(storage) {
+    Ok((value, 0)) => Ok(value),
+    Ok((_, len)) => Err(Error::InvalidLength(format!("{} trailing bytes after the value", len))),
+    Err(Error::At { ref source, .. }) if source.kind() == ErrorKind::Eof => {
+      Err(Error::InvalidLength(format!("{} bytes are not enough for the value", storage.len())))
+    }
+    Err(err) => Err(err),
+  }
+}
+
+/// Десериализует значение заданного типа из массива байт и проверяет, что после значения в
+/// массиве не осталось байт. В отличие от [`from_bytes`], который молча игнорирует лишние байты,
+/// позволяет обнаружить расхождение между описанием структуры и форматом файла.
+///
+/// # Параметры
+/// - `storage`: Массив байт, содержащий сериализованное значение
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором читать данные из потока
+/// - `T`: Десериализуемый тип
+///
+/// # Возвращаемое значение
+/// Прочитанное значение
+///
+/// # Ошибки
+/// Помимо ошибок [`from_bytes`], возвращает ошибку [`Error::TrailingBytes`] с количеством
+/// непрочитанных байт, если после значения в массиве остались байты
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes_strict, Error};
+/// assert_eq!(from_bytes_strict::<LE, u16>(&[0x01, 0x02]).unwrap(), 0x0201);
+/// match from_bytes_strict::<LE, u16>(&[0x01, 0x02, 0x03]) {
+///   Err(Error::TrailingBytes(1)) => {}
+///   result => panic!("unexpected result {:?}", result),
+/// }
+/// ```
+///
+/// [`from_bytes`]: fn.from_bytes.html
+/// [`Error::TrailingBytes`]: ../error/enum.Error.html#variant.TrailingBytes
+pub fn from_bytes_strict<'a, BO, T>(storage: &'a [u8]) -> Result<T>
+  where T: Deserialize<'a>,
+        BO: ByteOrder,
+{
+  match from_bytes_rest::<BO, T>(storage)? {
+    (value, 0) => Ok(value),
+    (_, len) => Err(Error::TrailingBytes(len)),
+  }
+}
+
+/// Десериализует значение заданного типа из массива байт и возвращает его вместе с количеством
+/// байт, оставшихся в массиве после значения
+fn from_bytes_rest<'a, BO, T>(storage: &'a [u8]) -> Result<(T, usize)>
+  where T: Deserialize<'a>,
+        BO: ByteOrder,
+{
+  let mut deserializer: Deserializer<BO, _> = Deserializer::new(storage);
+  let value = T::deserialize(&mut deserializer).map_err(|e| deserializer.locate(e))?;
+  Ok((value, deserializer.reader.inner.len()))
+}
+
+/// Десериализует значение заданного типа из начала массива байт и возвращает его вместе с
+/// количеством байт, занятых значением. Позволяет последовательно читать записи, следующие
+/// в массиве друг за другом, сдвигая начало массива на прочитанное количество байт.
+///
+/// # Параметры
+/// - `storage`: Массив байт, начинающийся с сериализованного значения
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором читать данные из потока
+/// - `T`: Десериализуемый тип
+///
+/// # Возвращаемое значение
+/// Прочитанное значение и количество байт, занятых им в массиве
+///
+/// # Ошибки
+/// Те же, что и у [`from_bytes`]
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::from_bytes_with_len;
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [0x00, 0x01,   0x02];
+/// let (first, len) = from_bytes_with_len::<BE, u16>(&data)?;
+/// let (second, _) = from_bytes_with_len::<BE, u8>(&data[len..])?;
+/// assert_eq!((first, len, second), (1, 2, 2));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`from_bytes`]: fn.from_bytes.html
+pub fn from_bytes_with_len<'a, BO, T>(storage: &'a [u8]) -> Result<(T, usize)>
+  where T: Deserialize<'a>,
+        BO: ByteOrder,
+{
+  let mut deserializer: Deserializer<BO, _> = Deserializer::new(storage);
+  let value = T::deserialize(&mut deserializer).map_err(|e| deserializer.locate(e))?;
+  Ok((value, storage.len() - deserializer.reader.inner.len()))
+}
+
+/// Десериализует значение заданного типа из потока, например, из [`BufReader`] над файлом,
+/// не считывая поток в память целиком. Результат не отличается от десериализации [`from_bytes`]
+/// из массива байт с тем же содержимым: в частности, последовательности читаются до конца потока.
+///
+/// # Параметры
+/// - `reader`: Поток, содержащий сериализованное значение
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором читать данные из потока
+/// - `R`: Тип потока
+/// - `T`: Десериализуемый тип
+///
+/// # Возвращаемое значение
+/// Прочитанное значение
+///
+/// # Ошибки
+/// Помимо ошибок [`from_bytes`], возвращает ошибку [`Error::Io`], если чтение из потока
+/// завершилось неудачей
+///
+/// [`BufReader`]: https://doc.rust-lang.org/std/io/struct.BufReader.html
+/// [`from_bytes`]: fn.from_bytes.html
+/// [`Error::Io`]: ../error/enum.Error.html#variant.Io
+pub fn from_reader<BO, R, T>(reader: R) -> Result<T>
+  where R: BufRead,
+        BO: ByteOrder,
+        T: DeserializeOwned,
+{
+  let mut deserializer: Deserializer<BO, _> = Deserializer::new(reader);
+  T::deserialize(&mut deserializer).map_err(|e| deserializer.locate(e))
+}
+
+/// Десериализует значение заданного типа из файла, отображенного в память. Данные не копируются
+/// из файла целиком, а читаются непосредственно из отображения по мере десериализации.
+///
+/// Доступно только при включенной функциональности `memmap2`.
+///
+/// # Параметры
+/// - `path`: Путь к файлу, содержащему сериализованное значение
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором читать данные из потока
+/// - `T`: Десериализуемый тип
+///
+/// # Возвращаемое значение
+/// Прочитанное значение
+///
+/// # Ошибки
+/// Возможны 3 причины, по которым данный метод вернет ошибку:
+/// - [`Error::Io`]: Файл не удалось открыть или отобразить в память
+/// - Реализация `Deserialize` для типа `T` вернет ошибку
+/// - [`Error::Encoding`]: Десериализуемый тип содержит [строки], и в десериализуемых
+///   данных они не содержат корректных UTF-8 последовательностей
+///
+/// # Безопасность
+/// Отображение файла в память корректно только до тех пор, пока файл не изменяется другими
+/// процессами. Изменение файла во время десериализации приводит к неопределенному поведению,
+/// поэтому вызывающий код должен гарантировать, что файл не будет изменен во время вызова.
+///
+/// [`Error::Io`]: ../error/enum.Error.html#variant.Io
+/// [`Error::Encoding`]: ../error/enum.Error.html#variant.Encoding
+/// [строки]: https://doc.rust-lang.org/std/string/struct.String.html
+#[cfg(feature = "memmap2")]
+pub fn from_mmap<BO, T>(path: impl AsRef<Path>) -> Result<T>
+  where T: DeserializeOwned,
+        BO: ByteOrder,
+{
+  let file = File::open(path)?;
+  // Безопасность: см. раздел "Безопасность" в документации функции
+  let mmap = unsafe { Mmap::map(&file)? };
+  from_bytes::<BO, T>(&mmap)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod integers {
+  use super::from_bytes;
+  use byteorder::{BE, LE};
+
+  #[test]
+  fn test_u8() {
+    let test: u8 = 0x12;
+    assert_eq!(from_bytes::<BE, u8>(&[0x12]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, u8>(&[0x12]).unwrap(), test);
+  }
+  #[test]
+  fn test_i8() {
+    let test: i8 = 0x12;
+    assert_eq!(from_bytes::<BE, i8>(&[0x12]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, i8>(&[0x12]).unwrap(), test);
+  }
+
+  #[test]
+  fn test_u16() {
+    let test: u16 = 0x1234;
+    assert_eq!(from_bytes::<BE, u16>(&[0x12, 0x34]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, u16>(&[0x34, 0x12]).unwrap(), test);
+  }
+  #[test]
+  fn test_i16() {
+    let test: i16 = 0x1234;
+    assert_eq!(from_bytes::<BE, i16>(&[0x12, 0x34]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, i16>(&[0x34, 0x12]).unwrap(), test);
+  }
+
+  #[test]
+  fn test_u32() {
+    let test: u32 = 0x12345678;
+    assert_eq!(from_bytes::<BE, u32>(&[0x12, 0x34, 0x56, 0x78]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, u32>(&[0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+  #[test]
+  fn test_i32() {
+    let test: i32 = 0x12345678;
+    assert_eq!(from_bytes::<BE, i32>(&[0x12, 0x34, 0x56, 0x78]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, i32>(&[0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+
+  #[test]
+  fn test_u64() {
+    let test: u64 = 0x12345678_90ABCDEF;
+    assert_eq!(from_bytes::<BE, u64>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, u64>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+  #[test]
+  fn test_i64() {
+    let test: i64 = 0x12345678_90ABCDEF;
+    assert_eq!(from_bytes::<BE, i64>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, i64>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+
+  #[test]
+  fn test_u128() {
+    let test: u128 = 0x12345678_90ABCDEF_12345678_90ABCDEF;
+    assert_eq!(from_bytes::<BE, u128>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF, 0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, u128>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12, 0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+  #[test]
+  fn test_i128() {
+    let test: i128 = 0x12345678_90ABCDEF_12345678_90ABCDEF;
+    assert_eq!(from_bytes::<BE, i128>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF, 0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, i128>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12, 0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+}
+#[cfg(test)]
+mod floats {
+  use super::from_bytes;
+  use byteorder::{ByteOrder, BE, LE};
+
+  macro_rules! float_test {
+    ($name:ident, $BO:ident :: $write:ident, $type:ty) => (
+      quickcheck! {
+        fn $name(test: $type) -> bool {
+          let mut buf = [0; std::mem::size_of::<$type>()];
+          $BO::$write(&mut buf, test);
+          from_bytes::<$BO, $type>(&buf).unwrap() == test
+        }
+      }
+    );
+  }
+
+  float_test!(test_f32_be, BE::write_f32, f32);
+  float_test!(test_f32_le, LE::write_f32, f32);
+
+  float_test!(test_f64_be, BE::write_f64, f64);
+  float_test!(test_f64_le, LE::write_f64, f64);
+}
+#[cfg(test)]
+mod unbuffered {
+  use std::io::{self, Read};
+  use byteorder::{BE, LE};
+  use serde::Deserialize;
+  use error::Error;
+  use super::{ReadDeserializer, Unbuffered};
+
+  /// Поток, реализующий только `Read`
+  struct OnlyRead<'a>(&'a [u8]);
+  impl<'a> Read for OnlyRead<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> { self.0.read(buf) }
+  }
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Test {
+    int1: u32,
+    int2: u16,
+    array: [u8; 2],
+  }
+
+  #[test]
+  fn test_struct() {
+    let test = Test { int1: 0x12345678, int2: 0xABCD, array: [0xEF, 0x01] };
+    let mut de: ReadDeserializer<BE, _> = ReadDeserializer::new(Unbuffered::new(OnlyRead(&[0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD,   0xEF, 0x01])));
+    assert_eq!(Test::deserialize(&mut de).unwrap(), test);
+    let mut de: ReadDeserializer<LE, _> = ReadDeserializer::new(Unbuffered::new(OnlyRead(&[0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB,   0xEF, 0x01])));
+    assert_eq!(Test::deserialize(&mut de).unwrap(), test);
+  }
+
+  /// Чтение до конца потока не поддерживается
+  #[test]
+  fn test_seq() {
+    let mut de: ReadDeserializer<BE, _> = ReadDeserializer::new(Unbuffered::new(OnlyRead(&[0x12, 0x34])));
+    match Vec::<u16>::deserialize(&mut de) {
+      Err(Error::Unsupported(_)) => {},
+      other => panic!("expected Error::Unsupported, got {:?}", other),
+    }
+  }
+  #[test]
+  fn test_string() {
+    let mut de: ReadDeserializer<BE, _> = ReadDeserializer::new(Unbuffered::new(OnlyRead(b"test")));
+    match String::deserialize(&mut de) {
+      Err(Error::Unsupported(_)) => {},
+      other => panic!("expected Error::Unsupported, got {:?}", other),
+    }
+  }
+}
+#[cfg(test)]
+mod complex {
+  use super::{from_bytes, Deserializer};
+  use byteorder::{BE, LE};
+  use serde::Deserialize;
+  use ser::to_vec;
+
+  quickcheck! {
+    /// Любое ненулевое значение означает `true`
+    fn test_bool(byte: u8) -> bool {
+      from_bytes::<BE, bool>(&[byte]).unwrap() == (byte != 0)
+    }
+  }
+  #[test]
+  fn test_strict_bool() {
+    let read = |byte: u8| bool::deserialize(&mut Deserializer::<BE, _>::new(&[byte][..]).with_strict_bool());
+    assert!(!read(0).unwrap());
+    assert!(read(1).unwrap());
+    assert!(read(2).is_err());
+    assert!(read(0xFF).is_err());
+  }
+  /// Структура с флагами без потерь проходит через сериализацию и десериализацию
+  #[test]
+  fn test_bool_round_trip() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Flags {
+      id: u16,
+      visible: bool,
+      locked: bool,
+    }
+
+    let test = Flags { id: 0x1234, visible: true, locked: false };
+    let data = to_vec::<LE, _>(&test).unwrap();
+    assert_eq!(data, [0x34, 0x12,   0x01,   0x00]);
+    assert_eq!(from_bytes::<LE, Flags>(&data).unwrap(), test);
+  }
+  /// При десериализации ничего не читает из потока
+  #[test]
+  fn test_unit() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test;
+
+    let test = Test;
+    assert_eq!(from_bytes::<BE, Test>(&[]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Test>(&[]).unwrap(), test);
+  }
+
+  /// При десериализации читает из потока нижележащий тип
+  #[test]
+  fn test_newtype() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test(u32);
+
+    let test = Test(0x12345678);
+    assert_eq!(from_bytes::<BE, Test>(&[0x12, 0x34, 0x56, 0x78]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Test>(&[0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+
+  /// Поля в кортеже записываются подряд, в порядке следования, без пробелов и дополнительных данных.
+  /// Порядок байт переворачивается для каждого поля независимо.
+  #[test]
+  fn test_tuple() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test(u32, u16);
+
+    let test = Test(0x12345678, 0xABCD);
+    assert_eq!(from_bytes::<BE, Test>(&[0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Test>(&[0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB]).unwrap(), test);
+  }
+
+  /// Поля в структуре записываются подряд, в порядке следования, без пробелов и дополнительных данных.
+  /// Порядок байт переворачивается для каждого поля независимо.
+  #[test]
+  fn test_struct() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+      int1: u32,
+      int2: u16,
+    }
+
+    let test = Test { int1: 0x12345678, int2: 0xABCD };
+    assert_eq!(from_bytes::<BE, Test>(&[0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Test>(&[0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB]).unwrap(), test);
+  }
+
+  /// Десериализатор не в состоянии различить `Some` и `None` -- для десериализации нужно
+  /// вручную прочитать (или определить) маркер, и прочитать значение, если маркер говорит,
+  /// что оно есть
+  #[test]
+  #[should_panic]
+  fn test_option_be() {
+    from_bytes::<BE, Option<u16>>(&[0x12, 0x34]).unwrap();
+  }
+  #[test]
+  #[should_panic]
+  fn test_option_le() {
+    from_bytes::<LE, Option<u16>>(&[0x12, 0x34]).unwrap();
+  }
+
+  /// Записывает все элементы последовательности подряд, без разделителей, заголовочной или
+  /// конечной информации, либо какой-либо информации о количестве элементов.
+  /// Порядок байт переворачивается для каждого поля независимо.
+  #[test]
+  fn test_seq() {
+    let test = [0x12, 0x34,   0x56, 0x78,   0xAB, 0xCD];
+    assert_eq!(from_bytes::<BE, Vec<u16>>(&test).unwrap(), vec![0x1234, 0x5678, 0xABCD]);
+    assert_eq!(from_bytes::<LE, Vec<u16>>(&test).unwrap(), vec![0x3412, 0x7856, 0xCDAB]);
+  }
+
+  /// Возврат срезов строки не поддерживается, т.к. десериализатор всегда выдает новую строку
+  #[test]
+  #[should_panic]
+  fn test_str_be() {
+    from_bytes::<BE, &str>("test".as_bytes()).unwrap();
+  }
+  #[test]
+  #[should_panic]
+  fn test_str_le() {
+    from_bytes::<LE, &str>("test".as_bytes()).unwrap();
+  }
+  #[test]
+  fn test_string() {
+    let test = "тест";
+    assert_eq!(from_bytes::<BE, String>(test.as_bytes()).unwrap(), test);
+    assert_eq!(from_bytes::<LE, String>(test.as_bytes()).unwrap(), test);
+  }
+
+  #[test]
+  fn test_array_empty() {
+    assert_eq!(from_bytes::<BE, [u16; 0]>(&[]).unwrap(), []);
+    assert_eq!(from_bytes::<LE, [u16; 0]>(&[]).unwrap(), []);
+  }
+  #[test]
+  fn test_array() {
+    let test = [0x12, 0x34, 0x56, 0x78, 0xAB, 0xCD];
+    assert_eq!(from_bytes::<BE, [u16; 3]>(&test).unwrap(), [0x1234, 0x5678, 0xABCD]);
+    assert_eq!(from_bytes::<LE, [u16; 3]>(&test).unwrap(), [0x3412, 0x7856, 0xCDAB]);
+  }
+  #[test]
+  #[should_panic]
+  fn test_array_no_data_be() {
+    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
+    from_bytes::<BE, [u16; 3]>(&test).unwrap();
+  }
+  #[test]
+  #[should_panic]
+  fn test_array_no_data_le() {
+    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
+    from_bytes::<LE, [u16; 3]>(&test).unwrap();
+  }
+  #[test]
+  fn test_vec() {
+    let test = [0x12, 0x34, 0x56, 0x78, 0xAB, 0xCD];
+    assert_eq!(from_bytes::<BE, Vec<u16>>(&test).unwrap(), vec![0x1234, 0x5678, 0xABCD]);
+    assert_eq!(from_bytes::<LE, Vec<u16>>(&test).unwrap(), vec![0x3412, 0x7856, 0xCDAB]);
+  }
+  #[test]
+  #[should_panic]
+  fn test_vec_no_data_be() {
+    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
+    from_bytes::<BE, Vec<u16>>(&test).unwrap();
+  }
+  #[test]
+  #[should_panic]
+  fn test_vec_no_data_le() {
+    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
+    from_bytes::<LE, Vec<u16>>(&test).unwrap();
+  }
+
+  /// Пары ключ-значение читаются подряд до конца потока с сохранением порядка
+  #[test]
+  fn test_vec_of_pairs() {
+    let test = [0x12, 0x34,   0x56, 0x78, 0x9A, 0xBC,   0x00, 0x01,   0x00, 0x00, 0x00, 0x02];
+    assert_eq!(from_bytes::<BE, Vec<(u16, u32)>>(&test).unwrap(), vec![(0x1234, 0x56789ABC), (0x0001, 0x00000002)]);
+    assert_eq!(from_bytes::<LE, Vec<(u16, u32)>>(&test).unwrap(), vec![(0x3412, 0xBC9A7856), (0x0100, 0x02000000)]);
+  }
+  #[test]
+  fn test_vec_of_pairs_empty() {
+    assert_eq!(from_bytes::<BE, Vec<(u16, u32)>>(&[]).unwrap(), vec![]);
+    assert_eq!(from_bytes::<LE, Vec<(u16, u32)>>(&[]).unwrap(), vec![]);
+  }
+  #[test]
+  #[should_panic]
+  fn test_vec_of_pairs_no_data_be() {
+    let test = [0x12, 0x34,   0x56, 0x78, 0x9A, 0xBC,   0x00, 0x01];
+    from_bytes::<BE, Vec<(u16, u32)>>(&test).unwrap();
+  }
+  #[test]
+  #[should_panic]
+  fn test_vec_of_pairs_no_data_le() {
+    let test = [0x12, 0x34,   0x56, 0x78, 0x9A, 0xBC,   0x00, 0x01];
+    from_bytes::<LE, Vec<(u16, u32)>>(&test).unwrap();
+  }
+}
+
+#[cfg(test)]
+mod header_then {
+  use byteorder::BE;
+  use error::Error;
+  use serde::Deserialize;
+  use super::Deserializer;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Header {
+    kind: u16,
+    len: u16,
+  }
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Point {
+    x: i16,
+    y: i16,
+  }
+  #[derive(Debug, PartialEq)]
+  enum Body {
+    Point(Point),
+    Bytes(Vec<u8>),
+  }
+
+  fn read(data: &[u8]) -> Result<(Header, Body), Error> {
+    let mut de = Deserializer::<BE, _>::new(data);
+    de.read_header_then(|header: &Header, de| match header.kind {
+      1 => Point::deserialize(de).map(Body::Point),
+      2 => de.bounded(header.len.into()).map(Body::Bytes),
+      kind => Err(Error::Unknown(format!("unknown body kind {}", kind))),
+    })
+  }
+
+  /// Вид заголовка определяет тип тела
+  #[test]
+  fn test_dispatch() {
+    let (header, body) = read(&[0x00, 0x01, 0x00, 0x04,   0xFF, 0xFF, 0x00, 0x02]).unwrap();
+    assert_eq!(header, Header { kind: 1, len: 4 });
+    assert_eq!(body, Body::Point(Point { x: -1, y: 2 }));
+
+    let (header, body) = read(&[0x00, 0x02, 0x00, 0x03,   0xAA, 0xBB, 0xCC,   0xDD]).unwrap();
+    assert_eq!(header, Header { kind: 2, len: 3 });
+    assert_eq!(body, Body::Bytes(vec![0xAA, 0xBB, 0xCC]));
+  }
+  #[test]
+  fn test_unknown_kind() {
+    assert!(read(&[0x00, 0x03, 0x00, 0x00]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod for_each {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use super::Deserializer;
+
+  #[test]
+  fn test_sum() {
+    let data: &[u8] = &[0x00, 0x00, 0x00, 0x01,   0x00, 0x00, 0x01, 0x00,   0x00, 0x01, 0x00, 0x00];
+    let mut sum = 0;
+    let mut de: Deserializer<BE, _> = Deserializer::new(data);
+    de.for_each_element(|x: u32| { sum += x; Ok(()) }).unwrap();
+    assert_eq!(sum, 0x0001_0101);
+  }
+  #[test]
+  fn test_empty() {
+    let mut de: Deserializer<LE, _> = Deserializer::new(&[][..]);
+    de.for_each_element(|_: u32| panic!("no elements expected")).unwrap();
+  }
+  /// Ошибка, возвращенная функцией, прерывает чтение
+  #[test]
+  fn test_abort() {
+    let data: &[u8] = &[0x01, 0x02, 0x03, 0x04];
+    let mut seen = Vec::new();
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    let result = de.for_each_element(|x: u8| {
+      seen.push(x);
+      if x == 2 { Err(Error::Unknown("stop".into())) } else { Ok(()) }
+    });
+    assert!(result.is_err());
+    assert_eq!(seen, [1, 2]);
+  }
+  /// Неполный последний элемент является ошибкой
+  #[test]
+  fn test_truncated() {
+    let mut de: Deserializer<LE, _> = Deserializer::new(&[0x01, 0x00, 0x02][..]);
+    assert!(de.for_each_element(|_: u16| Ok(())).is_err());
+  }
+}
+
+#[cfg(test)]
+mod bounded {
+  use byteorder::{BE, LE};
+  use serde::Deserialize;
+  use super::Deserializer;
+
+  /// Длина из заголовка ограничивает чтение строки, которая иначе читалась бы до конца потока
+  #[test]
+  fn test_header_length() {
+    let data: &[u8] = &[0x00, 0x03,   0x61, 0x62, 0x63,   0xFF];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data);
+    let len = u16::deserialize(&mut de).unwrap();
+    assert_eq!(de.bounded::<String>(len.into()).unwrap(), "abc");
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0xFF);
+  }
+  /// Непрочитанный остаток пропускается
+  #[test]
+  fn test_skip_rest() {
+    let data: &[u8] = &[0x34, 0x12, 0x00, 0x00,   0xFF];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert_eq!(de.bounded::<u16>(4).unwrap(), 0x1234);
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0xFF);
+  }
+  /// Значение не может выйти за границу
+  #[test]
+  fn test_too_short() {
+    let mut de: Deserializer<LE, _> = Deserializer::new(&[0x34, 0x12, 0x00, 0x00][..]);
+    assert!(de.bounded::<u32>(2).is_err());
+  }
+  /// Поток закончился раньше границы
+  #[test]
+  fn test_no_data() {
+    let mut de: Deserializer<LE, _> = Deserializer::new(&[0x34, 0x12][..]);
+    assert!(de.bounded::<u16>(4).is_err());
+  }
+}
+
+#[cfg(test)]
+mod size_prefixed {
+  use byteorder::LE;
+  use serde::Deserialize;
+  use super::Deserializer;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Point {
+    x: u16,
+    y: u16,
+  }
+
+  /// Второй элемент записан новой версией формата и длиннее, чем требуется типу
+  #[test]
+  fn test_extended() {
+    let data: &[u8] = &[
+      0x04, 0x00,   0x01, 0x00,   0x02, 0x00,
+      0x07, 0x00,   0x03, 0x00,   0x04, 0x00,   0xAA, 0xBB, 0xCC,
+    ];
+    let points = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert_eq!(de.read_size_prefixed::<u16, Point>(None).unwrap(), points);
+
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert_eq!(de.read_size_prefixed::<u16, Point>(Some(1)).unwrap(), &points[..1]);
+    assert_eq!(u16::deserialize(&mut de).unwrap(), 7);
+  }
+  /// Элемент не может выйти за свою границу
+  #[test]
+  fn test_too_short() {
+    let data: &[u8] = &[0x02, 0x00,   0x01, 0x00,   0x02, 0x00];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert!(de.read_size_prefixed::<u16, Point>(None).is_err());
+  }
+  #[test]
+  fn test_no_data() {
+    let data: &[u8] = &[0x04, 0x00,   0x01, 0x00];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert!(de.read_size_prefixed::<u16, Point>(None).is_err());
+  }
+}
+
+#[cfg(test)]
+mod scratch {
+  use std::fmt;
+  use byteorder::BE;
+  use serde::de::{self, Deserialize, Deserializer as _, Visitor};
+  use super::Deserializer;
+
+  /// Тип, которому не нужно владеть прочитанной строкой
+  #[derive(Debug, PartialEq)]
+  struct Len(usize);
+  impl<'de> Deserialize<'de> for Len {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+      struct LenVisitor;
+      impl<'de> Visitor<'de> for LenVisitor {
+        type Value = Len;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+          fmt.write_str("a string")
+        }
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Len, E> { Ok(Len(v.chars().count())) }
+      }
+      deserializer.deserialize_str(LenVisitor)
+    }
+  }
+
+  /// Буфер переходит от одной записи к другой и возвращается десериализатору
+  #[test]
+  fn test_records() {
+    let data: &[u8] = &[0x05,   b'C', b'i', b'r', b'i', b'!',   0x03,   0xD0, 0xAF, b'!'];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data).with_scratch(Vec::with_capacity(16));
+    let len = u8::deserialize(&mut de).unwrap();
+    assert_eq!(de.bounded::<Len>(len.into()).unwrap(), Len(5));
+    let len = u8::deserialize(&mut de).unwrap();
+    assert_eq!(de.bounded::<Len>(len.into()).unwrap(), Len(2));
+
+    let scratch = de.take_scratch().unwrap();
+    assert_eq!(scratch, [0xD0, 0xAF, b'!']);
+    assert!(scratch.capacity() >= 16);
+    assert_eq!(de.take_scratch(), None);
+  }
+  /// Типы, владеющие данными, читаются так же, как без буфера
+  #[test]
+  fn test_owned() {
+    let mut de: Deserializer<BE, _> = Deserializer::new(&b"Ciri"[..]).with_scratch(Vec::new());
+    assert_eq!(String::deserialize(&mut de).unwrap(), "Ciri");
+  }
+  #[test]
+  fn test_bytes() {
+    struct Sum(u32);
+    impl<'de> Visitor<'de> for Sum {
+      type Value = u32;
+
+      fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("bytes")
+      }
+      fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<u32, E> {
+        Ok(v.iter().fold(self.0, |acc, &b| acc + u32::from(b)))
+      }
+    }
+    let mut de: Deserializer<BE, _> = Deserializer::new(&[0x01, 0x02, 0x03][..]).with_scratch(Vec::new());
+    assert_eq!((&mut de).deserialize_bytes(Sum(0)).unwrap(), 6);
+  }
+  #[test]
+  fn test_invalid_utf8() {
+    let mut de: Deserializer<BE, _> = Deserializer::new(&[0xFF][..]).with_scratch(Vec::new());
+    assert!(Len::deserialize(&mut de).is_err());
+    assert!(de.take_scratch().is_some());
+  }
+}
+
+#[cfg(test)]
+mod alternating {
+  use byteorder::BE;
+  use serde::Deserialize;
+  use super::Deserializer;
+  use Endian;
+
+  /// Четные элементы читаются в порядке Big-Endian, нечетные -- в порядке Little-Endian
+  #[test]
+  fn test_u16() {
+    let data: &[u8] = &[0x12, 0x34,   0x34, 0x12,   0xAB, 0xCD,   0xCD, 0xAB,   0xFF];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data);
+    let orders = [Endian::Big, Endian::Little];
+    assert_eq!(de.read_alternating_seq::<u16>(4, &orders).unwrap(), [0x1234, 0x1234, 0xABCD, 0xABCD]);
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0xFF);
+  }
+  #[test]
+  fn test_empty_schedule() {
+    let mut de: Deserializer<BE, _> = Deserializer::new(&[0x12, 0x34][..]);
+    assert!(de.read_alternating_seq::<u16>(1, &[]).is_err());
+    assert_eq!(de.read_alternating_seq::<u16>(0, &[]).unwrap(), []);
+  }
+  #[test]
+  fn test_no_data() {
+    let mut de: Deserializer<BE, _> = Deserializer::new(&[0x12, 0x34, 0x34][..]);
+    assert!(de.read_alternating_seq::<u16>(2, &[Endian::Little]).is_err());
+  }
+  /// Элементы читаются с настройками исходного десериализатора
+  #[test]
+  fn test_options() {
+    let data: &[u8] = &[1, 0x12, 0x34,   0,   1, 0xCD, 0xAB];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data).with_option_marker();
+    let orders = [Endian::Big, Endian::Little];
+    assert_eq!(de.read_alternating_seq::<Option<u16>>(3, &orders).unwrap(), [Some(0x1234), None, Some(0xCDAB)]);
+  }
+}
+
+#[cfg(test)]
+mod counted_by {
+  use byteorder::{BE, LE};
+  use serde::Deserialize;
+  use super::Deserializer;
+
+  #[test]
+  fn test_u16() {
+    let data: &[u8] = &[0x12, 0x34,   0x56, 0x78,   0xFF];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data);
+    assert_eq!(de.read_counted_by::<u16>(2).unwrap(), [0x1234, 0x5678]);
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0xFF);
+
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert_eq!(de.read_counted_by::<u16>(0).unwrap(), []);
+    assert_eq!(de.read_counted_by::<u16>(1).unwrap(), [0x3412]);
+  }
+  /// Огромное количество элементов не приводит к выделению памяти под них
+  #[test]
+  fn test_no_data() {
+    let mut de: Deserializer<BE, _> = Deserializer::new(&[0x12, 0x34, 0x56][..]);
+    assert!(de.read_counted_by::<u16>(usize::MAX).is_err());
+  }
+}
+
+#[cfg(test)]
+mod rest_as_vec {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use super::Deserializer;
+
+  #[test]
+  fn test_u16() {
+    let data: &[u8] = &[0x00, 0x01,   0x00, 0x02,   0x00, 0x03,   0x00, 0x04,   0x00, 0x05];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data);
+    assert_eq!(de.read_rest_as_vec::<u16>().unwrap(), [1, 2, 3, 4, 5]);
+    assert_eq!(de.read_rest_as_vec::<u16>().unwrap(), []);
+
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert_eq!(de.read_rest_as_vec::<(u8, u8)>().unwrap().len(), 5);
+  }
+  #[test]
+  fn test_odd_length() {
+    let data: &[u8] = &[0x00, 0x01,   0x00, 0x02,   0x00];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data);
+    match de.read_rest_as_vec::<u16>() {
+      Err(Error::InvalidLength(_)) => {}
+      result => panic!("expected invalid length, but got {:?}", result),
+    }
+  }
+}
+
+#[cfg(test)]
+mod into_inner {
+  use std::io::{BufReader, Read};
+  use byteorder::LE;
+  use serde::Deserialize;
+  use super::Deserializer;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Header([u8; 4], u16);
+
+  #[test]
+  fn test_rest() {
+    let data: &[u8] = &[0x47, 0x46, 0x46, 0x20,   0x02, 0x00,   0xAA, 0xBB, 0xCC];
+    // Буфер больше заголовка, поэтому часть остатка уже находится в буфере
+    let mut de = Deserializer::<LE, _>::new(BufReader::with_capacity(8, data));
+    assert_eq!(Header::deserialize(&mut de).unwrap(), Header(*b"GFF ", 2));
+
+    let mut rest = Vec::new();
+    de.into_inner().read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, [0xAA, 0xBB, 0xCC]);
+  }
+}
+
+#[cfg(test)]
+mod vec_partial {
+  use byteorder::LE;
+  use error::Error;
+  use super::Deserializer;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Record {
+    id: u16,
+    value: u32,
+  }
+
+  #[test]
+  fn test_complete() {
+    let data: &[u8] = &[0x01, 0x00,   0x0A, 0x00, 0x00, 0x00];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    let (records, error) = de.read_vec_partial::<Record>();
+    assert_eq!(records, [Record { id: 1, value: 10 }]);
+    assert!(error.is_none());
+  }
+  /// Три полные записи, за которыми следует обрезанная четвертая
+  #[test]
+  fn test_truncated() {
+    let data: &[u8] = &[
+      0x01, 0x00,   0x0A, 0x00, 0x00, 0x00,
+      0x02, 0x00,   0x14, 0x00, 0x00, 0x00,
+      0x03, 0x00,   0x1E, 0x00, 0x00, 0x00,
+      0x04, 0x00,   0x28, 0x00,
+    ];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    let (records, error) = de.read_vec_partial::<Record>();
+    assert_eq!(records, [
+      Record { id: 1, value: 10 },
+      Record { id: 2, value: 20 },
+      Record { id: 3, value: 30 },
+    ]);
+    match error {
+      Some(Error::Eof(Some(4))) => {}
+      error => panic!("expected end of data error, but got {:?}", error),
+    }
+  }
+}
+
+#[cfg(test)]
+mod dynamic {
+  use byteorder::{BE, LE};
+  use serde::{Deserialize, Serialize};
+  use super::{from_bytes, DynDeserializer};
+  use Endian;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Header {
+    magic: [u8; 2],
+    version: u16,
+    size: u32,
+  }
+
+  /// Первый байт определяет порядок байт следующего за ним числа
+  fn read(data: &[u8]) -> u32 {
+    let mut de = DynDeserializer::new(data, Endian::Little);
+    let order = match u8::deserialize(&mut de).unwrap() {
+      b'M' => Endian::Big,
+      _ => Endian::Little,
+    };
+    de.set_byteorder(order);
+    assert_eq!(de.byteorder(), order);
+    u32::deserialize(&mut de).unwrap()
+  }
+
+  #[test]
+  fn test_flag() {
+    assert_eq!(read(&[b'M', 0x12, 0x34, 0x56, 0x78]), 0x1234_5678);
+    assert_eq!(read(&[b'I', 0x12, 0x34, 0x56, 0x78]), 0x7856_3412);
+  }
+  /// Структура целиком читается в текущем порядке байт
+  #[test]
+  fn test_struct() {
+    let data: &[u8] = &[b'M', b'M',   0x00, 0x2A,   0x00, 0x00, 0x00, 0x08];
+    let mut de = DynDeserializer::new(data, Endian::Big);
+    assert_eq!(Header::deserialize(&mut de).unwrap(), Header { magic: *b"MM", version: 42, size: 8 });
+
+    let mut de = DynDeserializer::new(data, Endian::Little);
+    assert_eq!(Header::deserialize(&mut de).unwrap(), Header { magic: *b"MM", version: 0x2A00, size: 0x0800_0000 });
+  }
+  /// Результат совпадает с результатом десериализатора с тем же порядком байт в параметре типа
+  #[test]
+  fn test_same_as_static() {
+    type Numbers = (u16, i32, u64, f32, f64, i128);
+    let data: Vec<u8> = (1..=46).collect();
+
+    let mut de = DynDeserializer::new(&data[..], Endian::Big);
+    assert_eq!(Numbers::deserialize(&mut de).unwrap(), from_bytes::<BE, Numbers>(&data).unwrap());
+    let mut de = DynDeserializer::new(&data[..], Endian::Little);
+    assert_eq!(Numbers::deserialize(&mut de).unwrap(), from_bytes::<LE, Numbers>(&data).unwrap());
+  }
+  /// Порядок байт задается сигнатурой, как в TIFF: `II` -- Little-Endian, `MM` -- Big-Endian
+  #[test]
+  fn test_tiff() {
+    fn read(data: &[u8]) -> (u16, u32) {
+      let mut de = DynDeserializer::new(data, Endian::Big);
+      let order = match <[u8; 2]>::deserialize(&mut de).unwrap() {
+        [b'I', b'I'] => Endian::Little,
+        _ => Endian::Big,
+      };
+      de.set_byteorder(order);
+      <(u16, u32)>::deserialize(&mut de).unwrap()
+    }
+    assert_eq!(read(&[b'I', b'I',   0x2A, 0x00,   0x08, 0x00, 0x00, 0x00]), (42, 8));
+    assert_eq!(read(&[b'M', b'M',   0x00, 0x2A,   0x00, 0x00, 0x00, 0x08]), (42, 8));
+  }
+  /// Значения по смещению читаются в текущем порядке байт, а чтение продолжается за смещением
+  #[test]
+  fn test_offset() {
+    use std::io::Cursor;
+    use ser::Serializer;
+    use wrappers::Offset;
+
+    let value = (0x01u8, Offset(0x0203u16), 0x04u8);
+    let mut cursor = Cursor::new(Vec::new());
+    {
+      let mut ser = Serializer::<BE, _>::seekable(&mut cursor);
+      value.serialize(&mut ser).unwrap();
+      ser.write_offset_targets().unwrap();
+    }
+    let data = cursor.into_inner();
+    assert_eq!(data, [0x01,   0x00, 0x00, 0x00, 0x05,   0x04,   0x02, 0x03]);
+
+    let mut de = DynDeserializer::seekable(Cursor::new(&data[..]), Endian::Big);
+    assert_eq!(<(u8, Offset<u16>, u8)>::deserialize(&mut de).unwrap(), value);
+    assert_eq!(de.position(), 6);
+
+    // Без возможности перемещения смещения не поддерживаются
+    let mut de = DynDeserializer::new(&data[..], Endian::Big);
+    assert!(<(u8, Offset<u16>)>::deserialize(&mut de).is_err());
+  }
+}
+
+#[cfg(test)]
+mod enums {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use ser::Serializer;
+  use super::Deserializer;
+  use serde::{Deserialize, Serialize};
+  use {EnumTag, TagWidth};
+
+  /// Новая версия перечисления, в которую добавлен вариант `Color`
+  #[derive(Debug, Serialize)]
+  enum ShapeV2 {
+    Circle(u16),
+    Rect { width: u8, height: u8 },
+    Color(u8, u8, u8),
+  }
+  /// Старая версия перечисления, которой неизвестен вариант `Color`
+  #[derive(Debug, Deserialize, PartialEq)]
+  enum Shape {
+    Circle(u16),
+    Rect { width: u8, height: u8 },
+    #[serde(other)]
+    Unknown,
+  }
+  #[derive(Debug, Serialize)]
+  struct RecordV2 {
+    shape: ShapeV2,
+    next: u16,
+  }
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Record {
+    shape: Shape,
+    next: u16,
+  }
+
+  const DELIMITED: EnumTag = EnumTag { width: TagWidth::U8, length_delimited: true };
+
+  fn write<T: Serialize>(tag: EnumTag, value: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    value.serialize(&mut Serializer::<BE, _>::new(&mut buf).with_enum_tag(tag)).unwrap();
+    buf
+  }
+  fn read<'de, T: Deserialize<'de>>(tag: EnumTag, data: &'de [u8]) -> Result<T, Error> {
+    T::deserialize(&mut Deserializer::<BE, _>::new(data).with_enum_tag(tag))
+  }
+
+  #[test]
+  fn test_tag() {
+    let tag = EnumTag { width: TagWidth::U16, length_delimited: false };
+    let data = write(tag, &RecordV2 { shape: ShapeV2::Rect { width: 3, height: 4 }, next: 0x1234 });
+    assert_eq!(data, [0x00, 0x01,   0x03, 0x04,   0x12, 0x34]);
+    let record = Record { shape: Shape::Rect { width: 3, height: 4 }, next: 0x1234 };
+    assert_eq!(read::<Record>(tag, &data).unwrap(), record);
+
+    let mut le = Vec::new();
+    ShapeV2::Circle(5).serialize(&mut Serializer::<LE, _>::new(&mut le).with_enum_tag(tag)).unwrap();
+    assert_eq!(le, [0x00, 0x00,   0x05, 0x00]);
+  }
+  #[test]
+  fn test_length_delimited() {
+    let data = write(DELIMITED, &RecordV2 { shape: ShapeV2::Circle(5), next: 0x1234 });
+    assert_eq!(data, [0x00,   0x00, 0x00, 0x00, 0x02,   0x00, 0x05,   0x12, 0x34]);
+    assert_eq!(read::<Record>(DELIMITED, &data).unwrap(), Record { shape: Shape::Circle(5), next: 0x1234 });
+  }
+  /// Неизвестный вариант пропускается, и чтение продолжается со следующего поля
+  #[test]
+  fn test_skip_unknown() {
+    let data = write(DELIMITED, &RecordV2 { shape: ShapeV2::Color(0xFF, 0x80, 0x00), next: 0x1234 });
+    assert_eq!(data, [0x02,   0x00, 0x00, 0x00, 0x03,   0xFF, 0x80, 0x00,   0x12, 0x34]);
+    assert_eq!(read::<Record>(DELIMITED, &data).unwrap(), Record { shape: Shape::Unknown, next: 0x1234 });
+  }
+  /// Без размера данных неизвестный вариант пропустить невозможно
+  #[test]
+  fn test_unknown_without_length() {
+    let tag = EnumTag { width: TagWidth::U8, length_delimited: false };
+    let data = write(tag, &RecordV2 { shape: ShapeV2::Color(0xFF, 0x80, 0x00), next: 0x1234 });
+    assert_ne!(read::<Record>(tag, &data).ok(), Some(Record { shape: Shape::Unknown, next: 0x1234 }));
+  }
+  #[test]
+  fn test_no_tag() {
+    let data = [0x00, 0x05];
+    match Shape::deserialize(&mut Deserializer::<BE, _>::new(&data[..])) {
+      Err(Error::Unsupported(_)) => {}
+      result => panic!("expected unsupported error, but got {:?}", result),
+    }
+  }
+  #[test]
+  fn test_unit() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    enum Kind { A, B }
+    let tag = EnumTag { width: TagWidth::U8, length_delimited: false };
+    assert_eq!(write(tag, &Kind::B), [0x01]);
+    assert_eq!(read::<Kind>(tag, &[0x01]).unwrap(), Kind::B);
+    assert_eq!(write(DELIMITED, &Kind::B), [0x01,   0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(read::<Kind>(DELIMITED, &[0x01,   0x00, 0x00, 0x00, 0x00]).unwrap(), Kind::B);
+  }
+}
+
+#[cfg(test)]
+mod custom_encoding {
+  use std::convert::TryFrom;
+  use byteorder::BE;
+  use serde::{Deserialize, Serialize};
+  use encoding::{CustomEncoding, Encoding, Utf8};
+  use error::{Error, Result};
+  use ser::Serializer;
+  use super::Deserializer;
+
+  /// Кодировка, в которой байты 0x00..0x1A соответствуют буквам A..Z, 0x20 -- пробелу,
+  /// 0x30..0x3A -- цифрам, а остальные -- символу-заменителю
+  fn table() -> CustomEncoding {
+    let mut table = ['\u{FFFD}'; 256];
+    for (slot, c) in table.iter_mut().zip('A'..='Z') {
+      *slot = c;
+    }
+    for (slot, c) in table[0x30..].iter_mut().zip('0'..='9') {
+      *slot = c;
+    }
+    table[0x20] = ' ';
+    CustomEncoding::new(table)
+  }
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Test {
+    id: u16,
+    initial: char,
+    name: String,
+  }
+
+  #[test]
+  fn test_roundtrip() {
+    let test = Test { id: 0x1234, initial: 'Z', name: "LEVEL 42".into() };
+    let data = [0x12, 0x34,   0x19,   0x0B, 0x04, 0x15, 0x04, 0x0B, 0x20, 0x34, 0x32];
+
+    let mut buf = Vec::new();
+    test.serialize(&mut Serializer::<BE, _>::new(&mut buf).with_custom_encoding(table())).unwrap();
+    assert_eq!(buf, data);
+
+    let mut de = Deserializer::<BE, _>::new(&data[..]).with_custom_encoding(table());
+    assert_eq!(Test::deserialize(&mut de).unwrap(), test);
+  }
+  #[test]
+  fn test_unmappable() {
+    let test = Test { id: 0x1234, initial: 'Z', name: "level".into() };
+    let mut ser = Serializer::<BE, _>::new(Vec::new()).with_custom_encoding(table());
+    match test.serialize(&mut ser) {
+      Err(Error::Unmappable('l')) => {}
+      result => panic!("expected unmappable character error, but got {:?}", result),
+    }
+  }
+
+  /// Однобайтовая кодировка, в которой каждый байт -- символ с тем же кодом
+  #[derive(Debug)]
+  struct Latin1;
+  impl Encoding for Latin1 {
+    fn encode(&self, s: &str) -> Result<Vec<u8>> {
+      s.chars().map(|c| u8::try_from(c).map_err(|_| Error::Unmappable(c))).collect()
+    }
+    fn decode(&self, bytes: &[u8]) -> Result<String> {
+      Ok(bytes.iter().map(|&b| char::from(b)).collect())
+    }
+  }
+
+  #[test]
+  fn test_encoding_trait() {
+    let test = Test { id: 0x1234, initial: 'é', name: "Café".into() };
+    let data = [0x12, 0x34,   0xE9,   b'C', b'a', b'f', 0xE9];
+
+    let mut buf = Vec::new();
+    test.serialize(&mut Serializer::<BE, _>::new(&mut buf).with_encoding(Latin1)).unwrap();
+    assert_eq!(buf, data);
+
+    let mut de = Deserializer::<BE, _>::new(&data[..]).with_encoding(Latin1);
+    assert_eq!(Test::deserialize(&mut de).unwrap(), test);
+
+    let mut ser = Serializer::<BE, _>::new(Vec::new()).with_encoding(Latin1);
+    assert!(matches!("ж".serialize(&mut ser), Err(Error::Unmappable('ж'))));
+  }
+  /// Символ многобайтовой кодировки читается целиком
+  #[test]
+  fn test_utf8_char() {
+    let mut de = Deserializer::<BE, _>::new(&[0xD0, 0xB6, 0x31][..]).with_encoding(Utf8);
+    assert_eq!(<(char, char)>::deserialize(&mut de).unwrap(), ('ж', '1'));
+  }
+}
+
+#[cfg(test)]
+mod reader {
+  use std::io::{BufReader, Cursor};
+  use byteorder::{BE, LE};
+  use serde::Deserialize;
+  use super::{from_bytes, from_reader, Deserializer};
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Test {
+    signature: [u8; 4],
+    count: u16,
+    items: Vec<u16>,
+  }
+
+  const DATA: [u8; 12] = [
+    0x47, 0x55, 0x49, 0x20,   0x00, 0x03,
+    0x00, 0x01,   0x00, 0x02,   0x00, 0x03,
+  ];
+
+  /// Последовательность в конце читается до конца потока так же, как и из массива байт
+  #[test]
+  fn test_same_as_slice() {
+    let expected = Test { signature: *b"GUI ", count: 3, items: vec![1, 2, 3] };
+    assert_eq!(from_bytes::<BE, Test>(&DATA).unwrap(), expected);
+    assert_eq!(from_reader::<BE, _, Test>(Cursor::new(&DATA[..])).unwrap(), expected);
+    // Границы буфера не совпадают с границами полей
+    let reader = BufReader::with_capacity(3, &DATA[..]);
+    assert_eq!(from_reader::<BE, _, Test>(reader).unwrap(), expected);
+
+    assert_eq!(
+      from_bytes::<LE, Test>(&DATA).unwrap(),
+      from_reader::<LE, _, Test>(Cursor::new(DATA.to_vec())).unwrap(),
+    );
+  }
+  #[test]
+  fn test_no_data() {
+    assert!(from_reader::<BE, _, Test>(Cursor::new(&DATA[..5])).is_err());
+  }
+  /// Небуферизованный поток оборачивается в буфер самим десериализатором
+  #[test]
+  fn test_from_read() {
+    let mut de = Deserializer::<BE, _>::from_read(Cursor::new(DATA.to_vec()));
+    let expected = Test { signature: *b"GUI ", count: 3, items: vec![1, 2, 3] };
+    assert_eq!(Test::deserialize(&mut de).unwrap(), expected);
+    assert!(!de.has_remaining().unwrap());
+    assert_eq!(de.position(), 12);
+  }
 }
 
-////////////////////////////////////////////////////////////////////////////////
-
 #[cfg(test)]
-mod integers {
-  use super::from_bytes;
+mod sized {
   use byteorder::{BE, LE};
+  use error::Error;
+  use super::from_bytes_sized;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Test(u16, u16);
 
   #[test]
-  fn test_u8() {
-    let test: u8 = 0x12;
-    assert_eq!(from_bytes::<BE, u8>(&[0x12]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, u8>(&[0x12]).unwrap(), test);
+  fn test_exact() {
+    let data = [0x12, 0x34,   0x56, 0x78];
+    assert_eq!(from_bytes_sized::<BE, Test>(&data, 4).unwrap(), Test(0x1234, 0x5678));
+    assert_eq!(from_bytes_sized::<LE, Test>(&data, 4).unwrap(), Test(0x3412, 0x7856));
   }
+  /// Тип занимает 4 байта, а формат -- 6 (например, второе поле должно быть `u32`)
   #[test]
-  fn test_i8() {
-    let test: i8 = 0x12;
-    assert_eq!(from_bytes::<BE, i8>(&[0x12]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, i8>(&[0x12]).unwrap(), test);
+  fn test_mismatch() {
+    let data = [0x12, 0x34,   0x56, 0x78, 0x9A, 0xBC];
+    match from_bytes_sized::<BE, Test>(&data, 6) {
+      Err(Error::InvalidLength(_)) => {}
+      result => panic!("expected invalid length error, but got {:?}", result),
+    }
+    assert!(from_bytes_sized::<BE, Test>(&data, 2).is_err());
   }
+}
+
+#[cfg(test)]
+mod exact {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use framing::LengthPrefixed;
+  use super::from_bytes_exact;
 
   #[test]
-  fn test_u16() {
-    let test: u16 = 0x1234;
-    assert_eq!(from_bytes::<BE, u16>(&[0x12, 0x34]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, u16>(&[0x34, 0x12]).unwrap(), test);
+  fn test_exact() {
+    let data = [0x00, 0x02,   0x12, 0x34,   0x56, 0x78];
+    let value = from_bytes_exact::<BE, LengthPrefixed<u16, Vec<u16>>>(&data).unwrap();
+    assert_eq!(value.as_slice(), [0x1234, 0x5678]);
   }
+  /// Заголовок заявляет 3 элемента, а данных хватает только на 2
   #[test]
-  fn test_i16() {
-    let test: i16 = 0x1234;
-    assert_eq!(from_bytes::<BE, i16>(&[0x12, 0x34]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, i16>(&[0x34, 0x12]).unwrap(), test);
+  fn test_too_short() {
+    let data = [0x03, 0x00,   0x12, 0x34,   0x56, 0x78];
+    match from_bytes_exact::<LE, LengthPrefixed<u16, Vec<u16>>>(&data) {
+      Err(Error::InvalidLength(_)) => {}
+      result => panic!("expected invalid length error, but got {:?}", result),
+    }
   }
-
+  /// Заголовок заявляет 1 элемент, а данных хватает на 2
   #[test]
-  fn test_u32() {
-    let test: u32 = 0x12345678;
-    assert_eq!(from_bytes::<BE, u32>(&[0x12, 0x34, 0x56, 0x78]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, u32>(&[0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  fn test_trailing() {
+    let data = [0x01, 0x00,   0x12, 0x34,   0x56, 0x78];
+    match from_bytes_exact::<LE, LengthPrefixed<u16, Vec<u16>>>(&data) {
+      Err(Error::InvalidLength(_)) => {}
+      result => panic!("expected invalid length error, but got {:?}", result),
+    }
   }
-  #[test]
-  fn test_i32() {
-    let test: i32 = 0x12345678;
-    assert_eq!(from_bytes::<BE, i32>(&[0x12, 0x34, 0x56, 0x78]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, i32>(&[0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+}
+
+#[cfg(test)]
+mod strict {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use super::from_bytes_strict;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Header {
+    magic: [u8; 2],
+    count: u16,
   }
 
   #[test]
-  fn test_u64() {
-    let test: u64 = 0x12345678_90ABCDEF;
-    assert_eq!(from_bytes::<BE, u64>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, u64>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  fn test_exact() {
+    let data = [b'G', b'F',   0x00, 0x03];
+    assert_eq!(from_bytes_strict::<BE, Header>(&data).unwrap(), Header { magic: *b"GF", count: 3 });
+    assert_eq!(from_bytes_strict::<LE, ()>(&[]).unwrap(), ());
   }
+  /// Поле `count` в файле занимает 4 байта, а не 2
   #[test]
-  fn test_i64() {
-    let test: i64 = 0x12345678_90ABCDEF;
-    assert_eq!(from_bytes::<BE, i64>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, i64>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  fn test_trailing() {
+    let data = [b'G', b'F',   0x03, 0x00, 0x00, 0x00];
+    match from_bytes_strict::<LE, Header>(&data) {
+      Err(Error::TrailingBytes(2)) => {}
+      result => panic!("expected trailing bytes error, but got {:?}", result),
+    }
+  }
+  /// Нехватка данных по-прежнему является ошибкой чтения
+  #[test]
+  fn test_too_short() {
+    match from_bytes_strict::<LE, Header>(&[b'G', b'F', 0x03]) {
+      Err(Error::At { offset: 3, .. }) => {}
+      result => panic!("expected located error, but got {:?}", result),
+    }
+  }
+}
+
+#[cfg(test)]
+mod with_len {
+  use byteorder::LE;
+  use super::from_bytes_with_len;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Point {
+    x: i16,
+    y: i16,
+  }
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Record {
+    id: u8,
+    corners: [Point; 2],
+    name: [u8; 3],
   }
 
+  /// Записи, следующие друг за другом, читаются по очереди
   #[test]
-  fn test_u128() {
-    let test: u128 = 0x12345678_90ABCDEF_12345678_90ABCDEF;
-    assert_eq!(from_bytes::<BE, u128>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF, 0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, u128>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12, 0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  fn test_concatenated() {
+    let data = [
+      0x01,   0x01, 0x00, 0x02, 0x00,   0x03, 0x00, 0x04, 0x00,   b'a', b'b', b'c',
+      0x02,   0xFF, 0xFF, 0xFE, 0xFF,   0x00, 0x00, 0x00, 0x00,   b'x', b'y', b'z',
+    ];
+    let (first, len) = from_bytes_with_len::<LE, Record>(&data).unwrap();
+    assert_eq!(len, 12);
+    assert_eq!(first, Record {
+      id: 1,
+      corners: [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }],
+      name: *b"abc",
+    });
+    let (second, len) = from_bytes_with_len::<LE, Record>(&data[len..]).unwrap();
+    assert_eq!(len, 12);
+    assert_eq!(second, Record {
+      id: 2,
+      corners: [Point { x: -1, y: -2 }, Point { x: 0, y: 0 }],
+      name: *b"xyz",
+    });
   }
   #[test]
-  fn test_i128() {
-    let test: i128 = 0x12345678_90ABCDEF_12345678_90ABCDEF;
-    assert_eq!(from_bytes::<BE, i128>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF, 0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD, 0xEF]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, i128>(&[0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12, 0xEF, 0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  fn test_too_short() {
+    assert!(from_bytes_with_len::<LE, Point>(&[0x01, 0x00, 0x02]).is_err());
   }
 }
+
 #[cfg(test)]
-mod floats {
-  use super::from_bytes;
-  use byteorder::{ByteOrder, BE, LE};
+mod option {
+  use byteorder::{BE, LE};
+  use serde::{Deserialize, Serialize};
+  use error::Error;
+  use ser::Serializer;
+  use super::Deserializer;
 
-  macro_rules! float_test {
-    ($name:ident, $BO:ident :: $write:ident, $type:ty) => (
-      quickcheck! {
-        fn $name(test: $type) -> bool {
-          let mut buf = [0; std::mem::size_of::<$type>()];
-          $BO::$write(&mut buf, test);
-          from_bytes::<$BO, $type>(&buf).unwrap() == test
-        }
-      }
-    );
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Test {
+    first: Option<u16>,
+    second: Option<u16>,
   }
 
-  float_test!(test_f32_be, BE::write_f32, f32);
-  float_test!(test_f32_le, LE::write_f32, f32);
+  fn round_trip(value: &Test, expected: &[u8]) {
+    let mut buf = Vec::new();
+    value.serialize(&mut Serializer::<LE, _>::new(&mut buf).with_option_marker()).unwrap();
+    assert_eq!(buf, expected);
+    let mut de = Deserializer::<LE, _>::new(&buf[..]).with_option_marker();
+    assert_eq!(&Test::deserialize(&mut de).unwrap(), value);
+  }
 
-  float_test!(test_f64_be, BE::write_f64, f64);
-  float_test!(test_f64_le, LE::write_f64, f64);
+  #[test]
+  fn test_some() {
+    round_trip(&Test { first: Some(0x1234), second: Some(0) }, &[0x01, 0x34, 0x12,   0x01, 0x00, 0x00]);
+  }
+  #[test]
+  fn test_none() {
+    round_trip(&Test { first: None, second: Some(0x1234) }, &[0x00,   0x01, 0x34, 0x12]);
+    round_trip(&Test { first: None, second: None }, &[0x00,   0x00]);
+  }
+  #[test]
+  fn test_invalid_marker() {
+    let mut de = Deserializer::<BE, _>::new(&[0x02, 0x12, 0x34][..]).with_option_marker();
+    assert!(Option::<u16>::deserialize(&mut de).is_err());
+  }
+  /// Без маркера `Option` по-прежнему не поддерживается
+  #[test]
+  fn test_no_marker() {
+    let mut de = Deserializer::<BE, _>::new(&[0x01, 0x12, 0x34][..]);
+    match Option::<u16>::deserialize(&mut de) {
+      Err(Error::Unsupported(_)) => {}
+      result => panic!("expected unsupported error, but got {:?}", result),
+    }
+  }
 }
+
 #[cfg(test)]
-mod complex {
-  use super::from_bytes;
-  use byteorder::{BE, LE};
+mod position {
+  use std::io::{BufReader, Cursor};
+  use byteorder::LE;
+  use serde::Deserialize;
+  use super::Deserializer;
 
-  quickcheck! {
-    #[should_panic]
-    fn test_bool(byte: u8) -> bool {
-      from_bytes::<BE, bool>(&[byte]).unwrap()
-    }
+  /// Позиция растет на количество байт каждого прочитанного поля
+  #[test]
+  fn test_fields() {
+    let data: &[u8] = &[
+      b'G', b'U', b'I', b' ',   0x38, 0x00, 0x00, 0x00,   0x0F, 0x00,   0xD0, 0xAF,   b'a', b'b',
+    ];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+    assert_eq!(de.position(), 0);
+    <[u8; 4]>::deserialize(&mut de).unwrap();
+    assert_eq!(de.position(), 4);
+    u32::deserialize(&mut de).unwrap();
+    assert_eq!(de.position(), 8);
+    u16::deserialize(&mut de).unwrap();
+    assert_eq!(de.position(), 10);
+    char::deserialize(&mut de).unwrap();
+    assert_eq!(de.position(), 12);
+    String::deserialize(&mut de).unwrap();
+    assert_eq!(de.position(), 14);
   }
-  /// При десериализации ничего не читает из потока
+  /// Байты, прочитанные вспомогательными десериализаторами, также учитываются
   #[test]
-  fn test_unit() {
-    #[derive(Debug, Deserialize, PartialEq)]
-    struct Test;
-
-    let test = Test;
-    assert_eq!(from_bytes::<BE, Test>(&[]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, Test>(&[]).unwrap(), test);
+  fn test_nested() {
+    let data: &[u8] = &[0x01, 0x00,   0x02, 0x00, 0x03, 0x00,   0xFF];
+    let mut de: Deserializer<LE, _> = Deserializer::new(BufReader::with_capacity(2, data));
+    de.bounded::<u16>(2).unwrap();
+    assert_eq!(de.position(), 2);
+    de.read_counted_by::<u16>(2).unwrap();
+    assert_eq!(de.position(), 6);
   }
-
-  /// При десериализации читает из потока нижележащий тип
+  /// После ошибки позиция указывает на место, где закончились данные
   #[test]
-  fn test_newtype() {
-    #[derive(Debug, Deserialize, PartialEq)]
-    struct Test(u32);
-
-    let test = Test(0x12345678);
-    assert_eq!(from_bytes::<BE, Test>(&[0x12, 0x34, 0x56, 0x78]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, Test>(&[0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  fn test_error() {
+    let mut de: Deserializer<LE, _> = Deserializer::new(&[0x01, 0x00, 0x02][..]);
+    assert!(<(u16, u16)>::deserialize(&mut de).is_err());
+    assert_eq!(de.position(), 3);
   }
-
-  /// Поля в кортеже записываются подряд, в порядке следования, без пробелов и дополнительных данных.
-  /// Порядок байт переворачивается для каждого поля независимо.
+  /// Чтение по смещению не изменяет позицию
   #[test]
-  fn test_tuple() {
-    #[derive(Debug, Deserialize, PartialEq)]
-    struct Test(u32, u16);
+  fn test_read_at() {
+    let data = [0x01, 0x02, 0x03, 0x04];
+    let mut de: Deserializer<LE, _> = Deserializer::seekable(Cursor::new(&data[..]));
+    u8::deserialize(&mut de).unwrap();
+    assert_eq!(de.read_at::<u16>(2).unwrap(), 0x0403);
+    assert_eq!(de.position(), 1);
+  }
+}
 
-    let test = Test(0x12345678, 0xABCD);
-    assert_eq!(from_bytes::<BE, Test>(&[0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, Test>(&[0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB]).unwrap(), test);
+#[cfg(test)]
+mod located {
+  use std::io::{self, Cursor, ErrorKind};
+  use byteorder::LE;
+  use serde::Deserialize;
+  use error::Error;
+  use super::{from_bytes, from_reader, Deserializer};
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Header {
+    signature: [u8; 4],
+    count: u32,
+    size: u16,
   }
 
-  /// Поля в структуре записываются подряд, в порядке следования, без пробелов и дополнительных данных.
-  /// Порядок байт переворачивается для каждого поля независимо.
+  /// Данные обрываются посреди поля `size`, начинающегося со смещения 8: его первый байт
+  /// прочитан, а второго нет
   #[test]
-  fn test_struct() {
-    #[derive(Debug, Deserialize, PartialEq)]
-    struct Test {
-      int1: u32,
-      int2: u16,
+  fn test_short_read() {
+    let data = [b'G', b'U', b'I', b' ',   0x0F, 0x00, 0x00, 0x00,   0x93];
+    match from_bytes::<LE, Header>(&data) {
+      Err(Error::At { offset, source }) => {
+        assert_eq!(offset, 9);
+        match *source {
+          Error::Eof(Some(2)) => {}
+          ref e => panic!("expected end of data error, but got {:?}", e),
+        }
+      }
+      result => panic!("expected error with offset, but got {:?}", result),
+    }
+    let err = from_reader::<LE, _, Header>(Cursor::new(&data[..])).unwrap_err();
+    assert!(err.to_string().starts_with("at offset 9: "), "{}", err);
+  }
+  #[test]
+  fn test_encoding() {
+    match from_bytes::<LE, (u8, String)>(&[0x01,   0x61, 0xFF]) {
+      Err(Error::At { offset: 3, source }) => assert!(matches!(*source, Error::Encoding(_))),
+      result => panic!("expected error with offset, but got {:?}", result),
+    }
+  }
+  /// Конец данных посреди числа сообщается отдельной ошибкой с размером числа
+  #[test]
+  fn test_eof() {
+    match u32::deserialize(&mut Deserializer::<LE, _>::new(&[0x01, 0x02][..])) {
+      Err(Error::Eof(Some(4))) => {}
+      result => panic!("expected end of data error, but got {:?}", result),
     }
+    assert_eq!(Error::Eof(Some(4)).to_string(), "unexpected end of data while reading 4 bytes");
+    assert!(matches!(Error::from(io::Error::from(ErrorKind::UnexpectedEof)), Error::Eof(None)));
+    assert!(matches!(Error::from(io::Error::from(ErrorKind::PermissionDenied)), Error::Io(_)));
+  }
+  /// Другие ошибки не оборачиваются
+  #[test]
+  fn test_other() {
+    assert!(matches!(from_bytes::<LE, Option<u8>>(&[0x01]), Err(Error::Unsupported(_))));
+  }
+}
 
-    let test = Test { int1: 0x12345678, int2: 0xABCD };
-    assert_eq!(from_bytes::<BE, Test>(&[0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD]).unwrap(), test);
-    assert_eq!(from_bytes::<LE, Test>(&[0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB]).unwrap(), test);
+#[cfg(test)]
+mod scoped {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use super::Deserializer;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Block {
+    kind: u8,
+    values: Vec<u16>,
   }
 
-  /// Десериализатор не в состоянии различить `Some` и `None` -- для десериализации нужно
-  /// вручную прочитать (или определить) маркер, и прочитать значение, если маркер говорит,
-  /// что оно есть
   #[test]
-  #[should_panic]
-  fn test_option_be() {
-    from_bytes::<BE, Option<u16>>(&[0x12, 0x34]).unwrap();
+  fn test_nested_vec() {
+    let data = [0x01,   0x00, 0x02, 0x00, 0x03,   0x2A, 0x00];
+    let mut de = Deserializer::<BE, _>::new(&data[..]);
+    let mut block = de.scoped(5);
+    assert_eq!(block.read_field::<Block>().unwrap(), Block { kind: 1, values: vec![2, 3] });
+    assert_eq!(block.remaining_len(), 0);
+    block.finish().unwrap();
+    assert_eq!(de.read_field::<u16>().unwrap(), 0x2A00);
+    assert_eq!(de.position(), 7);
   }
+  /// Непрочитанный остаток блока пропускается
   #[test]
-  #[should_panic]
-  fn test_option_le() {
-    from_bytes::<LE, Option<u16>>(&[0x12, 0x34]).unwrap();
+  fn test_skip_rest() {
+    let data = [0x01, 0x00,   0x02, 0x00,   0x03, 0x00];
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    let mut block = de.scoped(4);
+    assert_eq!(block.read_field::<u16>().unwrap(), 1);
+    assert_eq!(block.remaining_len(), 2);
+    block.finish().unwrap();
+    assert_eq!(de.read_field::<u16>().unwrap(), 3);
+  }
+  #[test]
+  fn test_read_past_end() {
+    let data = [0x01, 0x00,   0x02, 0x00];
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    let mut block = de.scoped(3);
+    assert_eq!(block.read_field::<u16>().unwrap(), 1);
+    match block.read_field::<u16>() {
+      Err(Error::Eof(Some(2))) => {}
+      result => panic!("expected end of data error, but got {:?}", result),
+    }
   }
+  #[test]
+  fn test_truncated() {
+    let mut de = Deserializer::<LE, _>::new(&[0x01, 0x02][..]);
+    assert!(matches!(de.scoped(3).finish(), Err(Error::Eof(None))));
+  }
+}
 
-  /// Записывает все элементы последовательности подряд, без разделителей, заголовочной или
-  /// конечной информации, либо какой-либо информации о количестве элементов.
-  /// Порядок байт переворачивается для каждого поля независимо.
+#[cfg(test)]
+mod seq_within {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use super::Deserializer;
+
+  /// Последовательность заканчивается раньше потока, за ней следуют другие данные
   #[test]
-  fn test_seq() {
-    let test = [0x12, 0x34,   0x56, 0x78,   0xAB, 0xCD];
-    assert_eq!(from_bytes::<BE, Vec<u16>>(&test).unwrap(), vec![0x1234, 0x5678, 0xABCD]);
-    assert_eq!(from_bytes::<LE, Vec<u16>>(&test).unwrap(), vec![0x3412, 0x7856, 0xCDAB]);
+  fn test_vec() {
+    let data = [0x01, 0x00, 0x02, 0x00, 0x03, 0x00,   0x04, 0x00, 0x05, 0x00];
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    assert_eq!(de.seq_within::<u16>(6).unwrap(), [1, 2, 3]);
+    assert_eq!(de.position(), 6);
+    assert_eq!(de.seq_within::<u16>(0).unwrap(), []);
+    assert_eq!(de.read_field::<[u16; 2]>().unwrap(), [4, 5]);
+  }
+  /// Строки внутри последовательности заканчиваются на ее границе
+  #[test]
+  fn test_nested() {
+    let data = [0x00, 0x01, b'a', b'b',   0x00, 0x2A];
+    let mut de = Deserializer::<BE, _>::new(&data[..]);
+    assert_eq!(de.seq_within::<(u16, String)>(4).unwrap(), [(1, "ab".to_string())]);
+    assert_eq!(de.read_field::<u16>().unwrap(), 42);
   }
+  /// Элемент, пересекающий границу, является ошибкой
+  #[test]
+  fn test_partial() {
+    let data = [0x00, 0x01, 0x00, 0x02];
+    let mut de = Deserializer::<BE, _>::new(&data[..]);
+    assert!(matches!(de.seq_within::<u16>(3), Err(Error::Eof(Some(2)))));
+    let mut de = Deserializer::<BE, _>::new(&data[..]);
+    assert!(matches!(de.seq_within::<u16>(6), Err(Error::Eof(_))));
+  }
+}
+
+#[cfg(test)]
+mod skip {
+  use byteorder::LE;
+  use error::Error;
+  use super::Deserializer;
 
-  /// Возврат срезов строки не поддерживается, т.к. десериализатор всегда выдает новую строку
   #[test]
-  #[should_panic]
-  fn test_str_be() {
-    from_bytes::<BE, &str>("test".as_bytes()).unwrap();
+  fn test_skip() {
+    let data = [0x01,   0xAA, 0xBB, 0xCC,   0x02, 0x00];
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    assert_eq!(de.read_field::<u8>().unwrap(), 1);
+    de.skip(3).unwrap();
+    assert_eq!(de.position(), 4);
+    de.skip(0).unwrap();
+    assert_eq!(de.read_field::<u16>().unwrap(), 2);
+    assert!(!de.has_remaining().unwrap());
   }
   #[test]
-  #[should_panic]
-  fn test_str_le() {
-    from_bytes::<LE, &str>("test".as_bytes()).unwrap();
+  fn test_no_data() {
+    let mut de = Deserializer::<LE, _>::new(&[0x01, 0x02][..]);
+    assert!(matches!(de.skip(3), Err(Error::Eof(Some(3)))));
+    assert!(!de.has_remaining().unwrap());
+  }
+}
+
+#[cfg(test)]
+mod has_remaining {
+  use std::io::BufReader;
+  use byteorder::BE;
+  use super::Deserializer;
+
+  #[test]
+  fn test_records() {
+    let data = [0x00, 0x01,   0x00, 0x02,   0x00, 0x03];
+    // Маленький буфер, чтобы записи читались из потока по мере необходимости
+    let mut de = Deserializer::<BE, _>::new(BufReader::with_capacity(1, &data[..]));
+    let mut records = Vec::new();
+    while de.has_remaining().unwrap() {
+      records.push(de.read_field::<u16>().unwrap());
+    }
+    assert_eq!(records, [1, 2, 3]);
+    assert!(!de.has_remaining().unwrap());
   }
   #[test]
-  fn test_string() {
-    let test = "тест";
-    assert_eq!(from_bytes::<BE, String>(test.as_bytes()).unwrap(), test);
-    assert_eq!(from_bytes::<LE, String>(test.as_bytes()).unwrap(), test);
+  fn test_empty() {
+    let mut de = Deserializer::<BE, _>::new(&[][..]);
+    assert!(!de.has_remaining().unwrap());
+  }
+}
+
+#[cfg(test)]
+mod ignored_any {
+  use byteorder::LE;
+  use serde::de::{Deserialize, IgnoredAny};
+  use super::{from_bytes, Deserializer};
+
+  /// Новая версия формата добавила в конец структуры поля, неизвестные этой программе
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Header {
+    version: u16,
+    size: u32,
+    rest: IgnoredAny,
   }
 
   #[test]
-  fn test_array_empty() {
-    assert_eq!(from_bytes::<BE, [u16; 0]>(&[]).unwrap(), []);
-    assert_eq!(from_bytes::<LE, [u16; 0]>(&[]).unwrap(), []);
+  fn test_last_field() {
+    let data = [0x02, 0x00,   0x10, 0x00, 0x00, 0x00,   0xDE, 0xAD, 0xBE, 0xEF];
+    let header = from_bytes::<LE, Header>(&data).unwrap();
+    assert_eq!((header.version, header.size), (2, 16));
   }
   #[test]
-  fn test_array() {
-    let test = [0x12, 0x34, 0x56, 0x78, 0xAB, 0xCD];
-    assert_eq!(from_bytes::<BE, [u16; 3]>(&test).unwrap(), [0x1234, 0x5678, 0xABCD]);
-    assert_eq!(from_bytes::<LE, [u16; 3]>(&test).unwrap(), [0x3412, 0x7856, 0xCDAB]);
+  fn test_empty() {
+    let header = from_bytes::<LE, Header>(&[0x02, 0x00,   0x10, 0x00, 0x00, 0x00]).unwrap();
+    assert_eq!((header.version, header.size), (2, 16));
   }
+  /// Пропускается только остаток ограниченного значения
   #[test]
-  #[should_panic]
-  fn test_array_no_data_be() {
-    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
-    from_bytes::<BE, [u16; 3]>(&test).unwrap();
+  fn test_bounded() {
+    let data = [0x02, 0x00,   0x10, 0x00, 0x00, 0x00,   0xDE, 0xAD,   0x2A];
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    let header: Header = de.bounded(8).unwrap();
+    assert_eq!((header.version, header.size), (2, 16));
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0x2A);
   }
+}
+
+#[cfg(test)]
+mod limit {
+  use std::io::{self, BufReader};
+  use byteorder::LE;
+  use serde::Deserialize;
+  use error::Error;
+  use super::{Deserializer, Limit};
+
+  fn elements(max: usize) -> Limit { Limit { max_elements: Some(max), ..Limit::default() } }
+  fn bytes(max: u64) -> Limit { Limit { max_bytes: Some(max), ..Limit::default() } }
+
   #[test]
-  #[should_panic]
-  fn test_array_no_data_le() {
-    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
-    from_bytes::<LE, [u16; 3]>(&test).unwrap();
+  fn test_elements() {
+    let data = [0x01, 0x00,   0x02, 0x00,   0x03, 0x00];
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_limit(elements(3));
+    assert_eq!(Vec::<u16>::deserialize(&mut de).unwrap(), vec![1, 2, 3]);
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_limit(elements(2));
+    assert!(matches!(Vec::<u16>::deserialize(&mut de), Err(Error::InvalidLength(_))));
   }
   #[test]
-  fn test_vec() {
-    let test = [0x12, 0x34, 0x56, 0x78, 0xAB, 0xCD];
-    assert_eq!(from_bytes::<BE, Vec<u16>>(&test).unwrap(), vec![0x1234, 0x5678, 0xABCD]);
-    assert_eq!(from_bytes::<LE, Vec<u16>>(&test).unwrap(), vec![0x3412, 0x7856, 0xCDAB]);
+  fn test_bytes() {
+    let data = [0x01, 0x00,   0x02, 0x00,   0x03, 0x00];
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_limit(bytes(6));
+    assert_eq!(Vec::<u16>::deserialize(&mut de).unwrap(), vec![1, 2, 3]);
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_limit(bytes(5));
+    assert!(matches!(Vec::<u16>::deserialize(&mut de), Err(Error::InvalidLength(_))));
   }
+  /// Бесконечный поток не приводит к бесконечному выделению памяти
   #[test]
-  #[should_panic]
-  fn test_vec_no_data_be() {
-    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
-    from_bytes::<BE, Vec<u16>>(&test).unwrap();
+  fn test_endless() {
+    let mut de = Deserializer::<LE, _>::new(BufReader::new(io::repeat(0))).with_limit(elements(1000));
+    assert!(matches!(Vec::<u32>::deserialize(&mut de), Err(Error::InvalidLength(_))));
+
+    let mut de = Deserializer::<LE, _>::new(BufReader::new(io::repeat(b'a'))).with_limit(bytes(1000));
+    assert!(matches!(String::deserialize(&mut de), Err(Error::InvalidLength(_))));
   }
+  /// Ограничение действует на каждую вложенную последовательность отдельно
   #[test]
-  #[should_panic]
-  fn test_vec_no_data_le() {
-    let test = [0x12, 0x34, 0x56, 0x78, 0xAB];
-    from_bytes::<LE, Vec<u16>>(&test).unwrap();
+  fn test_nested() {
+    let data = [0x01, 0x02, 0x03,   0x04, 0x05];
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_limit(elements(3));
+    let value: ([u8; 3], Vec<u8>) = Deserialize::deserialize(&mut de).unwrap();
+    assert_eq!(value, ([1, 2, 3], vec![4, 5]));
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_limit(bytes(2));
+    assert_eq!(de.bounded::<String>(2).unwrap(), "\u{1}\u{2}");
+    assert!(matches!(String::deserialize(&mut de), Err(Error::InvalidLength(_))));
   }
 }
@@ -0,0 +1,189 @@
+//! Содержит типаж кодировки строк и кодировки, отличные от UTF-8, используемой сериализатором
+//! и десериализатором по умолчанию.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use error::{Error, Result};
+
+/// Кодировка строк и символов. Задается сериализатору и десериализатору методом `with_encoding`,
+/// например, для чтения строк в кодировке Windows-1251, в которой хранятся тексты многих старых
+/// русских игр. Без заданной кодировки строки записываются и читаются в UTF-8.
+///
+/// Кодировка хранится в (де)сериализаторе как объект типажа, а не задается его параметром типа.
+/// Она часто известна только во время выполнения (например, кодовая страница указана в заголовке
+/// файла), а вложенные (де)сериализаторы для блоков, сжатых данных и значений с контрольной суммой
+/// получают ее вместе с остальными настройками. Параметр типа пришлось бы указывать во всех
+/// сигнатурах, в которых упоминаются сериализатор и десериализатор, а цена динамического вызова
+/// мала по сравнению с самим перекодированием. UTF-8 по умолчанию обрабатывается без вызова
+/// кодировки.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde::Deserialize;
+/// # use serde_pod::Result;
+/// # use serde_pod::de::Deserializer;
+/// # use serde_pod::encoding::Encoding;
+/// /// Кодировка ISO-8859-1: каждый байт -- символ с тем же кодом
+/// #[derive(Debug)]
+/// struct Latin1;
+/// impl Encoding for Latin1 {
+///   fn encode(&self, s: &str) -> Result<Vec<u8>> {
+///     s.chars().map(|c| if (c as u32) < 256 { Ok(c as u8) } else { Err(serde_pod::Error::Unmappable(c)) }).collect()
+///   }
+///   fn decode(&self, bytes: &[u8]) -> Result<String> {
+///     Ok(bytes.iter().map(|&b| b as char).collect())
+///   }
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let mut de = Deserializer::<LE, _>::new(&b"caf\xE9"[..]).with_encoding(Latin1);
+/// assert_eq!(String::deserialize(&mut de)?, "café");
+/// # Ok(())
+/// # }
+/// ```
+pub trait Encoding: fmt::Debug + Send + Sync {
+  /// Кодирует строку в последовательность байт.
+  ///
+  /// # Ошибки
+  /// Обычно [`Error::Unmappable`], если строка содержит символ, не представимый в кодировке
+  ///
+  /// [`Error::Unmappable`]: ../error/enum.Error.html#variant.Unmappable
+  fn encode(&self, s: &str) -> Result<Vec<u8>>;
+  /// Декодирует последовательность байт в строку
+  ///
+  /// # Ошибки
+  /// Обычно [`Error::Encoding`] или [`Error::Unknown`], если байты не являются корректно
+  /// закодированной строкой
+  ///
+  /// [`Error::Encoding`]: ../error/enum.Error.html#variant.Encoding
+  /// [`Error::Unknown`]: ../error/enum.Error.html#variant.Unknown
+  fn decode(&self, bytes: &[u8]) -> Result<String>;
+  /// Возвращает количество байт, занимаемых символом, по его первому байту. Используется при
+  /// чтении отдельных символов. Реализация по умолчанию возвращает `1`, что верно для всех
+  /// однобайтовых кодировок
+  fn char_width(&self, _first: u8) -> usize { 1 }
+}
+
+/// Кодировка UTF-8, используемая сериализатором и десериализатором по умолчанию
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Utf8;
+
+impl Encoding for Utf8 {
+  #[inline]
+  fn encode(&self, s: &str) -> Result<Vec<u8>> { Ok(s.as_bytes().to_vec()) }
+  #[inline]
+  fn decode(&self, bytes: &[u8]) -> Result<String> {
+    Ok(String::from_utf8(bytes.to_vec())?)
+  }
+  fn char_width(&self, first: u8) -> usize {
+    match first {
+      0xC2..=0xDF => 2,
+      0xE0..=0xEF => 3,
+      0xF0..=0xF4 => 4,
+      _ => 1,
+    }
+  }
+}
+
+/// Однобайтовая кодировка, заданная произвольной таблицей символов, например, нестандартная
+/// таблица глифов старой игры. Каждому из 256 значений байта соответствует символ из таблицы.
+///
+/// Если один и тот же символ встречается в таблице несколько раз, при кодировании используется
+/// байт его первого вхождения. Копирование кодировки не копирует таблицу.
+///
+/// # Пример
+/// ```rust
+/// # extern crate serde_pod;
+/// # use serde_pod::encoding::CustomEncoding;
+/// let mut table = ['\u{FFFD}'; 256];
+/// for (slot, c) in table.iter_mut().zip("ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars()) {
+///   *slot = c;
+/// }
+/// let encoding = CustomEncoding::new(table);
+///
+/// assert_eq!(encoding.encode("CAB").unwrap(), [2, 0, 1]);
+/// assert_eq!(encoding.decode(&[2, 0, 1]), "CAB");
+/// assert!(encoding.encode("abc").is_err());
+/// ```
+#[derive(Clone)]
+pub struct CustomEncoding(Arc<Tables>);
+
+/// Таблицы преобразования однобайтовой кодировки
+struct Tables {
+  /// Символы, соответствующие значениям байт
+  chars: [char; 256],
+  /// Байты, соответствующие символам
+  bytes: HashMap<char, u8>,
+}
+
+impl CustomEncoding {
+  /// Создает кодировку из таблицы символов, индексированной значениями байт
+  pub fn new(chars: [char; 256]) -> Self {
+    let mut bytes = HashMap::with_capacity(256);
+    for (b, &c) in chars.iter().enumerate() {
+      bytes.entry(c).or_insert(b as u8);
+    }
+    CustomEncoding(Arc::new(Tables { chars, bytes }))
+  }
+  /// Возвращает символ, соответствующий байту
+  #[inline]
+  pub fn char(&self, byte: u8) -> char {
+    self.0.chars[byte as usize]
+  }
+  /// Кодирует строку в последовательность байт.
+  ///
+  /// # Ошибки
+  /// [`Error::Unmappable`]: строка содержит символ, отсутствующий в таблице
+  ///
+  /// [`Error::Unmappable`]: ../error/enum.Error.html#variant.Unmappable
+  pub fn encode(&self, s: &str) -> Result<Vec<u8>> {
+    s.chars().map(|c| self.0.bytes.get(&c).cloned().ok_or(Error::Unmappable(c))).collect()
+  }
+  /// Декодирует последовательность байт в строку. Декодирование всегда успешно, т.к. каждому
+  /// байту соответствует символ
+  pub fn decode(&self, bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| self.char(b)).collect()
+  }
+}
+impl Encoding for CustomEncoding {
+  #[inline]
+  fn encode(&self, s: &str) -> Result<Vec<u8>> { CustomEncoding::encode(self, s) }
+  #[inline]
+  fn decode(&self, bytes: &[u8]) -> Result<String> { Ok(CustomEncoding::decode(self, bytes)) }
+}
+impl fmt::Debug for CustomEncoding {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.debug_tuple("CustomEncoding").field(&&self.0.chars[..]).finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use error::Error;
+  use super::{CustomEncoding, Encoding, Utf8};
+
+  #[test]
+  fn test_utf8() {
+    assert_eq!(Utf8.encode("ж1").unwrap(), [0xD0, 0xB6, 0x31]);
+    assert_eq!(Utf8.decode(&[0xD0, 0xB6, 0x31]).unwrap(), "ж1");
+    assert!(matches!(Utf8.decode(&[0xD0]), Err(Error::Encoding(_))));
+    assert_eq!(Utf8.char_width(0x31), 1);
+    assert_eq!(Utf8.char_width(0xD0), 2);
+    assert_eq!(Utf8.char_width(0xF0), 4);
+  }
+  #[test]
+  fn test_custom() {
+    let mut table = ['?'; 256];
+    table[1] = 'ж';
+    let encoding: &dyn Encoding = &CustomEncoding::new(table);
+    assert_eq!(encoding.encode("ж").unwrap(), [1]);
+    assert_eq!(encoding.decode(&[1, 2]).unwrap(), "ж?");
+    assert_eq!(encoding.char_width(1), 1);
+  }
+}
@@ -1,50 +1,218 @@
 //! Содержит тип ошибки и результата, описывающие неуспешный результат сериализации
 //! или десериализации.
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+use alloc::string::FromUtf8Error;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use alloc::fmt;
+use alloc::str::Utf8Error;
+#[cfg(feature = "std")]
 use std::error;
-use std::fmt;
+#[cfg(feature = "std")]
 use std::io;
-use std::result;
-use std::str::Utf8Error;
-use std::string::FromUtf8Error;
 use serde::{de, ser};
 
+#[cfg(feature = "std")]
+use de::NeedsBuffer;
+#[cfg(feature = "std")]
+use framing::LengthOverflow;
+
 /// Варианты ошибок, которые могут возникнуть при сериализации или десериализации
 #[derive(Debug)]
 pub enum Error {
   /// Ошибка при записи сформированных байт в поток во время сериализации или при
-  /// чтении из потока во время десериализации.
+  /// чтении из потока во время десериализации. Доступна только при включенной
+  /// функциональности `std`
+  #[cfg(feature = "std")]
   Io(io::Error),
+  /// Данные закончились раньше, чем было прочитано значение. Содержит количество байт, которое
+  /// требовалось прочитать, если оно известно, например, размер числа
+  Eof(Option<usize>),
   /// Ошибка декодирования строки или символа из массива байт
   Encoding(Utf8Error),
+  /// Символ не может быть представлен в кодировке сериализатора
+  Unmappable(char),
   /// Ошибка сериализации стороннего типа
   Unknown(String),
   /// Метод десериализации не поддерживается
   Unsupported(&'static str),
+  /// Длина данных не соответствует ожидаемой
+  InvalidLength(String),
+  /// После десериализованного значения в данных остались непрочитанные байты. Содержит их
+  /// количество
+  TrailingBytes(usize),
+  /// Контрольная сумма, записанная в потоке, не совпадает с вычисленной по прочитанным данным
+  ChecksumMismatch {
+    /// Контрольная сумма, прочитанная из потока
+    stored: u32,
+    /// Контрольная сумма, вычисленная по прочитанным данным
+    computed: u32,
+  },
+  /// Сигнатура формата, прочитанная из потока, не совпадает с ожидаемой
+  MagicMismatch {
+    /// Ожидаемая сигнатура
+    expected: Vec<u8>,
+    /// Сигнатура, прочитанная из потока
+    found: Vec<u8>,
+  },
+  /// Длина (количество элементов или байт), прочитанная из потока, не может быть представлена
+  /// типом `usize` на данной платформе, например, 64-битная длина на 32-битной платформе.
+  /// Такая длина никогда не усекается молча
+  Overflow(u64),
+  /// Ошибка чтения или декодирования данных, произошедшая при десериализации функциями
+  /// [`from_bytes`], [`from_reader`] и т.п. Смещение позволяет найти в файле место, в котором
+  /// данные испорчены или обрезаны
+  ///
+  /// [`from_bytes`]: ../de/fn.from_bytes.html
+  /// [`from_reader`]: ../de/fn.from_reader.html
+  At {
+    /// Количество байт, прочитанных из потока к моменту возникновения ошибки, включая байты,
+    /// при чтении или декодировании которых она возникла
+    offset: u64,
+    /// Исходная ошибка: [`Error::Io`], [`Error::Eof`] или [`Error::Encoding`]
+    ///
+    /// [`Error::Io`]: #variant.Io
+    /// [`Error::Eof`]: #variant.Eof
+    /// [`Error::Encoding`]: #variant.Encoding
+    source: Box<Error>,
+  },
 }
 /// Результат операции сериализации или десериализации
-pub type Result<T> = result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Вид ошибки без ее подробностей. Позволяет сравнивать ошибки, например, в тестах:
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use serde_pod::from_bytes;
+/// # use serde_pod::error::ErrorKind;
+/// let err = from_bytes::<byteorder::LE, Option<u8>>(&[0x01]).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Unsupported);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+  /// Ошибка [`Error::Io`]. Доступна только при включенной функциональности `std`
+  ///
+  /// [`Error::Io`]: enum.Error.html#variant.Io
+  #[cfg(feature = "std")]
+  Io(io::ErrorKind),
+  /// Ошибка [`Error::Eof`]
+  ///
+  /// [`Error::Eof`]: enum.Error.html#variant.Eof
+  Eof,
+  /// Ошибка [`Error::Encoding`]
+  ///
+  /// [`Error::Encoding`]: enum.Error.html#variant.Encoding
+  Encoding,
+  /// Ошибка [`Error::Unmappable`]
+  ///
+  /// [`Error::Unmappable`]: enum.Error.html#variant.Unmappable
+  Unmappable,
+  /// Ошибка [`Error::Unknown`]
+  ///
+  /// [`Error::Unknown`]: enum.Error.html#variant.Unknown
+  Unknown,
+  /// Ошибка [`Error::Unsupported`]
+  ///
+  /// [`Error::Unsupported`]: enum.Error.html#variant.Unsupported
+  Unsupported,
+  /// Ошибка [`Error::InvalidLength`]
+  ///
+  /// [`Error::InvalidLength`]: enum.Error.html#variant.InvalidLength
+  InvalidLength,
+  /// Ошибка [`Error::TrailingBytes`]
+  ///
+  /// [`Error::TrailingBytes`]: enum.Error.html#variant.TrailingBytes
+  TrailingBytes,
+  /// Ошибка [`Error::ChecksumMismatch`]
+  ///
+  /// [`Error::ChecksumMismatch`]: enum.Error.html#variant.ChecksumMismatch
+  ChecksumMismatch,
+  /// Ошибка [`Error::MagicMismatch`]
+  ///
+  /// [`Error::MagicMismatch`]: enum.Error.html#variant.MagicMismatch
+  MagicMismatch,
+  /// Ошибка [`Error::Overflow`]
+  ///
+  /// [`Error::Overflow`]: enum.Error.html#variant.Overflow
+  Overflow,
+}
+
+impl Error {
+  /// Возвращает вид ошибки. Для ошибки [`Error::At`] возвращается вид исходной ошибки, поэтому
+  /// вид не зависит от того, известно ли смещение, на котором ошибка произошла
+  ///
+  /// [`Error::At`]: enum.Error.html#variant.At
+  pub fn kind(&self) -> ErrorKind {
+    match *self {
+      #[cfg(feature = "std")]
+      Error::Io(ref err) => ErrorKind::Io(err.kind()),
+      Error::Eof(_) => ErrorKind::Eof,
+      Error::Encoding(_) => ErrorKind::Encoding,
+      Error::Unmappable(_) => ErrorKind::Unmappable,
+      Error::Unknown(_) => ErrorKind::Unknown,
+      Error::Unsupported(_) => ErrorKind::Unsupported,
+      Error::InvalidLength(_) => ErrorKind::InvalidLength,
+      Error::TrailingBytes(_) => ErrorKind::TrailingBytes,
+      Error::ChecksumMismatch { .. } => ErrorKind::ChecksumMismatch,
+      Error::MagicMismatch { .. } => ErrorKind::MagicMismatch,
+      Error::Overflow(_) => ErrorKind::Overflow,
+      Error::At { ref source, .. } => source.kind(),
+    }
+  }
+}
 
 impl fmt::Display for Error {
   fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
     match *self {
+      #[cfg(feature = "std")]
       Error::Io(ref err) => err.fmt(fmt),
+      Error::Eof(Some(size)) => write!(fmt, "unexpected end of data while reading {} bytes", size),
+      Error::Eof(None) => fmt.write_str("unexpected end of data"),
       Error::Encoding(ref err) => err.fmt(fmt),
+      Error::Unmappable(c) => write!(fmt, "character {:?} cannot be encoded", c),
       Error::Unknown(ref msg) => msg.fmt(fmt),
       Error::Unsupported(ref msg) => msg.fmt(fmt),
+      Error::InvalidLength(ref msg) => msg.fmt(fmt),
+      Error::TrailingBytes(len) => write!(fmt, "{} trailing bytes after the value", len),
+      Error::ChecksumMismatch { stored, computed } => write!(fmt,
+        "checksum mismatch: stored {:#010X}, computed {:#010X}", stored, computed
+      ),
+      Error::MagicMismatch { ref expected, ref found } => write!(fmt,
+        "magic mismatch: expected {:?}, found {:?}",
+        String::from_utf8_lossy(expected), String::from_utf8_lossy(found)
+      ),
+      Error::Overflow(len) => write!(fmt, "length {} does not fit into usize on this platform", len),
+      Error::At { offset, ref source } => write!(fmt, "at offset {}: {}", offset, source),
     }
   }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for Error {
   fn source(&self) -> Option<&(dyn error::Error + 'static)> {
     match *self {
       Error::Io(ref err) => Some(err),
+      Error::Eof(_) => None,
       Error::Encoding(ref err) => Some(err),
+      Error::Unmappable(_) => None,
       Error::Unknown(_) => None,
       Error::Unsupported(_) => None,
+      Error::InvalidLength(_) => None,
+      Error::TrailingBytes(_) => None,
+      Error::ChecksumMismatch { .. } => None,
+      Error::MagicMismatch { .. } => None,
+      Error::Overflow(_) => None,
+      Error::At { ref source, .. } => Some(&**source),
     }
   }
 }
+// Без стандартной библиотеки serde использует собственный типаж ошибки
+#[cfg(not(feature = "std"))]
+impl ser::StdError for Error {}
 // Конвертация из ошибок сериализации сторонних типов
 impl ser::Error for Error {
   fn custom<T: fmt::Display>(msg: T) -> Self {
@@ -56,10 +224,28 @@ impl de::Error for Error {
   fn custom<T: fmt::Display>(msg: T) -> Self {
     Error::Unknown(msg.to_string())
   }
+  fn invalid_value(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+    // Длина, не помещающаяся в usize, обнаруженная типами-обертками этой библиотеки
+    #[cfg(feature = "std")]
+    if let de::Unexpected::Unsigned(len) = unexp {
+      if LengthOverflow::is(exp) {
+        return Error::Overflow(len);
+      }
+    }
+    Self::custom(format_args!("invalid value: {}, expected {}", unexp, exp))
+  }
 }
 // Конвертация из ошибок, связанных с чтением/записью из потока
+#[cfg(feature = "std")]
 impl From<io::Error> for Error {
   fn from(err: io::Error) -> Self {
+    // Попытка прочитать до конца потока, не поддерживающего буферизацию
+    if err.get_ref().is_some_and(|e| e.is::<NeedsBuffer>()) {
+      return Error::Unsupported(NeedsBuffer::MESSAGE);
+    }
+    if err.kind() == io::ErrorKind::UnexpectedEof {
+      return Error::Eof(None);
+    }
     Error::Io(err)
   }
 }
@@ -74,3 +260,34 @@ impl From<FromUtf8Error> for Error {
     Error::Encoding(err.utf8_error())
   }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod kind {
+  use std::io;
+  use super::{Error, ErrorKind};
+
+  #[test]
+  fn test_kind() {
+    assert_eq!(Error::Unsupported("a").kind(), Error::Unsupported("b").kind());
+    assert_eq!(Error::Unknown("a".into()).kind(), ErrorKind::Unknown);
+    assert_eq!(Error::Eof(Some(4)).kind(), Error::Eof(None).kind());
+    assert_ne!(Error::Eof(None).kind(), ErrorKind::Unsupported);
+    assert_eq!(Error::Encoding(String::from_utf8(vec![0xFF]).unwrap_err().utf8_error()).kind(), ErrorKind::Encoding);
+  }
+  /// Вид ошибок ввода-вывода включает их собственный вид
+  #[test]
+  fn test_io() {
+    let err = Error::from(io::Error::from(io::ErrorKind::NotFound));
+    assert_eq!(err.kind(), ErrorKind::Io(io::ErrorKind::NotFound));
+    assert_ne!(err.kind(), ErrorKind::Io(io::ErrorKind::PermissionDenied));
+  }
+  /// Смещение не влияет на вид ошибки
+  #[test]
+  fn test_located() {
+    let err = Error::At { offset: 3, source: Box::new(Error::Eof(Some(2))) };
+    assert_eq!(err.kind(), ErrorKind::Eof);
+    assert_eq!(err.to_string(), "at offset 3: unexpected end of data while reading 2 bytes");
+  }
+}
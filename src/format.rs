@@ -0,0 +1,275 @@
+//! Содержит вспомогательные функции для чтения и записи типовых элементов файловых форматов:
+//! сигнатур (магических чисел), версий, таблиц смещений секций и т.п.
+
+use std::convert::TryFrom;
+use std::io::{BufRead, Seek, Write};
+use byteorder::ByteOrder;
+use serde::de::{DeserializeOwned, DeserializeSeed};
+use serde::ser::Serialize;
+
+use de::Deserializer;
+use error::{Error, Result};
+use raw::{Raw, RawBuf};
+use ser::{Reservation, Serializer};
+
+/// Записывает в поток сигнатуру формата как есть, независимо от порядка байт
+///
+/// # Параметры
+/// - `ser`: Сериализатор, в который записывается сигнатура
+/// - `magic`: Байты сигнатуры
+pub fn write_magic<BO, W>(ser: &mut Serializer<BO, W>, magic: &[u8]) -> Result<()>
+  where BO: ByteOrder,
+        W: Write,
+{
+  Raw(magic).serialize(ser)
+}
+
+/// Читает из потока сигнатуру формата и проверяет, что она совпадает с ожидаемой
+///
+/// # Параметры
+/// - `de`: Десериализатор, из которого читается сигнатура
+/// - `magic`: Ожидаемые байты сигнатуры
+///
+/// # Ошибки
+/// [`Error::MagicMismatch`]: прочитанные байты не совпадают с ожидаемыми
+///
+/// [`Error::MagicMismatch`]: ../error/enum.Error.html#variant.MagicMismatch
+pub fn expect_magic<BO, R>(de: &mut Deserializer<BO, R>, magic: &[u8]) -> Result<()>
+  where BO: ByteOrder,
+        R: BufRead,
+{
+  let found = RawBuf(magic.len()).deserialize(de)?;
+  if found != magic {
+    return Err(Error::MagicMismatch { expected: magic.to_vec(), found });
+  }
+  Ok(())
+}
+
+/// Начало файла, состоящее из сигнатуры формата и следующей за ней версии. Версия может быть
+/// любым типом: числом, массивом байт и т.п.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::de::Deserializer;
+/// # use serde_pod::format::Preamble;
+/// # use serde_pod::ser::Serializer;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut buf = Vec::new();
+/// Preamble::write(&mut Serializer::<LE, _>::new(&mut buf), b"GUI ", b"V3.2")?;
+/// assert_eq!(buf, b"GUI V3.2");
+///
+/// let mut de = Deserializer::<LE, _>::new(&buf[..]);
+/// let version: [u8; 4] = Preamble::read(&mut de, b"GUI ")?;
+/// assert_eq!(&version, b"V3.2");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Preamble;
+
+impl Preamble {
+  /// Читает сигнатуру, проверяя, что она совпадает с ожидаемой, а затем версию типа `V`
+  ///
+  /// # Параметры
+  /// - `de`: Десериализатор, из которого читается начало файла
+  /// - `magic`: Ожидаемые байты сигнатуры
+  ///
+  /// # Ошибки
+  /// - [`Error::MagicMismatch`]: прочитанная сигнатура не совпадает с ожидаемой
+  /// - ошибки чтения версии
+  ///
+  /// [`Error::MagicMismatch`]: ../error/enum.Error.html#variant.MagicMismatch
+  pub fn read<BO, R, V>(de: &mut Deserializer<BO, R>, magic: &[u8]) -> Result<V>
+    where BO: ByteOrder,
+          R: BufRead,
+          V: DeserializeOwned,
+  {
+    expect_magic(de, magic)?;
+    V::deserialize(de)
+  }
+  /// Записывает сигнатуру, а затем версию
+  ///
+  /// # Параметры
+  /// - `ser`: Сериализатор, в который записывается начало файла
+  /// - `magic`: Байты сигнатуры
+  /// - `version`: Версия формата
+  pub fn write<BO, W, V>(ser: &mut Serializer<BO, W>, magic: &[u8], version: &V) -> Result<()>
+    where BO: ByteOrder,
+          W: Write,
+          V: ?Sized + Serialize,
+  {
+    write_magic(ser, magic)?;
+    version.serialize(ser)
+  }
+}
+
+/// Заглушка смещения секции, созданная функцией [`reserve_offset`] и заполняемая функцией
+/// [`patch_offset`], когда положение секции становится известно
+///
+/// [`reserve_offset`]: fn.reserve_offset.html
+/// [`patch_offset`]: fn.patch_offset.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[must_use = "reserved offset must be patched"]
+pub struct OffsetSlot(Reservation);
+
+impl OffsetSlot {
+  /// Возвращает положение самой заглушки от начала потока
+  #[inline]
+  pub fn position(&self) -> u64 { self.0.offset() }
+}
+
+/// Записывает в поток заглушку для смещения секции, которое еще не известно, например, в
+/// заголовке файла, за которым следуют секции. Заглушка занимает 4 байта (`u32`) и заполняется
+/// нулями до вызова [`patch_offset`].
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde;
+/// # extern crate serde_pod;
+/// # use std::io::Cursor;
+/// # use byteorder::LE;
+/// # use serde::Serialize;
+/// # use serde_pod::format::{patch_offset, reserve_offset};
+/// # use serde_pod::ser::Serializer;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut ser = Serializer::<LE, _>::seekable(Cursor::new(Vec::new()));
+/// let slot = reserve_offset(&mut ser)?;
+/// 0xAABBu16.serialize(&mut ser)?;
+/// patch_offset(&mut ser, slot)?;
+/// 0xCCu8.serialize(&mut ser)?;
+/// assert_eq!(ser.into_inner().into_inner(), [0x06, 0x00, 0x00, 0x00,   0xBB, 0xAA,   0xCC]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`patch_offset`]: fn.patch_offset.html
+pub fn reserve_offset<BO, W>(ser: &mut Serializer<BO, W>) -> Result<OffsetSlot>
+  where BO: ByteOrder,
+        W: Write + Seek,
+{
+  ser.reserve(4).map(OffsetSlot)
+}
+
+/// Заполняет заглушку смещения количеством байт, записанных сериализатором к текущему моменту,
+/// т.е. смещением от начала сериализации значения, которое будет записано следующим. Смещение
+/// записывается, как `u32` в порядке байт сериализатора, после чего запись продолжается
+/// с текущего места.
+///
+/// # Параметры
+/// - `ser`: Сериализатор, в который записана заглушка
+/// - `slot`: Заглушка, полученная от функции [`reserve_offset`]
+///
+/// # Ошибки
+/// Если смещение не помещается в `u32`, возвращается ошибка [`Error::InvalidLength`]
+///
+/// [`reserve_offset`]: fn.reserve_offset.html
+/// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+pub fn patch_offset<BO, W>(ser: &mut Serializer<BO, W>, slot: OffsetSlot) -> Result<()>
+  where BO: ByteOrder,
+        W: Write + Seek,
+{
+  let pos = ser.bytes_written();
+  let offset = u32::try_from(pos).map_err(|_| Error::InvalidLength(format!(
+    "offset {} does not fit into u32", pos
+  )))?;
+  ser.fill(slot.0, &offset)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use de::Deserializer;
+  use error::Error;
+  use std::io::Cursor;
+  use serde::Serialize;
+  use ser::Serializer;
+  use super::{expect_magic, patch_offset, reserve_offset, Preamble};
+
+  /// Сигнатура и версия в стиле GFF: `GUI V3.2`
+  #[test]
+  fn test_gff() {
+    let mut buf = Vec::new();
+    Preamble::write(&mut Serializer::<LE, _>::new(&mut buf), b"GUI ", b"V3.2").unwrap();
+    assert_eq!(buf, b"GUI V3.2");
+
+    let mut de = Deserializer::<LE, _>::new(&buf[..]);
+    let version: [u8; 4] = Preamble::read(&mut de, b"GUI ").unwrap();
+    assert_eq!(&version, b"V3.2");
+  }
+  /// Сигнатура не зависит от порядка байт, а числовая версия -- зависит
+  #[test]
+  fn test_numeric_version() {
+    let mut be = Vec::new();
+    Preamble::write(&mut Serializer::<BE, _>::new(&mut be), b"PK", &0x0102u16).unwrap();
+    assert_eq!(be, [0x50, 0x4B,   0x01, 0x02]);
+    let mut le = Vec::new();
+    Preamble::write(&mut Serializer::<LE, _>::new(&mut le), b"PK", &0x0102u16).unwrap();
+    assert_eq!(le, [0x50, 0x4B,   0x02, 0x01]);
+
+    let version: u16 = Preamble::read(&mut Deserializer::<BE, _>::new(&be[..]), b"PK").unwrap();
+    assert_eq!(version, 0x0102);
+    let version: u16 = Preamble::read(&mut Deserializer::<LE, _>::new(&le[..]), b"PK").unwrap();
+    assert_eq!(version, 0x0102);
+  }
+  #[test]
+  fn test_mismatch() {
+    let mut de = Deserializer::<LE, _>::new(&b"ERF V1.0"[..]);
+    match expect_magic(&mut de, b"GUI ") {
+      Err(Error::MagicMismatch { expected, found }) => {
+        assert_eq!(expected, b"GUI ");
+        assert_eq!(found, b"ERF ");
+      }
+      result => panic!("expected magic mismatch, but got {:?}", result),
+    }
+  }
+  #[test]
+  fn test_no_data() {
+    let mut de = Deserializer::<LE, _>::new(&b"GU"[..]);
+    assert!(expect_magic(&mut de, b"GUI ").is_err());
+  }
+  /// Заголовок с таблицей секций `(offset, count)` в стиле GFF заполняется после записи секций
+  #[test]
+  fn test_section_offsets() {
+    let structs = [0x1111u32, 0x2222, 0x3333];
+    let labels = [*b"Name", *b"Tag "];
+
+    let mut ser = Serializer::<LE, _>::seekable(Cursor::new(Vec::new()));
+    Preamble::write(&mut ser, b"GUI ", b"V3.2").unwrap();
+    let structs_at = reserve_offset(&mut ser).unwrap();
+    (structs.len() as u32).serialize(&mut ser).unwrap();
+    let labels_at = reserve_offset(&mut ser).unwrap();
+    (labels.len() as u32).serialize(&mut ser).unwrap();
+    assert_eq!(structs_at.position(), 8);
+    assert_eq!(labels_at.position(), 16);
+
+    patch_offset(&mut ser, structs_at).unwrap();
+    structs.serialize(&mut ser).unwrap();
+    patch_offset(&mut ser, labels_at).unwrap();
+    labels.serialize(&mut ser).unwrap();
+    assert_eq!(ser.bytes_written(), 24 + 12 + 8);
+
+    let data = ser.into_inner().into_inner();
+    assert_eq!(&data[..24], [
+      0x47, 0x55, 0x49, 0x20, 0x56, 0x33, 0x2E, 0x32,
+      0x18, 0x00, 0x00, 0x00,   0x03, 0x00, 0x00, 0x00,
+      0x24, 0x00, 0x00, 0x00,   0x02, 0x00, 0x00, 0x00,
+    ]);
+    assert_eq!(&data[24..28], [0x11, 0x11, 0x00, 0x00]);
+    assert_eq!(&data[36..], b"NameTag ");
+  }
+  /// Смещение записывается в порядке байт сериализатора
+  #[test]
+  fn test_offset_be() {
+    let mut ser = Serializer::<BE, _>::seekable(Cursor::new(Vec::new()));
+    let slot = reserve_offset(&mut ser).unwrap();
+    [0u8; 0x100].serialize(&mut ser).unwrap();
+    patch_offset(&mut ser, slot).unwrap();
+    assert_eq!(&ser.into_inner().into_inner()[..4], [0x00, 0x00, 0x01, 0x04]);
+  }
+}
@@ -0,0 +1,1145 @@
+//! Содержит типы, описывающие обрамление данных в потоке: префиксы длины и т.п.
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use serde::de::{self, Deserialize, DeserializeOwned, DeserializeSeed, Deserializer, Expected, SeqAccess, Unexpected, Visitor};
+use serde::ser::{self, Serialize, SerializeTuple, Serializer};
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+
+use magic;
+use raw::{capped_capacity, Raw, RawBuf};
+
+/// Целочисленный тип, используемый для записи в поток длины (количества элементов) данных.
+/// Записывается и читается в порядке байт сериализатора, как обычное число.
+///
+/// Длина, прочитанная из потока, никогда не усекается при преобразовании в `usize`: если она
+/// не может быть представлена типом `usize` на данной платформе (например, длина типа `u64`
+/// на 32-битной платформе), десериализация завершается ошибкой [`Error::Overflow`].
+///
+/// [`Error::Overflow`]: ../error/enum.Error.html#variant.Overflow
+pub trait Length: Copy + Into<u64> + Serialize + DeserializeOwned {
+  /// Преобразует длину в значение данного типа. Возвращает `None`, если длина не может быть
+  /// представлена данным типом
+  fn from_usize(len: usize) -> Option<Self>;
+  /// Преобразует значение данного типа в длину. Возвращает `None`, если значение не может быть
+  /// представлено типом `usize` на данной платформе
+  fn to_usize(self) -> Option<usize>;
+}
+
+/// Макрос, реализующий типаж `Length` для целочисленных типов
+macro_rules! impl_length {
+  ($($type:ty),*) => {
+    $(
+      impl Length for $type {
+        #[inline]
+        fn from_usize(len: usize) -> Option<Self> { <$type>::try_from(len).ok() }
+        #[inline]
+        fn to_usize(self) -> Option<usize> { usize::try_from(self).ok() }
+      }
+    )*
+  }
+}
+impl_length!(u8, u16, u32, u64);
+
+/// Описание ожидаемого значения в ошибке, возвращаемой [`length_overflow`]. Ошибка этой
+/// библиотеки распознает его по адресу единственного экземпляра [`LENGTH_OVERFLOW`] и
+/// преобразуется в [`Error::Overflow`]. Поле нужно только для того, чтобы тип имел ненулевой
+/// размер, иначе адрес экземпляра не был бы уникальным
+///
+/// [`length_overflow`]: fn.length_overflow.html
+/// [`LENGTH_OVERFLOW`]: static.LENGTH_OVERFLOW.html
+/// [`Error::Overflow`]: ../error/enum.Error.html#variant.Overflow
+pub(crate) struct LengthOverflow(#[allow(dead_code)] u8);
+impl LengthOverflow {
+  /// Возвращает `true`, если `exp` -- описание, созданное функцией [`length_overflow`]
+  ///
+  /// [`length_overflow`]: fn.length_overflow.html
+  #[inline]
+  pub fn is(exp: &dyn Expected) -> bool {
+    ptr::eq(exp as *const dyn Expected as *const u8, &LENGTH_OVERFLOW as *const LengthOverflow as *const u8)
+  }
+}
+impl Expected for LengthOverflow {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a length that fits into usize")
+  }
+}
+/// Единственный экземпляр описания ожидаемого значения длины
+pub(crate) static LENGTH_OVERFLOW: LengthOverflow = LengthOverflow(0);
+
+/// Создает ошибку десериализации, сообщающую, что длина `len` не помещается в `usize`
+#[inline]
+pub(crate) fn length_overflow<E: de::Error>(len: u64) -> E {
+  E::invalid_value(Unexpected::Unsigned(len), &LENGTH_OVERFLOW)
+}
+
+/// Преобразует длину, прочитанную из потока, в `usize` без усечения
+#[inline]
+pub(crate) fn checked_len<L: Length, E: de::Error>(len: L) -> Result<usize, E> {
+  len.to_usize().ok_or_else(|| length_overflow(len.into()))
+}
+
+/// Последовательность элементов с префиксом количества. Сначала в поток записывается количество
+/// элементов в виде числа типа `L` в порядке байт сериализатора, затем сами элементы, каждый в
+/// своем обычном представлении (например, структуры -- как последовательности своих полей).
+///
+/// При десериализации каждый элемент читается полностью перед чтением следующего. Количеству,
+/// прочитанному из потока, не доверяется: память под элементы заранее выделяется не более, чем
+/// для 4096 элементов, поэтому испорченный префикс приводит к ошибке чтения, а не к попытке
+/// выделить огромный буфер.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается количество элементов
+/// - `T`: обернутая последовательность, например, `Vec<E>`
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::framing::LengthPrefixed;
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Point { x: u8, y: u8 }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let points = LengthPrefixed::<u16, _>::new(vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]);
+/// let data = [0x02, 0x00,   0x01, 0x02,   0x03, 0x04];
+/// assert_eq!(to_vec::<LE, _>(&points)?, data);
+/// assert_eq!(from_bytes::<LE, LengthPrefixed<u16, Vec<Point>>>(&data)?, points);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LengthPrefixed<L, T> {
+  /// Обернутая последовательность
+  value: T,
+  /// Тип количества элементов
+  _marker: PhantomData<L>,
+}
+
+impl<L, T> LengthPrefixed<L, T> {
+  /// Оборачивает указанную последовательность
+  #[inline]
+  pub fn new(value: T) -> Self {
+    LengthPrefixed { value, _marker: PhantomData }
+  }
+  /// Возвращает обернутую последовательность
+  #[inline]
+  pub fn into_inner(self) -> T { self.value }
+}
+impl<L, E> LengthPrefixed<L, Vec<E>> {
+  /// Возвращает элементы обернутой последовательности
+  #[inline]
+  pub fn as_slice(&self) -> &[E] { &self.value }
+}
+impl<L, E> From<Vec<E>> for LengthPrefixed<L, Vec<E>> {
+  #[inline]
+  fn from(value: Vec<E>) -> Self { Self::new(value) }
+}
+
+impl<L: Length, E: Serialize> Serialize for LengthPrefixed<L, Vec<E>> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(self.value.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "sequence of {} elements is too long for its length prefix", self.value.len()
+    )))?;
+    let mut tuple = serializer.serialize_tuple(1 + self.value.len())?;
+    tuple.serialize_element(&len)?;
+    for e in &self.value {
+      tuple.serialize_element(e)?;
+    }
+    tuple.end()
+  }
+}
+impl<'de, L: Length, E: Deserialize<'de>> Deserialize<'de> for LengthPrefixed<L, Vec<E>> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(2, PrefixedVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий количество элементов, а затем сами элементы
+struct PrefixedVisitor<L, E>(PhantomData<(L, E)>);
+impl<'de, L: Length, E: Deserialize<'de>> Visitor<'de> for PrefixedVisitor<L, E> {
+  type Value = LengthPrefixed<L, Vec<E>>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a length-prefixed sequence")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let len: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let len = checked_len(len)?;
+    let elements = seq.next_element_seed(Elements(len, PhantomData))?
+      .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    Ok(LengthPrefixed::new(elements))
+  }
+}
+
+/// Отображение с префиксом количества записей, сохраняющее порядок записей в потоке. Сначала
+/// в поток записывается количество записей в виде числа типа `L` в порядке байт сериализатора,
+/// затем пары ключ-значение в порядке их следования в [`IndexMap`].
+///
+/// Если в потоке встречаются повторяющиеся ключи, десериализация завершается ошибкой.
+///
+/// Доступно только при включенной функциональности `indexmap`.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate indexmap;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use indexmap::IndexMap;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::framing::LengthPrefixed;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut map = IndexMap::new();
+/// map.insert(9u8, 0x0102u16);
+/// map.insert(1u8, 0x0304u16);
+/// let map = LengthPrefixed::<u8, _>::new(map);
+/// let data = [0x02,   0x09, 0x02, 0x01,   0x01, 0x04, 0x03];
+/// assert_eq!(to_vec::<LE, _>(&map)?, data);
+/// assert_eq!(from_bytes::<LE, LengthPrefixed<u8, IndexMap<u8, u16>>>(&data)?, map);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`IndexMap`]: https://docs.rs/indexmap/2/indexmap/map/struct.IndexMap.html
+#[cfg(feature = "indexmap")]
+impl<L, K: Eq + Hash, V> LengthPrefixed<L, IndexMap<K, V>> {
+  /// Возвращает обернутое отображение
+  #[inline]
+  pub fn as_map(&self) -> &IndexMap<K, V> { &self.value }
+}
+#[cfg(feature = "indexmap")]
+impl<L, K: Eq + Hash, V> From<IndexMap<K, V>> for LengthPrefixed<L, IndexMap<K, V>> {
+  #[inline]
+  fn from(value: IndexMap<K, V>) -> Self { Self::new(value) }
+}
+#[cfg(feature = "indexmap")]
+impl<L, K, V> Serialize for LengthPrefixed<L, IndexMap<K, V>>
+  where L: Length,
+        K: Eq + Hash + Serialize,
+        V: Serialize,
+{
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(self.value.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "map of {} entries is too long for its length prefix", self.value.len()
+    )))?;
+    let mut tuple = serializer.serialize_tuple(1 + self.value.len())?;
+    tuple.serialize_element(&len)?;
+    for entry in &self.value {
+      tuple.serialize_element(&entry)?;
+    }
+    tuple.end()
+  }
+}
+#[cfg(feature = "indexmap")]
+impl<'de, L, K, V> Deserialize<'de> for LengthPrefixed<L, IndexMap<K, V>>
+  where L: Length,
+        K: Eq + Hash + Deserialize<'de>,
+        V: Deserialize<'de>,
+{
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let entries = LengthPrefixed::<L, Vec<(K, V)>>::deserialize(deserializer)?.into_inner();
+    let mut map = IndexMap::with_capacity(entries.len());
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+      if map.insert(k, v).is_some() {
+        return Err(de::Error::custom(format_args!("duplicate key at index {}", i)));
+      }
+    }
+    Ok(LengthPrefixed::new(map))
+  }
+}
+
+/// Читает из потока указанное количество элементов типа `E`
+struct Elements<E>(usize, PhantomData<E>);
+impl<'de, E: Deserialize<'de>> DeserializeSeed<'de> for Elements<E> {
+  type Value = Vec<E>;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_tuple(self.0, self)
+  }
+}
+impl<'de, E: Deserialize<'de>> Visitor<'de> for Elements<E> {
+  type Value = Vec<E>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "{} elements", self.0)
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut vec = Vec::with_capacity(capped_capacity(self.0));
+    for i in 0..self.0 {
+      vec.push(seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?);
+    }
+    Ok(vec)
+  }
+}
+
+/// Отображение, записываемое в виде параллельных массивов: сначала количество записей в виде
+/// числа типа `L` в порядке байт сериализатора, затем все ключи, затем все значения в том же
+/// порядке. Порядок записей в потоке определяется порядком обхода [`HashMap`].
+///
+/// Если в потоке встречаются повторяющиеся ключи, при `LAST_WINS == false` (по умолчанию)
+/// десериализация завершается ошибкой, иначе используется значение последней записи с этим ключом.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается количество записей
+/// - `K`: тип ключей
+/// - `V`: тип значений
+/// - `LAST_WINS`: разрешены ли повторяющиеся ключи
+///
+/// [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParallelMap<L, K: Eq + Hash, V, const LAST_WINS: bool = false> {
+  /// Обернутое отображение
+  value: HashMap<K, V>,
+  /// Тип количества записей
+  _marker: PhantomData<L>,
+}
+
+impl<L, K: Eq + Hash, V, const LAST_WINS: bool> ParallelMap<L, K, V, LAST_WINS> {
+  /// Оборачивает указанное отображение
+  #[inline]
+  pub fn new(value: HashMap<K, V>) -> Self {
+    ParallelMap { value, _marker: PhantomData }
+  }
+  /// Возвращает обернутое отображение
+  #[inline]
+  pub fn as_map(&self) -> &HashMap<K, V> { &self.value }
+  /// Возвращает обернутое отображение
+  #[inline]
+  pub fn into_inner(self) -> HashMap<K, V> { self.value }
+}
+impl<L, K: Eq + Hash, V, const LAST_WINS: bool> From<HashMap<K, V>> for ParallelMap<L, K, V, LAST_WINS> {
+  #[inline]
+  fn from(value: HashMap<K, V>) -> Self { Self::new(value) }
+}
+
+impl<L, K, V, const LAST_WINS: bool> Serialize for ParallelMap<L, K, V, LAST_WINS>
+  where L: Length,
+        K: Eq + Hash + Serialize,
+        V: Serialize,
+{
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(self.value.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "map of {} entries is too long for its length prefix", self.value.len()
+    )))?;
+    let mut tuple = serializer.serialize_tuple(1 + 2 * self.value.len())?;
+    tuple.serialize_element(&len)?;
+    // Отображение не изменяется, поэтому порядок обхода ключей и значений совпадает
+    for k in self.value.keys() {
+      tuple.serialize_element(k)?;
+    }
+    for v in self.value.values() {
+      tuple.serialize_element(v)?;
+    }
+    tuple.end()
+  }
+}
+impl<'de, L, K, V, const LAST_WINS: bool> Deserialize<'de> for ParallelMap<L, K, V, LAST_WINS>
+  where L: Length,
+        K: Eq + Hash + Deserialize<'de>,
+        V: Deserialize<'de>,
+{
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(3, ParallelVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий количество записей, затем ключи, затем значения
+struct ParallelVisitor<L, K, V, const LAST_WINS: bool>(PhantomData<(L, K, V)>);
+impl<'de, L, K, V, const LAST_WINS: bool> Visitor<'de> for ParallelVisitor<L, K, V, LAST_WINS>
+  where L: Length,
+        K: Eq + Hash + Deserialize<'de>,
+        V: Deserialize<'de>,
+{
+  type Value = ParallelMap<L, K, V, LAST_WINS>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a count followed by parallel arrays of keys and values")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let len: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let len = checked_len(len)?;
+    let keys: Vec<K> = seq.next_element_seed(Elements(len, PhantomData))?
+      .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    let values: Vec<V> = seq.next_element_seed(Elements(len, PhantomData))?
+      .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+    let mut map = HashMap::with_capacity(len);
+    for (i, (k, v)) in keys.into_iter().zip(values).enumerate() {
+      if map.insert(k, v).is_some() && !LAST_WINS {
+        return Err(de::Error::custom(format_args!("duplicate key at index {}", i)));
+      }
+    }
+    Ok(ParallelMap::new(map))
+  }
+}
+
+/// Отображение с префиксом количества записей, упорядоченными по ключу. Сначала в поток
+/// записывается количество записей в виде числа типа `L` в порядке байт сериализатора, затем
+/// пары ключ-значение в порядке возрастания ключей, каждая -- как кортеж `(K, V)`. Благодаря
+/// упорядоченности одно и то же отображение всегда записывается одинаково.
+///
+/// Если в потоке встречаются повторяющиеся ключи, десериализация завершается ошибкой. Записи
+/// в потоке могут следовать в любом порядке.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается количество записей
+/// - `K`: тип ключей
+/// - `V`: тип значений
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use std::collections::BTreeMap;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::framing::CountedMap;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut map = BTreeMap::new();
+/// map.insert(9u8, 0x0102u16);
+/// map.insert(1u8, 0x0304u16);
+/// let map = CountedMap::<u8, _, _>::new(map);
+/// let data = [0x02,   0x01, 0x04, 0x03,   0x09, 0x02, 0x01];
+/// assert_eq!(to_vec::<LE, _>(&map)?, data);
+/// assert_eq!(from_bytes::<LE, CountedMap<u8, u8, u16>>(&data)?, map);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CountedMap<L, K, V> {
+  /// Обернутое отображение
+  value: BTreeMap<K, V>,
+  /// Тип количества записей
+  _marker: PhantomData<L>,
+}
+
+impl<L, K, V> CountedMap<L, K, V> {
+  /// Оборачивает указанное отображение
+  #[inline]
+  pub fn new(value: BTreeMap<K, V>) -> Self {
+    CountedMap { value, _marker: PhantomData }
+  }
+  /// Возвращает обернутое отображение
+  #[inline]
+  pub fn as_map(&self) -> &BTreeMap<K, V> { &self.value }
+  /// Возвращает обернутое отображение
+  #[inline]
+  pub fn into_inner(self) -> BTreeMap<K, V> { self.value }
+  /// Возвращает записи отображения в виде [`HashMap`]
+  ///
+  /// [`HashMap`]: https://doc.rust-lang.org/std/collections/struct.HashMap.html
+  #[inline]
+  pub fn into_hash_map(self) -> HashMap<K, V> where K: Eq + Hash {
+    self.value.into_iter().collect()
+  }
+}
+impl<L, K, V> From<BTreeMap<K, V>> for CountedMap<L, K, V> {
+  #[inline]
+  fn from(value: BTreeMap<K, V>) -> Self { Self::new(value) }
+}
+impl<L, K: Ord, V> From<HashMap<K, V>> for CountedMap<L, K, V> {
+  #[inline]
+  fn from(value: HashMap<K, V>) -> Self { Self::new(value.into_iter().collect()) }
+}
+
+impl<L, K, V> Serialize for CountedMap<L, K, V>
+  where K: Serialize,
+        V: Serialize,
+        L: Length,
+{
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(self.value.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "map of {} entries is too long for its length prefix", self.value.len()
+    )))?;
+    let mut tuple = serializer.serialize_tuple(1 + self.value.len())?;
+    tuple.serialize_element(&len)?;
+    for entry in &self.value {
+      tuple.serialize_element(&entry)?;
+    }
+    tuple.end()
+  }
+}
+impl<'de, L, K, V> Deserialize<'de> for CountedMap<L, K, V>
+  where K: Ord + Deserialize<'de>,
+        V: Deserialize<'de>,
+        L: Length,
+{
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let entries = LengthPrefixed::<L, Vec<(K, V)>>::deserialize(deserializer)?.into_inner();
+    let mut map = BTreeMap::new();
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+      if map.insert(k, v).is_some() {
+        return Err(de::Error::custom(format_args!("duplicate key at index {}", i)));
+      }
+    }
+    Ok(CountedMap::new(map))
+  }
+}
+
+/// Последовательность элементов, за которой следует их количество, записанное в виде числа
+/// типа `L` в порядке байт сериализатора. Такая последовательность должна быть последней в
+/// потоке: при десериализации поток читается до конца, количество отделяется от его последних
+/// байт, а элементы читаются из оставшихся. Прочитанное количество элементов сверяется с
+/// записанным, и при несовпадении возвращается ошибка.
+///
+/// Чтобы расположить последовательность в середине потока, ограничьте поток, например, с помощью
+/// [`Deserializer::bounded`]. Десериализатор этого крейта буферизует все данные до конца потока.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается количество элементов
+/// - `T`: обернутая последовательность, например, `Vec<E>`
+///
+/// [`Deserializer::bounded`]: ../de/struct.Deserializer.html#method.bounded
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TrailerCounted<L, T> {
+  /// Обернутая последовательность
+  value: T,
+  /// Тип количества элементов
+  _marker: PhantomData<L>,
+}
+
+impl<L, T> TrailerCounted<L, T> {
+  /// Оборачивает указанную последовательность
+  #[inline]
+  pub fn new(value: T) -> Self {
+    TrailerCounted { value, _marker: PhantomData }
+  }
+  /// Возвращает обернутую последовательность
+  #[inline]
+  pub fn into_inner(self) -> T { self.value }
+}
+impl<L, E> TrailerCounted<L, Vec<E>> {
+  /// Возвращает элементы обернутой последовательности
+  #[inline]
+  pub fn as_slice(&self) -> &[E] { &self.value }
+}
+impl<L, E> From<Vec<E>> for TrailerCounted<L, Vec<E>> {
+  #[inline]
+  fn from(value: Vec<E>) -> Self { Self::new(value) }
+}
+
+impl<L: Length, E: Serialize> Serialize for TrailerCounted<L, Vec<E>> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(self.value.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "sequence of {} elements is too long for its count", self.value.len()
+    )))?;
+    let mut tuple = serializer.serialize_tuple(self.value.len() + 1)?;
+    for e in &self.value {
+      tuple.serialize_element(e)?;
+    }
+    tuple.serialize_element(&len)?;
+    tuple.end()
+  }
+}
+impl<'de, L: Length, E: Deserialize<'de>> Deserialize<'de> for TrailerCounted<L, Vec<E>> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let visitor = TrailerVisitor(PhantomData);
+    deserializer.deserialize_tuple_struct(magic::TRAILER_COUNTED, mem::size_of::<L>(), visitor)
+  }
+}
+
+/// Посетитель, читающий количество элементов, а затем элементы до конца данных
+struct TrailerVisitor<L, E>(PhantomData<(L, E)>);
+impl<'de, L: Length, E: Deserialize<'de>> Visitor<'de> for TrailerVisitor<L, E> {
+  type Value = TrailerCounted<L, Vec<E>>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a sequence followed by its element count")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let count: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let mut vec = Vec::new();
+    while let Some(e) = seq.next_element()? {
+      vec.push(e);
+    }
+    if count.to_usize() != Some(vec.len()) {
+      return Err(de::Error::custom(format_args!(
+        "sequence of {} elements does not match its count", vec.len()
+      )));
+    }
+    Ok(TrailerCounted::new(vec))
+  }
+}
+
+/// Массив байт с префиксом длины, дополненный нулями до границы `ALIGN` байт, как чанки RIFF/WAV.
+/// Сначала в поток записывается длина массива в байтах в виде числа типа `L` в порядке байт
+/// сериализатора, затем сами байты, затем дополнение. Граница отсчитывается от начала блока, т.е.
+/// от начала префикса длины, поэтому блок целиком занимает в потоке кратное `ALIGN` число байт.
+///
+/// Байты массива записываются одной операцией методом `serialize_bytes` сериализатора и читаются
+/// одной операцией чтения, как у [`PrefixedBytes`]. При десериализации байты дополнения
+/// пропускаются без проверки. Значения `ALIGN`, равные 0 и 1, означают отсутствие выравнивания.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается длина массива (без учета дополнения)
+/// - `ALIGN`: граница выравнивания блока в байтах
+///
+/// [`PrefixedBytes`]: struct.PrefixedBytes.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AlignedBlob<L, const ALIGN: usize> {
+  /// Обернутые байты
+  value: Vec<u8>,
+  /// Тип длины
+  _marker: PhantomData<L>,
+}
+
+impl<L, const ALIGN: usize> AlignedBlob<L, ALIGN> {
+  /// Оборачивает указанные байты
+  #[inline]
+  pub fn new<B: Into<Vec<u8>>>(value: B) -> Self {
+    AlignedBlob { value: value.into(), _marker: PhantomData }
+  }
+  /// Возвращает обернутые байты
+  #[inline]
+  pub fn as_slice(&self) -> &[u8] { &self.value }
+  /// Возвращает обернутые байты
+  #[inline]
+  pub fn into_inner(self) -> Vec<u8> { self.value }
+  /// Возвращает количество байт дополнения после массива из `len` байт
+  fn padding(len: usize) -> usize {
+    if ALIGN <= 1 {
+      return 0;
+    }
+    let block = (mem::size_of::<L>() + len) % ALIGN;
+    if block == 0 { 0 } else { ALIGN - block }
+  }
+}
+impl<L, const ALIGN: usize> From<Vec<u8>> for AlignedBlob<L, ALIGN> {
+  #[inline]
+  fn from(value: Vec<u8>) -> Self { Self::new(value) }
+}
+
+impl<L: Length, const ALIGN: usize> Serialize for AlignedBlob<L, ALIGN> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(self.value.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "blob of {} bytes is too long for its length prefix", self.value.len()
+    )))?;
+    let padding = vec![0; Self::padding(self.value.len())];
+    let mut tuple = serializer.serialize_tuple(3)?;
+    tuple.serialize_element(&len)?;
+    tuple.serialize_element(&ByteSlice(&self.value))?;
+    tuple.serialize_element(&Raw(&padding))?;
+    tuple.end()
+  }
+}
+impl<'de, L: Length, const ALIGN: usize> Deserialize<'de> for AlignedBlob<L, ALIGN> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(3, AlignedVisitor(PhantomData))
+  }
+}
+
+/// Записывает байты одной операцией методом `serialize_bytes`
+struct ByteSlice<'a>(&'a [u8]);
+impl<'a> Serialize for ByteSlice<'a> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(magic::RAW_BYTES, &RawSlice(self.0))
+  }
+}
+/// Байты, передаваемые сериализатору методом `serialize_bytes`
+struct RawSlice<'a>(&'a [u8]);
+impl<'a> Serialize for RawSlice<'a> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(self.0)
+  }
+}
+
+/// Читает указанное количество байт одной операцией чтения и получает их от десериализатора
+/// методом `visit_byte_buf` посетителя, без копирования
+struct ByteBuf(usize);
+impl<'de> DeserializeSeed<'de> for ByteBuf {
+  type Value = Vec<u8>;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_tuple_struct(magic::RAW_BYTES, self.0, self)
+  }
+}
+impl<'de> Visitor<'de> for ByteBuf {
+  type Value = Vec<u8>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "{} bytes", self.0)
+  }
+  fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+    if v.len() != self.0 {
+      return Err(de::Error::invalid_length(v.len(), &self));
+    }
+    Ok(v)
+  }
+  fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+    RawBuf(self.0).visit_bytes(v)
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+    RawBuf(self.0).visit_seq(seq)
+  }
+}
+
+/// Посетитель, читающий длину массива, байты массива и дополнение до границы выравнивания
+struct AlignedVisitor<L, const ALIGN: usize>(PhantomData<L>);
+impl<'de, L: Length, const ALIGN: usize> Visitor<'de> for AlignedVisitor<L, ALIGN> {
+  type Value = AlignedBlob<L, ALIGN>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    if ALIGN <= 1 {
+      return fmt.write_str("a length-prefixed byte blob");
+    }
+    write!(fmt, "a length-prefixed byte blob aligned to {} bytes", ALIGN)
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let len: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let len = checked_len(len)?;
+    let bytes = seq.next_element_seed(ByteBuf(len))?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    let padding = AlignedBlob::<L, ALIGN>::padding(len);
+    seq.next_element_seed(RawBuf(padding))?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+    Ok(AlignedBlob::new(bytes))
+  }
+}
+
+/// Массив байт с префиксом длины без выравнивания. Сначала в поток записывается длина массива
+/// в байтах в виде числа типа `L` в порядке байт сериализатора, затем сами байты.
+///
+/// В отличие от [`LengthPrefixed<L, Vec<u8>>`], байты записываются одной операцией методом
+/// `serialize_bytes` сериализатора, а читаются одной операцией чтения и передаются посетителю
+/// методом `visit_byte_buf`, а не по одному, что существенно быстрее для больших массивов.
+/// Представление в потоке у них совпадает.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается длина массива
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::framing::PrefixedBytes;
+/// # fn main() -> serde_pod::Result<()> {
+/// let blob = PrefixedBytes::<u16>::new(&b"abc"[..]);
+/// let data = [0x00, 0x03,   b'a', b'b', b'c'];
+/// assert_eq!(to_vec::<BE, _>(&blob)?, data);
+/// assert_eq!(from_bytes::<BE, PrefixedBytes<u16>>(&data)?, blob);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`LengthPrefixed<L, Vec<u8>>`]: struct.LengthPrefixed.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PrefixedBytes<L>(pub Vec<u8>, PhantomData<L>);
+
+impl<L> PrefixedBytes<L> {
+  /// Оборачивает указанные байты
+  #[inline]
+  pub fn new<B: Into<Vec<u8>>>(value: B) -> Self {
+    PrefixedBytes(value.into(), PhantomData)
+  }
+  /// Возвращает обернутые байты
+  #[inline]
+  pub fn as_slice(&self) -> &[u8] { &self.0 }
+  /// Возвращает обернутые байты
+  #[inline]
+  pub fn into_inner(self) -> Vec<u8> { self.0 }
+}
+impl<L> From<Vec<u8>> for PrefixedBytes<L> {
+  #[inline]
+  fn from(value: Vec<u8>) -> Self { Self::new(value) }
+}
+
+impl<L: Length> Serialize for PrefixedBytes<L> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(self.0.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "blob of {} bytes is too long for its length prefix", self.0.len()
+    )))?;
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&len)?;
+    tuple.serialize_element(&ByteSlice(&self.0))?;
+    tuple.end()
+  }
+}
+impl<'de, L: Length> Deserialize<'de> for PrefixedBytes<L> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(2, BytesVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий длину массива и байты массива
+struct BytesVisitor<L>(PhantomData<L>);
+impl<'de, L: Length> Visitor<'de> for BytesVisitor<L> {
+  type Value = PrefixedBytes<L>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a length-prefixed byte blob")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let len: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let len = checked_len(len)?;
+    let bytes = seq.next_element_seed(ByteBuf(len))?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    Ok(PrefixedBytes::new(bytes))
+  }
+}
+
+/// Читает байт типа, в битовом поле которого упаковано количество следующих за ним элементов
+/// типа `T`, а затем сами элементы. Количество извлекается из байта, как `(byte & mask) >> shift`.
+/// Результатом является прочитанный байт типа целиком (чтобы можно было разобрать остальные его
+/// биты) и вектор элементов.
+///
+/// Такое представление невозможно описать производными реализациями, поэтому оно читается с
+/// помощью [`DeserializeSeed`]. Если количество известно заранее, используйте
+/// [`Deserializer::read_counted_by`].
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde::de::DeserializeSeed;
+/// # use serde_pod::de::Deserializer;
+/// # use serde_pod::framing::CountInBits;
+/// // Старшие 3 бита -- количество элементов, младшие 5 бит -- вид записи
+/// let data = [0b010_00111, 0x12, 0x34, 0x56, 0x78];
+/// let mut de = Deserializer::<BE, _>::new(&data[..]);
+/// let (kind, values) = CountInBits::<u16>::new(0b1110_0000, 5).deserialize(&mut de).unwrap();
+/// assert_eq!(kind & 0b0001_1111, 7);
+/// assert_eq!(values, [0x1234, 0x5678]);
+/// ```
+///
+/// [`DeserializeSeed`]: https://docs.serde.rs/serde/de/trait.DeserializeSeed.html
+/// [`Deserializer::read_counted_by`]: ../de/struct.Deserializer.html#method.read_counted_by
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CountInBits<T> {
+  /// Маска битов байта типа, в которых хранится количество элементов
+  mask: u8,
+  /// Сдвиг вправо, применяемый к замаскированному байту типа
+  shift: u32,
+  /// Тип элементов
+  _marker: PhantomData<T>,
+}
+
+impl<T> CountInBits<T> {
+  /// Создает помощник, извлекающий количество элементов из байта типа, как `(byte & mask) >> shift`
+  #[inline]
+  pub fn new(mask: u8, shift: u32) -> Self {
+    CountInBits { mask, shift, _marker: PhantomData }
+  }
+  /// Извлекает количество элементов из байта типа
+  #[inline]
+  pub fn count(&self, byte: u8) -> usize {
+    (byte & self.mask).checked_shr(self.shift).unwrap_or(0) as usize
+  }
+}
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for CountInBits<T> {
+  type Value = (u8, Vec<T>);
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    deserializer.deserialize_tuple(2, self)
+  }
+}
+impl<'de, T: Deserialize<'de>> Visitor<'de> for CountInBits<T> {
+  type Value = (u8, Vec<T>);
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a type byte with an element count in its bits followed by elements")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let byte: u8 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let elements = seq.next_element_seed(Elements(self.count(byte), PhantomData))?
+      .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    Ok((byte, elements))
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use std::collections::{BTreeMap, HashMap};
+  use byteorder::{BE, LE};
+  use serde::Deserialize;
+  use serde::de::DeserializeSeed;
+  use de::{from_bytes, Deserializer};
+  use error::Error;
+  use ser::to_vec;
+  use super::{length_overflow, AlignedBlob, CountInBits, CountedMap, LengthPrefixed, ParallelMap, PrefixedBytes, TrailerCounted};
+
+  /// Описание секции в стиле заголовков исполняемых файлов
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Section {
+    name: [u8; 4],
+    offset: u32,
+    size: u16,
+  }
+
+  fn sections() -> LengthPrefixed<u16, Vec<Section>> {
+    LengthPrefixed::new(vec![
+      Section { name: *b"text", offset: 0x0000_0040, size: 0x0100 },
+      Section { name: *b"data", offset: 0x0000_0140, size: 0x0020 },
+      Section { name: *b"bss ", offset: 0x0000_0160, size: 0x0000 },
+    ])
+  }
+
+  #[test]
+  fn test_sections() {
+    let be = [
+      0x00, 0x03,
+      b't', b'e', b'x', b't',   0x00, 0x00, 0x00, 0x40,   0x01, 0x00,
+      b'd', b'a', b't', b'a',   0x00, 0x00, 0x01, 0x40,   0x00, 0x20,
+      b'b', b's', b's', b' ',   0x00, 0x00, 0x01, 0x60,   0x00, 0x00,
+    ];
+    let le = [
+      0x03, 0x00,
+      b't', b'e', b'x', b't',   0x40, 0x00, 0x00, 0x00,   0x00, 0x01,
+      b'd', b'a', b't', b'a',   0x40, 0x01, 0x00, 0x00,   0x20, 0x00,
+      b'b', b's', b's', b' ',   0x60, 0x01, 0x00, 0x00,   0x00, 0x00,
+    ];
+    assert_eq!(to_vec::<BE,_>(&sections()).unwrap(), &be[..]);
+    assert_eq!(to_vec::<LE,_>(&sections()).unwrap(), &le[..]);
+    assert_eq!(from_bytes::<BE, LengthPrefixed<u16, Vec<Section>>>(&be).unwrap(), sections());
+    assert_eq!(from_bytes::<LE, LengthPrefixed<u16, Vec<Section>>>(&le).unwrap(), sections());
+  }
+  /// Префикс количества и сами элементы записываются в порядке байт сериализатора
+  #[test]
+  fn test_u32_prefix() {
+    let value = LengthPrefixed::<u32, _>::new(vec![0x0102u16, 0x0304]);
+    let be = [0x00, 0x00, 0x00, 0x02,   0x01, 0x02,   0x03, 0x04];
+    let le = [0x02, 0x00, 0x00, 0x00,   0x02, 0x01,   0x04, 0x03];
+    assert_eq!(to_vec::<BE,_>(&value).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&value).unwrap(), le);
+    assert_eq!(from_bytes::<BE, LengthPrefixed<u32, Vec<u16>>>(&be).unwrap(), value);
+    assert_eq!(from_bytes::<LE, LengthPrefixed<u32, Vec<u16>>>(&le).unwrap(), value);
+  }
+  #[test]
+  fn test_empty() {
+    let empty = LengthPrefixed::<u8, Vec<Section>>::new(Vec::new());
+    assert_eq!(to_vec::<BE,_>(&empty).unwrap(), [0x00]);
+    assert_eq!(from_bytes::<BE, LengthPrefixed<u8, Vec<Section>>>(&[0x00]).unwrap(), empty);
+  }
+  /// Огромное количество элементов не приводит к выделению памяти под них
+  #[test]
+  fn test_hostile_count() {
+    let data = [0xFF, 0xFF, 0xFF, 0xFF,   b't', b'e', b'x', b't'];
+    assert!(from_bytes::<BE, LengthPrefixed<u32, Vec<Section>>>(&data).is_err());
+  }
+  /// Ключи и значения записываются отдельными массивами в одном и том же порядке
+  #[test]
+  fn test_parallel_map() {
+    let map: HashMap<u8, u16> = vec![(1, 0x1111), (2, 0x2222), (3, 0x3333)].into_iter().collect();
+    let test = ParallelMap::<u16, _, _>::new(map);
+    let data = [0x00, 0x03,   0x02, 0x01, 0x03,   0x22, 0x22, 0x11, 0x11, 0x33, 0x33];
+    assert_eq!(from_bytes::<BE, ParallelMap<u16, u8, u16>>(&data).unwrap(), test);
+
+    let be = to_vec::<BE,_>(&test).unwrap();
+    assert_eq!(be.len(), 11);
+    assert_eq!(&be[..2], [0x00, 0x03]);
+    for i in 0..3 {
+      assert_eq!(be[5 + 2 * i], be[2 + i] * 0x11);
+    }
+    assert_eq!(from_bytes::<BE, ParallelMap<u16, u8, u16>>(&be).unwrap(), test);
+    let le = to_vec::<LE,_>(&test).unwrap();
+    assert_eq!(from_bytes::<LE, ParallelMap<u16, u8, u16>>(&le).unwrap(), test);
+  }
+  /// Повторяющиеся ключи по умолчанию являются ошибкой
+  #[test]
+  fn test_parallel_map_duplicates() {
+    let data = [0x02,   0x01, 0x01,   0xAA, 0xBB];
+    assert!(from_bytes::<BE, ParallelMap<u8, u8, u8>>(&data).is_err());
+    let map = from_bytes::<BE, ParallelMap<u8, u8, u8, true>>(&data).unwrap().into_inner();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map[&1], 0xBB);
+  }
+  /// Записи упорядочены по ключу, каждая записывается как кортеж ключа и значения
+  #[test]
+  fn test_counted_map() {
+    let map: BTreeMap<u16, u32> = vec![(3, 0x3333_3333), (1, 0x1111_1111), (2, 0x0000_2222)].into_iter().collect();
+    let test = CountedMap::<u32, _, _>::new(map.clone());
+    let be = [
+      0x00, 0x00, 0x00, 0x03,
+      0x00, 0x01,   0x11, 0x11, 0x11, 0x11,
+      0x00, 0x02,   0x00, 0x00, 0x22, 0x22,
+      0x00, 0x03,   0x33, 0x33, 0x33, 0x33,
+    ];
+    let le = [
+      0x03, 0x00, 0x00, 0x00,
+      0x01, 0x00,   0x11, 0x11, 0x11, 0x11,
+      0x02, 0x00,   0x22, 0x22, 0x00, 0x00,
+      0x03, 0x00,   0x33, 0x33, 0x33, 0x33,
+    ];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, CountedMap<u32, u16, u32>>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, CountedMap<u32, u16, u32>>(&le).unwrap(), test);
+
+    let hash_map: HashMap<u16, u32> = map.into_iter().collect();
+    assert_eq!(to_vec::<BE,_>(&CountedMap::<u32, _, _>::from(hash_map.clone())).unwrap(), be);
+    assert_eq!(from_bytes::<BE, CountedMap<u32, u16, u32>>(&be).unwrap().into_hash_map(), hash_map);
+  }
+  /// Записи в потоке могут быть не упорядочены, но повторяющиеся ключи являются ошибкой
+  #[test]
+  fn test_counted_map_order() {
+    let data = [0x02,   0x00, 0x02, 0x00, 0x00, 0x00, 0x02,   0x00, 0x01, 0x00, 0x00, 0x00, 0x01];
+    let map = from_bytes::<BE, CountedMap<u8, u16, u32>>(&data).unwrap().into_inner();
+    assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, 1), (2, 2)]);
+
+    let data = [0x02,   0x00, 0x01, 0x00, 0x00, 0x00, 0x02,   0x00, 0x01, 0x00, 0x00, 0x00, 0x01];
+    assert!(from_bytes::<BE, CountedMap<u8, u16, u32>>(&data).is_err());
+    assert!(from_bytes::<BE, CountedMap<u8, u16, u32>>(&data[..10]).is_err());
+  }
+  /// Количество элементов записывается после них
+  #[test]
+  fn test_trailer_counted() {
+    let test = TrailerCounted::<u16, _>::new(vec![0x1234u16, 0x5678, 0x9ABC]);
+    let be = [0x12, 0x34,   0x56, 0x78,   0x9A, 0xBC,   0x00, 0x03];
+    let le = [0x34, 0x12,   0x78, 0x56,   0xBC, 0x9A,   0x03, 0x00];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, TrailerCounted<u16, Vec<u16>>>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, TrailerCounted<u16, Vec<u16>>>(&le).unwrap(), test);
+    assert_eq!(from_bytes::<BE, TrailerCounted<u8, Vec<u16>>>(&[0x00]).unwrap().into_inner(), []);
+  }
+  /// Несовпадение количества элементов с записанным обнаруживается
+  #[test]
+  fn test_trailer_count_mismatch() {
+    let data = [0x12, 0x34,   0x56, 0x78,   0x00, 0x03];
+    assert!(from_bytes::<BE, TrailerCounted<u16, Vec<u16>>>(&data).is_err());
+    let data = [0x12, 0x34,   0x56, 0x78,   0x00, 0x01];
+    assert!(from_bytes::<BE, TrailerCounted<u16, Vec<u16>>>(&data).is_err());
+    // Данные не делятся на целое число элементов
+    let data = [0x12, 0x34,   0x56,   0x00, 0x02];
+    assert!(from_bytes::<BE, TrailerCounted<u16, Vec<u16>>>(&data).is_err());
+    // Нет места даже для количества
+    assert!(from_bytes::<BE, TrailerCounted<u16, Vec<u16>>>(&[0x00]).is_err());
+  }
+  /// Блок из префикса длины и 3 байт дополняется одним байтом до границы 4 байт
+  #[test]
+  fn test_aligned_blob() {
+    let blob = AlignedBlob::<u32, 4>::new(&b"abc"[..]);
+    let be = [0x00, 0x00, 0x00, 0x03,   b'a', b'b', b'c',   0x00,   0xFF];
+    let le = [0x03, 0x00, 0x00, 0x00,   b'a', b'b', b'c',   0x00,   0xFF];
+    assert_eq!(to_vec::<BE,_>(&blob).unwrap(), &be[..8]);
+    assert_eq!(to_vec::<LE,_>(&blob).unwrap(), &le[..8]);
+
+    let mut de = Deserializer::<BE, _>::new(&be[..]);
+    assert_eq!(AlignedBlob::<u32, 4>::deserialize(&mut de).unwrap(), blob);
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0xFF);
+    let mut de = Deserializer::<LE, _>::new(&le[..]);
+    assert_eq!(AlignedBlob::<u32, 4>::deserialize(&mut de).unwrap(), blob);
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0xFF);
+  }
+  /// Выровненные блоки не дополняются, граница отсчитывается от начала префикса длины
+  #[test]
+  fn test_aligned_blob_padding() {
+    assert_eq!(to_vec::<BE,_>(&AlignedBlob::<u32, 4>::new(&b"abcd"[..])).unwrap(), b"\0\0\0\x04abcd");
+    assert_eq!(to_vec::<BE,_>(&AlignedBlob::<u32, 4>::new(Vec::new())).unwrap(), [0; 4]);
+    assert_eq!(to_vec::<BE,_>(&AlignedBlob::<u8, 4>::new(&b"a"[..])).unwrap(), [0x01, b'a', 0x00, 0x00]);
+    assert_eq!(to_vec::<BE,_>(&AlignedBlob::<u8, 0>::new(&b"a"[..])).unwrap(), [0x01, b'a']);
+    assert!(from_bytes::<BE, AlignedBlob<u32, 4>>(&[0x00, 0x00, 0x00, 0x03, b'a', b'b', b'c']).is_err());
+  }
+  #[test]
+  fn test_prefixed_bytes_empty() {
+    let empty = PrefixedBytes::<u32>::new(Vec::new());
+    assert_eq!(to_vec::<BE,_>(&empty).unwrap(), [0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(to_vec::<LE,_>(&empty).unwrap(), [0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(from_bytes::<BE, PrefixedBytes<u32>>(&[0x00, 0x00, 0x00, 0x00]).unwrap(), empty);
+    assert_eq!(from_bytes::<LE, PrefixedBytes<u32>>(&[0x00, 0x00, 0x00, 0x00]).unwrap(), empty);
+  }
+  /// Представление совпадает с представлением `LengthPrefixed<L, Vec<u8>>`
+  #[test]
+  fn test_prefixed_bytes_large() {
+    let bytes: Vec<u8> = (0..5000u32).map(|i| (i * 7) as u8).collect();
+    let blob = PrefixedBytes::<u16>::new(bytes.clone());
+
+    let be = to_vec::<BE,_>(&blob).unwrap();
+    assert_eq!(&be[..2], [0x13, 0x88]);
+    assert_eq!(&be[2..], &bytes[..]);
+    assert_eq!(be, to_vec::<BE,_>(&LengthPrefixed::<u16, _>::new(bytes.clone())).unwrap());
+    assert_eq!(from_bytes::<BE, PrefixedBytes<u16>>(&be).unwrap(), blob);
+
+    let le = to_vec::<LE,_>(&blob).unwrap();
+    assert_eq!(&le[..2], [0x88, 0x13]);
+    assert_eq!(&le[2..], &bytes[..]);
+    assert_eq!(from_bytes::<LE, PrefixedBytes<u16>>(&le).unwrap(), blob);
+    assert_eq!(blob.into_inner(), bytes);
+  }
+  #[test]
+  fn test_prefixed_bytes_invalid() {
+    assert!(from_bytes::<BE, PrefixedBytes<u8>>(&[0x03,   b'a', b'b']).is_err());
+    assert!(to_vec::<BE,_>(&PrefixedBytes::<u8>::new(vec![0; 256])).is_err());
+  }
+  /// Количество упаковано в 3 старших бита байта типа
+  #[test]
+  fn test_count_in_bits() {
+    let seed = CountInBits::<u16>::new(0b1110_0000, 5);
+    let data = [0b011_10101,   0x12, 0x34,   0x56, 0x78,   0x9A, 0xBC,   0xFF];
+    let mut de = Deserializer::<BE, _>::new(&data[..]);
+    assert_eq!(seed.deserialize(&mut de).unwrap(), (0b011_10101, vec![0x1234, 0x5678, 0x9ABC]));
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    assert_eq!(seed.deserialize(&mut de).unwrap(), (0b011_10101, vec![0x3412, 0x7856, 0xBC9A]));
+
+    let mut de = Deserializer::<BE, _>::new(&[0b000_11111][..]);
+    assert_eq!(seed.deserialize(&mut de).unwrap(), (0b000_11111, vec![]));
+    let mut de = Deserializer::<BE, _>::new(&[0b111_00000, 0x00][..]);
+    assert!(seed.deserialize(&mut de).is_err());
+  }
+  #[test]
+  fn test_too_long() {
+    let long = LengthPrefixed::<u8, _>::new(vec![0u8; 256]);
+    assert!(to_vec::<BE,_>(&long).is_err());
+  }
+
+  /// Длина, не помещающаяся в `usize`, не усекается, а приводит к ошибке
+  #[test]
+  fn test_length_overflow() {
+    let err: Error = length_overflow(u64::MAX);
+    match err {
+      Error::Overflow(len) => assert_eq!(len, u64::MAX),
+      err => panic!("expected overflow, but got {:?}", err),
+    }
+  }
+  #[cfg(target_pointer_width = "32")]
+  #[test]
+  fn test_u64_length_on_32_bit() {
+    let data = [0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,   0x01, 0x02];
+    match from_bytes::<BE, LengthPrefixed<u64, Vec<u8>>>(&data) {
+      Err(Error::Overflow(len)) => assert_eq!(len, 0x1_0000_0000),
+      result => panic!("expected overflow, but got {:?}", result),
+    }
+  }
+
+  /// Порядок записей сохраняется при записи и чтении, независимо от порядка ключей
+  #[cfg(feature = "indexmap")]
+  #[test]
+  fn test_index_map() {
+    use indexmap::IndexMap;
+
+    let mut map = IndexMap::new();
+    map.insert(*b"zeta", 1u16);
+    map.insert(*b"alfa", 2u16);
+    map.insert(*b"mike", 3u16);
+    let map = LengthPrefixed::<u8, _>::new(map);
+    let data = [
+      0x03,
+      b'z', b'e', b't', b'a',   0x00, 0x01,
+      b'a', b'l', b'f', b'a',   0x00, 0x02,
+      b'm', b'i', b'k', b'e',   0x00, 0x03,
+    ];
+    assert_eq!(to_vec::<BE, _>(&map).unwrap(), data);
+
+    let read = from_bytes::<BE, LengthPrefixed<u8, IndexMap<[u8; 4], u16>>>(&data).unwrap();
+    let keys: Vec<_> = read.as_map().keys().collect();
+    assert_eq!(keys, [b"zeta", b"alfa", b"mike"]);
+    assert_eq!(read, map);
+
+    // Повторяющийся ключ
+    let data = [0x02,   b'z', b'e', b't', b'a', 0x00, 0x01,   b'z', b'e', b't', b'a', 0x00, 0x02];
+    assert!(from_bytes::<BE, LengthPrefixed<u8, IndexMap<[u8; 4], u16>>>(&data).is_err());
+  }
+}
@@ -0,0 +1,381 @@
+//! Содержит типы, описывающие расположение полей структур в потоке, но не хранящие данных.
+
+use std::fmt;
+use std::marker::PhantomData;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+
+use magic;
+use raw::{Raw, RawBuf};
+
+/// Заполнитель из `N` байт между полями структуры, например, зарезервированные поля или
+/// выравнивание. При сериализации записывается `N` нулевых байт, а при десериализации `N` байт
+/// читаются и отбрасываются, независимо от их значения. Сам тип не занимает памяти.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::layout::Pad;
+/// #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+/// struct Entry {
+///   kind: u8,
+///   _reserved: Pad<3>,
+///   size: u32,
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let entry = Entry { kind: 1, size: 2, ..Entry::default() };
+/// assert_eq!(to_vec::<LE, _>(&entry)?, [0x01,   0x00, 0x00, 0x00,   0x02, 0x00, 0x00, 0x00]);
+/// assert_eq!(from_bytes::<LE, Entry>(&[0x01,   0xFF, 0xFF, 0xFF,   0x02, 0x00, 0x00, 0x00])?, entry);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pad<const N: usize>;
+
+impl<const N: usize> Serialize for Pad<N> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Raw(&[0u8; N]).serialize(serializer)
+  }
+}
+impl<'de, const N: usize> Deserialize<'de> for Pad<N> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    RawBuf(N).deserialize(deserializer)?;
+    Ok(Pad)
+  }
+}
+
+/// Значение, начинающееся на границе `A` байт. Перед значением при сериализации записываются
+/// нулевые байты до ближайшей границы, а при десериализации столько же байт пропускается.
+/// Граница отсчитывается от начала записи или чтения, т.е. от значений
+/// [`Serializer::bytes_written`] и [`Deserializer::position`].
+///
+/// Граница отсчитывается от начала потока и для значений, вложенных в другие: внутри блоков
+/// [`Deserializer::scoped`] и [`Deserializer::bounded`], значений с контрольной суммой и данных
+/// вариантов с длиной. Граница сжатого значения отсчитывается от начала сжатых данных. Значение,
+/// прочитанное методом [`Deserializer::read_at`], выравнивается относительно начала потока, поэтому
+/// десериализатор должен быть создан в его начале. Значение, на которое ссылается [`Offset`],
+/// записывается на границе наибольшего выравнивания своих полей. В столбцах [`Columnar`] позиция
+/// поля не определена, поэтому выравнивание в них не поддерживается.
+///
+/// Поддерживаются границы 2, 4, 8, 16, 32 и 64 байта; значения `A`, равные 0 и 1, означают
+/// отсутствие выравнивания, а для других значений сериализация завершается ошибкой. Другие
+/// форматы сериализуют значение без выравнивания, как newtype-структуру.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::layout::Aligned;
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Entry {
+///   kind: u8,
+///   size: Aligned<4, u32>,
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let entry = Entry { kind: 1, size: Aligned(2) };
+/// let data = [0x01,   0x00, 0x00, 0x00,   0x02, 0x00, 0x00, 0x00];
+/// assert_eq!(to_vec::<LE, _>(&entry)?, data);
+/// assert_eq!(from_bytes::<LE, Entry>(&data)?, entry);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Serializer::bytes_written`]: ../ser/struct.Serializer.html#method.bytes_written
+/// [`Deserializer::position`]: ../de/struct.Deserializer.html#method.position
+/// [`Deserializer::scoped`]: ../de/struct.Deserializer.html#method.scoped
+/// [`Deserializer::bounded`]: ../de/struct.Deserializer.html#method.bounded
+/// [`Deserializer::read_at`]: ../de/struct.Deserializer.html#method.read_at
+/// [`Offset`]: ../wrappers/struct.Offset.html
+/// [`Columnar`]: ../wrappers/struct.Columnar.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Aligned<const A: usize, T>(pub T);
+
+impl<const A: usize, T> Aligned<A, T> {
+  /// Возвращает выровненное значение
+  #[inline]
+  pub fn into_inner(self) -> T { self.0 }
+}
+impl<const A: usize, T> From<T> for Aligned<A, T> {
+  #[inline]
+  fn from(value: T) -> Self { Aligned(value) }
+}
+
+impl<const A: usize, T: Serialize> Serialize for Aligned<A, T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    if A <= 1 {
+      return self.0.serialize(serializer);
+    }
+    let name = magic::aligned_name(A).ok_or_else(|| ser::Error::custom(format_args!(
+      "alignment to {} bytes is not supported", A
+    )))?;
+    serializer.serialize_newtype_struct(name, &self.0)
+  }
+}
+impl<'de, const A: usize, T: Deserialize<'de>> Deserialize<'de> for Aligned<A, T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    if A <= 1 {
+      return T::deserialize(deserializer).map(Aligned);
+    }
+    let name = magic::aligned_name(A).ok_or_else(|| de::Error::custom(format_args!(
+      "alignment to {} bytes is not supported", A
+    )))?;
+    deserializer.deserialize_newtype_struct(name, AlignedVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий выровненное значение
+struct AlignedVisitor<const A: usize, T>(PhantomData<T>);
+impl<'de, const A: usize, T: Deserialize<'de>> Visitor<'de> for AlignedVisitor<A, T> {
+  type Value = Aligned<A, T>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "a value aligned to {} bytes", A)
+  }
+  fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    T::deserialize(deserializer).map(Aligned)
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod pad {
+  use std::mem;
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::Pad;
+
+  #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+  struct Test {
+    first: u16,
+    _pad: Pad<6>,
+    second: u32,
+  }
+
+  #[test]
+  fn test_roundtrip() {
+    let test = Test { first: 0x0102, second: 0x03040506, ..Test::default() };
+    let be = [0x01, 0x02,   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,   0x03, 0x04, 0x05, 0x06];
+    let le = [0x02, 0x01,   0x00, 0x00, 0x00, 0x00, 0x00, 0x00,   0x06, 0x05, 0x04, 0x03];
+    assert_eq!(to_vec::<BE, _>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE, _>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Test>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Test>(&le).unwrap(), test);
+  }
+  /// Содержимое заполнителя игнорируется
+  #[test]
+  fn test_ignored() {
+    let data = [0x01, 0x02,   0xDE, 0xAD, 0xBE, 0xEF, 0x12, 0x34,   0x03, 0x04, 0x05, 0x06];
+    assert_eq!(from_bytes::<BE, Test>(&data).unwrap(), Test { first: 0x0102, second: 0x03040506, ..Test::default() });
+  }
+  #[test]
+  fn test_no_data() {
+    assert!(from_bytes::<BE, Test>(&[0x01, 0x02,   0x00, 0x00]).is_err());
+  }
+  #[test]
+  fn test_zero_sized() {
+    assert_eq!(mem::size_of::<Pad<1024>>(), 0);
+  }
+}
+
+#[cfg(test)]
+mod aligned {
+  use std::io::Cursor;
+  use byteorder::{BE, LE};
+  use serde::{Deserialize, Serialize};
+  use checksum::Crc32Wrapped;
+  use de::{from_bytes, Deserializer, DynDeserializer};
+  use ser::{to_vec, DynSerializer, Serializer};
+  use wrappers::{Columnar, Offset};
+  use {Endian, EnumTag, TagWidth};
+  use super::Aligned;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Record {
+    name: [u8; 3],
+    size: Aligned<4, u32>,
+    flag: u8,
+    offset: Aligned<8, u16>,
+  }
+
+  /// Поле нечетной длины сдвигает следующее поле `u32` на границу 4 байт
+  #[test]
+  fn test_roundtrip() {
+    let record = Record { name: *b"abc", size: Aligned(0x01020304), flag: 0xFF, offset: Aligned(0x0506) };
+    let be = [
+      b'a', b'b', b'c',   0x00,
+      0x01, 0x02, 0x03, 0x04,
+      0xFF,   0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x05, 0x06,
+    ];
+    let le = [
+      b'a', b'b', b'c',   0x00,
+      0x04, 0x03, 0x02, 0x01,
+      0xFF,   0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x06, 0x05,
+    ];
+    assert_eq!(to_vec::<BE, _>(&record).unwrap(), be);
+    assert_eq!(to_vec::<LE, _>(&record).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Record>(&be).unwrap(), record);
+    assert_eq!(from_bytes::<LE, Record>(&le).unwrap(), record);
+  }
+  /// Граница отсчитывается от начала записи, а не от начала значения
+  #[test]
+  fn test_offset() {
+    let mut ser = Serializer::<LE, _>::new(Vec::new());
+    0x01u16.serialize(&mut ser).unwrap();
+    Aligned::<4, _>(0x02u8).serialize(&mut ser).unwrap();
+    Aligned::<4, _>(0x03u32).serialize(&mut ser).unwrap();
+    let data = ser.into_inner();
+    assert_eq!(data, [0x01, 0x00,   0x00, 0x00,   0x02,   0x00, 0x00, 0x00,   0x03, 0x00, 0x00, 0x00]);
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    assert_eq!(de.read_field::<u16>().unwrap(), 1);
+    assert_eq!(de.read_field::<Aligned<4, u8>>().unwrap(), Aligned(2));
+    assert_eq!(de.read_field::<Aligned<4, u32>>().unwrap(), Aligned(3));
+  }
+  /// Выровненное значение не дополняется
+  #[test]
+  fn test_already_aligned() {
+    assert_eq!(to_vec::<BE, _>(&Aligned::<8, _>(0x01u8)).unwrap(), [0x01]);
+    assert_eq!(to_vec::<BE, _>(&(0x01u8, Aligned::<1, _>(0x02u8))).unwrap(), [0x01, 0x02]);
+  }
+  #[test]
+  fn test_unsupported() {
+    assert!(to_vec::<BE, _>(&Aligned::<3, _>(0x01u8)).is_err());
+    assert!(from_bytes::<BE, Aligned<3, u8>>(&[0x01]).is_err());
+  }
+  #[test]
+  fn test_no_padding() {
+    assert!(from_bytes::<BE, (u8, Aligned<4, u8>)>(&[0x01, 0x00, 0x00]).is_err());
+  }
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Entry {
+    kind: u8,
+    size: Aligned<4, u32>,
+  }
+
+  /// Значение по смещению записывается на границе выравнивания своих полей, а граница
+  /// отсчитывается от начала потока, а не от начала значения
+  #[test]
+  fn test_in_offset() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Header {
+      flag: u8,
+      entry: Offset<Entry>,
+    }
+    let header = Header { flag: 0xFF, entry: Offset(Entry { kind: 1, size: Aligned(2) }) };
+
+    let mut cursor = Cursor::new(Vec::new());
+    {
+      let mut ser = Serializer::<LE, _>::seekable(&mut cursor);
+      header.serialize(&mut ser).unwrap();
+      ser.write_offset_targets().unwrap();
+    }
+    let data = cursor.into_inner();
+    assert_eq!(data, [
+      0xFF,   0x07, 0x00, 0x00, 0x00,   0x00, 0x00, 0x00,
+      0x01,   0x00, 0x00, 0x00,   0x02, 0x00, 0x00, 0x00,
+    ]);
+
+    let mut de = Deserializer::<LE, _>::seekable(Cursor::new(&data[..]));
+    assert_eq!(Header::deserialize(&mut de).unwrap(), header);
+  }
+  #[test]
+  fn test_dynamic() {
+    let mut ser = DynSerializer::new(Vec::new(), Endian::Little);
+    ser.serialize(&0x01u8).unwrap();
+    ser.serialize(&Aligned::<4, _>(0x02u32)).unwrap();
+    let data = ser.into_inner();
+    assert_eq!(data, [0x01,   0x00, 0x00, 0x00,   0x02, 0x00, 0x00, 0x00]);
+
+    let mut de = DynDeserializer::new(&data[..], Endian::Little);
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 1);
+    assert_eq!(Aligned::<4, u32>::deserialize(&mut de).unwrap(), Aligned(2));
+    assert_eq!(de.position(), 8);
+  }
+  /// Граница в блоке отсчитывается от начала потока, а не от начала блока
+  #[test]
+  fn test_scoped() {
+    let data = [0x07,   0x01,   0x00, 0x00,   0x02, 0x00, 0x00, 0x00,   0xFF];
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    let len: u8 = de.read_field().unwrap();
+    {
+      let mut block = de.scoped(len.into());
+      assert_eq!(block.read_field::<u8>().unwrap(), 1);
+      assert_eq!(block.read_field::<Aligned<4, u32>>().unwrap(), Aligned(2));
+      block.finish().unwrap();
+    }
+    assert_eq!(de.read_field::<u8>().unwrap(), 0xFF);
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    let len: u8 = de.read_field().unwrap();
+    let (kind, size): (u8, Aligned<4, u32>) = de.bounded(len.into()).unwrap();
+    assert_eq!((kind, size), (1, Aligned(2)));
+    assert_eq!(de.read_field::<u8>().unwrap(), 0xFF);
+  }
+  /// Граница значения, прочитанного по абсолютному смещению, отсчитывается от начала потока
+  #[test]
+  fn test_read_at() {
+    let data = [0xAA, 0xBB,   0x00, 0x00,   0x01,   0x00, 0x00, 0x00,   0x02, 0x00, 0x00, 0x00];
+
+    let mut de = Deserializer::<LE, _>::seekable(Cursor::new(&data[..]));
+    assert_eq!(de.read_field::<u16>().unwrap(), 0xBBAA);
+    assert_eq!(de.read_at::<Entry>(4).unwrap(), Entry { kind: 1, size: Aligned(2) });
+    assert_eq!(de.position(), 2);
+    assert_eq!(de.read_field::<u16>().unwrap(), 0);
+  }
+  /// Граница внутри значения с контрольной суммой отсчитывается от начала потока
+  #[test]
+  fn test_in_crc32() {
+    let value = (0x0102u16, Crc32Wrapped(Entry { kind: 3, size: Aligned(4) }));
+    let data = to_vec::<LE, _>(&value).unwrap();
+    assert_eq!(data[..8], [0x02, 0x01,   0x03,   0x00,   0x04, 0x00, 0x00, 0x00]);
+    assert_eq!(data.len(), 12);
+    assert_eq!(from_bytes::<LE, (u16, Crc32Wrapped<Entry>)>(&data).unwrap(), value);
+  }
+  /// Длина данных варианта не сдвигает границу выравнивания его полей
+  #[test]
+  fn test_in_length_delimited_enum() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    enum Message {
+      Entry(u8, Aligned<4, u32>),
+    }
+    let tag = EnumTag { width: TagWidth::U8, length_delimited: true };
+    let message = Message::Entry(1, Aligned(2));
+
+    let mut ser = Serializer::<LE, _>::new(Vec::new()).with_enum_tag(tag);
+    message.serialize(&mut ser).unwrap();
+    let data = ser.into_inner();
+    assert_eq!(data, [
+      0x00,   0x07, 0x00, 0x00, 0x00,
+      0x01,   0x00, 0x00,   0x02, 0x00, 0x00, 0x00,
+    ]);
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_enum_tag(tag);
+    assert_eq!(Message::deserialize(&mut de).unwrap(), message);
+  }
+  /// В столбцах позиция поля не определена, поэтому выравнивание не поддерживается
+  #[test]
+  fn test_columnar() {
+    let entries = Columnar([Entry { kind: 1, size: Aligned(2) }]);
+    assert!(to_vec::<LE, _>(&entries).is_err());
+
+    let data = [0x01,   0x00, 0x00, 0x00,   0x02, 0x00, 0x00, 0x00];
+    assert!(from_bytes::<LE, Columnar<Entry, 1>>(&data).is_err());
+  }
+}
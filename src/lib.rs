@@ -66,9 +66,34 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Использование без стандартной библиотеки
+//! Функциональность `std` включена по умолчанию. Если ее отключить, крейт собирается с атрибутом
+//! `#![no_std]` и требует только крейта `alloc`. В этом случае доступны только модули [`error`]
+//! и [`slice`]: последний сериализует значения в срезы байт и десериализует их из срезов, не
+//! используя потоки ввода-вывода стандартной библиотеки. Это позволяет, например, читать
+//! структуры из флеш-памяти во встраиваемых системах:
+//! ```toml
+//! [dependencies]
+//! serde-pod = { version = "0.2", default-features = false }
+//! ```
+//!
+//! [`error`]: error/index.html
+//! [`slice`]: slice/index.html
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+#[cfg_attr(feature = "std", macro_use)]
 extern crate serde;
 extern crate byteorder;
+#[cfg(feature = "memmap2")]
+extern crate memmap2;
+#[cfg(feature = "flate2")]
+extern crate flate2;
+#[cfg(feature = "zstd")]
+extern crate zstd;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
 
 #[cfg(test)]
 #[macro_use]
@@ -77,22 +102,137 @@ extern crate serde_derive;
 #[macro_use]
 extern crate quickcheck;
 
+#[cfg(feature = "std")]
 use byteorder::{BE, LE};
 
 pub mod error;
+#[cfg(feature = "std")]
 pub mod ser;
+#[cfg(feature = "std")]
 pub mod de;
+#[cfg(feature = "std")]
+pub mod wrappers;
+#[cfg(feature = "std")]
+pub mod framing;
+#[cfg(feature = "std")]
+pub mod strings;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod bits;
+#[cfg(feature = "std")]
+pub mod checksum;
+#[cfg(feature = "std")]
+pub mod encoding;
+#[cfg(feature = "std")]
+pub mod format;
+#[cfg(feature = "std")]
+pub mod riff;
+#[cfg(feature = "std")]
+pub mod bcd;
+#[cfg(feature = "std")]
+pub mod compress;
+#[cfg(feature = "std")]
+pub mod varint;
+#[cfg(feature = "std")]
+pub mod tagged;
+#[cfg(feature = "std")]
+pub mod string_table;
+#[cfg(feature = "std")]
+pub mod xor;
+#[cfg(feature = "std")]
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod layout;
+#[cfg(feature = "std")]
+pub mod net;
+#[cfg(feature = "std")]
+pub mod time;
+pub mod slice;
+#[cfg(feature = "std")]
+mod magic;
+#[cfg(feature = "std")]
+mod raw;
 
 /// Сериализатор, записывающий числа в поток в порядке `Big-Endian`
+#[cfg(feature = "std")]
 pub type BESerializer<W> = ser::Serializer<BE, W>;
 /// Сериализатор, записывающий числа в поток в порядке `Little-Endian`
+#[cfg(feature = "std")]
 pub type LESerializer<W> = ser::Serializer<LE, W>;
 
 /// Десериализатор, читающий числа из потока в порядке `Big-Endian`
+#[cfg(feature = "std")]
 pub type BEDeserializer<R> = de::Deserializer<BE, R>;
 /// Десериализатор, читающий числа из потока в порядке `Little-Endian`
+#[cfg(feature = "std")]
 pub type LEDeserializer<R> = de::Deserializer<LE, R>;
 
+/// Порядок байт, выбираемый во время выполнения программы, а не параметром типа
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endian {
+  /// Порядок `Big-Endian`: старший байт числа записывается первым
+  Big,
+  /// Порядок `Little-Endian`: младший байт числа записывается первым
+  Little,
+}
+
+/// Тип метки варианта перечисления или длины строки в потоке
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TagWidth {
+  /// Значение записывается, как `u8`
+  U8,
+  /// Значение записывается, как `u16` в порядке байт сериализатора
+  U16,
+  /// Значение записывается, как `u32` в порядке байт сериализатора
+  U32,
+}
+
+/// Способ, которым сериализатор отделяет строки и массивы байт, записываемые методами
+/// `serialize_str` и `serialize_bytes`, от следующих за ними данных. Задается методом
+/// `with_string_framing` сериализатора. Символы (`char`) записываются без разделителей
+/// при любом способе.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StringFraming {
+  /// Байты записываются как есть, без длины и завершающего символа. Такие строки читаются
+  /// до конца потока, поэтому должны быть последним полем, либо их длина должна быть известна
+  /// из других полей
+  #[default]
+  Raw,
+  /// После байт записывается завершающий нулевой байт. Записываемые данные не должны содержать
+  /// нулевых байт
+  NulTerminated,
+  /// Перед байтами записывается их количество в виде числа указанного типа
+  LengthPrefixed(TagWidth),
+}
+
+/// Представление перечислений в потоке. По умолчанию сериализатор не записывает вариант
+/// перечисления, а десериализатор не поддерживает перечисления. Если представление задано
+/// методом `with_enum_tag` сериализатора и десериализатора, каждое значение перечисления
+/// записывается, как индекс варианта (метка), за которым следуют данные варианта.
+///
+/// Если установлен флаг `length_delimited`, между меткой и данными записывается размер данных
+/// варианта в байтах в виде `u32`. Это позволяет десериализатору пропускать варианты, неизвестные
+/// читающей программе: если перечисление содержит unit-вариант, помеченный атрибутом
+/// `#[serde(other)]`, то неизвестная метка десериализуется в него, а данные варианта пропускаются.
+/// Данные известного варианта, оставшиеся непрочитанными, также пропускаются, поэтому новые поля
+/// могут добавляться в конец варианта без потери совместимости.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EnumTag {
+  /// Тип метки варианта
+  pub width: TagWidth,
+  /// Записывать ли после метки размер данных варианта
+  pub length_delimited: bool,
+}
+
 pub use error::{Error, Result};
+#[cfg(feature = "std")]
 pub use ser::{to_vec, to_writer};
-pub use de::from_bytes;
+#[cfg(feature = "std")]
+pub use de::{from_bytes, from_bytes_exact, from_bytes_sized, from_bytes_strict, from_bytes_with_len, from_reader};
+#[cfg(feature = "std")]
+pub use strings::pascal_string;
+#[cfg(feature = "std")]
+pub use wrappers::{ordering_byte, NanOption};
+#[cfg(all(feature = "std", feature = "memmap2"))]
+pub use de::from_mmap;
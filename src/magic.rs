@@ -0,0 +1,208 @@
+//! Содержит имена, по которым сериализатор и десериализатор распознают типы, требующие
+//! особой обработки, и вспомогательные функции для их сериализации.
+//!
+//! Типы-обертки реализуют [`Serialize`] и [`Deserialize`] в общем виде и поэтому не знают, с каким
+//! порядком байт работает сериализатор. Если представление типа зависит от порядка байт, он
+//! передается сериализатору в виде newtype-структуры (или tuple-структуры при десериализации)
+//! со специальным именем, а сериализатор и десериализатор этого крейта обрабатывают ее особым
+//! образом. Другие форматы видят такие типы, как обычные newtype- или tuple-структуры.
+//!
+//! [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+//! [`Deserialize`]: https://docs.serde.rs/serde/trait.Deserialize.html
+
+use std::error;
+use std::fmt;
+use byteorder::ByteOrder;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use TagWidth;
+
+/// Массив байт, записанный в порядке `Big-Endian`. Сериализатор записывает его байты в своем
+/// порядке байт, десериализатор читает столько байт, сколько указано в длине tuple-структуры,
+/// и передает их посетителю в порядке `Big-Endian`
+pub const BE_BYTES: &str = "$serde_pod::BeBytes";
+
+/// Значение, расположенное в другом месте потока, на которое ссылается относительное смещение.
+/// Сериализатор записывает вместо значения заглушку смещения, а само значение записывает позже,
+/// десериализатор читает смещение и переходит по нему для чтения значения
+pub const OFFSET: &str = "$serde_pod::Offset";
+
+/// Значение, за которым следует его контрольная сумма CRC-32. Сериализатор дописывает контрольную
+/// сумму после значения, десериализатор проверяет ее
+pub const CRC32: &str = "$serde_pod::Crc32";
+
+/// Последовательность, за которой следует количество ее элементов. Длина tuple-структуры задает
+/// ширину количества в байтах. Десериализатор читает поток до конца, отделяет от него количество
+/// и передает посетителю сначала количество, а затем элементы до конца оставшихся данных
+pub const TRAILER_COUNTED: &str = "$serde_pod::TrailerCounted";
+
+/// Последовательность структур, записываемая по столбцам: сначала первые поля всех структур,
+/// затем вторые и т.д. Сериализатор раскладывает поля по столбцам, десериализатор читает
+/// tuple-структуру с этим именем из `len` элементов, собирая каждый из них из столбцов
+pub const COLUMNAR: &str = "$serde_pod::Columnar";
+/// Сообщение об ошибке (де)сериализации по столбцам значения, не являющегося последовательностью
+/// структур или кортежей
+pub const COLUMNAR_ERROR: &str = "columnar layout requires a sequence of structs or tuples";
+
+/// Массив байт, записываемый сериализатором через `serialize_bytes` как есть, без длины или
+/// завершающего символа, независимо от способа записи строк, см. [`StringFraming`]. Десериализатор
+/// читает tuple-структуру с этим именем из `len` байт одной операцией чтения и передает их
+/// посетителю методом `visit_byte_buf`
+///
+/// [`StringFraming`]: ../enum.StringFraming.html
+pub const RAW_BYTES: &str = "$serde_pod::RawBytes";
+
+/// Имена newtype-структур, обозначающих перечисления, перед данными вариантов которых записывается
+/// метка указанной ширины. Сериализатор и десериализатор обрабатывают значение так, как будто
+/// представление перечислений задано методом `with_enum_tag`
+const TAGGED_ENUMS: [(&str, TagWidth); 3] = [
+  ("$serde_pod::TaggedEnum1", TagWidth::U8),
+  ("$serde_pod::TaggedEnum2", TagWidth::U16),
+  ("$serde_pod::TaggedEnum4", TagWidth::U32),
+];
+
+/// Возвращает имя newtype-структуры, обозначающей перечисление с меткой ширины `width`
+pub fn tagged_enum_name(width: TagWidth) -> &'static str {
+  TAGGED_ENUMS.iter().find(|&&(_, w)| w == width).map(|&(name, _)| name).unwrap()
+}
+/// Возвращает ширину метки перечисления, обозначаемого newtype-структурой `name`
+pub fn tagged_enum_width(name: &str) -> Option<TagWidth> {
+  TAGGED_ENUMS.iter().find(|&&(n, _)| n == name).map(|&(_, width)| width)
+}
+
+/// Имена newtype-структур, обозначающих значения, выровненные на границу указанного количества
+/// байт. Сериализатор перед значением дописывает нулевые байты до границы, отсчитываемой от
+/// начала записи, десериализатор пропускает столько же байт
+const ALIGNMENTS: [(&str, usize); 6] = [
+  ("$serde_pod::Align2", 2),
+  ("$serde_pod::Align4", 4),
+  ("$serde_pod::Align8", 8),
+  ("$serde_pod::Align16", 16),
+  ("$serde_pod::Align32", 32),
+  ("$serde_pod::Align64", 64),
+];
+
+/// Возвращает имя newtype-структуры, обозначающей значение, выровненное на границу `align` байт
+pub fn aligned_name(align: usize) -> Option<&'static str> {
+  ALIGNMENTS.iter().find(|&&(_, a)| a == align).map(|&(name, _)| name)
+}
+/// Возвращает границу выравнивания значения, обозначаемого newtype-структурой `name`
+pub fn aligned_width(name: &str) -> Option<usize> {
+  ALIGNMENTS.iter().find(|&&(n, _)| n == name).map(|&(_, align)| align)
+}
+/// Возвращает количество байт, которые нужно пропустить от позиции `position`, чтобы
+/// достичь границы `align` байт
+#[inline]
+pub fn padding(position: u64, align: usize) -> usize {
+  let rest = (position % align as u64) as usize;
+  if rest == 0 { 0 } else { align - rest }
+}
+
+/// Имена tuple-структур, обозначающих массивы примитивных чисел указанной ширины. Десериализатор
+/// читает все элементы такого массива из потока за одну операцию чтения
+const BULK_ARRAYS: [(&str, usize); 5] = [
+  ("$serde_pod::Bulk1", 1),
+  ("$serde_pod::Bulk2", 2),
+  ("$serde_pod::Bulk4", 4),
+  ("$serde_pod::Bulk8", 8),
+  ("$serde_pod::Bulk16", 16),
+];
+
+/// Возвращает имя tuple-структуры, обозначающей массив примитивных чисел шириной `width` байт
+pub fn bulk_array_name(width: usize) -> Option<&'static str> {
+  BULK_ARRAYS.iter().find(|&&(_, w)| w == width).map(|&(name, _)| name)
+}
+/// Возвращает ширину элементов массива примитивных чисел, обозначаемого tuple-структурой `name`
+pub fn bulk_array_width(name: &str) -> Option<usize> {
+  BULK_ARRAYS.iter().find(|&&(n, _)| n == name).map(|&(_, width)| width)
+}
+
+/// Общее начало имен всех newtype- и tuple-структур, требующих особой обработки
+const PREFIX: &str = "$serde_pod::";
+
+/// Возвращает `true`, если newtype- или tuple-структура `name` требует особой обработки, которую
+/// нельзя заменить обработкой обычной структуры, т.е. поддерживается только сериализатором и
+/// десериализатором потоков. Массивы байт, записываемые как есть, и массивы примитивных чисел
+/// обычной обработкой записываются и читаются так же
+pub fn requires_stream(name: &str) -> bool {
+  name.starts_with(PREFIX) && name != RAW_BYTES && bulk_array_width(name).is_none()
+}
+
+/// Определяет, десериализуется ли тип `T`, как примитивное число, и если да, возвращает
+/// его ширину в байтах. Для этого тип десериализуется из специального десериализатора,
+/// который запоминает вызванный метод и всегда возвращает ошибку
+pub fn primitive_width<'de, T: Deserialize<'de>>() -> Option<usize> {
+  match T::deserialize(WidthProbe) {
+    Err(ProbeResult(width)) => width,
+    Ok(_) => None,
+  }
+}
+
+/// Результат определения ширины примитивного типа, передаваемый в виде ошибки десериализации
+#[derive(Debug)]
+struct ProbeResult(Option<usize>);
+impl fmt::Display for ProbeResult {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "width probe: {:?}", self.0)
+  }
+}
+impl error::Error for ProbeResult {}
+impl de::Error for ProbeResult {
+  fn custom<T: fmt::Display>(_msg: T) -> Self { ProbeResult(None) }
+}
+
+/// Десериализатор, определяющий, какой метод десериализации вызывает тип
+struct WidthProbe;
+
+/// Макрос, генерирующий метод, сообщающий ширину примитивного типа
+macro_rules! probe {
+  ($($dser_method:ident = $width:expr),*) => {
+    $(
+      fn $dser_method<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(ProbeResult(Some($width)))
+      }
+    )*
+  }
+}
+impl<'de> Deserializer<'de> for WidthProbe {
+  type Error = ProbeResult;
+
+  fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+    Err(ProbeResult(None))
+  }
+  probe!(
+    deserialize_i8 = 1, deserialize_u8 = 1,
+    deserialize_i16 = 2, deserialize_u16 = 2,
+    deserialize_i32 = 4, deserialize_u32 = 4, deserialize_f32 = 4,
+    deserialize_i64 = 8, deserialize_u64 = 8, deserialize_f64 = 8,
+    deserialize_i128 = 16, deserialize_u128 = 16
+  );
+  forward_to_deserialize_any! {
+    bool char str string bytes byte_buf option unit unit_struct newtype_struct seq tuple
+    tuple_struct map struct enum identifier ignored_any
+  }
+}
+
+/// Возвращает `true`, если порядок байт `BO` -- `Big-Endian`
+#[inline]
+pub fn is_big_endian<BO: ByteOrder>() -> bool {
+  BO::read_u16(&[0, 1]) == 1
+}
+
+/// Массив байт, сериализуемый через [`Serializer::serialize_bytes`]
+///
+/// [`Serializer::serialize_bytes`]: https://docs.serde.rs/serde/trait.Serializer.html#tymethod.serialize_bytes
+struct RawBytes<'a>(&'a [u8]);
+impl<'a> Serialize for RawBytes<'a> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(self.0)
+  }
+}
+
+/// Сериализует массив байт, записанный в порядке `Big-Endian`, так, чтобы сериализатор
+/// записал их в своем порядке байт
+#[inline]
+pub fn serialize_be_bytes<S: Serializer>(serializer: S, bytes: &[u8]) -> Result<S::Ok, S::Error> {
+  serializer.serialize_newtype_struct(BE_BYTES, &RawBytes(bytes))
+}
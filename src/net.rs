@@ -0,0 +1,228 @@
+//! Содержит типы-обертки для адресов и портов сетевых протоколов. Адреса и порты записываются
+//! в сетевом порядке байт (`Big-Endian`) независимо от порядка байт сериализатора, как они
+//! передаются в заголовках пакетов.
+//!
+//! # Пример
+//! ```rust
+//! # extern crate byteorder;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_pod;
+//! # use std::net::{IpAddr, Ipv4Addr};
+//! # use byteorder::LE;
+//! # use serde_pod::{from_bytes, to_vec};
+//! # use serde_pod::net::{Ipv4Be, PortBe};
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Endpoint {
+//!   id: u16,
+//!   addr: Ipv4Be,
+//!   port: PortBe,
+//! }
+//!
+//! # fn main() -> serde_pod::Result<()> {
+//! let endpoint = Endpoint { id: 1, addr: Ipv4Be(Ipv4Addr::new(192, 168, 0, 1)), port: PortBe(8080) };
+//! let data = [0x01, 0x00,   192, 168, 0, 1,   0x1F, 0x90];
+//! assert_eq!(to_vec::<LE, _>(&endpoint)?, data);
+//! let endpoint = from_bytes::<LE, Endpoint>(&data)?;
+//! assert_eq!(IpAddr::from(endpoint.addr), IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use raw::{Raw, RawBuf};
+
+/// Читает из потока `N` байт
+fn read_array<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+  let mut array = [0; N];
+  array.copy_from_slice(&RawBuf(N).deserialize(deserializer)?);
+  Ok(array)
+}
+
+/// Адрес IPv4, записываемый, как 4 байта в сетевом порядке
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ipv4Be(pub Ipv4Addr);
+
+/// Адрес IPv6, записываемый, как 16 байт в сетевом порядке
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ipv6Be(pub Ipv6Addr);
+
+/// Номер порта, записываемый, как `u16` в сетевом порядке (`Big-Endian`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortBe(pub u16);
+
+/// Адрес сокета IPv4, записываемый, как 4 байта адреса и 2 байта порта в сетевом порядке
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SocketV4Be(pub SocketAddrV4);
+
+/// Адрес сокета IPv6, записываемый, как 16 байт адреса и 2 байта порта в сетевом порядке.
+/// Идентификаторы потока и области не записываются и при чтении равны нулю
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SocketV6Be(pub SocketAddrV6);
+
+impl From<Ipv4Addr> for Ipv4Be {
+  #[inline]
+  fn from(value: Ipv4Addr) -> Self { Ipv4Be(value) }
+}
+impl From<Ipv4Be> for IpAddr {
+  #[inline]
+  fn from(value: Ipv4Be) -> Self { IpAddr::V4(value.0) }
+}
+impl From<Ipv6Addr> for Ipv6Be {
+  #[inline]
+  fn from(value: Ipv6Addr) -> Self { Ipv6Be(value) }
+}
+impl From<Ipv6Be> for IpAddr {
+  #[inline]
+  fn from(value: Ipv6Be) -> Self { IpAddr::V6(value.0) }
+}
+impl From<u16> for PortBe {
+  #[inline]
+  fn from(value: u16) -> Self { PortBe(value) }
+}
+impl From<SocketAddrV4> for SocketV4Be {
+  #[inline]
+  fn from(value: SocketAddrV4) -> Self { SocketV4Be(value) }
+}
+impl From<SocketV4Be> for SocketAddr {
+  #[inline]
+  fn from(value: SocketV4Be) -> Self { SocketAddr::V4(value.0) }
+}
+impl From<SocketAddrV6> for SocketV6Be {
+  #[inline]
+  fn from(value: SocketAddrV6) -> Self { SocketV6Be(value) }
+}
+impl From<SocketV6Be> for SocketAddr {
+  #[inline]
+  fn from(value: SocketV6Be) -> Self { SocketAddr::V6(value.0) }
+}
+
+impl Serialize for Ipv4Be {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Raw(&self.0.octets()).serialize(serializer)
+  }
+}
+impl<'de> Deserialize<'de> for Ipv4Be {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    read_array::<D, 4>(deserializer).map(|octets| Ipv4Be(octets.into()))
+  }
+}
+
+impl Serialize for Ipv6Be {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Raw(&self.0.octets()).serialize(serializer)
+  }
+}
+impl<'de> Deserialize<'de> for Ipv6Be {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    read_array::<D, 16>(deserializer).map(|octets| Ipv6Be(octets.into()))
+  }
+}
+
+impl Serialize for PortBe {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Raw(&self.0.to_be_bytes()).serialize(serializer)
+  }
+}
+impl<'de> Deserialize<'de> for PortBe {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    read_array::<D, 2>(deserializer).map(|bytes| PortBe(u16::from_be_bytes(bytes)))
+  }
+}
+
+impl Serialize for SocketV4Be {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&Ipv4Be(*self.0.ip()))?;
+    tuple.serialize_element(&PortBe(self.0.port()))?;
+    tuple.end()
+  }
+}
+impl<'de> Deserialize<'de> for SocketV4Be {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let bytes = read_array::<D, 6>(deserializer)?;
+    let ip = Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]);
+    Ok(SocketV4Be(SocketAddrV4::new(ip, u16::from_be_bytes([bytes[4], bytes[5]]))))
+  }
+}
+
+impl Serialize for SocketV6Be {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&Ipv6Be(*self.0.ip()))?;
+    tuple.serialize_element(&PortBe(self.0.port()))?;
+    tuple.end()
+  }
+}
+impl<'de> Deserialize<'de> for SocketV6Be {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let bytes = read_array::<D, 18>(deserializer)?;
+    let mut ip = [0; 16];
+    ip.copy_from_slice(&bytes[..16]);
+    Ok(SocketV6Be(SocketAddrV6::new(ip.into(), u16::from_be_bytes([bytes[16], bytes[17]]), 0, 0)))
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::{Ipv4Be, Ipv6Be, PortBe, SocketV4Be, SocketV6Be};
+
+  /// Адрес записывается в сетевом порядке независимо от порядка байт сериализатора
+  #[test]
+  fn test_ipv4() {
+    for &(addr, data) in &[
+      (Ipv4Addr::LOCALHOST, [127, 0, 0, 1]),
+      (Ipv4Addr::BROADCAST, [255, 255, 255, 255]),
+      (Ipv4Addr::new(192, 168, 1, 254), [192, 168, 1, 254]),
+    ] {
+      assert_eq!(to_vec::<BE, _>(&Ipv4Be(addr)).unwrap(), data);
+      assert_eq!(to_vec::<LE, _>(&Ipv4Be(addr)).unwrap(), data);
+      assert_eq!(from_bytes::<BE, Ipv4Be>(&data).unwrap(), Ipv4Be(addr));
+      assert_eq!(IpAddr::from(from_bytes::<LE, Ipv4Be>(&data).unwrap()), IpAddr::V4(addr));
+    }
+  }
+  #[test]
+  fn test_ipv6() {
+    let addr = Ipv6Addr::new(0x2001, 0x0db8, 0, 0, 0, 0, 0, 0x0001);
+    let data = [0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x01];
+    assert_eq!(to_vec::<BE, _>(&Ipv6Be(addr)).unwrap(), data);
+    assert_eq!(to_vec::<LE, _>(&Ipv6Be(addr)).unwrap(), data);
+    assert_eq!(from_bytes::<LE, Ipv6Be>(&data).unwrap(), Ipv6Be(addr));
+
+    let localhost = from_bytes::<LE, Ipv6Be>(&Ipv6Addr::LOCALHOST.octets()).unwrap();
+    assert_eq!(IpAddr::from(localhost), IpAddr::V6(Ipv6Addr::LOCALHOST));
+  }
+  #[test]
+  fn test_port() {
+    assert_eq!(to_vec::<LE, _>(&PortBe(443)).unwrap(), [0x01, 0xBB]);
+    assert_eq!(to_vec::<BE, _>(&PortBe(443)).unwrap(), [0x01, 0xBB]);
+    assert_eq!(from_bytes::<LE, PortBe>(&[0x01, 0xBB]).unwrap(), PortBe(443));
+  }
+  #[test]
+  fn test_socket() {
+    let v4 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 53);
+    let data = [10, 0, 0, 1,   0x00, 0x35];
+    assert_eq!(to_vec::<LE, _>(&SocketV4Be(v4)).unwrap(), data);
+    assert_eq!(SocketAddr::from(from_bytes::<LE, SocketV4Be>(&data).unwrap()), SocketAddr::V4(v4));
+
+    let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0);
+    let mut data = Ipv6Addr::LOCALHOST.octets().to_vec();
+    data.extend(&[0x1F, 0x90]);
+    assert_eq!(to_vec::<LE, _>(&SocketV6Be(v6)).unwrap(), data);
+    assert_eq!(SocketAddr::from(from_bytes::<BE, SocketV6Be>(&data).unwrap()), SocketAddr::V6(v6));
+  }
+  #[test]
+  fn test_no_data() {
+    assert!(from_bytes::<BE, Ipv4Be>(&[127, 0, 0]).is_err());
+    assert!(from_bytes::<BE, SocketV6Be>(&[0; 17]).is_err());
+  }
+}
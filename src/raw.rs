@@ -0,0 +1,69 @@
+//! Содержит вспомогательные типы для записи и чтения массивов байт фиксированной длины.
+//!
+//! В отличие от [`Serializer::serialize_bytes`], массивы записываются, как кортежи из байт,
+//! поэтому их представление не зависит от настроек сериализатора, а при чтении из потока
+//! читается ровно указанное количество байт.
+//!
+//! [`Serializer::serialize_bytes`]: https://docs.serde.rs/serde/trait.Serializer.html#tymethod.serialize_bytes
+
+use std::fmt;
+use serde::de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use magic;
+
+/// Массив байт, записываемый в поток как есть
+pub struct Raw<'a>(pub &'a [u8]);
+impl<'a> Serialize for Raw<'a> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(self.0.len())?;
+    for b in self.0 {
+      tuple.serialize_element(b)?;
+    }
+    tuple.end()
+  }
+}
+
+/// Читает из потока указанное количество байт за одну операцию чтения
+pub struct RawBuf(pub usize);
+impl<'de> DeserializeSeed<'de> for RawBuf {
+  type Value = Vec<u8>;
+
+  fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    // Ширина байта всегда есть в таблице
+    let name = magic::bulk_array_name(1).unwrap_or("RawBuf");
+    deserializer.deserialize_tuple_struct(name, self.0, self)
+  }
+}
+impl<'de> Visitor<'de> for RawBuf {
+  type Value = Vec<u8>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "{} bytes", self.0)
+  }
+  fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+    if v.len() != self.0 {
+      return Err(de::Error::invalid_length(v.len(), &self));
+    }
+    Ok(v.to_vec())
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut buf = Vec::with_capacity(capped_capacity(self.0));
+    for i in 0..self.0 {
+      buf.push(seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?);
+    }
+    Ok(buf)
+  }
+}
+
+/// Наибольшее количество элементов, под которое заранее выделяется память
+const MAX_PREALLOCATED: usize = 4096;
+
+/// Возвращает емкость буфера, выделяемого заранее под `count` элементов. Количество обычно
+/// прочитано из потока или взято из заголовка формата, поэтому ему не доверяется: память
+/// выделяется не более, чем под 4096 элементов, и поврежденный заголовок приводит к ошибке
+/// чтения, а не к попытке выделить огромный буфер. Остальная память выделяется по мере чтения
+#[inline]
+pub(crate) fn capped_capacity(count: usize) -> usize {
+  count.min(MAX_PREALLOCATED)
+}
@@ -0,0 +1,437 @@
+//! Содержит таблицы функций чтения, позволяющие десериализовать данные, тип которых
+//! определяется числовой меткой в потоке или предшествующим полем.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, Write};
+use byteorder::ByteOrder;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+
+use de::Deserializer;
+use error::{Error, Result};
+use ser::Serializer;
+
+/// Функция, читающая запись из десериализатора
+type Reader<BO, R, T> = Box<dyn Fn(&mut Deserializer<BO, R>) -> Result<T>>;
+
+/// Таблица, сопоставляющая числовым меткам (дискриминантам) функции, которые читают из потока
+/// данные соответствующих типов и преобразуют их в значение общего типа `T`, как правило,
+/// перечисления. Используется для чтения данных, тип которых определяется предшествующим
+/// им полем, например, полем `kind` структуры.
+///
+/// Для чтения значения используется метод [`Deserializer::deserialize_variant`].
+///
+/// # Параметры типа
+/// - `BO`: порядок байт десериализатора, с которым будет использоваться таблица
+/// - `R`: тип потока десериализатора, с которым будет использоваться таблица
+/// - `T`: тип значений, возвращаемых функциями чтения
+///
+/// [`Deserializer::deserialize_variant`]: ../de/struct.Deserializer.html#method.deserialize_variant
+pub struct VariantTable<BO, R, T> {
+  /// Функции чтения, индексированные меткой
+  readers: HashMap<u64, Reader<BO, R, T>>,
+}
+
+/// Реестр, сопоставляющий числовым меткам функции, которые читают из потока записи
+/// соответствующих типов. Позволяет, например, подключаемым модулям регистрировать
+/// новые типы записей во время выполнения программы.
+///
+/// Для чтения записи используется метод [`Deserializer::read_registered`].
+///
+/// # Параметры типа
+/// - `BO`: порядок байт десериализатора, с которым будет использоваться реестр
+/// - `R`: тип потока десериализатора, с которым будет использоваться реестр
+///
+/// [`Deserializer::read_registered`]: ../de/struct.Deserializer.html#method.read_registered
+pub type Registry<BO, R> = VariantTable<BO, R, Box<dyn Any>>;
+
+impl<BO, R, T> VariantTable<BO, R, T> {
+  /// Создает пустую таблицу
+  pub fn new() -> Self {
+    VariantTable { readers: HashMap::new() }
+  }
+  /// Регистрирует функцию чтения данных с указанной меткой. Если для метки уже была
+  /// зарегистрирована функция, она заменяется новой
+  ///
+  /// # Параметры
+  /// - `tag`: Метка, которой соответствуют данные
+  /// - `reader`: Функция, читающая данные из десериализатора. Метка к моменту ее вызова
+  ///   уже прочитана
+  pub fn register<F>(&mut self, tag: u64, reader: F) -> &mut Self
+    where F: Fn(&mut Deserializer<BO, R>) -> Result<T> + 'static,
+  {
+    self.readers.insert(tag, Box::new(reader));
+    self
+  }
+  /// Возвращает `true`, если для указанной метки зарегистрирована функция чтения
+  #[inline]
+  pub fn contains(&self, tag: u64) -> bool {
+    self.readers.contains_key(&tag)
+  }
+}
+impl<BO, R, T> Default for VariantTable<BO, R, T> {
+  fn default() -> Self { Self::new() }
+}
+impl<BO, R, T> fmt::Debug for VariantTable<BO, R, T> {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.debug_set().entries(self.readers.keys()).finish()
+  }
+}
+
+/// Таблица миграций записей, формат которых менялся со временем. Каждая запись в потоке
+/// начинается с номера версии формата (`u16`), за которым следуют поля этой версии. Для каждой
+/// устаревшей версии регистрируется тип, описывающий ее поля, и функция, преобразующая его
+/// в запись текущей версии: заполняет значениями по умолчанию добавленные поля, переносит
+/// значения переименованных полей и т.п.
+///
+/// Для чтения записи используется функция [`read_migrating`], для записи -- метод [`write`].
+///
+/// # Параметры типа
+/// - `BO`: порядок байт десериализатора, с которым будет использоваться таблица
+/// - `R`: тип потока десериализатора, с которым будет использоваться таблица
+/// - `T`: тип записи текущей версии
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::de::Deserializer;
+/// # use serde_pod::registry::{read_migrating, MigrationTable};
+/// #[derive(Deserialize)]
+/// struct ItemV1 { id: u16, cost: u16 }
+///
+/// #[derive(Debug, Deserialize, PartialEq)]
+/// struct Item { id: u16, price: u32 }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut table = MigrationTable::new(2);
+/// table.register(1, |old: ItemV1| Item { id: old.id, price: old.cost.into() });
+///
+/// let data: &[u8] = &[0x01, 0x00,   0x07, 0x00, 0x64, 0x00];
+/// let item = read_migrating(&mut Deserializer::<LE, _>::new(data), &table)?;
+/// assert_eq!(item, Item { id: 7, price: 100 });
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`read_migrating`]: fn.read_migrating.html
+/// [`write`]: #method.write
+pub struct MigrationTable<BO, R, T> {
+  /// Номер текущей версии формата
+  current: u16,
+  /// Функции чтения записей, индексированные версией
+  readers: VariantTable<BO, R, T>,
+}
+
+impl<BO, R, T> MigrationTable<BO, R, T>
+  where BO: ByteOrder + 'static,
+        R: BufRead + 'static,
+        T: DeserializeOwned + 'static,
+{
+  /// Создает таблицу, в которой зарегистрировано только чтение записей текущей версии
+  /// непосредственно в тип `T`
+  ///
+  /// # Параметры
+  /// - `current`: Номер текущей версии формата
+  pub fn new(current: u16) -> Self {
+    let mut readers = VariantTable::new();
+    readers.register(current.into(), |de| T::deserialize(de));
+    MigrationTable { current, readers }
+  }
+}
+impl<BO, R, T> MigrationTable<BO, R, T>
+  where BO: ByteOrder + 'static,
+        R: BufRead + 'static,
+        T: 'static,
+{
+  /// Регистрирует устаревшую версию формата. Если для версии уже была зарегистрирована
+  /// миграция, она заменяется новой
+  ///
+  /// # Параметры
+  /// - `version`: Номер версии формата
+  /// - `migrate`: Функция, преобразующая запись версии `version`, прочитанную как тип `Old`,
+  ///   в запись текущей версии
+  pub fn register<Old, F>(&mut self, version: u16, migrate: F) -> &mut Self
+    where Old: DeserializeOwned,
+          F: Fn(Old) -> T + 'static,
+  {
+    self.readers.register(version.into(), move |de| Old::deserialize(de).map(&migrate));
+    self
+  }
+}
+impl<BO, R, T> MigrationTable<BO, R, T> {
+  /// Возвращает номер текущей версии формата
+  #[inline]
+  pub fn current(&self) -> u16 { self.current }
+  /// Возвращает `true`, если для указанной версии зарегистрирована функция чтения
+  #[inline]
+  pub fn contains(&self, version: u16) -> bool {
+    self.readers.contains(version.into())
+  }
+}
+impl<BO: ByteOrder, R, T: Serialize> MigrationTable<BO, R, T> {
+  /// Записывает номер текущей версии формата, а затем саму запись
+  ///
+  /// # Параметры
+  /// - `ser`: Сериализатор, в который записывается запись
+  /// - `value`: Записываемая запись
+  pub fn write<W: Write>(&self, ser: &mut Serializer<BO, W>, value: &T) -> Result<()> {
+    self.current.serialize(&mut *ser)?;
+    value.serialize(ser)
+  }
+}
+impl<BO, R, T> fmt::Debug for MigrationTable<BO, R, T> {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.debug_struct("MigrationTable")
+       .field("current", &self.current)
+       .field("versions", &self.readers)
+       .finish()
+  }
+}
+
+/// Читает из потока номер версии формата (`u16`), а затем запись этой версии, преобразуя ее
+/// в запись текущей версии функцией миграции, зарегистрированной в таблице.
+///
+/// # Параметры
+/// - `de`: Десериализатор, из которого читается запись
+/// - `table`: Таблица миграций
+///
+/// # Ошибки
+/// - [`Error::Unknown`]: для прочитанной версии не зарегистрирована миграция
+/// - ошибки чтения записи
+///
+/// [`Error::Unknown`]: ../error/enum.Error.html#variant.Unknown
+pub fn read_migrating<BO, R, T>(de: &mut Deserializer<BO, R>, table: &MigrationTable<BO, R, T>) -> Result<T>
+  where BO: ByteOrder,
+        R: BufRead,
+{
+  let version = u16::deserialize(&mut *de)?;
+  match table.readers.readers.get(&version.into()) {
+    Some(reader) => reader(de),
+    None => Err(Error::Unknown(format!("no migration registered for version {}", version))),
+  }
+}
+
+impl<BO, R> Deserializer<BO, R>
+  where R: BufRead,
+        BO: ByteOrder,
+{
+  /// Читает данные, тип которых определяется уже прочитанной меткой `tag`, функцией,
+  /// зарегистрированной в таблице для этой метки.
+  ///
+  /// # Параметры
+  /// - `table`: Таблица функций чтения
+  /// - `tag`: Метка (дискриминант) данных
+  ///
+  /// # Ошибки
+  /// - [`Error::Unknown`]: для метки не зарегистрирована функция чтения
+  /// - ошибки, возвращенные функцией чтения
+  ///
+  /// # Пример
+  /// ```rust
+  /// # extern crate byteorder;
+  /// # #[macro_use]
+  /// # extern crate serde_derive;
+  /// # extern crate serde;
+  /// # extern crate serde_pod;
+  /// # use byteorder::LE;
+  /// # use serde::Deserialize;
+  /// # use serde_pod::de::Deserializer;
+  /// # use serde_pod::registry::VariantTable;
+  /// #[derive(Debug, Deserialize, PartialEq)]
+  /// struct Circle { radius: u16 }
+  ///
+  /// #[derive(Debug, Deserialize, PartialEq)]
+  /// struct Rect { width: u16, height: u16 }
+  ///
+  /// #[derive(Debug, PartialEq)]
+  /// enum Shape { Circle(Circle), Rect(Rect) }
+  ///
+  /// # fn main() -> serde_pod::Result<()> {
+  /// let mut table = VariantTable::new();
+  /// table
+  ///   .register(1, |de| Circle::deserialize(de).map(Shape::Circle))
+  ///   .register(2, |de| Rect::deserialize(de).map(Shape::Rect));
+  ///
+  /// // kind: u32, затем данные, тип которых зависит от kind
+  /// let data: &[u8] = &[0x02, 0x00, 0x00, 0x00,   0x0A, 0x00, 0x14, 0x00];
+  /// let mut de: Deserializer<LE, _> = Deserializer::new(data);
+  /// let kind = u32::deserialize(&mut de)?;
+  /// let shape = de.deserialize_variant(&table, kind.into())?;
+  ///
+  /// assert_eq!(shape, Shape::Rect(Rect { width: 10, height: 20 }));
+  /// # Ok(())
+  /// # }
+  /// ```
+  ///
+  /// [`Error::Unknown`]: ../error/enum.Error.html#variant.Unknown
+  pub fn deserialize_variant<T>(&mut self, table: &VariantTable<BO, R, T>, tag: u64) -> Result<T> {
+    match table.readers.get(&tag) {
+      Some(reader) => reader(self),
+      None => Err(Error::Unknown(format!("no reader registered for tag {}", tag))),
+    }
+  }
+  /// Читает из потока беззнаковую метку шириной `tag_width` байт (1, 2, 4 или 8), а затем
+  /// запись, функция чтения которой зарегистрирована в реестре для этой метки.
+  ///
+  /// # Параметры
+  /// - `registry`: Реестр функций чтения записей
+  /// - `tag_width`: Ширина метки в байтах
+  ///
+  /// # Возвращаемое значение
+  /// Прочитанная запись. Для получения значения конкретного типа используйте [`Box::downcast`]
+  ///
+  /// # Ошибки
+  /// - [`Error::Unsupported`]: ширина метки не равна 1, 2, 4 или 8
+  /// - [`Error::Unknown`]: для прочитанной метки не зарегистрирована функция чтения
+  /// - ошибки, возвращенные функцией чтения записи
+  ///
+  /// [`Box::downcast`]: https://doc.rust-lang.org/std/boxed/struct.Box.html#method.downcast
+  /// [`Error::Unsupported`]: ../error/enum.Error.html#variant.Unsupported
+  /// [`Error::Unknown`]: ../error/enum.Error.html#variant.Unknown
+  pub fn read_registered(&mut self, registry: &Registry<BO, R>, tag_width: usize) -> Result<Box<dyn Any>> {
+    let tag = match tag_width {
+      1 => u64::from(u8::deserialize(&mut *self)?),
+      2 => u64::from(u16::deserialize(&mut *self)?),
+      4 => u64::from(u32::deserialize(&mut *self)?),
+      8 => u64::deserialize(&mut *self)?,
+      _ => return Err(Error::Unsupported("tag width must be 1, 2, 4 or 8 bytes")),
+    };
+    self.deserialize_variant(registry, tag)
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use std::any::Any;
+  use byteorder::LE;
+  use serde::Deserialize;
+  use de::Deserializer;
+  use super::{Registry, VariantTable};
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Point {
+    x: u16,
+    y: u16,
+  }
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Color(u8, u8, u8);
+
+  #[test]
+  fn test_dispatch() {
+    let mut registry = Registry::new();
+    registry
+      .register(1, |de| Ok(Box::new(Point::deserialize(de)?) as Box<dyn Any>))
+      .register(2, |de| Ok(Box::new(Color::deserialize(de)?) as Box<dyn Any>));
+
+    let data: &[u8] = &[
+      0x02, 0x00,   0xFF, 0x80, 0x00,
+      0x01, 0x00,   0x34, 0x12, 0x78, 0x56,
+      0x03, 0x00,
+    ];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+
+    let color = de.read_registered(&registry, 2).unwrap();
+    assert_eq!(color.downcast_ref::<Color>(), Some(&Color(0xFF, 0x80, 0x00)));
+
+    let point = de.read_registered(&registry, 2).unwrap();
+    assert_eq!(point.downcast_ref::<Point>(), Some(&Point { x: 0x1234, y: 0x5678 }));
+
+    // Неизвестная метка
+    assert!(de.read_registered(&registry, 2).is_err());
+  }
+  #[test]
+  fn test_invalid_width() {
+    let registry = Registry::new();
+    let mut de: Deserializer<LE, _> = Deserializer::new(&[0x01, 0x00, 0x00][..]);
+    assert!(de.read_registered(&registry, 3).is_err());
+  }
+
+  /// Тип данных определяется полем, прочитанным ранее
+  #[test]
+  fn test_variant() {
+    #[derive(Debug, PartialEq)]
+    enum Payload {
+      Point(Point),
+      Color(Color),
+    }
+    let mut table = VariantTable::new();
+    table
+      .register(1, |de| Point::deserialize(de).map(Payload::Point))
+      .register(2, |de| Color::deserialize(de).map(Payload::Color));
+
+    let data: &[u8] = &[
+      0x01, 0x00, 0x00, 0x00,   0x34, 0x12, 0x78, 0x56,
+      0x02, 0x00, 0x00, 0x00,   0xFF, 0x80, 0x00,
+    ];
+    let mut de: Deserializer<LE, _> = Deserializer::new(data);
+
+    let kind = u32::deserialize(&mut de).unwrap();
+    assert_eq!(de.deserialize_variant(&table, kind.into()).unwrap(), Payload::Point(Point { x: 0x1234, y: 0x5678 }));
+    let kind = u32::deserialize(&mut de).unwrap();
+    assert_eq!(de.deserialize_variant(&table, kind.into()).unwrap(), Payload::Color(Color(0xFF, 0x80, 0x00)));
+
+    assert!(de.deserialize_variant(&table, 3).is_err());
+  }
+}
+
+#[cfg(test)]
+mod migration {
+  use std::io::{BufRead, Cursor};
+  use byteorder::LE;
+  use de::Deserializer;
+  use ser::Serializer;
+  use super::{read_migrating, MigrationTable};
+
+  /// Первая версия записи
+  #[derive(Debug, Deserialize)]
+  struct RecordV1 {
+    id: u32,
+    label: u16,
+  }
+  /// Текущая версия записи: поле `label` переименовано в `name`, добавлено поле `flags`
+  #[derive(Debug, Deserialize, Serialize, PartialEq)]
+  struct Record {
+    id: u32,
+    name: u16,
+    flags: u8,
+  }
+
+  fn table<R: BufRead + 'static>() -> MigrationTable<LE, R, Record> {
+    let mut table = MigrationTable::new(2);
+    table.register(1, |old: RecordV1| Record { id: old.id, name: old.label, flags: 0 });
+    table
+  }
+
+  #[test]
+  fn test_v1() {
+    let data: &[u8] = &[0x01, 0x00,   0x2A, 0x00, 0x00, 0x00,   0x05, 0x00];
+    let mut de = Deserializer::<LE, _>::new(data);
+    assert_eq!(read_migrating(&mut de, &table()).unwrap(), Record { id: 42, name: 5, flags: 0 });
+    assert!(de.is_eof().unwrap());
+  }
+  #[test]
+  fn test_current() {
+    let table = table::<Cursor<Vec<u8>>>();
+    let record = Record { id: 42, name: 5, flags: 3 };
+    let mut buf = Vec::new();
+    table.write(&mut Serializer::<LE, _>::new(&mut buf), &record).unwrap();
+    assert_eq!(buf, [0x02, 0x00,   0x2A, 0x00, 0x00, 0x00,   0x05, 0x00,   0x03]);
+
+    let mut de = Deserializer::<LE, _>::new(Cursor::new(buf));
+    assert_eq!(read_migrating(&mut de, &table).unwrap(), record);
+  }
+  #[test]
+  fn test_unknown_version() {
+    let data: &[u8] = &[0x03, 0x00,   0x2A, 0x00, 0x00, 0x00];
+    let mut de = Deserializer::<LE, _>::new(data);
+    assert!(read_migrating(&mut de, &table()).is_err());
+  }
+}
@@ -0,0 +1,135 @@
+//! Содержит типы для чтения и записи форматов, состоящих из чанков: RIFF (WAV, AVI) и IFF.
+//!
+//! Чанк состоит из 4-х байтового идентификатора (FourCC), размера данных в виде `u32` и самих
+//! данных, дополненных нулевым байтом до четной длины. В RIFF размер записывается в порядке
+//! `Little-Endian`, в IFF -- в порядке `Big-Endian`, поэтому формат выбирается порядком байт
+//! сериализатора.
+//!
+//! # Пример
+//! ```rust
+//! # extern crate byteorder;
+//! # extern crate serde_pod;
+//! # use byteorder::LE;
+//! # use serde_pod::{from_bytes, to_vec};
+//! # use serde_pod::riff::Chunk;
+//! # fn main() -> serde_pod::Result<()> {
+//! let chunk = Chunk { id: *b"LIST", data: b"abc".to_vec() };
+//! let data = b"LIST\x03\0\0\0abc\0";
+//! assert_eq!(to_vec::<LE, _>(&chunk)?, &data[..]);
+//! assert_eq!(from_bytes::<LE, Chunk>(data)?, chunk);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeTuple, Serializer};
+
+use framing::checked_len;
+use raw::{Raw, RawBuf};
+
+/// Чанк формата RIFF/IFF: идентификатор и данные. При сериализации размер данных и байт
+/// дополнения до четной длины вычисляются автоматически, при десериализации байт дополнения
+/// пропускается без проверки.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Chunk {
+  /// Идентификатор чанка (FourCC), например, `RIFF`, `fmt ` или `data`
+  pub id: [u8; 4],
+  /// Данные чанка без байта дополнения
+  pub data: Vec<u8>,
+}
+
+impl Chunk {
+  /// Возвращает количество байт, занимаемых чанком в потоке, включая заголовок и дополнение
+  #[inline]
+  pub fn size(&self) -> usize {
+    8 + self.data.len() + padding(self.data.len())
+  }
+}
+
+/// Возвращает количество байт дополнения после данных длиной `len` байт
+#[inline]
+fn padding(len: usize) -> usize { len & 1 }
+
+impl Serialize for Chunk {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = self.data.len();
+    if len > u32::MAX as usize {
+      return Err(ser::Error::custom(format_args!("chunk of {} bytes is too big", len)));
+    }
+    let mut tuple = serializer.serialize_tuple(4)?;
+    tuple.serialize_element(&Raw(&self.id))?;
+    tuple.serialize_element(&(len as u32))?;
+    tuple.serialize_element(&Raw(&self.data))?;
+    tuple.serialize_element(&Raw(&[0; 1][..padding(len)]))?;
+    tuple.end()
+  }
+}
+impl<'de> Deserialize<'de> for Chunk {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(4, ChunkVisitor)
+  }
+}
+
+/// Посетитель, читающий идентификатор, размер, данные и дополнение чанка
+struct ChunkVisitor;
+impl<'de> Visitor<'de> for ChunkVisitor {
+  type Value = Chunk;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a RIFF/IFF chunk")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let id: [u8; 4] = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let len: u32 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    let len = checked_len(len)?;
+    let data = seq.next_element_seed(RawBuf(len))?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+    seq.next_element_seed(RawBuf(padding(len)))?.ok_or_else(|| de::Error::invalid_length(3, &self))?;
+    Ok(Chunk { id, data })
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use serde::Deserialize;
+  use de::{from_bytes, Deserializer};
+  use ser::to_vec;
+  use super::Chunk;
+
+  /// Данные нечетной длины дополняются нулевым байтом, который не входит в размер
+  #[test]
+  fn test_odd() {
+    let chunk = Chunk { id: *b"fmt ", data: vec![0x01, 0x02, 0x03] };
+    let riff = [b'f', b'm', b't', b' ',   0x03, 0x00, 0x00, 0x00,   0x01, 0x02, 0x03,   0x00];
+    let iff  = [b'f', b'm', b't', b' ',   0x00, 0x00, 0x00, 0x03,   0x01, 0x02, 0x03,   0x00];
+    assert_eq!(chunk.size(), 12);
+    assert_eq!(to_vec::<LE,_>(&chunk).unwrap(), riff);
+    assert_eq!(to_vec::<BE,_>(&chunk).unwrap(), iff);
+    assert_eq!(from_bytes::<LE, Chunk>(&riff).unwrap(), chunk);
+    assert_eq!(from_bytes::<BE, Chunk>(&iff).unwrap(), chunk);
+  }
+  #[test]
+  fn test_even() {
+    let chunk = Chunk { id: *b"data", data: vec![0x01, 0x02] };
+    let riff = [b'd', b'a', b't', b'a',   0x02, 0x00, 0x00, 0x00,   0x01, 0x02];
+    assert_eq!(chunk.size(), 10);
+    assert_eq!(to_vec::<LE,_>(&chunk).unwrap(), riff);
+    assert_eq!(from_bytes::<LE, Chunk>(&riff).unwrap(), chunk);
+  }
+  /// После байта дополнения следующий чанк читается с правильной позиции
+  #[test]
+  fn test_sequence() {
+    let data = b"ab  \x01\0\0\0Z\0cd  \0\0\0\0";
+    let mut de = Deserializer::<LE, _>::new(&data[..]);
+    assert_eq!(Chunk::deserialize(&mut de).unwrap(), Chunk { id: *b"ab  ", data: b"Z".to_vec() });
+    assert_eq!(Chunk::deserialize(&mut de).unwrap(), Chunk { id: *b"cd  ", data: Vec::new() });
+  }
+  /// Отсутствие байта дополнения является ошибкой
+  #[test]
+  fn test_no_padding() {
+    assert!(from_bytes::<LE, Chunk>(b"fmt \x03\0\0\0abc").is_err());
+  }
+}
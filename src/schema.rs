@@ -0,0 +1,493 @@
+//! Содержит схему данных -- описание полей значения (их имен и примитивных типов), которое
+//! может быть записано в поток перед самими данными. Такие самоописывающие данные могут быть
+//! прочитаны программой, не знающей их типа: она читает схему, а затем значения полей по ней.
+//!
+//! Схема строится обходом значения специальным сериализатором, поэтому описываются только
+//! значения с фиксированным набором полей: структуры, кортежи и массивы, состоящие из чисел
+//! и `bool`. Поля вложенных структур и кортежей описываются плоским списком с составными
+//! именами, например, `pos.x` или `color.0`.
+//!
+//! # Пример
+//! ```rust
+//! # extern crate byteorder;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_pod;
+//! # use byteorder::LE;
+//! # use serde_pod::schema::{from_bytes_self_describing, to_vec_self_describing, Value};
+//! #[derive(Serialize)]
+//! struct Point { x: i16, y: i16 }
+//!
+//! # fn main() -> serde_pod::Result<()> {
+//! let data = to_vec_self_describing::<LE, _>(&Point { x: 1, y: -1 })?;
+//!
+//! let (schema, values) = from_bytes_self_describing::<LE>(&data)?;
+//! assert_eq!(schema.fields()[1].name, "y");
+//! assert_eq!(values, [Value::I16(1), Value::I16(-1)]);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{BufRead, Write};
+use byteorder::ByteOrder;
+use serde::Deserialize;
+use serde::ser::{self, Impossible, Serialize};
+
+use de::Deserializer;
+use error::{Error, Result};
+use ser::Serializer;
+use strings::PascalString;
+
+/// Примитивный тип поля, описываемого схемой
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FieldType {
+  /// `bool`, 1 байт
+  Bool,
+  /// `u8`
+  U8,
+  /// `u16`
+  U16,
+  /// `u32`
+  U32,
+  /// `u64`
+  U64,
+  /// `i8`
+  I8,
+  /// `i16`
+  I16,
+  /// `i32`
+  I32,
+  /// `i64`
+  I64,
+  /// `f32`
+  F32,
+  /// `f64`
+  F64,
+}
+
+/// Типы полей в порядке их кодов в потоке
+const TYPES: [FieldType; 11] = [
+  FieldType::Bool,
+  FieldType::U8, FieldType::U16, FieldType::U32, FieldType::U64,
+  FieldType::I8, FieldType::I16, FieldType::I32, FieldType::I64,
+  FieldType::F32, FieldType::F64,
+];
+
+impl FieldType {
+  /// Возвращает количество байт, занимаемых значением этого типа в потоке
+  pub fn width(self) -> usize {
+    match self {
+      FieldType::Bool | FieldType::U8 | FieldType::I8 => 1,
+      FieldType::U16 | FieldType::I16 => 2,
+      FieldType::U32 | FieldType::I32 | FieldType::F32 => 4,
+      FieldType::U64 | FieldType::I64 | FieldType::F64 => 8,
+    }
+  }
+  /// Возвращает код, которым тип записывается в поток
+  fn code(self) -> u8 {
+    TYPES.iter().position(|&ty| ty == self).unwrap() as u8
+  }
+  /// Возвращает тип по его коду в потоке
+  fn from_code(code: u8) -> Option<Self> {
+    TYPES.get(code as usize).cloned()
+  }
+}
+
+/// Описание одного поля
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+  /// Имя поля. Имена полей вложенных структур и кортежей составляются из имен (или индексов)
+  /// всех охватывающих полей через точку. Значение примитивного типа, описываемое схемой
+  /// целиком, имеет пустое имя
+  pub name: String,
+  /// Тип поля
+  pub ty: FieldType,
+}
+
+/// Значение поля, прочитанное по схеме
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+  /// Значение типа `bool`
+  Bool(bool),
+  /// Значение типа `u8`
+  U8(u8),
+  /// Значение типа `u16`
+  U16(u16),
+  /// Значение типа `u32`
+  U32(u32),
+  /// Значение типа `u64`
+  U64(u64),
+  /// Значение типа `i8`
+  I8(i8),
+  /// Значение типа `i16`
+  I16(i16),
+  /// Значение типа `i32`
+  I32(i32),
+  /// Значение типа `i64`
+  I64(i64),
+  /// Значение типа `f32`
+  F32(f32),
+  /// Значение типа `f64`
+  F64(f64),
+}
+
+/// Схема данных: список полей значения в порядке их записи в поток.
+///
+/// В потоке схема записывается, как количество полей типа `u16`, за которым для каждого поля
+/// следуют длина имени типа `u8`, имя в UTF-8 и код типа поля размером 1 байт. Числа
+/// записываются в порядке байт сериализатора.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Schema {
+  /// Поля в порядке их записи в поток
+  fields: Vec<Field>,
+}
+
+impl Schema {
+  /// Строит схему указанного значения, обходя его поля
+  ///
+  /// # Ошибки
+  /// [`Error::Unsupported`]: значение содержит данные, которые не могут быть описаны схемой:
+  /// строки, последовательности переменной длины, отображения, `Option` или перечисления
+  ///
+  /// [`Error::Unsupported`]: ../error/enum.Error.html#variant.Unsupported
+  pub fn of<T: ?Sized + Serialize>(value: &T) -> Result<Self> {
+    let mut fields = Vec::new();
+    value.serialize(Describer { fields: &mut fields, name: String::new() })?;
+    Ok(Schema { fields })
+  }
+  /// Возвращает поля в порядке их записи в поток
+  #[inline]
+  pub fn fields(&self) -> &[Field] { &self.fields }
+  /// Записывает схему в поток
+  ///
+  /// # Ошибки
+  /// [`Error::InvalidLength`]: полей больше `u16::MAX` или имя поля длиннее 255 байт
+  ///
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  pub fn write<BO, W>(&self, ser: &mut Serializer<BO, W>) -> Result<()>
+    where BO: ByteOrder,
+          W: Write,
+  {
+    let count = self.fields.len();
+    if count > u16::MAX as usize {
+      return Err(Error::InvalidLength(format!("{} fields do not fit into a schema", count)));
+    }
+    (count as u16).serialize(&mut *ser)?;
+    for field in &self.fields {
+      PascalString::<u8>::new(field.name.as_str()).serialize(&mut *ser)
+        .map_err(|_| Error::InvalidLength(format!("field name `{}` is too long", field.name)))?;
+      field.ty.code().serialize(&mut *ser)?;
+    }
+    Ok(())
+  }
+  /// Читает схему из потока
+  ///
+  /// # Ошибки
+  /// Ошибка чтения или [`Error::Unknown`], если код типа поля неизвестен
+  ///
+  /// [`Error::Unknown`]: ../error/enum.Error.html#variant.Unknown
+  pub fn read<BO, R>(de: &mut Deserializer<BO, R>) -> Result<Self>
+    where BO: ByteOrder,
+          R: BufRead,
+  {
+    let count = u16::deserialize(&mut *de)?;
+    let mut fields = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+      let name = PascalString::<u8>::deserialize(&mut *de)?.into_inner();
+      let code = u8::deserialize(&mut *de)?;
+      let ty = FieldType::from_code(code)
+        .ok_or_else(|| Error::Unknown(format!("unknown type code {} of field `{}`", code, name)))?;
+      fields.push(Field { name, ty });
+    }
+    Ok(Schema { fields })
+  }
+  /// Читает из потока значения всех полей схемы
+  pub fn read_values<BO, R>(&self, de: &mut Deserializer<BO, R>) -> Result<Vec<Value>>
+    where BO: ByteOrder,
+          R: BufRead,
+  {
+    self.fields.iter().map(|field| Ok(match field.ty {
+      FieldType::Bool => Value::Bool(bool::deserialize(&mut *de)?),
+      FieldType::U8  => Value::U8(u8::deserialize(&mut *de)?),
+      FieldType::U16 => Value::U16(u16::deserialize(&mut *de)?),
+      FieldType::U32 => Value::U32(u32::deserialize(&mut *de)?),
+      FieldType::U64 => Value::U64(u64::deserialize(&mut *de)?),
+      FieldType::I8  => Value::I8(i8::deserialize(&mut *de)?),
+      FieldType::I16 => Value::I16(i16::deserialize(&mut *de)?),
+      FieldType::I32 => Value::I32(i32::deserialize(&mut *de)?),
+      FieldType::I64 => Value::I64(i64::deserialize(&mut *de)?),
+      FieldType::F32 => Value::F32(f32::deserialize(&mut *de)?),
+      FieldType::F64 => Value::F64(f64::deserialize(&mut *de)?),
+    })).collect()
+  }
+}
+
+/// Строит схему типа `T` по его значению по умолчанию. Подходит для типов, набор полей которых
+/// не зависит от значения
+///
+/// # Ошибки
+/// См. [`Schema::of`](struct.Schema.html#method.of)
+pub fn describe<T: Default + Serialize>() -> Result<Schema> {
+  Schema::of(&T::default())
+}
+
+/// Сериализует значение в вектор, предваряя его схемой
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором записываются схема и данные
+///
+/// # Ошибки
+/// Ошибки построения схемы (см. [`Schema::of`]) или сериализации значения
+///
+/// [`Schema::of`]: struct.Schema.html#method.of
+pub fn to_vec_self_describing<BO, T>(value: &T) -> Result<Vec<u8>>
+  where BO: ByteOrder,
+        T: ?Sized + Serialize,
+{
+  let schema = Schema::of(value)?;
+  let mut buf = Vec::new();
+  {
+    let mut ser = Serializer::<BO, _>::new(&mut buf);
+    schema.write(&mut ser)?;
+    value.serialize(&mut ser)?;
+  }
+  Ok(buf)
+}
+
+/// Читает из массива байт схему, а затем значения полей по ней. Обратная операция к
+/// [`to_vec_self_describing`], не требующая знания типа записанного значения
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором записаны схема и данные
+///
+/// [`to_vec_self_describing`]: fn.to_vec_self_describing.html
+pub fn from_bytes_self_describing<BO: ByteOrder>(storage: &[u8]) -> Result<(Schema, Vec<Value>)> {
+  let mut de = Deserializer::<BO, _>::new(storage);
+  let schema = Schema::read(&mut de)?;
+  let values = schema.read_values(&mut de)?;
+  Ok((schema, values))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Сериализатор, ничего не записывающий, а собирающий описания полей значения
+struct Describer<'a> {
+  /// Собранные описания полей
+  fields: &'a mut Vec<Field>,
+  /// Имя описываемого значения
+  name: String,
+}
+
+impl<'a> Describer<'a> {
+  /// Добавляет описание значения примитивного типа
+  fn push(self, ty: FieldType) -> Result<()> {
+    self.fields.push(Field { name: self.name, ty });
+    Ok(())
+  }
+  /// Создает сериализатор для описания вложенного поля `part`
+  fn child(&mut self, part: &str) -> Describer<'_> {
+    let name = if self.name.is_empty() { part.into() } else { format!("{}.{}", self.name, part) };
+    Describer { fields: &mut *self.fields, name }
+  }
+}
+
+/// Макрос, реализующий методы сериализатора, которые не могут быть описаны схемой
+macro_rules! undescribable {
+  ($($method:ident($($arg:ty),*)),*) => {
+    $(
+      fn $method(self, $(_: $arg),*) -> Result<()> {
+        Err(Error::Unsupported(concat!("`", stringify!($method), "` cannot be described by a schema")))
+      }
+    )*
+  };
+}
+
+impl<'a> ser::Serializer for Describer<'a> {
+  type Ok = ();
+  type Error = Error;
+
+  type SerializeSeq = Impossible<(), Error>;
+  type SerializeTuple = Compound<'a>;
+  type SerializeTupleStruct = Compound<'a>;
+  type SerializeTupleVariant = Impossible<(), Error>;
+  type SerializeMap = Impossible<(), Error>;
+  type SerializeStruct = Compound<'a>;
+  type SerializeStructVariant = Impossible<(), Error>;
+
+  fn serialize_bool(self, _: bool) -> Result<()> { self.push(FieldType::Bool) }
+  fn serialize_u8 (self, _: u8 ) -> Result<()> { self.push(FieldType::U8) }
+  fn serialize_u16(self, _: u16) -> Result<()> { self.push(FieldType::U16) }
+  fn serialize_u32(self, _: u32) -> Result<()> { self.push(FieldType::U32) }
+  fn serialize_u64(self, _: u64) -> Result<()> { self.push(FieldType::U64) }
+  fn serialize_i8 (self, _: i8 ) -> Result<()> { self.push(FieldType::I8) }
+  fn serialize_i16(self, _: i16) -> Result<()> { self.push(FieldType::I16) }
+  fn serialize_i32(self, _: i32) -> Result<()> { self.push(FieldType::I32) }
+  fn serialize_i64(self, _: i64) -> Result<()> { self.push(FieldType::I64) }
+  fn serialize_f32(self, _: f32) -> Result<()> { self.push(FieldType::F32) }
+  fn serialize_f64(self, _: f64) -> Result<()> { self.push(FieldType::F64) }
+
+  undescribable!(
+    serialize_u128(u128), serialize_i128(i128),
+    serialize_char(char), serialize_str(&str), serialize_bytes(&[u8]),
+    serialize_none(), serialize_unit_variant(&'static str, u32, &'static str)
+  );
+  fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<()> {
+    Err(Error::Unsupported("`serialize_some` cannot be described by a schema"))
+  }
+  fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _name: &'static str, _index: u32, _variant: &'static str, _value: &T) -> Result<()> {
+    Err(Error::Unsupported("`serialize_newtype_variant` cannot be described by a schema"))
+  }
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+    Err(Error::Unsupported("`serialize_seq` cannot be described by a schema"))
+  }
+  fn serialize_tuple_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant> {
+    Err(Error::Unsupported("`serialize_tuple_variant` cannot be described by a schema"))
+  }
+  fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+    Err(Error::Unsupported("`serialize_map` cannot be described by a schema"))
+  }
+  fn serialize_struct_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant> {
+    Err(Error::Unsupported("`serialize_struct_variant` cannot be described by a schema"))
+  }
+
+  /// Ничего не описывает, т.к. значение не занимает места в потоке
+  fn serialize_unit(self) -> Result<()> { Ok(()) }
+  /// Ничего не описывает, т.к. значение не занимает места в потоке
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<()> { Ok(()) }
+  /// Описывает обернутое значение под именем самой структуры
+  fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<()> {
+    value.serialize(self)
+  }
+  fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+    Ok(Compound { de: self, index: 0 })
+  }
+  fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> {
+    Ok(Compound { de: self, index: 0 })
+  }
+  fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+    Ok(Compound { de: self, index: 0 })
+  }
+}
+
+/// Описывает поля структуры или элементы кортежа
+struct Compound<'a> {
+  /// Описание самой структуры или кортежа
+  de: Describer<'a>,
+  /// Индекс следующего элемента кортежа
+  index: usize,
+}
+
+impl<'a> Compound<'a> {
+  /// Описывает очередной элемент кортежа, именуя его индексом
+  fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+    let index = self.index.to_string();
+    self.index += 1;
+    value.serialize(self.de.child(&index))
+  }
+}
+
+impl<'a> ser::SerializeTuple for Compound<'a> {
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> { self.element(value) }
+  fn end(self) -> Result<()> { Ok(()) }
+}
+impl<'a> ser::SerializeTupleStruct for Compound<'a> {
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> { self.element(value) }
+  fn end(self) -> Result<()> { Ok(()) }
+}
+impl<'a> ser::SerializeStruct for Compound<'a> {
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+    value.serialize(self.de.child(key))
+  }
+  fn end(self) -> Result<()> { Ok(()) }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use error::Error;
+  use super::{describe, from_bytes_self_describing, to_vec_self_describing, FieldType, Schema, Value};
+
+  #[derive(Default, Serialize)]
+  struct Point {
+    x: i32,
+    y: i32,
+  }
+  #[derive(Default, Serialize)]
+  struct Creature {
+    level: u8,
+    pos: Point,
+    color: (u8, u8, u8),
+    scale: f32,
+    hostile: bool,
+  }
+
+  fn creature() -> Creature {
+    Creature { level: 7, pos: Point { x: -3, y: 12 }, color: (0xFF, 0x80, 0x00), scale: 1.5, hostile: true }
+  }
+
+  #[test]
+  fn test_describe() {
+    let schema = describe::<Creature>().unwrap();
+    let fields: Vec<_> = schema.fields().iter().map(|f| (f.name.as_str(), f.ty)).collect();
+    assert_eq!(fields, [
+      ("level",   FieldType::U8),
+      ("pos.x",   FieldType::I32),
+      ("pos.y",   FieldType::I32),
+      ("color.0", FieldType::U8),
+      ("color.1", FieldType::U8),
+      ("color.2", FieldType::U8),
+      ("scale",   FieldType::F32),
+      ("hostile", FieldType::Bool),
+    ]);
+    assert_eq!(Schema::of(&creature()).unwrap(), schema);
+  }
+  /// Данные читаются по записанной перед ними схеме без знания их типа
+  #[test]
+  fn test_round_trip() {
+    let expected = [
+      Value::U8(7), Value::I32(-3), Value::I32(12),
+      Value::U8(0xFF), Value::U8(0x80), Value::U8(0x00),
+      Value::F32(1.5), Value::Bool(true),
+    ];
+    let data = to_vec_self_describing::<LE, _>(&creature()).unwrap();
+    let (schema, values) = from_bytes_self_describing::<LE>(&data).unwrap();
+    assert_eq!(schema, describe::<Creature>().unwrap());
+    assert_eq!(values, expected);
+
+    let data = to_vec_self_describing::<BE, _>(&creature()).unwrap();
+    assert_eq!(from_bytes_self_describing::<BE>(&data).unwrap().1, expected);
+  }
+  #[test]
+  fn test_primitive() {
+    let data = to_vec_self_describing::<BE, _>(&0x1234u16).unwrap();
+    assert_eq!(data, [0x00, 0x01,   0x00,   0x02,   0x12, 0x34]);
+    let (schema, values) = from_bytes_self_describing::<BE>(&data).unwrap();
+    assert_eq!(schema.fields()[0].name, "");
+    assert_eq!(values, [Value::U16(0x1234)]);
+  }
+  #[test]
+  fn test_unsupported() {
+    match Schema::of(&"text") {
+      Err(Error::Unsupported(_)) => {}
+      result => panic!("expected unsupported error, but got {:?}", result),
+    }
+    assert!(Schema::of(&vec![1u8, 2]).is_err());
+    assert!(Schema::of(&Some(1u8)).is_err());
+  }
+  #[test]
+  fn test_unknown_type() {
+    let data = [0x00, 0x01,   0x01, b'a',   0xFF];
+    assert!(from_bytes_self_describing::<BE>(&data).is_err());
+  }
+}
@@ -1,11 +1,22 @@
 //! Содержит тип, реализующий простую сериализацию данных, как POD типов.
 
-use std::io::Write;
+use std::convert::TryFrom;
+#[cfg(feature = "debug-trace")]
+use std::fmt;
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
 use std::marker::PhantomData;
-use byteorder::{ByteOrder, WriteBytesExt};
+use std::mem;
+use std::sync::Arc;
+use byteorder::{BE, LE, ByteOrder, WriteBytesExt};
 use serde::ser::{self, Serialize};
 
+use checksum;
+use compress;
+use encoding::{CustomEncoding, Encoding};
 use error::{Error, Result};
+use framing::Length;
+use magic::{self, COLUMNAR_ERROR};
+use {Endian, EnumTag, StringFraming, TagWidth};
 
 /// Структура для сериализации значений Rust в простой поток байт. Внедрение разделителей
 /// и любой другой метаинформации для десериализации остается заботой вызывающего кода.
@@ -21,8 +32,11 @@ use error::{Error, Result};
 ///
 /// Тип [`()`], [`None`]-вариант [`Option`], а также unit-вариант перечисления никак не записываются
 /// в поток, писатель должен самостоятельно позаботится о сохранении информации об их наличии.
+/// Если включен байт-маркер методом [`with_option_marker`], перед значением [`Option`] записывается
+/// `0` для `None` или `1` для `Some`.
 /// Например, для записи С-like перечислений он может использовать вместо enum-поля в структуре
-/// поле одного из примитивных типов.
+/// поле одного из примитивных типов. Если представление перечислений задано методом
+/// [`with_enum_tag`], перед данными варианта записывается его индекс, см. [`EnumTag`].
 ///
 /// `bool`-значения сериализуются, как 1 байт со значением `0` или `1`.
 ///
@@ -32,28 +46,37 @@ use error::{Error, Result};
 ///
 /// Сериализация [строковых срезов][str] выполняется записью в поток UTF-8 кодированного значения,
 /// которая является нативной для Rust и таким образом ведет за собой нулевые накладные расходы на
-/// сериализацию. Записываются только байты самой строки, нулевого байта или длины строки никуда не
-/// добавляется. В случае, если требуется записывать строки в других кодировках, оберните их в
-/// структуры, для которых будет реализован типаж [`Serialize`], выполняющий сохранение данных в
-/// требуемой кодировке, например, с помощью крейта [encoding].
+/// сериализацию. По умолчанию записываются только байты самой строки, нулевого байта или длины
+/// строки никуда не добавляется; способ отделения строк и массивов байт от следующих за ними данных
+/// задается методом [`with_string_framing`]. В случае, если требуется записывать строки в других
+/// кодировках, задайте кодировку методом [`with_encoding`], либо оберните их в структуры, для которых
+/// будет реализован типаж [`Serialize`], выполняющий сохранение данных в требуемой кодировке,
+/// например, с помощью крейта [encoding].
 ///
-/// Отдельные символы записываются, как строки из одного символа, в UTF-8. Также как и для строк, нулевой
-/// байт в конце символа не записывается.
+/// Отдельные символы записываются, как строки из одного символа, в UTF-8. В отличие от строк, ни
+/// нулевой байт, ни длина символа не записываются независимо от способа записи строк.
 ///
 /// Сериализация последовательностей и их срезов осуществляется простой последовательной сериализацией
 /// их элементов. Ни количество, ни разделители между элементами, ни какой-либо маркер конца
 /// последовательности не записываются. В случае, если они требуются для корректной десериализации,
-/// они должны быть добавлены в сериализуемые структуры вручную.
+/// они должны быть добавлены в сериализуемые структуры вручную, либо, если требуется количество
+/// элементов, сериализатор должен записывать его сам, см. [`with_seq_prefix`].
 ///
 /// Key-value типы сериализуются, как последовательность структур ключ-значение по уже описанным выше
 /// правилам. Порядок таких пар определяется сериализуемой структурой.
 ///
 /// # Параметры типа
 /// - `BO`: определяет порядок байт, в котором будут записаны примитивные числовые типы:
-///         `u16`, `u32`, `u64`, `u128`, `i16`, `i32`, `i64`, `i128`, `f32` и `f64`.
+///   `u16`, `u32`, `u64`, `u128`, `i16`, `i32`, `i64`, `i128`, `f32` и `f64`.
 /// - `W`: определяет тип, обеспечивающих сохранение сериализуемых данных в хранилище
 ///
 /// [`()`]: https://doc.rust-lang.org/std/primitive.unit.html
+/// [`with_enum_tag`]: #method.with_enum_tag
+/// [`with_option_marker`]: #method.with_option_marker
+/// [`with_seq_prefix`]: #method.with_seq_prefix
+/// [`with_encoding`]: #method.with_encoding
+/// [`with_string_framing`]: #method.with_string_framing
+/// [`EnumTag`]: ../struct.EnumTag.html
 /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
 /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
 /// [Newtype]: https://doc.rust-lang.org/rust-by-example/generics/new_types.html
@@ -66,6 +89,71 @@ pub struct Serializer<BO, W> {
   writer: W,
   /// Порядок байт, используемый при записи чисел
   _byteorder: PhantomData<BO>,
+  /// Функция перемещения по потоку, если поток поддерживает перемещение и сериализатор
+  /// создан методом [`seekable`](#method.seekable)
+  seek: Option<fn(&mut W, SeekFrom) -> io::Result<u64>>,
+  /// Значения, расположенные по смещению, которые еще не записаны в поток
+  offsets: Vec<PendingOffset>,
+  /// Кодировка строк, если она отличается от UTF-8. Задается во время выполнения, а не
+  /// параметром типа, см. [`Encoding`]
+  ///
+  /// [`Encoding`]: ../encoding/trait.Encoding.html
+  encoding: Option<Arc<dyn Encoding>>,
+  /// Представление перечислений, если их варианты записываются в поток
+  enum_tag: Option<EnumTag>,
+  /// Записывать ли перед значением `Option` байт-маркер его наличия
+  option_marker: bool,
+  /// Способ отделения строк и массивов байт от следующих за ними данных
+  string_framing: StringFraming,
+  /// Функция записи количества элементов перед последовательностями и отображениями, если
+  /// она задана методом [`with_seq_prefix`](#method.with_seq_prefix)
+  seq_prefix: Option<WritePrefix>,
+  /// Количество байт, записанных в поток
+  written: u64,
+  /// Наибольшая граница выравнивания значений, записанных сериализатором. Значения, на которые
+  /// ссылаются смещения, размещаются на этой границе, чтобы выравнивание внутри них сохранилось
+  align: usize,
+  /// Состояние протоколирования записи, если оно включено
+  #[cfg(feature = "debug-trace")]
+  trace: Option<Trace>,
+}
+
+/// Функция записи количества элементов последовательности в поток
+type WritePrefix = fn(usize, &mut dyn Write) -> Result<()>;
+
+/// Записывает количество элементов последовательности, как число типа `L` в порядке байт `BO`
+fn write_prefix<BO: ByteOrder, L: Length>(len: usize, writer: &mut dyn Write) -> Result<()> {
+  let prefix = L::from_usize(len).ok_or_else(|| Error::InvalidLength(format!(
+    "sequence of {} elements is too long for its length prefix", len
+  )))?;
+  prefix.serialize(&mut Serializer::<BO, _>::new(writer))
+}
+
+/// Поток, подсчитывающий записанные в него байты
+struct Counter<'a, W: 'a> {
+  /// Поток, в который записываются данные
+  inner: &'a mut W,
+  /// Счетчик записанных байт
+  written: &'a mut u64,
+}
+impl<'a, W: Write> Write for Counter<'a, W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let len = self.inner.write(buf)?;
+    *self.written += len as u64;
+    Ok(len)
+  }
+  #[inline]
+  fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+/// Значение, на которое ссылается смещение, записанное в поток в виде заглушки
+struct PendingOffset {
+  /// Позиция заглушки смещения в потоке
+  at: u64,
+  /// Сериализованное значение
+  target: Vec<u8>,
+  /// Граница, на которой должно начинаться значение, чтобы сохранить выравнивание внутри него
+  align: usize,
 }
 
 impl<BO, W> Serializer<BO, W>
@@ -81,7 +169,439 @@ impl<BO, W> Serializer<BO, W>
   /// # Возвращаемое значение
   /// Сериализатор для записи данных в указанный поток и кодированием строк в UTF-8
   pub fn new(writer: W) -> Self {
-    Serializer { writer, _byteorder: PhantomData }
+    Serializer {
+      writer,
+      _byteorder: PhantomData,
+      seek: None,
+      offsets: Vec::new(),
+      encoding: None,
+      enum_tag: None,
+      option_marker: false,
+      string_framing: StringFraming::Raw,
+      seq_prefix: None,
+      written: 0,
+      align: 1,
+      #[cfg(feature = "debug-trace")]
+      trace: None,
+    }
+  }
+  /// Устанавливает кодировку строк и символов вместо UTF-8. Если строку не удается закодировать,
+  /// сериализация прерывается с ошибкой, возвращенной кодировкой
+  ///
+  /// # Параметры
+  /// - `encoding`: Кодировка, используемая для записи строк и символов
+  pub fn with_encoding<E: Encoding + 'static>(mut self, encoding: E) -> Self {
+    self.encoding = Some(Arc::new(encoding));
+    self
+  }
+  /// Устанавливает однобайтовую кодировку строк с произвольной таблицей символов вместо UTF-8.
+  /// Если строка содержит символ, отсутствующий в таблице, сериализация прерывается с ошибкой
+  /// [`Error::Unmappable`]
+  ///
+  /// # Параметры
+  /// - `encoding`: Кодировка, используемая для записи строк и символов
+  ///
+  /// [`Error::Unmappable`]: ../error/enum.Error.html#variant.Unmappable
+  pub fn with_custom_encoding(self, encoding: CustomEncoding) -> Self {
+    self.with_encoding(encoding)
+  }
+  /// Включает запись вариантов перечислений: перед данными варианта записывается его индекс
+  /// и, если требуется, размер данных
+  ///
+  /// # Параметры
+  /// - `tag`: Представление перечислений
+  pub fn with_enum_tag(mut self, tag: EnumTag) -> Self {
+    self.enum_tag = Some(tag);
+    self
+  }
+  /// Включает запись байта-маркера перед значениями [`Option`]: `None` записывается, как `0`,
+  /// а `Some` -- как `1`, за которым следует само значение. Такое представление читает
+  /// десериализатор, для которого вызван метод [`with_option_marker`]
+  ///
+  /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+  /// [`with_option_marker`]: ../de/struct.Deserializer.html#method.with_option_marker
+  pub fn with_option_marker(mut self) -> Self {
+    self.option_marker = true;
+    self
+  }
+  /// Устанавливает способ отделения строк и массивов байт, записываемых методами `serialize_str`
+  /// и `serialize_bytes`, от следующих за ними данных: завершающим нулевым байтом или префиксом
+  /// длины. По умолчанию байты записываются как есть, см. [`StringFraming`]
+  ///
+  /// # Параметры
+  /// - `framing`: Способ записи строк
+  ///
+  /// [`StringFraming`]: ../enum.StringFraming.html
+  pub fn with_string_framing(mut self, framing: StringFraming) -> Self {
+    self.string_framing = framing;
+    self
+  }
+  /// Включает запись количества элементов перед последовательностями (например, `Vec<T>`) и
+  /// количества пар перед отображениями. Количество записывается, как число типа `L` в порядке
+  /// байт сериализатора. Такие последовательности можно прочитать, например, оберткой
+  /// [`LengthPrefixed`] с тем же типом `L`.
+  ///
+  /// Если количество элементов неизвестно заранее или не может быть представлено типом `L`,
+  /// сериализация прерывается с ошибкой
+  ///
+  /// [`LengthPrefixed`]: ../framing/struct.LengthPrefixed.html
+  pub fn with_seq_prefix<L: Length>(mut self) -> Self {
+    self.seq_prefix = Some(write_prefix::<BO, L>);
+    self
+  }
+  /// Записывает количество элементов последовательности или отображения, если это требуется
+  fn seq_prefix(&mut self, len: Option<usize>) -> Result<()> {
+    let prefix = match self.seq_prefix {
+      Some(prefix) => prefix,
+      None => return Ok(()),
+    };
+    let len = len.ok_or(Error::Unsupported("length prefix requires a sequence of known length"))?;
+    let mut buf = Vec::new();
+    prefix(len, &mut buf)?;
+    self.emit(|w| w.write_all(&buf))
+  }
+  /// Возвращает поток, в который записываются данные. Значения, расположенные по смещению
+  /// и еще не записанные методом [`write_offset_targets`], теряются
+  ///
+  /// [`write_offset_targets`]: #method.write_offset_targets
+  #[inline]
+  pub fn into_inner(self) -> W { self.writer }
+  /// Возвращает ссылку на поток, в который записываются данные
+  #[inline]
+  pub fn get_ref(&self) -> &W { &self.writer }
+  /// Возвращает изменяемую ссылку на поток, в который записываются данные, например, чтобы
+  /// сбросить его буфер. Запись в поток в обход сериализатора не отражается в протоколе записи
+  #[inline]
+  pub fn get_mut(&mut self) -> &mut W { &mut self.writer }
+  /// Возвращает количество байт, записанных в поток с момента создания сериализатора. Помогает
+  /// запоминать смещения записываемых данных, например, чтобы заполнить оглавление файла.
+  /// Учитываются все данные, добавленные в поток, в том числе значения, на которые ссылаются
+  /// смещения, и зарезервированные области, но не перезапись уже записанных данных, например,
+  /// методом `fill`. Запись в поток в обход сериализатора также не учитывается
+  #[inline]
+  pub fn bytes_written(&self) -> u64 { self.written }
+  /// Записывает в поток `len` нулевых байт, например, зарезервированные поля или выравнивание
+  /// при ручной реализации [`Serialize`]. Записанные байты учитываются [`bytes_written`].
+  /// Для полей структур удобнее использовать тип [`Pad`]
+  ///
+  /// # Параметры
+  /// - `len`: Количество записываемых байт
+  ///
+  /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+  /// [`bytes_written`]: #method.bytes_written
+  /// [`Pad`]: ../layout/struct.Pad.html
+  pub fn write_padding(&mut self, len: usize) -> Result<()> {
+    self.emit(|w| io::copy(&mut io::repeat(0).take(len as u64), w).map(|_| ()))
+  }
+  /// Создает сериализатор для записи части данных в другой поток с теми же настройками.
+  /// Данные другого потока предназначены для записи в этот поток с текущей позиции, от которой
+  /// и продолжается отсчет записанных байт, например, для выравнивания значений
+  fn nested<W2: Write>(&self, writer: W2) -> Serializer<BO, W2> {
+    let mut ser = Serializer::new(writer);
+    ser.written = self.written;
+    ser.encoding = self.encoding.clone();
+    ser.enum_tag = self.enum_tag;
+    ser.option_marker = self.option_marker;
+    ser.string_framing = self.string_framing;
+    ser.seq_prefix = self.seq_prefix;
+    ser
+  }
+  /// Записывает значение в буфер с теми же настройками, считая, что буфер будет записан
+  /// в поток с позиции `position`
+  fn serialize_nested<T>(&mut self, value: &T, position: u64) -> Result<Vec<u8>>
+    where T: ?Sized + Serialize,
+  {
+    let mut ser = self.nested(Vec::new());
+    ser.written = position;
+    value.serialize(&mut ser)?;
+    self.align = self.align.max(ser.align);
+    Ok(ser.writer)
+  }
+  /// Записывает метку варианта перечисления, если представление перечислений задано
+  ///
+  /// # Возвращаемое значение
+  /// Сериализатор для записи данных варианта, если данные нужно предварить их размером
+  fn variant_tag(&mut self, index: u32) -> Result<Option<Serializer<BO, Vec<u8>>>> {
+    let tag = match self.enum_tag {
+      Some(tag) => tag,
+      None => return Ok(None),
+    };
+    let overflow = |_| Error::InvalidLength(format!("variant index {} does not fit into the tag", index));
+    match tag.width {
+      TagWidth::U8  => u8::try_from(index).map_err(overflow)?.serialize(&mut *self)?,
+      TagWidth::U16 => u16::try_from(index).map_err(overflow)?.serialize(&mut *self)?,
+      TagWidth::U32 => index.serialize(&mut *self)?,
+    }
+    if !tag.length_delimited {
+      return Ok(None);
+    }
+    let mut data = self.nested(Vec::new());
+    // Данные будут записаны после их размера типа `u32`
+    data.written += 4;
+    Ok(Some(data))
+  }
+  /// Записывает размер данных варианта перечисления, а затем сами данные
+  fn variant_data(&mut self, data: Serializer<BO, Vec<u8>>) -> Result<()> {
+    self.align = self.align.max(data.align);
+    let data = data.writer;
+    let len = u32::try_from(data.len())
+      .map_err(|_| Error::InvalidLength(format!("variant of {} bytes is too big", data.len())))?;
+    len.serialize(&mut *self)?;
+    self.emit(|w| w.write_all(&data))
+  }
+  /// Записывает байты строки или массива байт способом, заданным методом [`with_string_framing`]
+  ///
+  /// [`with_string_framing`]: #method.with_string_framing
+  fn write_framed(&mut self, bytes: &[u8]) -> Result<()> {
+    match self.string_framing {
+      StringFraming::Raw => {}
+      StringFraming::NulTerminated => {
+        if let Some(pos) = bytes.iter().position(|&b| b == 0) {
+          return Err(Error::InvalidLength(format!("string contains a NUL byte at position {}", pos)));
+        }
+        self.emit(|w| w.write_all(bytes))?;
+        return self.emit(|w| w.write_u8(0));
+      }
+      StringFraming::LengthPrefixed(width) => {
+        let overflow = |_| Error::InvalidLength(format!(
+          "string of {} bytes is too long for its length prefix", bytes.len()
+        ));
+        match width {
+          TagWidth::U8  => u8::try_from(bytes.len()).map_err(overflow)?.serialize(&mut *self)?,
+          TagWidth::U16 => u16::try_from(bytes.len()).map_err(overflow)?.serialize(&mut *self)?,
+          TagWidth::U32 => u32::try_from(bytes.len()).map_err(overflow)?.serialize(&mut *self)?,
+        }
+      }
+    }
+    self.emit(|w| w.write_all(bytes))
+  }
+  /// Включает протоколирование записи: для каждой операции записи в поток в функцию `sink`
+  /// передается смещение записанных данных, имя поля структуры, к которому они относятся,
+  /// и сами записанные байты. Смещение отсчитывается от момента включения протоколирования.
+  /// Запись значений в зарезервированные области и заполнение смещений, выполняемые с
+  /// перемещением по потоку, не протоколируются.
+  ///
+  /// Доступно только при включенной функциональности `debug-trace`.
+  ///
+  /// # Параметры
+  /// - `sink`: Функция, получающая записи протокола
+  #[cfg(feature = "debug-trace")]
+  pub fn set_trace<F>(&mut self, sink: F)
+    where F: FnMut(&TraceEntry) + 'static,
+  {
+    self.trace = Some(Trace { sink: Box::new(sink), offset: 0, field: None });
+  }
+  /// Записывает в поток данные, формируемые функцией `f`
+  #[cfg(not(feature = "debug-trace"))]
+  #[inline]
+  fn emit<F>(&mut self, f: F) -> Result<()>
+    where F: FnOnce(&mut Counter<W>) -> io::Result<()>,
+  {
+    f(&mut Counter { inner: &mut self.writer, written: &mut self.written }).map_err(Into::into)
+  }
+  /// Записывает в поток данные, формируемые функцией `f`, и, если включено протоколирование,
+  /// передает их в протокол
+  #[cfg(feature = "debug-trace")]
+  fn emit<F>(&mut self, f: F) -> Result<()>
+    where F: FnOnce(&mut dyn Write) -> io::Result<()>,
+  {
+    let trace = match self.trace {
+      Some(ref mut trace) => trace,
+      None => {
+        return f(&mut Counter { inner: &mut self.writer, written: &mut self.written }).map_err(Into::into);
+      }
+    };
+    let mut buf = Vec::new();
+    f(&mut buf)?;
+    self.writer.write_all(&buf)?;
+    self.written += buf.len() as u64;
+    (trace.sink)(&TraceEntry { offset: trace.offset, field: trace.field, bytes: &buf });
+    trace.offset += buf.len() as u64;
+    Ok(())
+  }
+  /// Записывает в поток значение поля структуры с именем `key`
+  #[cfg(not(feature = "debug-trace"))]
+  #[inline]
+  fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    value.serialize(self)
+  }
+  /// Записывает в поток значение поля структуры с именем `key`, запоминая имя для протокола
+  #[cfg(feature = "debug-trace")]
+  fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    let outer = self.trace.as_mut().map(|trace| trace.field.replace(key));
+    let result = value.serialize(&mut *self);
+    if let (Some(trace), Some(outer)) = (self.trace.as_mut(), outer) {
+      trace.field = outer;
+    }
+    result
+  }
+}
+
+/// Запись протокола сериализации, описывающая одну операцию записи в поток.
+///
+/// Доступно только при включенной функциональности `debug-trace`.
+#[cfg(feature = "debug-trace")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceEntry<'a> {
+  /// Смещение записанных данных от момента включения протоколирования
+  pub offset: u64,
+  /// Имя поля структуры, к которому относятся данные, если они записаны в составе структуры
+  pub field: Option<&'static str>,
+  /// Записанные байты
+  pub bytes: &'a [u8],
+}
+/// Форматирует запись в виде строки шестнадцатеричного дампа: `00000004 field: 12 34`
+#[cfg(feature = "debug-trace")]
+impl<'a> fmt::Display for TraceEntry<'a> {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "{:08X}", self.offset)?;
+    if let Some(field) = self.field {
+      write!(fmt, " {}:", field)?;
+    }
+    for b in self.bytes {
+      write!(fmt, " {:02X}", b)?;
+    }
+    Ok(())
+  }
+}
+
+/// Состояние протоколирования сериализации
+#[cfg(feature = "debug-trace")]
+struct Trace {
+  /// Получатель записей протокола
+  sink: Box<dyn FnMut(&TraceEntry)>,
+  /// Количество байт, записанных с момента включения протоколирования
+  offset: u64,
+  /// Имя поля структуры, значение которого записывается в данный момент
+  field: Option<&'static str>,
+}
+
+/// Область потока, зарезервированная методом [`Serializer::reserve`] для последующего
+/// заполнения методом [`Serializer::fill`]
+///
+/// [`Serializer::reserve`]: struct.Serializer.html#method.reserve
+/// [`Serializer::fill`]: struct.Serializer.html#method.fill
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reservation {
+  /// Смещение начала области от начала потока
+  offset: u64,
+  /// Позиция начала области, отсчитываемая так же, как количество записанных байт
+  written: u64,
+  /// Размер области в байтах
+  len: u64,
+}
+impl Reservation {
+  /// Возвращает смещение начала зарезервированной области от начала потока
+  #[inline]
+  pub fn offset(&self) -> u64 { self.offset }
+  /// Возвращает размер зарезервированной области в байтах
+  #[inline]
+  pub fn len(&self) -> u64 { self.len }
+  /// Возвращает `true`, если зарезервированная область имеет нулевой размер
+  #[inline]
+  pub fn is_empty(&self) -> bool { self.len == 0 }
+}
+
+impl<BO, W> Serializer<BO, W>
+  where W: Write + Seek,
+        BO: ByteOrder,
+{
+  /// Создает сериализатор для потока, поддерживающего перемещение. В отличие от сериализатора,
+  /// созданного методом [`new`], такой сериализатор может записывать значения, расположенные
+  /// по смещению, например, [`Offset`]. Сами значения записываются в поток методом
+  /// [`write_offset_targets`].
+  ///
+  /// # Параметры
+  /// - `writer`: Поток, в который записывать сериализуемые данные
+  ///
+  /// [`new`]: #method.new
+  /// [`Offset`]: ../wrappers/struct.Offset.html
+  /// [`write_offset_targets`]: #method.write_offset_targets
+  pub fn seekable(writer: W) -> Self {
+    let mut ser = Self::new(writer);
+    ser.seek = Some(<W as Seek>::seek);
+    ser
+  }
+  /// Записывает в текущую позицию потока все значения, на которые ссылаются ранее записанные
+  /// смещения, в порядке записи смещений, и заполняет заглушки смещений. Смещения отсчитываются
+  /// от позиции самого смещения и записываются, как `u32` в порядке байт сериализатора.
+  /// Перед значениями, содержащими выровненные значения (см. [`Aligned`]), записываются нулевые
+  /// байты до наибольшей из их границ выравнивания.
+  ///
+  /// # Ошибки
+  /// Если значение оказывается дальше, чем может быть представлено смещением, возвращается
+  /// ошибка [`Error::InvalidLength`].
+  ///
+  /// [`Aligned`]: ../layout/struct.Aligned.html
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  pub fn write_offset_targets(&mut self) -> Result<()> {
+    for pending in mem::take(&mut self.offsets) {
+      let padding = magic::padding(self.written, pending.align);
+      self.write_padding(padding)?;
+      self.align = self.align.max(pending.align);
+      let pos = self.writer.stream_position()?;
+      let offset = u32::try_from(pos - pending.at).map_err(|_| Error::InvalidLength(format!(
+        "offset {} does not fit into u32", pos - pending.at
+      )))?;
+      self.emit(|w| w.write_all(&pending.target))?;
+      let end = self.writer.stream_position()?;
+      self.writer.seek(SeekFrom::Start(pending.at))?;
+      self.writer.write_u32::<BO>(offset)?;
+      self.writer.seek(SeekFrom::Start(end))?;
+    }
+    Ok(())
+  }
+  /// Резервирует в потоке область указанного размера, заполняя ее нулями, для того, чтобы
+  /// позже записать в нее значение методом [`fill`]. Это позволяет записывать данные не в том
+  /// порядке, в котором они располагаются в потоке, например, сначала записать тело файла,
+  /// а потом -- заголовок со смещениями его секций.
+  ///
+  /// # Параметры
+  /// - `len`: Размер резервируемой области в байтах
+  ///
+  /// # Возвращаемое значение
+  /// Описание зарезервированной области, которое необходимо передать в метод [`fill`]
+  ///
+  /// [`fill`]: #method.fill
+  pub fn reserve(&mut self, len: u64) -> Result<Reservation> {
+    let offset = self.writer.stream_position()?;
+    let written = self.written;
+    self.emit(|w| io::copy(&mut io::repeat(0).take(len), w).map(|_| ()))?;
+    Ok(Reservation { offset, written, len })
+  }
+  /// Записывает значение в ранее зарезервированную область потока. После записи позиция
+  /// в потоке восстанавливается, поэтому дальнейшая сериализация продолжается с того места,
+  /// где она была остановлена. Если значение занимает меньше места, чем было зарезервировано,
+  /// остаток области остается заполненным нулями.
+  ///
+  /// # Параметры
+  /// - `reservation`: Область, полученная от метода [`reserve`]
+  /// - `value`: Записываемое значение
+  ///
+  /// # Ошибки
+  /// Если сериализованное значение не помещается в зарезервированную область, возвращается
+  /// ошибка [`Error::InvalidLength`], а поток не изменяется.
+  ///
+  /// [`reserve`]: #method.reserve
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  pub fn fill<T>(&mut self, reservation: Reservation, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    let buf = self.serialize_nested(value, reservation.written)?;
+    if buf.len() as u64 > reservation.len {
+      return Err(Error::InvalidLength(format!(
+        "value takes {} bytes, but only {} bytes are reserved", buf.len(), reservation.len
+      )));
+    }
+    let pos = self.writer.stream_position()?;
+    self.writer.seek(SeekFrom::Start(reservation.offset))?;
+    self.writer.write_all(&buf)?;
+    self.writer.seek(SeekFrom::Start(pos))?;
+    Ok(())
   }
 }
 
@@ -95,35 +615,35 @@ impl<'a, BO, W> ser::Serializer for &'a mut Serializer<BO, W>
   type SerializeSeq = Self;
   type SerializeTuple = Self;
   type SerializeTupleStruct = Self;
-  type SerializeTupleVariant = Self;
+  type SerializeTupleVariant = Variant<'a, BO, W>;
   type SerializeMap = Self;
   type SerializeStruct = Self;
-  type SerializeStructVariant = Self;
+  type SerializeStructVariant = Variant<'a, BO, W>;
 
   /// Записывает в выходной поток 1 байт
-  fn serialize_i8 (self, v: i8 ) -> Result<Self::Ok> { self.writer.write_i8(v).map_err(Into::into) }
+  fn serialize_i8 (self, v: i8 ) -> Result<Self::Ok> { self.emit(|w| w.write_i8(v)) }
   /// Записывает в выходной поток 1 байт
-  fn serialize_u8 (self, v: u8 ) -> Result<Self::Ok> { self.writer.write_u8(v).map_err(Into::into) }
+  fn serialize_u8 (self, v: u8 ) -> Result<Self::Ok> { self.emit(|w| w.write_u8(v)) }
   /// Записывает в выходной поток 2 байта в указанном в сериализаторе порядке байт
-  fn serialize_i16(self, v: i16) -> Result<Self::Ok> { self.writer.write_i16::<BO>(v).map_err(Into::into) }
+  fn serialize_i16(self, v: i16) -> Result<Self::Ok> { self.emit(|w| w.write_i16::<BO>(v)) }
   /// Записывает в выходной поток 2 байта в указанном в сериализаторе порядке байт
-  fn serialize_u16(self, v: u16) -> Result<Self::Ok> { self.writer.write_u16::<BO>(v).map_err(Into::into) }
+  fn serialize_u16(self, v: u16) -> Result<Self::Ok> { self.emit(|w| w.write_u16::<BO>(v)) }
   /// Записывает в выходной поток 4 байта в указанном в сериализаторе порядке байт
-  fn serialize_i32(self, v: i32) -> Result<Self::Ok> { self.writer.write_i32::<BO>(v).map_err(Into::into) }
+  fn serialize_i32(self, v: i32) -> Result<Self::Ok> { self.emit(|w| w.write_i32::<BO>(v)) }
   /// Записывает в выходной поток 4 байта в указанном в сериализаторе порядке байт
-  fn serialize_u32(self, v: u32) -> Result<Self::Ok> { self.writer.write_u32::<BO>(v).map_err(Into::into) }
+  fn serialize_u32(self, v: u32) -> Result<Self::Ok> { self.emit(|w| w.write_u32::<BO>(v)) }
   /// Записывает в выходной поток 8 байт в указанном в сериализаторе порядке байт
-  fn serialize_i64(self, v: i64) -> Result<Self::Ok> { self.writer.write_i64::<BO>(v).map_err(Into::into) }
+  fn serialize_i64(self, v: i64) -> Result<Self::Ok> { self.emit(|w| w.write_i64::<BO>(v)) }
   /// Записывает в выходной поток 8 байт в указанном в сериализаторе порядке байт
-  fn serialize_u64(self, v: u64) -> Result<Self::Ok> { self.writer.write_u64::<BO>(v).map_err(Into::into) }
+  fn serialize_u64(self, v: u64) -> Result<Self::Ok> { self.emit(|w| w.write_u64::<BO>(v)) }
   /// Записывает в выходной поток 16 байт в указанном в сериализаторе порядке байт
-  fn serialize_i128(self, v: i128) -> Result<Self::Ok> { self.writer.write_i128::<BO>(v).map_err(Into::into) }
+  fn serialize_i128(self, v: i128) -> Result<Self::Ok> { self.emit(|w| w.write_i128::<BO>(v)) }
   /// Записывает в выходной поток 16 байт в указанном в сериализаторе порядке байт
-  fn serialize_u128(self, v: u128) -> Result<Self::Ok> { self.writer.write_u128::<BO>(v).map_err(Into::into) }
+  fn serialize_u128(self, v: u128) -> Result<Self::Ok> { self.emit(|w| w.write_u128::<BO>(v)) }
   /// Записывает в выходной поток 4 байта в указанном в сериализаторе порядке байт
-  fn serialize_f32(self, v: f32) -> Result<Self::Ok> { self.writer.write_f32::<BO>(v).map_err(Into::into) }
+  fn serialize_f32(self, v: f32) -> Result<Self::Ok> { self.emit(|w| w.write_f32::<BO>(v)) }
   /// Записывает в выходной поток 8 байт в указанном в сериализаторе порядке байт
-  fn serialize_f64(self, v: f64) -> Result<Self::Ok> { self.writer.write_f64::<BO>(v).map_err(Into::into) }
+  fn serialize_f64(self, v: f64) -> Result<Self::Ok> { self.emit(|w| w.write_f64::<BO>(v)) }
 
   /// Записывает в выходной поток 1 байт: `0x00` для `false` и `0x01` для `true`
   fn serialize_bool(self, v: bool) -> Result<Self::Ok> { self.serialize_u8(if v { 1 } else { 0 }) }
@@ -131,75 +651,214 @@ impl<'a, BO, W> ser::Serializer for &'a mut Serializer<BO, W>
   #[inline]
   fn serialize_char(self, v: char) -> Result<Self::Ok> {
     let mut buf = [0u8; 4];// Символ в UTF-8 может занимать максимум 4 байта
-    self.serialize_str(v.encode_utf8(&mut buf))
+    let v = v.encode_utf8(&mut buf);
+    // Символ читается по ширине, определяемой его первым байтом, поэтому разделители не нужны
+    match self.encoding {
+      Some(ref encoding) => {
+        let bytes = encoding.encode(v)?;
+        self.emit(|w| w.write_all(&bytes))
+      }
+      None => self.emit(|w| w.write_all(v.as_bytes())),
+    }
   }
 
-  /// Записывает в выходной поток UTF-8 байты представления указанной строки
+  /// Записывает в выходной поток байты представления указанной строки в кодировке сериализатора
+  /// (по умолчанию UTF-8), отделяя их способом, заданным методом `with_string_framing`
   #[inline]
   fn serialize_str(self, v: &str) -> Result<Self::Ok> {
-    self.serialize_bytes(v.as_bytes())
+    match self.encoding {
+      Some(ref encoding) => {
+        let bytes = encoding.encode(v)?;
+        self.serialize_bytes(&bytes)
+      }
+      None => self.serialize_bytes(v.as_bytes()),
+    }
   }
-  /// Записывает в выходной поток байты указанного массива как есть
-  fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> { self.writer.write_all(v).map_err(Into::into) }
+  /// Записывает в выходной поток байты указанного массива, отделяя их способом, заданным
+  /// методом `with_string_framing` (по умолчанию -- как есть)
+  fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> { self.write_framed(v) }
 
-  /// Ничего не записывает в поток
-  fn serialize_none(self) -> Result<Self::Ok> { Ok(()) }
-  /// Записывает в выходной поток представление `value` с помощью данного сериализатора
+  /// Записывает в поток байт `0`, если включен байт-маркер, иначе ничего не записывает
+  fn serialize_none(self) -> Result<Self::Ok> {
+    if self.option_marker {
+      return self.serialize_u8(0);
+    }
+    Ok(())
+  }
+  /// Записывает в выходной поток байт `1`, если включен байт-маркер, а затем представление
+  /// `value` с помощью данного сериализатора
   fn serialize_some<T>(self, value: &T) -> Result<Self::Ok>
     where T: ?Sized + Serialize,
   {
+    if self.option_marker {
+      self.serialize_u8(1)?;
+    }
     value.serialize(self)
   }
   /// Ничего не записывает в поток
   fn serialize_unit(self) -> Result<Self::Ok> { Ok(()) }
   /// Ничего не записывает в поток
   fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> { Ok(()) }
-  /// Ничего не записывает в поток
+  /// Записывает в поток метку варианта и нулевой размер данных, если представление перечислений
+  /// задано, иначе ничего не записывает
   fn serialize_unit_variant(
-    self, _name: &'static str, _variant_index: u32, _variant: &'static str
-  ) -> Result<Self::Ok> { Ok(()) }
+    self, _name: &'static str, variant_index: u32, _variant: &'static str
+  ) -> Result<Self::Ok> {
+    match self.variant_tag(variant_index)? {
+      Some(data) => self.variant_data(data),
+      None => Ok(()),
+    }
+  }
 
-  /// Записывает в выходной поток представление `value` с помощью данного сериализатора
-  fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+  /// Записывает в выходной поток представление `value` с помощью данного сериализатора.
+  /// Типы-обертки этого крейта, чье представление зависит от порядка байт, распознаются
+  /// по имени и записываются с учетом порядка байт сериализатора. Для значений, расположенных
+  /// по смещению, записывается заглушка смещения, а само значение запоминается до вызова
+  /// [`Serializer::write_offset_targets`]. После значений, защищенных контрольной суммой,
+  /// записывается их контрольная сумма CRC-32 типа `u32`. Поля последовательностей структур,
+  /// записываемых по столбцам, записываются столбец за столбцом. Сжатые значения записываются
+  /// в виде несжатого и сжатого размеров и сжатых данных. Перечисления с меткой варианта
+  /// записываются так, как будто представление перечислений задано методом [`with_enum_tag`].
+  /// Перед выровненными значениями записываются нулевые байты до границы выравнивания,
+  /// отсчитываемой от начала записи (см. [`bytes_written`]), в том числе внутри значений,
+  /// записываемых по смещению или через отдельный буфер
+  ///
+  /// [`bytes_written`]: struct.Serializer.html#method.bytes_written
+  /// [`Serializer::write_offset_targets`]: struct.Serializer.html#method.write_offset_targets
+  /// [`with_enum_tag`]: struct.Serializer.html#method.with_enum_tag
+  fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok>
     where T: ?Sized + Serialize,
   {
+    if name == magic::OFFSET {
+      let seek = self.seek.ok_or(Error::Unsupported("offsets require a seekable stream"))?;
+      let at = seek(&mut self.writer, SeekFrom::Current(0))?;
+      // Смещения внутри значения относительные, поэтому значение можно записать вместе с
+      // теми, на которые оно ссылается, в отдельный буфер, а затем перенести его в поток.
+      // Значение размещается на границе выравнивания его содержимого, поэтому позиции внутри
+      // него можно отсчитывать от его начала
+      let mut target = self.nested(Cursor::new(Vec::new()));
+      target.seek = Some(<Cursor<Vec<u8>> as Seek>::seek);
+      target.written = 0;
+      value.serialize(&mut target)?;
+      target.write_offset_targets()?;
+      self.offsets.push(PendingOffset { at, target: target.writer.into_inner(), align: target.align });
+      return self.emit(|w| w.write_u32::<BO>(0));
+    }
+    if let Some(width) = magic::tagged_enum_width(name) {
+      let outer = self.enum_tag.replace(EnumTag { width, length_delimited: false });
+      let result = value.serialize(&mut *self);
+      self.enum_tag = outer;
+      return result;
+    }
+    if name == magic::CRC32 {
+      let buf = self.serialize_nested(value, self.written)?;
+      self.emit(|w| w.write_all(&buf))?;
+      return self.emit(|w| w.write_u32::<BO>(checksum::crc32(&buf)));
+    }
+    if let Some(codec) = compress::codec(name) {
+      // Позиция распакованных данных отсчитывается от начала сжатого значения
+      let buf = self.serialize_nested(value, self.written)?;
+      if buf.len() > compress::MAX_UNCOMPRESSED_LEN as usize {
+        return Err(Error::InvalidLength(format!("{} bytes are too many to compress", buf.len())));
+      }
+      let data = (codec.compress)(&buf)?;
+      let len = u32::try_from(data.len())
+        .map_err(|_| Error::InvalidLength(format!("{} compressed bytes do not fit into u32", data.len())))?;
+      self.emit(|w| w.write_u32::<BO>(buf.len() as u32))?;
+      self.emit(|w| w.write_u32::<BO>(len))?;
+      return self.emit(|w| w.write_all(&data));
+    }
+    if name == magic::COLUMNAR {
+      let mut columns = Vec::new();
+      value.serialize(Columns { ser: &*self, columns: &mut columns, row: false, field: 0 })?;
+      for column in columns {
+        self.emit(|w| w.write_all(&column))?;
+      }
+      return Ok(());
+    }
+    if let Some(align) = magic::aligned_width(name) {
+      let padding = magic::padding(self.written, align);
+      self.write_padding(padding)?;
+      self.align = self.align.max(align);
+      return value.serialize(self);
+    }
+    if name == magic::RAW_BYTES {
+      let framing = mem::replace(&mut self.string_framing, StringFraming::Raw);
+      let result = value.serialize(&mut *self);
+      self.string_framing = framing;
+      return result;
+    }
+    if name == magic::BE_BYTES {
+      let mut buf = Vec::new();
+      value.serialize(&mut Serializer::<BE, _>::new(&mut buf))?;
+      if !magic::is_big_endian::<BO>() {
+        buf.reverse();
+      }
+      return self.emit(|w| w.write_all(&buf));
+    }
     value.serialize(self)
   }
-  /// Записывает в выходной поток представление `value` с помощью данного сериализатора.
-  /// Остальные параметры игнорируются
+  /// Записывает в выходной поток метку варианта, если представление перечислений задано,
+  /// а затем представление `value` с помощью данного сериализатора
   fn serialize_newtype_variant<T>(
-    self, _name: &'static str, _variant_index: u32, _variant: &'static str, value: &T
+    self, _name: &'static str, variant_index: u32, _variant: &'static str, value: &T
   ) -> Result<Self::Ok>
     where T: ?Sized + Serialize,
   {
-    value.serialize(self)
+    match self.variant_tag(variant_index)? {
+      Some(mut data) => {
+        value.serialize(&mut data)?;
+        self.variant_data(data)
+      }
+      None => value.serialize(self),
+    }
   }
 
 //-------------------------------------------------------------------------------------------------
-  /// Просто возвращает данный сериализатор. Параметр `_len` игнорируется
-  fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> { Ok(self) }
+  /// Записывает количество элементов, если это включено методом [`with_seq_prefix`], и
+  /// возвращает данный сериализатор
+  ///
+  /// [`with_seq_prefix`]: struct.Serializer.html#method.with_seq_prefix
+  fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+    self.seq_prefix(len)?;
+    Ok(self)
+  }
   /// Просто возвращает данный сериализатор. Параметр `_len` игнорируется
   fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> { Ok(self) }
   /// Просто возвращает данный сериализатор. Все параметры игнорируются
   fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> { Ok(self) }
-  /// Просто возвращает данный сериализатор. Все параметры игнорируются
+  /// Записывает в выходной поток метку варианта, если представление перечислений задано.
+  /// Остальные параметры игнорируются
   fn serialize_tuple_variant(
-    self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize
-  ) -> Result<Self::SerializeTupleVariant> { Ok(self) }
-  /// Просто возвращает данный сериализатор. Параметр `_len` игнорируется
-  fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> { Ok(self) }
+    self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize
+  ) -> Result<Self::SerializeTupleVariant> {
+    let data = self.variant_tag(variant_index)?;
+    Ok(Variant { ser: self, data })
+  }
+  /// Записывает количество пар, если это включено методом [`with_seq_prefix`], и возвращает
+  /// данный сериализатор
+  ///
+  /// [`with_seq_prefix`]: struct.Serializer.html#method.with_seq_prefix
+  fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+    self.seq_prefix(len)?;
+    Ok(self)
+  }
   /// Просто возвращает данный сериализатор. Все параметры игнорируются
   fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> { Ok(self) }
-  /// Просто возвращает данный сериализатор. Все параметры игнорируются
+  /// Записывает в выходной поток метку варианта, если представление перечислений задано.
+  /// Остальные параметры игнорируются
   fn serialize_struct_variant(
-    self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize
-  ) -> Result<Self::SerializeStructVariant> { Ok(self) }
+    self, _name: &'static str, variant_index: u32, _variant: &'static str, _len: usize
+  ) -> Result<Self::SerializeStructVariant> {
+    let data = self.variant_tag(variant_index)?;
+    Ok(Variant { ser: self, data })
+  }
 
   /// Возвращает `false`
   fn is_human_readable(&self) -> bool { false }
 }
 
-impl<'a, BO, W> ser::SerializeSeq for &'a mut Serializer<BO, W>
+impl<BO, W> ser::SerializeSeq for &mut Serializer<BO, W>
   where W: Write,
         BO: ByteOrder,
 {
@@ -216,7 +875,7 @@ impl<'a, BO, W> ser::SerializeSeq for &'a mut Serializer<BO, W>
   fn end(self) -> Result<Self::Ok> { Ok(()) }
 }
 
-impl<'a, BO, W> ser::SerializeTuple for &'a mut Serializer<BO, W>
+impl<BO, W> ser::SerializeTuple for &mut Serializer<BO, W>
   where W: Write,
         BO: ByteOrder,
 {
@@ -233,7 +892,7 @@ impl<'a, BO, W> ser::SerializeTuple for &'a mut Serializer<BO, W>
   fn end(self) -> Result<Self::Ok> { Ok(()) }
 }
 
-impl<'a, BO, W> ser::SerializeTupleStruct for &'a mut Serializer<BO, W>
+impl<BO, W> ser::SerializeTupleStruct for &mut Serializer<BO, W>
   where W: Write,
         BO: ByteOrder,
 {
@@ -250,15 +909,21 @@ impl<'a, BO, W> ser::SerializeTupleStruct for &'a mut Serializer<BO, W>
   fn end(self) -> Result<Self::Ok> { Ok(()) }
 }
 
-impl<'a, BO, W> ser::SerializeTupleVariant for &'a mut Serializer<BO, W>
+impl<BO, W> ser::SerializeMap for &mut Serializer<BO, W>
   where W: Write,
         BO: ByteOrder,
 {
   type Ok = ();
   type Error = Error;
 
+  /// Записывает в выходной поток представление `key` с помощью данного сериализатора
+  fn serialize_key<T>(&mut self, key: &T) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    key.serialize(&mut **self)
+  }
   /// Записывает в выходной поток представление `value` с помощью данного сериализатора
-  fn serialize_field<T>(&mut self, value: &T) -> Result<Self::Ok>
+  fn serialize_value<T>(&mut self, value: &T) -> Result<Self::Ok>
     where T: ?Sized + Serialize,
   {
     value.serialize(&mut **self)
@@ -267,60 +932,409 @@ impl<'a, BO, W> ser::SerializeTupleVariant for &'a mut Serializer<BO, W>
   fn end(self) -> Result<Self::Ok> { Ok(()) }
 }
 
-impl<'a, BO, W> ser::SerializeMap for &'a mut Serializer<BO, W>
+impl<BO, W> ser::SerializeStruct for &mut Serializer<BO, W>
   where W: Write,
         BO: ByteOrder,
 {
   type Ok = ();
   type Error = Error;
 
-  /// Записывает в выходной поток представление `key` с помощью данного сериализатора
-  fn serialize_key<T>(&mut self, key: &T) -> Result<Self::Ok>
-    where T: ?Sized + Serialize,
-  {
-    key.serialize(&mut **self)
-  }
   /// Записывает в выходной поток представление `value` с помощью данного сериализатора
-  fn serialize_value<T>(&mut self, value: &T) -> Result<Self::Ok>
+  fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<Self::Ok>
     where T: ?Sized + Serialize,
   {
-    value.serialize(&mut **self)
+    Serializer::serialize_field(&mut **self, key, value)
   }
   /// Ничего не записывает в поток
   fn end(self) -> Result<Self::Ok> { Ok(()) }
 }
 
-impl<'a, BO, W> ser::SerializeStruct for &'a mut Serializer<BO, W>
+/// Сериализатор полей кортежных и структурных вариантов перечислений. Если данные варианта
+/// предваряются их размером, поля накапливаются в буфере и записываются в поток при завершении
+/// варианта, иначе записываются в поток сразу
+pub struct Variant<'a, BO, W> {
+  /// Сериализатор, в который записывается вариант
+  ser: &'a mut Serializer<BO, W>,
+  /// Сериализатор данных варианта, если они записываются после их размера
+  data: Option<Serializer<BO, Vec<u8>>>,
+}
+
+impl<'a, BO, W> ser::SerializeTupleVariant for Variant<'a, BO, W>
   where W: Write,
         BO: ByteOrder,
 {
   type Ok = ();
   type Error = Error;
 
-  /// Записывает в выходной поток представление `value` с помощью данного сериализатора
-  fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<Self::Ok>
+  /// Записывает в выходной поток или в буфер данных варианта представление `value`
+  fn serialize_field<T>(&mut self, value: &T) -> Result<Self::Ok>
     where T: ?Sized + Serialize,
   {
-    value.serialize(&mut **self)
+    match self.data {
+      Some(ref mut data) => value.serialize(data),
+      None => value.serialize(&mut *self.ser),
+    }
+  }
+  /// Записывает размер и данные варианта, если они накапливались в буфере
+  fn end(self) -> Result<Self::Ok> {
+    match self.data {
+      Some(data) => self.ser.variant_data(data),
+      None => Ok(()),
+    }
+  }
+}
+
+impl<'a, BO, W> ser::SerializeStructVariant for Variant<'a, BO, W>
+  where W: Write,
+        BO: ByteOrder,
+{
+  type Ok = ();
+  type Error = Error;
+
+  /// Записывает в выходной поток или в буфер данных варианта представление `value`
+  fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    match self.data {
+      Some(ref mut data) => Serializer::serialize_field(data, key, value),
+      None => Serializer::serialize_field(self.ser, key, value),
+    }
+  }
+  /// Записывает размер и данные варианта, если они накапливались в буфере
+  fn end(self) -> Result<Self::Ok> {
+    match self.data {
+      Some(data) => self.ser.variant_data(data),
+      None => Ok(()),
+    }
+  }
+}
+
+/// Сериализатор, порядок байт которого выбирается во время выполнения программы значением
+/// [`Endian`], а не параметром типа, например, если он задается настройками программы. Порядок
+/// байт можно изменить в любой момент методом [`set_byteorder`].
+///
+/// Каждое значение, переданное методу [`serialize`], записывается обычным [`Serializer`] с
+/// текущим порядком байт, поэтому результат совпадает с результатом такого сериализатора.
+/// Поток не обязан поддерживать перемещение, поэтому значения, расположенные по смещению,
+/// не поддерживаются.
+///
+/// # Пример
+/// ```rust
+/// # extern crate serde_pod;
+/// # use serde_pod::Endian;
+/// # use serde_pod::ser::DynSerializer;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut ser = DynSerializer::new(Vec::new(), Endian::Big);
+/// ser.serialize(&b'M')?;
+/// ser.set_byteorder(Endian::Little);
+/// ser.serialize(&0x1234_5678u32)?;
+/// assert_eq!(ser.into_inner(), [b'M', 0x78, 0x56, 0x34, 0x12]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Endian`]: ../enum.Endian.html
+/// [`set_byteorder`]: #method.set_byteorder
+/// [`serialize`]: #method.serialize
+/// [`Serializer`]: struct.Serializer.html
+pub struct DynSerializer<W> {
+  /// Приемник сериализованных данных
+  writer: W,
+  /// Текущий порядок байт
+  endian: Endian,
+  /// Кодировка строк, если она отличается от UTF-8
+  encoding: Option<Arc<dyn Encoding>>,
+  /// Представление перечислений, если их варианты записываются в поток
+  enum_tag: Option<EnumTag>,
+  /// Записывать ли перед значением `Option` байт-маркер его наличия
+  option_marker: bool,
+  /// Способ отделения строк и массивов байт от следующих за ними данных
+  string_framing: StringFraming,
+  /// Функции записи количества элементов перед последовательностями в порядке `Big-Endian`
+  /// и `Little-Endian`, если они заданы методом [`with_seq_prefix`](#method.with_seq_prefix)
+  seq_prefix: Option<(WritePrefix, WritePrefix)>,
+  /// Количество байт, записанных в поток
+  written: u64,
+}
+
+impl<W: Write> DynSerializer<W> {
+  /// Создает сериализатор с указанным начальным порядком байт. Строки кодируются в UTF-8
+  ///
+  /// # Параметры
+  /// - `writer`: Поток, в который записывать сериализуемые данные
+  /// - `endian`: Порядок байт, в котором записываются числа, пока он не будет изменен
+  pub fn new(writer: W, endian: Endian) -> Self {
+    DynSerializer {
+      writer,
+      endian,
+      encoding: None,
+      enum_tag: None,
+      option_marker: false,
+      string_framing: StringFraming::Raw,
+      seq_prefix: None,
+      written: 0,
+    }
+  }
+  /// Устанавливает кодировку строк и символов вместо UTF-8, см. [`Serializer::with_encoding`]
+  ///
+  /// [`Serializer::with_encoding`]: struct.Serializer.html#method.with_encoding
+  pub fn with_encoding<E: Encoding + 'static>(mut self, encoding: E) -> Self {
+    self.encoding = Some(Arc::new(encoding));
+    self
+  }
+  /// Устанавливает однобайтовую кодировку строк с произвольной таблицей символов вместо UTF-8
+  pub fn with_custom_encoding(self, encoding: CustomEncoding) -> Self {
+    self.with_encoding(encoding)
+  }
+  /// Включает запись вариантов перечислений, см. [`Serializer::with_enum_tag`]
+  ///
+  /// [`Serializer::with_enum_tag`]: struct.Serializer.html#method.with_enum_tag
+  pub fn with_enum_tag(mut self, tag: EnumTag) -> Self {
+    self.enum_tag = Some(tag);
+    self
+  }
+  /// Включает запись байта-маркера перед значениями [`Option`], см. [`Serializer::with_option_marker`]
+  ///
+  /// [`Option`]: https://doc.rust-lang.org/std/option/enum.Option.html
+  /// [`Serializer::with_option_marker`]: struct.Serializer.html#method.with_option_marker
+  pub fn with_option_marker(mut self) -> Self {
+    self.option_marker = true;
+    self
+  }
+  /// Устанавливает способ записи строк и массивов байт, см. [`Serializer::with_string_framing`]
+  ///
+  /// [`Serializer::with_string_framing`]: struct.Serializer.html#method.with_string_framing
+  pub fn with_string_framing(mut self, framing: StringFraming) -> Self {
+    self.string_framing = framing;
+    self
+  }
+  /// Включает запись количества элементов перед последовательностями, см.
+  /// [`Serializer::with_seq_prefix`]. Количество записывается в текущем порядке байт
+  ///
+  /// [`Serializer::with_seq_prefix`]: struct.Serializer.html#method.with_seq_prefix
+  pub fn with_seq_prefix<L: Length>(mut self) -> Self {
+    self.seq_prefix = Some((write_prefix::<BE, L>, write_prefix::<LE, L>));
+    self
+  }
+  /// Возвращает текущий порядок байт
+  #[inline]
+  pub fn byteorder(&self) -> Endian { self.endian }
+  /// Изменяет порядок байт, в котором будут записываться последующие значения
+  #[inline]
+  pub fn set_byteorder(&mut self, endian: Endian) {
+    self.endian = endian;
+  }
+  /// Возвращает количество байт, записанных в поток с момента создания сериализатора
+  #[inline]
+  pub fn bytes_written(&self) -> u64 { self.written }
+  /// Возвращает поток, в который записываются данные
+  #[inline]
+  pub fn into_inner(self) -> W { self.writer }
+  /// Записывает значение в поток в текущем порядке байт
+  ///
+  /// # Параметры
+  /// - `value`: Записываемое значение
+  ///
+  /// # Ошибки
+  /// Те же, что и у [`Serializer`](struct.Serializer.html)
+  pub fn serialize<T>(&mut self, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    match self.endian {
+      Endian::Big    => self.serialize_as::<BE, T>(value, self.seq_prefix.map(|(be, _)| be)),
+      Endian::Little => self.serialize_as::<LE, T>(value, self.seq_prefix.map(|(_, le)| le)),
+    }
+  }
+  /// Записывает значение сериализатором с порядком байт `BO`, пишущим в поток этого сериализатора
+  fn serialize_as<BO: ByteOrder, T>(&mut self, value: &T, seq_prefix: Option<WritePrefix>) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    let mut ser = Serializer::<BO, _>::new(&mut self.writer);
+    ser.encoding = self.encoding.clone();
+    ser.enum_tag = self.enum_tag;
+    ser.option_marker = self.option_marker;
+    ser.string_framing = self.string_framing;
+    ser.seq_prefix = seq_prefix;
+    ser.written = self.written;
+    let result = value.serialize(&mut ser);
+    self.written = ser.written;
+    result
+  }
+}
+
+/// Сериализатор, раскладывающий поля элементов последовательности по столбцам: `i`-е поле каждого
+/// элемента записывается в `i`-й буфер. Сначала он принимает саму последовательность, а затем
+/// (в режиме строки) каждый ее элемент
+struct Columns<'a, BO, W> {
+  /// Сериализатор, настройки которого используются для записи полей
+  ser: &'a Serializer<BO, W>,
+  /// Буферы столбцов
+  columns: &'a mut Vec<Vec<u8>>,
+  /// Если `true`, сериализуется элемент последовательности, иначе сама последовательность
+  row: bool,
+  /// Индекс следующего поля элемента
+  field: usize,
+}
+impl<'a, BO, W> Columns<'a, BO, W>
+  where W: Write,
+        BO: ByteOrder,
+{
+  /// Сериализует элемент последовательности, как строку, или поле элемента в его столбец
+  fn serialize_item<T>(&mut self, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    if !self.row {
+      return value.serialize(Columns { ser: self.ser, columns: &mut *self.columns, row: true, field: 0 });
+    }
+    if self.columns.len() <= self.field {
+      self.columns.push(Vec::new());
+    }
+    let mut ser = self.ser.nested(&mut self.columns[self.field]);
+    value.serialize(&mut ser)?;
+    // Позиции полей в потоке станут известны только после записи всех столбцов
+    if ser.align > 1 {
+      return Err(Error::Unsupported("alignment is not supported in columnar layout"));
+    }
+    self.field += 1;
+    Ok(())
+  }
+  /// Начинает сериализацию последовательности или элемента
+  fn start(self, row: bool) -> Result<Self> {
+    if self.row == row { Ok(self) } else { Err(Error::Unsupported(COLUMNAR_ERROR)) }
+  }
+}
+
+/// Реализует методы сериализатора столбцов, не поддерживаемые для последовательностей и их элементов
+macro_rules! columnar_unsupported {
+  ($($ser_method:ident($($type:ty),*)),*) => {
+    $(
+      fn $ser_method(self, $(_: $type),*) -> Result<Self::Ok> {
+        Err(Error::Unsupported(COLUMNAR_ERROR))
+      }
+    )*
+  }
+}
+
+impl<'a, BO, W> ser::Serializer for Columns<'a, BO, W>
+  where W: Write,
+        BO: ByteOrder,
+{
+  type Ok = ();
+  type Error = Error;
+  type SerializeSeq = Self;
+  type SerializeTuple = Self;
+  type SerializeTupleStruct = Self;
+  type SerializeTupleVariant = ser::Impossible<(), Error>;
+  type SerializeMap = ser::Impossible<(), Error>;
+  type SerializeStruct = Self;
+  type SerializeStructVariant = ser::Impossible<(), Error>;
+
+  columnar_unsupported!(
+    serialize_bool(bool),
+    serialize_i8(i8), serialize_i16(i16), serialize_i32(i32), serialize_i64(i64), serialize_i128(i128),
+    serialize_u8(u8), serialize_u16(u16), serialize_u32(u32), serialize_u64(u64), serialize_u128(u128),
+    serialize_f32(f32), serialize_f64(f64),
+    serialize_char(char), serialize_str(&str), serialize_bytes(&[u8]),
+    serialize_none(), serialize_unit(), serialize_unit_struct(&'static str),
+    serialize_unit_variant(&'static str, u32, &'static str)
+  );
+  fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    Err(Error::Unsupported(COLUMNAR_ERROR))
+  }
+  fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    value.serialize(self)
+  }
+  fn serialize_newtype_variant<T>(
+    self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T
+  ) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    Err(Error::Unsupported(COLUMNAR_ERROR))
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> { self.start(false) }
+  /// Кортеж может быть как последовательностью (массивом), так и ее элементом
+  fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> { Ok(self) }
+  fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct> {
+    self.start(true)
+  }
+  fn serialize_tuple_variant(
+    self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize
+  ) -> Result<Self::SerializeTupleVariant> {
+    Err(Error::Unsupported(COLUMNAR_ERROR))
+  }
+  fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+    Err(Error::Unsupported(COLUMNAR_ERROR))
+  }
+  fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+    self.start(true)
+  }
+  fn serialize_struct_variant(
+    self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize
+  ) -> Result<Self::SerializeStructVariant> {
+    Err(Error::Unsupported(COLUMNAR_ERROR))
+  }
+
+  fn is_human_readable(&self) -> bool { false }
+}
+impl<'a, BO, W> ser::SerializeSeq for Columns<'a, BO, W>
+  where W: Write,
+        BO: ByteOrder,
+{
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_element<T>(&mut self, value: &T) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    self.serialize_item(value)
   }
-  /// Ничего не записывает в поток
   fn end(self) -> Result<Self::Ok> { Ok(()) }
 }
+impl<'a, BO, W> ser::SerializeTuple for Columns<'a, BO, W>
+  where W: Write,
+        BO: ByteOrder,
+{
+  type Ok = ();
+  type Error = Error;
 
-impl<'a, BO, W> ser::SerializeStructVariant for &'a mut Serializer<BO, W>
+  fn serialize_element<T>(&mut self, value: &T) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    self.serialize_item(value)
+  }
+  fn end(self) -> Result<Self::Ok> { Ok(()) }
+}
+impl<'a, BO, W> ser::SerializeTupleStruct for Columns<'a, BO, W>
+  where W: Write,
+        BO: ByteOrder,
+{
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_field<T>(&mut self, value: &T) -> Result<Self::Ok>
+    where T: ?Sized + Serialize,
+  {
+    self.serialize_item(value)
+  }
+  fn end(self) -> Result<Self::Ok> { Ok(()) }
+}
+impl<'a, BO, W> ser::SerializeStruct for Columns<'a, BO, W>
   where W: Write,
         BO: ByteOrder,
 {
   type Ok = ();
   type Error = Error;
 
-  /// Записывает в выходной поток представление `value` с помощью данного сериализатора
   fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<Self::Ok>
     where T: ?Sized + Serialize,
   {
-    value.serialize(&mut **self)
+    self.serialize_item(value)
   }
-  /// Ничего не записывает в поток
   fn end(self) -> Result<Self::Ok> { Ok(()) }
 }
 
@@ -594,8 +1608,10 @@ mod complex {
 
 #[cfg(test)]
 mod enums {
-  use super::to_vec;
+  use super::{to_vec, Serializer};
   use byteorder::{BE, LE};
+  use serde::Serialize;
+  use {EnumTag, TagWidth};
 
   #[derive(Serialize)]
   enum E {
@@ -638,4 +1654,418 @@ mod enums {
     assert_eq!(to_vec::<BE,_>(&s).unwrap(), [0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD]);
     assert_eq!(to_vec::<LE,_>(&s).unwrap(), [0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB]);
   }
+
+  /// Сериализует значение с записью индексов вариантов указанной ширины
+  fn tagged<T: Serialize>(value: &T, width: TagWidth, big_endian: bool) -> Vec<u8> {
+    let tag = EnumTag { width, length_delimited: false };
+    if big_endian {
+      let mut ser = Serializer::<BE, _>::new(Vec::new()).with_enum_tag(tag);
+      value.serialize(&mut ser).unwrap();
+      ser.into_inner()
+    } else {
+      let mut ser = Serializer::<LE, _>::new(Vec::new()).with_enum_tag(tag);
+      value.serialize(&mut ser).unwrap();
+      ser.into_inner()
+    }
+  }
+
+  /// Индекс варианта записывается перед полями в порядке байт сериализатора
+  #[test]
+  fn test_tagged_tuple() {
+    let t = E::Tuple(0x12345678, 0xABCD);
+    assert_eq!(tagged(&t, TagWidth::U32, true), [
+      0x00, 0x00, 0x00, 0x02,   0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD,
+    ]);
+    assert_eq!(tagged(&t, TagWidth::U32, false), [
+      0x02, 0x00, 0x00, 0x00,   0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB,
+    ]);
+  }
+  /// Для всех видов вариантов записывается только индекс заданной ширины
+  #[test]
+  fn test_tagged_width() {
+    assert_eq!(tagged(&E::Unit, TagWidth::U8, true), [0x00]);
+    assert_eq!(tagged(&E::Newtype(0x01), TagWidth::U16, true), [0x00, 0x01,   0x00, 0x00, 0x00, 0x01]);
+    assert_eq!(tagged(&E::Struct { int1: 0x01, int2: 0x02 }, TagWidth::U16, false), [
+      0x03, 0x00,   0x01, 0x00, 0x00, 0x00,   0x02, 0x00,
+    ]);
+  }
+}
+
+#[cfg(test)]
+mod dynamic {
+  use byteorder::{BE, LE};
+  use serde::Serialize;
+  use error::Error;
+  use wrappers::Offset;
+  use super::{to_vec, DynSerializer};
+  use {BESerializer, Endian, EnumTag, LESerializer, TagWidth};
+
+  #[derive(Serialize)]
+  enum Shape {
+    Point,
+    Circle { radius: u16 },
+  }
+  #[derive(Serialize)]
+  struct Header {
+    magic: [u8; 2],
+    version: u16,
+    numbers: (i32, u64, f32, f64, i128),
+    shapes: Vec<Shape>,
+    name: &'static str,
+  }
+  fn header() -> Header {
+    Header {
+      magic: *b"MM",
+      version: 42,
+      numbers: (-2, 0x0102_0304_0506_0708, 1.5, -0.25, -3),
+      shapes: vec![Shape::Point, Shape::Circle { radius: 0x0102 }],
+      name: "abc",
+    }
+  }
+
+  fn write<T: Serialize>(endian: Endian, value: &T) -> Vec<u8> {
+    let tag = EnumTag { width: TagWidth::U16, length_delimited: true };
+    let mut ser = DynSerializer::new(Vec::new(), endian).with_enum_tag(tag).with_seq_prefix::<u32>();
+    ser.serialize(value).unwrap();
+    assert_eq!(ser.bytes_written(), ser.writer.len() as u64);
+    ser.into_inner()
+  }
+
+  /// Результат совпадает с результатом сериализатора с тем же порядком байт в параметре типа
+  #[test]
+  fn test_same_as_static() {
+    let tag = EnumTag { width: TagWidth::U16, length_delimited: true };
+
+    let mut ser = BESerializer::new(Vec::new()).with_enum_tag(tag).with_seq_prefix::<u32>();
+    header().serialize(&mut ser).unwrap();
+    assert_eq!(write(Endian::Big, &header()), ser.into_inner());
+
+    let mut ser = LESerializer::new(Vec::new()).with_enum_tag(tag).with_seq_prefix::<u32>();
+    header().serialize(&mut ser).unwrap();
+    assert_eq!(write(Endian::Little, &header()), ser.into_inner());
+  }
+  /// Порядок байт задается сигнатурой, как в TIFF: `II` -- Little-Endian, `MM` -- Big-Endian
+  #[test]
+  fn test_switch() {
+    let mut ser = DynSerializer::new(Vec::new(), Endian::Big);
+    assert_eq!(ser.byteorder(), Endian::Big);
+    ser.serialize(b"II").unwrap();
+    ser.set_byteorder(Endian::Little);
+    assert_eq!(ser.byteorder(), Endian::Little);
+    ser.serialize(&(42u16, 8u32)).unwrap();
+    assert_eq!(ser.bytes_written(), 8);
+
+    let mut expected = to_vec::<BE, _>(b"II").unwrap();
+    expected.extend(to_vec::<LE, _>(&(42u16, 8u32)).unwrap());
+    assert_eq!(ser.into_inner(), expected);
+  }
+  /// Значения по смещению требуют потока, поддерживающего перемещение
+  #[test]
+  fn test_offset() {
+    let mut ser = DynSerializer::new(Vec::new(), Endian::Big);
+    assert!(matches!(ser.serialize(&Offset(1u8)), Err(Error::Unsupported(_))));
+  }
+}
+
+#[cfg(test)]
+mod seq_prefix {
+  use byteorder::{BE, LE};
+  use serde::Serialize;
+  use error::Error;
+  use super::Serializer;
+
+  #[test]
+  fn test_vec() {
+    let value: Vec<u16> = vec![0x0102, 0x0304];
+
+    let mut ser = Serializer::<BE, _>::new(Vec::new()).with_seq_prefix::<u32>();
+    value.serialize(&mut ser).unwrap();
+    assert_eq!(ser.into_inner(), [0x00, 0x00, 0x00, 0x02,   0x01, 0x02,   0x03, 0x04]);
+
+    let mut ser = Serializer::<LE, _>::new(Vec::new()).with_seq_prefix::<u32>();
+    value.serialize(&mut ser).unwrap();
+    assert_eq!(ser.into_inner(), [0x02, 0x00, 0x00, 0x00,   0x02, 0x01,   0x04, 0x03]);
+  }
+  /// Количество записывается перед каждой вложенной последовательностью, но не перед массивами
+  /// и кортежами, размер которых известен из типа
+  #[test]
+  fn test_nested() {
+    let value = (vec![vec![1u8], vec![]], [2u8, 3]);
+    let mut ser = Serializer::<LE, _>::new(Vec::new()).with_seq_prefix::<u8>();
+    value.serialize(&mut ser).unwrap();
+    assert_eq!(ser.into_inner(), [0x02,   0x01, 0x01,   0x00,   0x02, 0x03]);
+  }
+  #[test]
+  fn test_too_long() {
+    let mut ser = Serializer::<LE, _>::new(Vec::new()).with_seq_prefix::<u8>();
+    assert!(matches!(vec![0u8; 256].serialize(&mut ser), Err(Error::InvalidLength(_))));
+  }
+}
+
+#[cfg(test)]
+mod written {
+  use std::io::Cursor;
+  use byteorder::LE;
+  use serde::Serialize;
+  use super::Serializer;
+
+  #[test]
+  fn test_tuple() {
+    let mut ser = Serializer::<LE, _>::new(Vec::new());
+    assert_eq!(ser.bytes_written(), 0);
+    (0x12u8, 0x3456u16, *b"GFF ", "str").serialize(&mut ser).unwrap();
+    assert_eq!(ser.bytes_written(), 10);
+    assert_eq!(ser.bytes_written(), ser.into_inner().len() as u64);
+  }
+  /// Заполнение зарезервированной области не увеличивает количество записанных байт
+  #[test]
+  fn test_reserve() {
+    let mut ser = Serializer::<LE, _>::seekable(Cursor::new(Vec::new()));
+    0x01u8.serialize(&mut ser).unwrap();
+    let reservation = ser.reserve(4).unwrap();
+    assert_eq!(ser.bytes_written(), 5);
+    ser.fill(reservation, &0x02u32).unwrap();
+    0x03u16.serialize(&mut ser).unwrap();
+    assert_eq!(ser.bytes_written(), 7);
+    assert_eq!(ser.bytes_written(), ser.into_inner().into_inner().len() as u64);
+  }
+}
+
+#[cfg(test)]
+mod string_framing {
+  use byteorder::{BE, LE};
+  use serde::Serialize;
+  use de::from_bytes;
+  use error::Error;
+  use framing::PrefixedBytes;
+  use strings::{NulString, PascalString};
+  use super::Serializer;
+  use {StringFraming, TagWidth};
+
+  /// Массив байт, записываемый методом `serialize_bytes`
+  struct Bytes<'a>(&'a [u8]);
+  impl<'a> Serialize for Bytes<'a> {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+      serializer.serialize_bytes(self.0)
+    }
+  }
+
+  fn framed<BO: ::byteorder::ByteOrder, T: ?Sized + Serialize>(framing: StringFraming, value: &T) -> Result<Vec<u8>, Error> {
+    let mut ser = Serializer::<BO, _>::new(Vec::new()).with_string_framing(framing);
+    value.serialize(&mut ser)?;
+    Ok(ser.into_inner())
+  }
+
+  #[test]
+  fn test_raw() {
+    assert_eq!(framed::<BE, _>(StringFraming::Raw, &("ab", 'c')).unwrap(), b"abc");
+    assert_eq!(framed::<LE, _>(StringFraming::default(), &Bytes(b"ab")).unwrap(), b"ab");
+  }
+  #[test]
+  fn test_nul_terminated() {
+    for data in &[framed::<BE, _>(StringFraming::NulTerminated, &("ab", "", 'c')).unwrap(),
+                  framed::<LE, _>(StringFraming::NulTerminated, &("ab", "", 'c')).unwrap()] {
+      assert_eq!(data, b"ab\0\0c");
+    }
+    assert_eq!(framed::<LE, _>(StringFraming::NulTerminated, &Bytes(b"ab")).unwrap(), b"ab\0");
+
+    let data = framed::<LE, _>(StringFraming::NulTerminated, &("ab", "cd")).unwrap();
+    let value = from_bytes::<LE, (NulString, NulString)>(&data).unwrap();
+    assert_eq!((value.0.as_str(), value.1.as_str()), ("ab", "cd"));
+
+    assert!(matches!(framed::<BE, _>(StringFraming::NulTerminated, "a\0b"), Err(Error::InvalidLength(_))));
+  }
+  #[test]
+  fn test_length_prefixed() {
+    let u8 = StringFraming::LengthPrefixed(TagWidth::U8);
+    let u16 = StringFraming::LengthPrefixed(TagWidth::U16);
+    let u32 = StringFraming::LengthPrefixed(TagWidth::U32);
+    assert_eq!(framed::<BE, _>(u8, "ab").unwrap(), b"\x02ab");
+    assert_eq!(framed::<LE, _>(u8, "ab").unwrap(), b"\x02ab");
+    assert_eq!(framed::<BE, _>(u16, "ab").unwrap(), b"\x00\x02ab");
+    assert_eq!(framed::<LE, _>(u16, "ab").unwrap(), b"\x02\x00ab");
+    assert_eq!(framed::<BE, _>(u32, &Bytes(b"ab")).unwrap(), b"\x00\x00\x00\x02ab");
+    assert_eq!(framed::<LE, _>(u32, &Bytes(b"ab")).unwrap(), b"\x02\x00\x00\x00ab");
+    // Символы записываются без длины
+    assert_eq!(framed::<LE, _>(u16, &'c').unwrap(), b"c");
+
+    let data = framed::<BE, _>(u16, &("ab", "cde")).unwrap();
+    let value = from_bytes::<BE, (PascalString<u16>, PascalString<u16>)>(&data).unwrap();
+    assert_eq!((value.0.as_str(), value.1.as_str()), ("ab", "cde"));
+    let data = framed::<LE, _>(u16, &("ab", "cde")).unwrap();
+    let value = from_bytes::<LE, (PascalString<u16>, PascalString<u16>)>(&data).unwrap();
+    assert_eq!((value.0.as_str(), value.1.as_str()), ("ab", "cde"));
+
+    let long = "x".repeat(256);
+    assert!(matches!(framed::<BE, _>(u8, &long), Err(Error::InvalidLength(_))));
+  }
+  /// Типы, сами записывающие длину массива байт, не получают дополнительной длины
+  #[test]
+  fn test_prefixed_bytes() {
+    let blob = PrefixedBytes::<u16>::new(&b"abc"[..]);
+    let framing = StringFraming::LengthPrefixed(TagWidth::U32);
+    assert_eq!(framed::<BE, _>(framing, &blob).unwrap(), b"\x00\x03abc");
+    assert_eq!(framed::<LE, _>(StringFraming::NulTerminated, &blob).unwrap(), b"\x03\x00abc");
+  }
+}
+
+#[cfg(test)]
+mod padding {
+  use byteorder::{BE, LE};
+  use serde::Serialize;
+  use super::Serializer;
+
+  #[test]
+  fn test_write_padding() {
+    let mut ser = Serializer::<BE, _>::new(Vec::new());
+    0x01u8.serialize(&mut ser).unwrap();
+    ser.write_padding(3).unwrap();
+    0x0203u16.serialize(&mut ser).unwrap();
+    ser.write_padding(0).unwrap();
+    assert_eq!(ser.bytes_written(), 6);
+    assert_eq!(ser.into_inner(), [0x01,   0x00, 0x00, 0x00,   0x02, 0x03]);
+  }
+  #[test]
+  fn test_large() {
+    let mut ser = Serializer::<LE, _>::new(Vec::new());
+    ser.write_padding(10_000).unwrap();
+    assert_eq!(ser.bytes_written(), 10_000);
+    assert!(ser.into_inner().iter().all(|&b| b == 0));
+  }
+}
+
+#[cfg(test)]
+mod writer {
+  use byteorder::{BE, LE};
+  use serde::Serialize;
+  use super::Serializer;
+
+  #[derive(Serialize)]
+  struct Header {
+    signature: [u8; 4],
+    size: u32,
+  }
+
+  #[test]
+  fn test_into_inner() {
+    let mut ser = Serializer::<LE, _>::new(Vec::new());
+    Header { signature: *b"GFF ", size: 0x10 }.serialize(&mut ser).unwrap();
+    assert_eq!(ser.get_ref().len(), 8);
+
+    0xABCDu16.serialize(&mut ser).unwrap();
+    assert_eq!(ser.into_inner(), [
+      0x47, 0x46, 0x46, 0x20,   0x10, 0x00, 0x00, 0x00,
+      0xCD, 0xAB,
+    ]);
+  }
+  /// Данные, записанные в поток напрямую, располагаются между значениями
+  #[test]
+  fn test_get_mut() {
+    let mut ser = Serializer::<BE, _>::new(Vec::new());
+    0x1234u16.serialize(&mut ser).unwrap();
+    ser.get_mut().push(0xFF);
+    0x5678u16.serialize(&mut ser).unwrap();
+    assert_eq!(ser.get_ref(), &[0x12, 0x34,   0xFF,   0x56, 0x78]);
+  }
+}
+
+#[cfg(test)]
+mod reservation {
+  use std::io::Cursor;
+  use byteorder::{BE, LE};
+  use serde::Serialize;
+  use super::Serializer;
+
+  #[derive(Serialize)]
+  struct Section {
+    offset: u32,
+    count: u32,
+  }
+
+  /// Заголовок с описанием секции записывается после самой секции
+  #[test]
+  fn test_out_of_order_section() {
+    let mut ser: Serializer<BE, _> = Serializer::new(Cursor::new(Vec::new()));
+    0xABCDu16.serialize(&mut ser).unwrap();
+    let header = ser.reserve(8).unwrap();
+    let offset = ser.writer.position() as u32;
+    [0x1234u16, 0x5678].serialize(&mut ser).unwrap();
+    ser.fill(header, &Section { offset, count: 2 }).unwrap();
+    0xEFu8.serialize(&mut ser).unwrap();
+
+    assert_eq!(ser.writer.into_inner(), [
+      0xAB, 0xCD,
+      0x00, 0x00, 0x00, 0x0A,   0x00, 0x00, 0x00, 0x02,
+      0x12, 0x34,   0x56, 0x78,
+      0xEF,
+    ]);
+  }
+  /// Значение, меньшее зарезервированной области, дополняется нулями
+  #[test]
+  fn test_fill_shorter() {
+    let mut ser: Serializer<LE, _> = Serializer::new(Cursor::new(Vec::new()));
+    let r = ser.reserve(4).unwrap();
+    0xABCDu16.serialize(&mut ser).unwrap();
+    ser.fill(r, &0x1234u16).unwrap();
+
+    assert_eq!(ser.writer.into_inner(), [0x34, 0x12, 0x00, 0x00,   0xCD, 0xAB]);
+  }
+  /// Значение, не помещающееся в зарезервированную область, не записывается
+  #[test]
+  fn test_fill_too_long() {
+    let mut ser: Serializer<LE, _> = Serializer::new(Cursor::new(Vec::new()));
+    let r = ser.reserve(2).unwrap();
+    assert!(ser.fill(r, &0x12345678u32).is_err());
+    assert_eq!(ser.writer.into_inner(), [0x00, 0x00]);
+  }
+}
+
+#[cfg(all(test, feature = "debug-trace"))]
+mod trace {
+  use std::cell::RefCell;
+  use std::rc::Rc;
+  use byteorder::BE;
+  use serde::Serialize;
+  use super::Serializer;
+
+  #[derive(Serialize)]
+  struct Inner {
+    flag: bool,
+  }
+  #[derive(Serialize)]
+  struct Test {
+    id: u16,
+    value: u32,
+  }
+
+  #[test]
+  fn test_two_fields() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let sink = log.clone();
+    let mut buf = Vec::new();
+    let mut ser = Serializer::<BE, _>::new(&mut buf);
+    ser.set_trace(move |entry| sink.borrow_mut().push(entry.to_string()));
+
+    Test { id: 0x1234, value: 0xDEADBEEF }.serialize(&mut ser).unwrap();
+    assert_eq!(*log.borrow(), [
+      "00000000 id: 12 34",
+      "00000002 value: DE AD BE EF",
+    ]);
+  }
+  /// Имя поля вложенной структуры заменяет имя внешнего поля только на время его записи
+  #[test]
+  fn test_nested() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let sink = log.clone();
+    let mut buf = Vec::new();
+    let mut ser = Serializer::<BE, _>::new(&mut buf);
+    ser.set_trace(move |entry| sink.borrow_mut().push(entry.to_string()));
+
+    (0xAAu8, Inner { flag: true }, 0xBBu8).serialize(&mut ser).unwrap();
+    assert_eq!(*log.borrow(), [
+      "00000000 AA",
+      "00000001 flag: 01",
+      "00000002 BB",
+    ]);
+  }
 }
@@ -0,0 +1,628 @@
+//! Содержит сериализатор, записывающий данные в срез байт, и десериализатор, читающий их из среза.
+//! В отличие от типов модулей [`ser`] и [`de`], они не используют потоки ввода-вывода стандартной
+//! библиотеки, поэтому доступны и без функциональности `std`, например, для чтения структур из
+//! флеш-памяти во встраиваемых системах.
+//!
+//! Правила сериализации и десериализации совпадают с правилами сериализатора и десериализатора
+//! с настройками по умолчанию: строки, массивы байт и последовательности без определенной длины
+//! читаются до конца среза, а `Option`, перечисления и отображения не десериализуются.
+//!
+//! Настройки сериализатора и десериализатора потоков не поддерживаются: байт-маркер `Option`,
+//! метки вариантов перечислений, строгая проверка `bool`, ограничения [`Limit`], кодировки строк,
+//! способы записи строк [`StringFraming`] и количество элементов перед последовательностями.
+//! Типы, требующие особой обработки сериализатором потоков, например, [`Offset`], [`Crc32Wrapped`]
+//! или [`Aligned`], не поддерживаются: их сериализация и десериализация завершается ошибкой
+//! [`Error::Unsupported`], а не записью другого представления.
+//!
+//! # Пример
+//! ```rust
+//! # extern crate byteorder;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_pod;
+//! # use byteorder::LE;
+//! # use serde_pod::slice::{from_slice, to_slice};
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Section {
+//!   offset: u32,
+//!   count: u16,
+//! }
+//!
+//! # fn main() -> serde_pod::Result<()> {
+//! let mut buf = [0u8; 16];
+//! let len = to_slice::<LE, _>(&mut buf, &Section { offset: 0x38, count: 15 })?;
+//! assert_eq!(&buf[..len], [0x38, 0x00, 0x00, 0x00,   0x0F, 0x00]);
+//! assert_eq!(from_slice::<LE, Section>(&buf[..len])?, Section { offset: 0x38, count: 15 });
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ser`]: ../ser/index.html
+//! [`de`]: ../de/index.html
+//! [`Limit`]: ../de/struct.Limit.html
+//! [`StringFraming`]: ../enum.StringFraming.html
+//! [`Offset`]: ../wrappers/struct.Offset.html
+//! [`Crc32Wrapped`]: ../checksum/struct.Crc32Wrapped.html
+//! [`Aligned`]: ../layout/struct.Aligned.html
+//! [`Error::Unsupported`]: ../error/enum.Error.html#variant.Unsupported
+
+use alloc::str;
+use byteorder::ByteOrder;
+use serde::de::{self, Deserialize, DeserializeSeed, SeqAccess, Visitor};
+use serde::ser::{self, Serialize};
+
+use error::{Error, Result};
+#[cfg(feature = "std")]
+use magic;
+
+/// Возвращает ошибку, если newtype- или tuple-структура `name` требует особой обработки, которую
+/// выполняют только сериализатор и десериализатор потоков
+#[inline]
+fn check_name(name: &'static str) -> Result<()> {
+  #[cfg(feature = "std")]
+  {
+    if magic::requires_stream(name) {
+      return Err(Error::Unsupported("type is supported only by stream (de)serializers"));
+    }
+  }
+  #[cfg(not(feature = "std"))]
+  let _ = name;
+  Ok(())
+}
+
+/// Десериализатор, читающий данные из среза байт. Строки и массивы байт заимствуются из среза,
+/// поэтому могут десериализовываться в `&str` и `&[u8]` без копирования.
+///
+/// # Параметры типа
+/// - `BO`: определяет порядок байт, в котором записаны примитивные числовые типы
+pub struct Deserializer<'de, BO> {
+  /// Еще не прочитанные данные
+  input: &'de [u8],
+  /// Порядок байт, используемый при чтении чисел
+  _byteorder: core::marker::PhantomData<BO>,
+}
+
+impl<'de, BO: ByteOrder> Deserializer<'de, BO> {
+  /// Создает десериализатор, читающий данные из указанного среза
+  pub fn new(input: &'de [u8]) -> Self {
+    Deserializer { input, _byteorder: core::marker::PhantomData }
+  }
+  /// Возвращает еще не прочитанные данные
+  #[inline]
+  pub fn remaining(&self) -> &'de [u8] { self.input }
+  /// Читает значение, занимающее ровно `len` байт. Для значения данные заканчиваются на границе
+  /// этих байт, поэтому строки и массивы байт, заимствуемые из среза, занимают остаток блока,
+  /// а не остаток всех данных. Это позволяет без копирования читать блоки фиксированной длины,
+  /// за которыми следуют другие данные. Непрочитанный остаток блока пропускается.
+  ///
+  /// # Параметры
+  /// - `len`: Количество байт, занимаемых значением
+  ///
+  /// # Ошибки
+  /// Ошибка чтения значения или [`Error::Eof`], если в срезе осталось меньше `len` байт
+  ///
+  /// # Пример
+  /// ```rust
+  /// # extern crate byteorder;
+  /// # extern crate serde_pod;
+  /// # use byteorder::LE;
+  /// # use serde_pod::slice::Deserializer;
+  /// # fn main() -> serde_pod::Result<()> {
+  /// let data = [b'a', b'b', b'c',   0x01, 0x00];
+  /// let mut de = Deserializer::<LE>::new(&data);
+  /// let blob: &[u8] = de.bounded(3)?;
+  /// assert_eq!(blob, b"abc");
+  /// assert_eq!(de.remaining(), [0x01, 0x00]);
+  /// # Ok(())
+  /// # }
+  /// ```
+  ///
+  /// [`Error::Eof`]: ../error/enum.Error.html#variant.Eof
+  pub fn bounded<T>(&mut self, len: usize) -> Result<T>
+    where T: Deserialize<'de>,
+  {
+    let mut sub = Deserializer::<BO>::new(self.take(len)?);
+    T::deserialize(&mut sub)
+  }
+  /// Отделяет от начала еще не прочитанных данных `len` байт
+  fn take(&mut self, len: usize) -> Result<&'de [u8]> {
+    if self.input.len() < len {
+      return Err(Error::Eof(Some(len)));
+    }
+    let (head, tail) = self.input.split_at(len);
+    self.input = tail;
+    Ok(head)
+  }
+  /// Отделяет все еще не прочитанные данные
+  fn take_rest(&mut self) -> &'de [u8] {
+    let rest = self.input;
+    self.input = &[];
+    rest
+  }
+  /// Читает один символ в кодировке UTF-8
+  fn read_char(&mut self) -> Result<char> {
+    let width = match *self.input.first().ok_or(Error::Eof(Some(1)))? {
+      0xC2..=0xDF => 2,
+      0xE0..=0xEF => 3,
+      0xF0..=0xF4 => 4,
+      _ => 1,
+    };
+    let s = str::from_utf8(self.take(width)?)?;
+    s.chars().next().ok_or_else(|| Error::Unknown("UTF-8 bytes decoded as empty string".into()))
+  }
+}
+
+/// Реализует метод десериализации числа, читающий его из среза в порядке байт десериализатора
+macro_rules! number {
+  ($dser_method:ident, $visitor_method:ident, $read:ident, $size:expr) => {
+    fn $dser_method<V>(self, visitor: V) -> Result<V::Value>
+      where V: Visitor<'de>,
+    {
+      visitor.$visitor_method(BO::$read(self.take($size)?))
+    }
+  };
+}
+/// Реализует неподдерживаемый метод десериализации
+macro_rules! unsupported {
+  ($dser_method:ident($($arg:ident: $type:ty),*)) => {
+    fn $dser_method<V>(self, $(_: $type,)* _visitor: V) -> Result<V::Value>
+      where V: Visitor<'de>,
+    {
+      Err(Error::Unsupported(concat!("`", stringify!($dser_method), "` is not supported")))
+    }
+  };
+}
+
+impl<'de, BO: ByteOrder> de::Deserializer<'de> for &mut Deserializer<'de, BO> {
+  type Error = Error;
+
+  /// Читает 1 байт: `0` означает `false`, любое другое значение -- `true`
+  fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_bool(self.take(1)?[0] != 0)
+  }
+  fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_i8(self.take(1)?[0] as i8)
+  }
+  fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_u8(self.take(1)?[0])
+  }
+  number!(deserialize_i16, visit_i16, read_i16, 2);
+  number!(deserialize_u16, visit_u16, read_u16, 2);
+  number!(deserialize_i32, visit_i32, read_i32, 4);
+  number!(deserialize_u32, visit_u32, read_u32, 4);
+  number!(deserialize_i64, visit_i64, read_i64, 8);
+  number!(deserialize_u64, visit_u64, read_u64, 8);
+  number!(deserialize_i128, visit_i128, read_i128, 16);
+  number!(deserialize_u128, visit_u128, read_u128, 16);
+  number!(deserialize_f32, visit_f32, read_f32, 4);
+  number!(deserialize_f64, visit_f64, read_f64, 8);
+
+  /// Читает от 1 до 4 байт UTF-8 представления символа
+  fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_char(self.read_char()?)
+  }
+  /// Заимствует из среза все оставшиеся данные и передает их посетителю, как строку в UTF-8
+  fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_borrowed_str(str::from_utf8(self.take_rest())?)
+  }
+  fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    self.deserialize_str(visitor)
+  }
+  /// Заимствует из среза все оставшиеся данные и передает их посетителю
+  fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_borrowed_bytes(self.take_rest())
+  }
+  fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    self.deserialize_bytes(visitor)
+  }
+  fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_unit()
+  }
+  fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_unit()
+  }
+  fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    check_name(name)?;
+    visitor.visit_newtype_struct(self)
+  }
+  /// Читает элементы последовательности, пока в срезе остаются данные
+  fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_seq(Elements { de: self, count: None })
+  }
+  fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    visitor.visit_seq(Elements { de: self, count: Some(len) })
+  }
+  fn deserialize_tuple_struct<V>(self, name: &'static str, len: usize, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    check_name(name)?;
+    self.deserialize_tuple(len, visitor)
+  }
+  fn deserialize_struct<V>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    self.deserialize_tuple(fields.len(), visitor)
+  }
+
+  unsupported!(deserialize_any());
+  unsupported!(deserialize_option());
+  unsupported!(deserialize_map());
+  unsupported!(deserialize_enum(name: &'static str, variants: &'static [&'static str]));
+  unsupported!(deserialize_identifier());
+  /// Пропускает все оставшиеся в срезе данные, так как длина произвольного значения неизвестна
+  fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where V: Visitor<'de>,
+  {
+    self.take_rest();
+    visitor.visit_unit()
+  }
+
+  /// Возвращает `false`
+  fn is_human_readable(&self) -> bool { false }
+}
+
+/// Элементы кортежа, структуры или, если количество не задано, последовательности до конца среза
+struct Elements<'a, 'de: 'a, BO: 'a> {
+  /// Десериализатор, из которого читаются элементы
+  de: &'a mut Deserializer<'de, BO>,
+  /// Количество элементов, которое осталось прочитать, или `None`, если элементы читаются
+  /// до конца среза
+  count: Option<usize>,
+}
+impl<'a, 'de, BO: ByteOrder> SeqAccess<'de> for Elements<'a, 'de, BO> {
+  type Error = Error;
+
+  fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where T: DeserializeSeed<'de>,
+  {
+    match self.count {
+      Some(0) => return Ok(None),
+      Some(ref mut count) => *count -= 1,
+      None if self.de.input.is_empty() => return Ok(None),
+      None => {},
+    }
+    seed.deserialize(&mut *self.de).map(Some)
+  }
+
+  fn size_hint(&self) -> Option<usize> { self.count }
+}
+
+/// Сериализатор, записывающий данные в срез байт фиксированного размера. Если данные не помещаются
+/// в срез, сериализация прерывается с ошибкой [`Error::InvalidLength`]
+///
+/// # Параметры типа
+/// - `BO`: определяет порядок байт, в котором будут записаны примитивные числовые типы
+///
+/// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+pub struct Serializer<'a, BO> {
+  /// Срез, в который записываются данные
+  output: &'a mut [u8],
+  /// Количество байт, записанных в начало среза
+  written: usize,
+  /// Порядок байт, используемый при записи чисел
+  _byteorder: core::marker::PhantomData<BO>,
+}
+
+impl<'a, BO: ByteOrder> Serializer<'a, BO> {
+  /// Создает сериализатор, записывающий данные с начала указанного среза
+  pub fn new(output: &'a mut [u8]) -> Self {
+    Serializer { output, written: 0, _byteorder: core::marker::PhantomData }
+  }
+  /// Возвращает количество байт, записанных в начало среза
+  #[inline]
+  pub fn bytes_written(&self) -> usize { self.written }
+  /// Дописывает байты после уже записанных данных
+  fn put(&mut self, bytes: &[u8]) -> Result<()> {
+    let end = self.written + bytes.len();
+    if end > self.output.len() {
+      return Err(Error::InvalidLength("output buffer is too small".into()));
+    }
+    self.output[self.written..end].copy_from_slice(bytes);
+    self.written = end;
+    Ok(())
+  }
+}
+
+/// Реализует метод сериализации числа, записывающий его в порядке байт сериализатора
+macro_rules! write_number {
+  ($ser_method:ident, $type:ty, $write:ident, $size:expr) => {
+    fn $ser_method(self, v: $type) -> Result<()> {
+      let mut buf = [0u8; $size];
+      BO::$write(&mut buf, v);
+      self.put(&buf)
+    }
+  };
+}
+
+impl<'a, 'b, BO: ByteOrder> ser::Serializer for &'b mut Serializer<'a, BO> {
+  type Ok = ();
+  type Error = Error;
+
+  type SerializeSeq = Self;
+  type SerializeTuple = Self;
+  type SerializeTupleStruct = Self;
+  type SerializeTupleVariant = Self;
+  type SerializeMap = Self;
+  type SerializeStruct = Self;
+  type SerializeStructVariant = Self;
+
+  fn serialize_i8(self, v: i8) -> Result<()> { self.put(&[v as u8]) }
+  fn serialize_u8(self, v: u8) -> Result<()> { self.put(&[v]) }
+  write_number!(serialize_i16, i16, write_i16, 2);
+  write_number!(serialize_u16, u16, write_u16, 2);
+  write_number!(serialize_i32, i32, write_i32, 4);
+  write_number!(serialize_u32, u32, write_u32, 4);
+  write_number!(serialize_i64, i64, write_i64, 8);
+  write_number!(serialize_u64, u64, write_u64, 8);
+  write_number!(serialize_i128, i128, write_i128, 16);
+  write_number!(serialize_u128, u128, write_u128, 16);
+  write_number!(serialize_f32, f32, write_f32, 4);
+  write_number!(serialize_f64, f64, write_f64, 8);
+
+  /// Записывает 1 байт: `0x00` для `false` и `0x01` для `true`
+  fn serialize_bool(self, v: bool) -> Result<()> { self.put(&[v as u8]) }
+  /// Записывает UTF-8 байты представления символа
+  fn serialize_char(self, v: char) -> Result<()> {
+    let mut buf = [0u8; 4];
+    self.put(v.encode_utf8(&mut buf).as_bytes())
+  }
+  /// Записывает UTF-8 байты строки без длины и завершающего нуля
+  fn serialize_str(self, v: &str) -> Result<()> { self.put(v.as_bytes()) }
+  /// Записывает байты массива как есть
+  fn serialize_bytes(self, v: &[u8]) -> Result<()> { self.put(v) }
+
+  /// Ничего не записывает
+  fn serialize_none(self) -> Result<()> { Ok(()) }
+  /// Записывает значение без маркера его наличия
+  fn serialize_some<T>(self, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    value.serialize(self)
+  }
+  /// Ничего не записывает
+  fn serialize_unit(self) -> Result<()> { Ok(()) }
+  /// Ничего не записывает
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<()> { Ok(()) }
+  /// Ничего не записывает
+  fn serialize_unit_variant(self, _name: &'static str, _index: u32, _variant: &'static str) -> Result<()> {
+    Ok(())
+  }
+  fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    check_name(name)?;
+    value.serialize(self)
+  }
+  /// Записывает данные варианта без его метки
+  fn serialize_newtype_variant<T>(
+    self, _name: &'static str, _index: u32, _variant: &'static str, value: &T
+  ) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    value.serialize(self)
+  }
+
+  fn serialize_seq(self, _len: Option<usize>) -> Result<Self> { Ok(self) }
+  fn serialize_tuple(self, _len: usize) -> Result<Self> { Ok(self) }
+  fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<Self> {
+    check_name(name)?;
+    Ok(self)
+  }
+  fn serialize_tuple_variant(
+    self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize
+  ) -> Result<Self> {
+    Ok(self)
+  }
+  fn serialize_map(self, _len: Option<usize>) -> Result<Self> { Ok(self) }
+  fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self> { Ok(self) }
+  fn serialize_struct_variant(
+    self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize
+  ) -> Result<Self> {
+    Ok(self)
+  }
+
+  /// Возвращает `false`
+  fn is_human_readable(&self) -> bool { false }
+}
+
+/// Реализует типаж составного значения, поля которого записываются друг за другом
+macro_rules! compound {
+  ($trait:ident, $method:ident($($arg:ident: $type:ty),*)) => {
+    impl<'a, 'b, BO: ByteOrder> ser::$trait for &'b mut Serializer<'a, BO> {
+      type Ok = ();
+      type Error = Error;
+
+      fn $method<T>(&mut self, $($arg: $type,)* value: &T) -> Result<()>
+        where T: ?Sized + Serialize,
+      {
+        value.serialize(&mut **self)
+      }
+      fn end(self) -> Result<()> { Ok(()) }
+    }
+  };
+}
+compound!(SerializeSeq, serialize_element());
+compound!(SerializeTuple, serialize_element());
+compound!(SerializeTupleStruct, serialize_field());
+compound!(SerializeTupleVariant, serialize_field());
+compound!(SerializeStruct, serialize_field(_key: &'static str));
+compound!(SerializeStructVariant, serialize_field(_key: &'static str));
+
+impl<'a, 'b, BO: ByteOrder> ser::SerializeMap for &'b mut Serializer<'a, BO> {
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    key.serialize(&mut **self)
+  }
+  fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where T: ?Sized + Serialize,
+  {
+    value.serialize(&mut **self)
+  }
+  fn end(self) -> Result<()> { Ok(()) }
+}
+
+/// Десериализует значение заданного типа из среза байт. Данные, оставшиеся в срезе после
+/// значения, игнорируются.
+///
+/// # Параметры
+/// - `input`: Срез, содержащий сериализованное значение
+///
+/// # Параметры типа
+/// - `BO`: Порядок байт, в котором записаны числа
+/// - `T`: Тип десериализуемого значения. Строки и массивы байт могут заимствоваться из среза
+pub fn from_slice<'de, BO, T>(input: &'de [u8]) -> Result<T>
+  where BO: ByteOrder,
+        T: Deserialize<'de>,
+{
+  T::deserialize(&mut Deserializer::<BO>::new(input))
+}
+
+/// Сериализует значение в начало среза байт.
+///
+/// # Параметры
+/// - `output`: Срез, в который записывается значение
+/// - `value`: Сериализуемое значение
+///
+/// # Возвращаемое значение
+/// Количество байт, записанных в начало среза
+///
+/// # Ошибки
+/// [`Error::InvalidLength`], если значение не помещается в срез
+///
+/// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+pub fn to_slice<BO, T>(output: &mut [u8], value: &T) -> Result<usize>
+  where BO: ByteOrder,
+        T: ?Sized + Serialize,
+{
+  let mut ser = Serializer::<BO>::new(output);
+  value.serialize(&mut ser)?;
+  Ok(ser.bytes_written())
+}
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use serde::de::IgnoredAny;
+  use error::Error;
+  use super::{from_slice, to_slice, Deserializer};
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Record<'a> {
+    id: u16,
+    value: f32,
+    flag: bool,
+    letter: char,
+    name: &'a str,
+  }
+
+  #[test]
+  fn test_roundtrip() {
+    let record = Record { id: 0x0102, value: 1.5, flag: true, letter: 'ж', name: "abc" };
+    let mut buf = [0u8; 32];
+    let len = to_slice::<BE, _>(&mut buf, &record).unwrap();
+    assert_eq!(&buf[..len], [
+      0x01, 0x02,   0x3F, 0xC0, 0x00, 0x00,   0x01,   0xD0, 0xB6,   b'a', b'b', b'c',
+    ]);
+    assert_eq!(from_slice::<BE, Record>(&buf[..len]).unwrap(), record);
+  }
+  /// Результат совпадает с результатом сериализатора, пишущего в поток
+  #[test]
+  fn test_same_as_stream() {
+    let value = (0x12345678u32, [1i8, -1], vec![0xABCDu16, 0xEF01]);
+    let mut buf = [0u8; 16];
+    let len = to_slice::<LE, _>(&mut buf, &value).unwrap();
+    assert_eq!(&buf[..len], &::to_vec::<LE, _>(&value).unwrap()[..]);
+    assert_eq!(from_slice::<LE, (u32, [i8; 2], Vec<u16>)>(&buf[..len]).unwrap(), value);
+  }
+  /// Блок фиксированной длины, содержимое которого заимствуется из среза
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Blob<'a> {
+    kind: u16,
+    #[serde(borrow)]
+    data: &'a [u8],
+  }
+
+  #[test]
+  fn test_borrowed_blob() {
+    let input = [0x00, 0x07,   0xDE, 0xAD, 0xBE, 0xEF,   0x00, 0x2A];
+    let mut de = Deserializer::<BE>::new(&input);
+    let blob: Blob = de.bounded(6).unwrap();
+    assert_eq!(blob, Blob { kind: 7, data: &[0xDE, 0xAD, 0xBE, 0xEF] });
+    // Данные не копируются
+    assert_eq!(blob.data.as_ptr(), input[2..].as_ptr());
+    assert_eq!(de.bounded::<u16>(2).unwrap(), 0x2A);
+    assert!(de.remaining().is_empty());
+    assert!(matches!(de.bounded::<Blob>(1), Err(Error::Eof(Some(1)))));
+  }
+  #[test]
+  fn test_too_small() {
+    let mut buf = [0u8; 3];
+    assert!(matches!(to_slice::<LE, _>(&mut buf, &0u32), Err(Error::InvalidLength(_))));
+    assert!(matches!(from_slice::<LE, u32>(&buf), Err(Error::Eof(Some(4)))));
+  }
+  #[test]
+  fn test_ignored_any() {
+    let value: (u8, IgnoredAny) = from_slice::<LE, _>(&[0x01, 0x02, 0x03]).unwrap();
+    assert_eq!(value.0, 1);
+  }
+  #[test]
+  fn test_unsupported() {
+    assert!(matches!(from_slice::<LE, Option<u8>>(&[1]), Err(Error::Unsupported(_))));
+  }
+  /// Типы, требующие особой обработки сериализатором потоков, не записываются молча в другом виде
+  #[test]
+  fn test_stream_only_types() {
+    use checksum::Crc32Wrapped;
+    use framing::PrefixedBytes;
+    use layout::Aligned;
+    use wrappers::{Array, Offset};
+
+    let mut buf = [0u8; 16];
+    assert!(matches!(to_slice::<LE, _>(&mut buf, &Crc32Wrapped(1u8)), Err(Error::Unsupported(_))));
+    assert!(matches!(to_slice::<LE, _>(&mut buf, &Offset(1u8)), Err(Error::Unsupported(_))));
+    assert!(matches!(to_slice::<LE, _>(&mut buf, &(1u8, Aligned::<4, _>(2u8))), Err(Error::Unsupported(_))));
+    assert!(matches!(from_slice::<LE, Crc32Wrapped<u8>>(&[1, 0, 0, 0, 0]), Err(Error::Unsupported(_))));
+    assert!(matches!(from_slice::<LE, (u8, Aligned<4, u8>)>(&[1, 0, 0, 0, 2]), Err(Error::Unsupported(_))));
+
+    // Массивы примитивных чисел и массивы байт с длиной обрабатываются так же, как в потоках
+    let value = Array([0x0102u16, 0x0304]);
+    let len = to_slice::<BE, _>(&mut buf, &value).unwrap();
+    assert_eq!(&buf[..len], [0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(from_slice::<BE, Array<u16, 2>>(&buf[..len]).unwrap(), value);
+
+    let blob = PrefixedBytes::<u8>::new(&b"ab"[..]);
+    let len = to_slice::<BE, _>(&mut buf, &blob).unwrap();
+    assert_eq!(&buf[..len], b"\x02ab");
+    assert_eq!(from_slice::<BE, PrefixedBytes<u8>>(&buf[..len]).unwrap(), blob);
+  }
+}
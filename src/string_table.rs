@@ -0,0 +1,187 @@
+//! Содержит таблицу строк, на элементы которой ссылаются поля записей. Такие таблицы
+//! используются во многих форматах (например, таблица меток в GFF или `.strtab` в ELF), чтобы
+//! не хранить одинаковые строки несколько раз и сделать записи фиксированного размера.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+use std::str;
+use byteorder::ByteOrder;
+use serde::de::DeserializeSeed;
+use serde::ser::Serialize;
+
+use de::Deserializer;
+use error::{Error, Result};
+use raw::{Raw, RawBuf};
+use ser::Serializer;
+
+/// Таблица строк, завершающихся нулевым байтом, расположенных в потоке одна за другой.
+/// Поля записей ссылаются на строки таблицы смещением (`u32`) от начала таблицы.
+///
+/// При сериализации строки добавляются в таблицу методом [`intern`], который возвращает
+/// смещение, записываемое в поле вместо строки. Одинаковые строки хранятся в таблице один раз.
+/// Затем таблица целиком записывается в поток методом [`write`].
+///
+/// При десериализации таблица читается из отведенной ей области потока методом [`read`],
+/// а смещения, прочитанные из полей, преобразуются в строки методом [`resolve`].
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::de::Deserializer;
+/// # use serde_pod::ser::Serializer;
+/// # use serde_pod::string_table::StringTable;
+/// # fn main() -> serde_pod::Result<()> {
+/// let mut table = StringTable::new();
+/// assert_eq!(table.intern("Name")?, 0);
+/// assert_eq!(table.intern("Tag")?, 5);
+/// assert_eq!(table.intern("Name")?, 0);
+///
+/// let mut buf = Vec::new();
+/// table.write(&mut Serializer::<LE, _>::new(&mut buf))?;
+/// assert_eq!(buf, b"Name\0Tag\0");
+///
+/// let table = StringTable::read(&mut Deserializer::<LE, _>::new(&buf[..]), buf.len())?;
+/// assert_eq!(table.resolve(5)?, "Tag");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`intern`]: #method.intern
+/// [`write`]: #method.write
+/// [`read`]: #method.read
+/// [`resolve`]: #method.resolve
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StringTable {
+  /// Содержимое таблицы в том виде, в котором оно хранится в потоке
+  data: Vec<u8>,
+  /// Смещения уже добавленных в таблицу строк
+  offsets: HashMap<String, u32>,
+}
+
+impl StringTable {
+  /// Создает пустую таблицу
+  pub fn new() -> Self { Self::default() }
+  /// Создает таблицу из ее содержимого, прочитанного из потока
+  ///
+  /// # Параметры
+  /// - `data`: Строки таблицы, каждая из которых завершается нулевым байтом
+  pub fn from_bytes(data: Vec<u8>) -> Self {
+    StringTable { data, offsets: HashMap::new() }
+  }
+  /// Возвращает содержимое таблицы в том виде, в котором оно хранится в потоке
+  #[inline]
+  pub fn as_bytes(&self) -> &[u8] { &self.data }
+  /// Возвращает размер таблицы в байтах
+  #[inline]
+  pub fn len(&self) -> usize { self.data.len() }
+  /// Возвращает `true`, если в таблице нет ни одной строки
+  #[inline]
+  pub fn is_empty(&self) -> bool { self.data.is_empty() }
+
+  /// Добавляет строку в таблицу, если ее там еще нет, и возвращает ее смещение от начала таблицы
+  ///
+  /// # Ошибки
+  /// - [`Error::Unknown`]: строка содержит нулевой байт и не может быть записана в таблицу
+  /// - [`Error::InvalidLength`]: смещение строки не помещается в `u32`
+  ///
+  /// [`Error::Unknown`]: ../error/enum.Error.html#variant.Unknown
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  pub fn intern(&mut self, value: &str) -> Result<u32> {
+    if let Some(&offset) = self.offsets.get(value) {
+      return Ok(offset);
+    }
+    if value.as_bytes().contains(&0) {
+      return Err(Error::Unknown(format!("string {:?} contains a NUL byte", value)));
+    }
+    let offset = u32::try_from(self.data.len()).map_err(|_| Error::InvalidLength(format!(
+      "string table of {} bytes is too large for u32 offsets", self.data.len()
+    )))?;
+    self.data.extend_from_slice(value.as_bytes());
+    self.data.push(0);
+    self.offsets.insert(value.into(), offset);
+    Ok(offset)
+  }
+  /// Возвращает строку, начинающуюся в таблице по указанному смещению
+  ///
+  /// # Ошибки
+  /// - [`Error::InvalidLength`]: смещение выходит за границы таблицы или строка
+  ///   не завершается нулевым байтом
+  /// - [`Error::Encoding`]: строка не является корректной строкой UTF-8
+  ///
+  /// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+  /// [`Error::Encoding`]: ../error/enum.Error.html#variant.Encoding
+  pub fn resolve(&self, offset: u32) -> Result<&str> {
+    let rest = usize::try_from(offset).ok()
+      .and_then(|offset| self.data.get(offset..))
+      .ok_or_else(|| Error::InvalidLength(format!(
+        "offset {} is outside of string table of {} bytes", offset, self.data.len()
+      )))?;
+    let end = rest.iter().position(|&b| b == 0)
+      .ok_or_else(|| Error::InvalidLength(format!("string at offset {} is not terminated", offset)))?;
+    Ok(str::from_utf8(&rest[..end])?)
+  }
+
+  /// Читает из потока таблицу размером `len` байт
+  ///
+  /// # Параметры
+  /// - `de`: Десериализатор, из которого читается таблица
+  /// - `len`: Размер таблицы в байтах, как правило, прочитанный из заголовка файла
+  pub fn read<BO, R>(de: &mut Deserializer<BO, R>, len: usize) -> Result<Self>
+    where BO: ByteOrder,
+          R: BufRead,
+  {
+    RawBuf(len).deserialize(de).map(Self::from_bytes)
+  }
+  /// Записывает в поток содержимое таблицы
+  ///
+  /// # Параметры
+  /// - `ser`: Сериализатор, в который записывается таблица
+  pub fn write<BO, W>(&self, ser: &mut Serializer<BO, W>) -> Result<()>
+    where BO: ByteOrder,
+          W: Write,
+  {
+    Raw(&self.data).serialize(ser)
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use error::Error;
+  use super::StringTable;
+
+  #[test]
+  fn test_intern() {
+    let mut table = StringTable::new();
+    assert!(table.is_empty());
+    assert_eq!(table.intern("").unwrap(), 0);
+    assert_eq!(table.intern("abc").unwrap(), 1);
+    assert_eq!(table.intern("").unwrap(), 0);
+    assert_eq!(table.as_bytes(), b"\0abc\0");
+    assert_eq!(table.len(), 5);
+
+    assert!(table.intern("a\0b").is_err());
+    assert_eq!(table.len(), 5);
+  }
+  #[test]
+  fn test_resolve() {
+    let table = StringTable::from_bytes(b"Name\0Tag\0bad".to_vec());
+    assert_eq!(table.resolve(0).unwrap(), "Name");
+    assert_eq!(table.resolve(2).unwrap(), "me");
+    assert_eq!(table.resolve(5).unwrap(), "Tag");
+    assert_eq!(table.resolve(8).unwrap(), "");
+
+    match table.resolve(9) {
+      Err(Error::InvalidLength(_)) => {}
+      result => panic!("expected invalid length, but got {:?}", result),
+    }
+    match table.resolve(100) {
+      Err(Error::InvalidLength(_)) => {}
+      result => panic!("expected invalid length, but got {:?}", result),
+    }
+  }
+}
@@ -0,0 +1,832 @@
+//! Содержит типы-обертки над строками, задающие их представление в потоке.
+
+use std::fmt;
+use std::marker::PhantomData;
+use byteorder::ByteOrder;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, SeqAccess, Unexpected, Visitor};
+use serde::ser::{self, Serialize, SerializeTuple, Serializer};
+
+use framing::{checked_len, length_overflow, Length};
+use raw::{capped_capacity, Raw, RawBuf};
+
+/// Строка в кодировке UTF-16 с префиксом длины, аналогичная `BSTR` в Windows.
+///
+/// Сначала в поток записывается количество кодовых единиц UTF-16 (а не байт) в виде числа
+/// типа `L` в порядке байт сериализатора, затем сами кодовые единицы в порядке байт `E`.
+/// Символы вне базовой многоязычной плоскости занимают две кодовые единицы (суррогатную пару).
+///
+/// При десериализации непарные суррогаты считаются ошибкой.
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается количество кодовых единиц
+/// - `E`: порядок байт, в котором записываются кодовые единицы
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf16LenPrefixed<L, E> {
+  /// Обернутая строка
+  value: String,
+  /// Типы длины и порядка байт
+  _marker: PhantomData<(L, E)>,
+}
+
+impl<L, E> Utf16LenPrefixed<L, E> {
+  /// Оборачивает указанную строку
+  #[inline]
+  pub fn new<S: Into<String>>(value: S) -> Self {
+    Utf16LenPrefixed { value: value.into(), _marker: PhantomData }
+  }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn as_str(&self) -> &str { &self.value }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn into_inner(self) -> String { self.value }
+}
+impl<L, E> From<String> for Utf16LenPrefixed<L, E> {
+  #[inline]
+  fn from(value: String) -> Self { Self::new(value) }
+}
+
+impl<L: Length, E: ByteOrder> Serialize for Utf16LenPrefixed<L, E> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let units: Vec<u16> = self.value.encode_utf16().collect();
+    let len = L::from_usize(units.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "string of {} UTF-16 code units is too long for its length prefix", units.len()
+    )))?;
+    let mut bytes = vec![0; units.len() * 2];
+    E::write_u16_into(&units, &mut bytes);
+
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&len)?;
+    tuple.serialize_element(&Raw(&bytes))?;
+    tuple.end()
+  }
+}
+impl<'de, L: Length, E: ByteOrder> Deserialize<'de> for Utf16LenPrefixed<L, E> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(2, Utf16Visitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий длину строки в кодовых единицах UTF-16, а затем сами кодовые единицы
+struct Utf16Visitor<L, E>(PhantomData<(L, E)>);
+impl<'de, L: Length, E: ByteOrder> Visitor<'de> for Utf16Visitor<L, E> {
+  type Value = Utf16LenPrefixed<L, E>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a length-prefixed UTF-16 string")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let len: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let len = checked_len(len)?.checked_mul(2).ok_or_else(|| length_overflow(len.into()))?;
+    let bytes = seq.next_element_seed(RawBuf(len))?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+    let mut units = vec![0; len / 2];
+    E::read_u16_into(&bytes, &mut units);
+    String::from_utf16(&units).map(Utf16LenPrefixed::new).map_err(de::Error::custom)
+  }
+}
+
+/// Строка в кодировке UTF-8 с префиксом длины, как в Pascal. Сначала в поток записывается
+/// количество байт строки в виде числа типа `L` в порядке байт сериализатора, затем сами байты.
+///
+/// При десериализации читается ровно столько байт, сколько указано в префиксе, поэтому строка
+/// может располагаться в середине структуры. Для полей типа [`String`] вместо обертки можно
+/// использовать атрибут `#[serde(with = "serde_pod::pascal_string::<L>")]`, см. [`pascal_string`].
+///
+/// # Параметры типа
+/// - `L`: тип, в котором записывается длина строки в байтах
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`pascal_string`]: struct.pascal_string.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PascalString<L> {
+  /// Обернутая строка
+  value: String,
+  /// Тип длины
+  _marker: PhantomData<L>,
+}
+
+impl<L> PascalString<L> {
+  /// Оборачивает указанную строку
+  #[inline]
+  pub fn new<S: Into<String>>(value: S) -> Self {
+    PascalString { value: value.into(), _marker: PhantomData }
+  }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn as_str(&self) -> &str { &self.value }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn into_inner(self) -> String { self.value }
+}
+impl<L> From<String> for PascalString<L> {
+  #[inline]
+  fn from(value: String) -> Self { Self::new(value) }
+}
+
+impl<L: Length> Serialize for PascalString<L> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    pascal_string::<L>::serialize(&self.value, serializer)
+  }
+}
+impl<'de, L: Length> Deserialize<'de> for PascalString<L> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    pascal_string::<L>::deserialize(deserializer).map(PascalString::new)
+  }
+}
+
+/// Функции сериализации и десериализации строк с префиксом длины, аналогичных [`PascalString`],
+/// для использования в атрибуте `with` полей типа [`String`]. Тип длины задается параметром типа:
+///
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Label {
+///   id: u32,
+///   #[serde(with = "serde_pod::pascal_string::<u16>")]
+///   name: String,
+///   flags: u8,
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [0x00, 0x00, 0x00, 0x01,   0x00, 0x02, 0x4F, 0x4B,   0xFF];
+/// let label = Label { id: 1, name: "OK".into(), flags: 0xFF };
+/// assert_eq!(to_vec::<BE, _>(&label)?, data);
+/// assert_eq!(from_bytes::<BE, Label>(&data)?, label);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`PascalString`]: struct.PascalString.html
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+#[allow(non_camel_case_types)]
+pub struct pascal_string<L>(PhantomData<L>);
+
+impl<L: Length> pascal_string<L> {
+  /// Записывает длину строки в байтах, а затем байты строки
+  pub fn serialize<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let len = L::from_usize(value.len()).ok_or_else(|| ser::Error::custom(format_args!(
+      "string of {} bytes is too long for its length prefix", value.len()
+    )))?;
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&len)?;
+    tuple.serialize_element(&Raw(value.as_bytes()))?;
+    tuple.end()
+  }
+  /// Читает длину строки в байтах, а затем ровно столько байт строки
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    deserializer.deserialize_tuple(2, PascalVisitor::<L>(PhantomData))
+  }
+}
+
+/// Посетитель, читающий длину строки в байтах, а затем сами байты
+struct PascalVisitor<L>(PhantomData<L>);
+impl<'de, L: Length> Visitor<'de> for PascalVisitor<L> {
+  type Value = String;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a length-prefixed UTF-8 string")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let len: L = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let len = checked_len(len)?;
+    let bytes = seq.next_element_seed(RawBuf(len))?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    String::from_utf8(bytes).map_err(de::Error::custom)
+  }
+}
+
+/// Строка из ровно `N` символов, представленная в потоке как массив `[char; N]`: символы
+/// записываются один за другим в кодировке сериализатора (по умолчанию UTF-8, так что каждый
+/// символ занимает от 1 до 4 байт). Длина строки в потоке не записывается.
+///
+/// При сериализации строки, содержащей не ровно `N` символов, возвращается ошибка.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::strings::CharArrayString;
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [0x61, 0xC3, 0xA9, 0x62];
+/// let value = CharArrayString::<3>::new("aéb");
+/// assert_eq!(to_vec::<BE, _>(&value)?, data);
+/// assert_eq!(from_bytes::<BE, CharArrayString<3>>(&data)?, value);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CharArrayString<const N: usize> {
+  /// Обернутая строка
+  value: String,
+}
+
+impl<const N: usize> CharArrayString<N> {
+  /// Оборачивает указанную строку
+  #[inline]
+  pub fn new<S: Into<String>>(value: S) -> Self {
+    CharArrayString { value: value.into() }
+  }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn as_str(&self) -> &str { &self.value }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn into_inner(self) -> String { self.value }
+}
+impl<const N: usize> From<String> for CharArrayString<N> {
+  #[inline]
+  fn from(value: String) -> Self { Self::new(value) }
+}
+
+impl<const N: usize> Serialize for CharArrayString<N> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let count = self.value.chars().count();
+    if count != N {
+      return Err(ser::Error::custom(format_args!(
+        "string of {} chars does not fit into an array of {} chars", count, N
+      )));
+    }
+    let mut tuple = serializer.serialize_tuple(N)?;
+    for ch in self.value.chars() {
+      tuple.serialize_element(&ch)?;
+    }
+    tuple.end()
+  }
+}
+impl<'de, const N: usize> Deserialize<'de> for CharArrayString<N> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(N, CharArrayVisitor::<N>)
+  }
+}
+
+/// Посетитель, читающий `N` символов и объединяющий их в строку
+struct CharArrayVisitor<const N: usize>;
+impl<'de, const N: usize> Visitor<'de> for CharArrayVisitor<N> {
+  type Value = CharArrayString<N>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "an array of {} chars", N)
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut value = String::with_capacity(N);
+    for i in 0..N {
+      let ch: char = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+      value.push(ch);
+    }
+    Ok(CharArrayString::new(value))
+  }
+}
+
+/// Строка в кодировке UTF-8, занимающая в потоке ровно `WIDTH` байт. При сериализации строка,
+/// короче `WIDTH` байт, дополняется нулевыми байтами, а более длинная строка считается ошибкой.
+/// При десериализации нулевые байты в конце строки отбрасываются.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::strings::FixedStr;
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [0x4F, 0x4B, 0x00, 0x00];
+/// let value = FixedStr::<4>::new("OK");
+/// assert_eq!(to_vec::<LE, _>(&value)?, data);
+/// assert_eq!(from_bytes::<LE, FixedStr<4>>(&data)?, value);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedStr<const WIDTH: usize> {
+  /// Обернутая строка
+  value: String,
+}
+
+impl<const WIDTH: usize> FixedStr<WIDTH> {
+  /// Оборачивает указанную строку
+  #[inline]
+  pub fn new<S: Into<String>>(value: S) -> Self {
+    FixedStr { value: value.into() }
+  }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn as_str(&self) -> &str { &self.value }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn into_inner(self) -> String { self.value }
+}
+impl<const WIDTH: usize> From<String> for FixedStr<WIDTH> {
+  #[inline]
+  fn from(value: String) -> Self { Self::new(value) }
+}
+
+impl<const WIDTH: usize> Serialize for FixedStr<WIDTH> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let bytes = self.value.as_bytes();
+    if bytes.len() > WIDTH {
+      return Err(ser::Error::custom(format_args!(
+        "string of {} bytes does not fit into {} bytes", bytes.len(), WIDTH
+      )));
+    }
+    let mut buf = vec![0; WIDTH];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    Raw(&buf).serialize(serializer)
+  }
+}
+impl<'de, const WIDTH: usize> Deserialize<'de> for FixedStr<WIDTH> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let mut bytes = RawBuf(WIDTH).deserialize(deserializer)?;
+    let len = bytes.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    bytes.truncate(len);
+    String::from_utf8(bytes).map(FixedStr::new).map_err(de::Error::custom)
+  }
+}
+
+/// Таблица из `COUNT` строк, каждая из которых занимает в потоке ровно `WIDTH` байт и записывается,
+/// как [`FixedStr<WIDTH>`]. Такие таблицы часто используются для списков имен фиксированного размера.
+///
+/// При сериализации таблицы, содержащей меньше `COUNT` строк, недостающие строки записываются
+/// пустыми (из нулевых байт), а таблица из большего количества строк считается ошибкой.
+/// При десериализации всегда читается ровно `COUNT` строк.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::strings::FixedStrArray;
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [0x41, 0x42, 0x00,   0x43, 0x00, 0x00];
+/// let names = FixedStrArray::<2, 3>::new(vec!["AB".into(), "C".into()]);
+/// assert_eq!(to_vec::<LE, _>(&names)?, data);
+/// assert_eq!(from_bytes::<LE, FixedStrArray<2, 3>>(&data)?.as_slice(), ["AB", "C"]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`FixedStr<WIDTH>`]: struct.FixedStr.html
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedStrArray<const COUNT: usize, const WIDTH: usize> {
+  /// Обернутые строки
+  value: Vec<String>,
+}
+
+impl<const COUNT: usize, const WIDTH: usize> FixedStrArray<COUNT, WIDTH> {
+  /// Оборачивает указанные строки
+  #[inline]
+  pub fn new(value: Vec<String>) -> Self {
+    FixedStrArray { value }
+  }
+  /// Возвращает обернутые строки
+  #[inline]
+  pub fn as_slice(&self) -> &[String] { &self.value }
+  /// Возвращает обернутые строки
+  #[inline]
+  pub fn into_inner(self) -> Vec<String> { self.value }
+}
+impl<const COUNT: usize, const WIDTH: usize> From<Vec<String>> for FixedStrArray<COUNT, WIDTH> {
+  #[inline]
+  fn from(value: Vec<String>) -> Self { Self::new(value) }
+}
+
+impl<const COUNT: usize, const WIDTH: usize> Serialize for FixedStrArray<COUNT, WIDTH> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    if self.value.len() > COUNT {
+      return Err(ser::Error::custom(format_args!(
+        "{} strings do not fit into a table of {} strings", self.value.len(), COUNT
+      )));
+    }
+    let mut tuple = serializer.serialize_tuple(COUNT)?;
+    for i in 0..COUNT {
+      let value = self.value.get(i).map_or("", String::as_str);
+      tuple.serialize_element(&FixedStr::<WIDTH>::new(value))?;
+    }
+    tuple.end()
+  }
+}
+impl<'de, const COUNT: usize, const WIDTH: usize> Deserialize<'de> for FixedStrArray<COUNT, WIDTH> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_tuple(COUNT, FixedStrArrayVisitor::<COUNT, WIDTH>)
+  }
+}
+
+/// Посетитель, читающий `COUNT` строк шириной `WIDTH` байт
+struct FixedStrArrayVisitor<const COUNT: usize, const WIDTH: usize>;
+impl<'de, const COUNT: usize, const WIDTH: usize> Visitor<'de> for FixedStrArrayVisitor<COUNT, WIDTH> {
+  type Value = FixedStrArray<COUNT, WIDTH>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "{} strings of {} bytes", COUNT, WIDTH)
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut value = Vec::with_capacity(capped_capacity(COUNT));
+    for i in 0..COUNT {
+      let s: FixedStr<WIDTH> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+      value.push(s.into_inner());
+    }
+    Ok(FixedStrArray::new(value))
+  }
+}
+
+/// Строка в кодировке UTF-8, завершающаяся нулевым байтом, как строки языка C. Длина строки
+/// в потоке не записывается: при десериализации байты читаются до первого нулевого байта, который
+/// также извлекается из потока, но не включается в строку.
+///
+/// При сериализации строки, содержащей нулевой байт, возвращается ошибка, так как такая строка
+/// не может быть прочитана обратно. Если поток закончился раньше, чем был прочитан нулевой байт,
+/// десериализация завершается ошибкой.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::strings::NulString;
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [0x4F, 0x4B, 0x00];
+/// let value = NulString::new("OK");
+/// assert_eq!(to_vec::<LE, _>(&value)?, data);
+/// assert_eq!(from_bytes::<LE, NulString>(&data)?, value);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NulString {
+  /// Обернутая строка
+  value: String,
+}
+
+impl NulString {
+  /// Оборачивает указанную строку
+  #[inline]
+  pub fn new<S: Into<String>>(value: S) -> Self {
+    NulString { value: value.into() }
+  }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn as_str(&self) -> &str { &self.value }
+  /// Возвращает обернутую строку
+  #[inline]
+  pub fn into_inner(self) -> String { self.value }
+}
+impl From<String> for NulString {
+  #[inline]
+  fn from(value: String) -> Self { Self::new(value) }
+}
+
+impl Serialize for NulString {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    if let Some(pos) = self.value.bytes().position(|b| b == 0) {
+      return Err(ser::Error::custom(format_args!(
+        "string contains a NUL byte at position {}", pos
+      )));
+    }
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&Raw(self.value.as_bytes()))?;
+    tuple.serialize_element(&0u8)?;
+    tuple.end()
+  }
+}
+impl<'de> Deserialize<'de> for NulString {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    // Длина строки неизвестна, поэтому байты читаются по одному до нулевого
+    deserializer.deserialize_tuple(usize::MAX, NulVisitor)
+  }
+}
+
+/// Посетитель, читающий байты до нулевого и объединяющий их в строку
+struct NulVisitor;
+impl<'de> Visitor<'de> for NulVisitor {
+  type Value = NulString;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a NUL-terminated string")
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut bytes = Vec::new();
+    loop {
+      let byte: u8 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(bytes.len(), &self))?;
+      if byte == 0 {
+        break;
+      }
+      bytes.push(byte);
+    }
+    String::from_utf8(bytes).map(NulString::new).map_err(de::Error::custom)
+  }
+}
+
+/// Символ, записываемый как его код UTF-32 -- число типа `u32` в порядке байт сериализатора,
+/// в отличие от `char`, который записывается в переменном количестве байт в UTF-8. При
+/// десериализации число, не являющееся кодом символа Unicode (например, суррогат UTF-16 или
+/// значение больше `0x10FFFF`), считается ошибкой.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::strings::Utf32Char;
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = [0x36, 0x04, 0x00, 0x00];
+/// assert_eq!(to_vec::<LE, _>(&Utf32Char('ж'))?, data);
+/// assert_eq!(from_bytes::<LE, Utf32Char>(&data)?, Utf32Char('ж'));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf32Char(pub char);
+
+impl From<char> for Utf32Char {
+  #[inline]
+  fn from(value: char) -> Self { Utf32Char(value) }
+}
+impl Serialize for Utf32Char {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    u32::from(self.0).serialize(serializer)
+  }
+}
+impl<'de> Deserialize<'de> for Utf32Char {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let code = u32::deserialize(deserializer)?;
+    char::from_u32(code).map(Utf32Char).ok_or_else(|| de::Error::invalid_value(
+      Unexpected::Unsigned(code.into()), &"a Unicode scalar value"
+    ))
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod utf16 {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::Utf16LenPrefixed;
+
+  /// Символ вне базовой плоскости занимает 2 кодовые единицы, длина считается в кодовых единицах.
+  /// Порядок байт длины и кодовых единиц задается независимо
+  #[test]
+  fn test_astral() {
+    let test = Utf16LenPrefixed::<u16, LE>::new("a\u{1D11E}");
+    let be = [0x00, 0x03,   0x61, 0x00,   0x34, 0xD8, 0x1E, 0xDD];
+    let le = [0x03, 0x00,   0x61, 0x00,   0x34, 0xD8, 0x1E, 0xDD];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Utf16LenPrefixed<u16, LE>>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Utf16LenPrefixed<u16, LE>>(&le).unwrap(), test);
+
+    let test = Utf16LenPrefixed::<u32, BE>::new("a\u{1D11E}");
+    let be = [0x00, 0x00, 0x00, 0x03,   0x00, 0x61,   0xD8, 0x34, 0xDD, 0x1E];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(from_bytes::<BE, Utf16LenPrefixed<u32, BE>>(&be).unwrap(), test);
+  }
+  #[test]
+  fn test_empty() {
+    let test = Utf16LenPrefixed::<u8, BE>::new("");
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x00]);
+    assert_eq!(from_bytes::<BE, Utf16LenPrefixed<u8, BE>>(&[0x00]).unwrap(), test);
+  }
+  /// Непарный суррогат не является корректной строкой
+  #[test]
+  fn test_lone_surrogate() {
+    assert!(from_bytes::<BE, Utf16LenPrefixed<u8, BE>>(&[0x02,   0xD8, 0x34, 0x00, 0x61]).is_err());
+    assert!(from_bytes::<BE, Utf16LenPrefixed<u8, BE>>(&[0x01,   0xDD, 0x1E]).is_err());
+  }
+  /// Строка длиннее, чем может быть записано в префиксе, не сериализуется
+  #[test]
+  fn test_too_long() {
+    let test = Utf16LenPrefixed::<u8, BE>::new("a".repeat(256));
+    assert!(to_vec::<BE,_>(&test).is_err());
+  }
+  #[test]
+  #[should_panic]
+  fn test_no_data() {
+    from_bytes::<BE, Utf16LenPrefixed<u8, BE>>(&[0x02,   0x00, 0x61, 0x00]).unwrap();
+  }
+}
+
+#[cfg(test)]
+mod pascal {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::PascalString;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Test {
+    id: u32,
+    #[serde(with = "::strings::pascal_string::<u16>")]
+    name: String,
+    flags: u8,
+  }
+
+  /// Строка с префиксом длины в середине структуры не мешает чтению следующих полей
+  #[test]
+  fn test_struct() {
+    let test = Test { id: 0x12345678, name: "Имя".into(), flags: 0xAB };
+    let be = [0x12, 0x34, 0x56, 0x78,   0x00, 0x06,   0xD0, 0x98, 0xD0, 0xBC, 0xD1, 0x8F,   0xAB];
+    let le = [0x78, 0x56, 0x34, 0x12,   0x06, 0x00,   0xD0, 0x98, 0xD0, 0xBC, 0xD1, 0x8F,   0xAB];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Test>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Test>(&le).unwrap(), test);
+  }
+  #[test]
+  fn test_wrapper() {
+    let test = PascalString::<u8>::new("abc");
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x03,   0x61, 0x62, 0x63]);
+    assert_eq!(from_bytes::<BE, PascalString<u8>>(&[0x03,   0x61, 0x62, 0x63]).unwrap(), test);
+  }
+  #[test]
+  fn test_too_long() {
+    assert!(to_vec::<BE,_>(&PascalString::<u8>::new("a".repeat(256))).is_err());
+  }
+  #[test]
+  fn test_invalid_utf8() {
+    assert!(from_bytes::<BE, PascalString<u8>>(&[0x01,   0xFF]).is_err());
+  }
+  #[test]
+  #[should_panic]
+  fn test_no_data() {
+    from_bytes::<BE, Test>(&[0x12, 0x34, 0x56, 0x78,   0x00, 0x06,   0xD0, 0x98]).unwrap();
+  }
+}
+
+#[cfg(test)]
+mod char_array {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::CharArrayString;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Test {
+    code: CharArrayString<4>,
+    flags: u8,
+  }
+
+  /// Многобайтовый символ не мешает чтению следующих полей
+  #[test]
+  fn test_multibyte() {
+    let test = Test { code: CharArrayString::new("ab€c"), flags: 0xAB };
+    let data = [0x61, 0x62,   0xE2, 0x82, 0xAC,   0x63,   0xAB];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), data);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), data);
+    assert_eq!(from_bytes::<BE, Test>(&data).unwrap(), test);
+    assert_eq!(test.code.as_str(), "ab€c");
+  }
+  #[test]
+  fn test_wrong_length() {
+    assert!(to_vec::<BE,_>(&CharArrayString::<4>::new("abc")).is_err());
+    assert!(to_vec::<BE,_>(&CharArrayString::<4>::new("abcde")).is_err());
+  }
+  #[test]
+  fn test_no_data() {
+    assert!(from_bytes::<BE, CharArrayString<4>>(&[0x61, 0x62, 0xE2, 0x82]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod fixed {
+  use byteorder::BE;
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::{FixedStr, FixedStrArray};
+
+  /// Одно имя занимает всю ячейку, другое короче нее, третья ячейка пустая
+  #[test]
+  fn test_array() {
+    let names = FixedStrArray::<3, 8>::new(vec!["Aribeth".into(), "Nasher Al".into()]);
+    assert!(to_vec::<BE, _>(&names).is_err());
+
+    let names = FixedStrArray::<3, 8>::new(vec!["Aribeth!".into(), "Nasher".into()]);
+    let data = [
+      b'A', b'r', b'i', b'b', b'e', b't', b'h', b'!',
+      b'N', b'a', b's', b'h', b'e', b'r', 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(to_vec::<BE, _>(&names).unwrap(), data);
+    assert_eq!(from_bytes::<BE, FixedStrArray<3, 8>>(&data).unwrap().into_inner(), ["Aribeth!", "Nasher", ""]);
+  }
+  #[test]
+  fn test_too_many() {
+    let names = FixedStrArray::<1, 8>::new(vec!["a".into(), "b".into()]);
+    assert!(to_vec::<BE, _>(&names).is_err());
+  }
+  /// Метки в стиле секции меток GFF: 16 байт на метку
+  #[test]
+  fn test_exact_fit() {
+    let value = FixedStr::<16>::new("ArmorClassBonus!");
+    let data = b"ArmorClassBonus!";
+    assert_eq!(to_vec::<BE, _>(&value).unwrap(), data);
+    assert_eq!(from_bytes::<BE, FixedStr<16>>(data).unwrap(), value);
+  }
+  #[test]
+  fn test_padded() {
+    let value = FixedStr::<16>::new("Tag");
+    let data = b"Tag\0\0\0\0\0\0\0\0\0\0\0\0\0";
+    assert_eq!(to_vec::<BE, _>(&value).unwrap(), data);
+    assert_eq!(from_bytes::<BE, FixedStr<16>>(data).unwrap(), value);
+  }
+  #[test]
+  fn test_too_long() {
+    assert!(to_vec::<BE, _>(&FixedStr::<16>::new("TemplateResRef_1x")).is_err());
+    // Длина проверяется в байтах, а не в символах
+    assert!(to_vec::<BE, _>(&FixedStr::<2>::new("é!")).is_err());
+  }
+  #[test]
+  fn test_no_data() {
+    assert!(from_bytes::<BE, FixedStr<16>>(b"Tag").is_err());
+  }
+  /// Отбрасываются только нули в конце строки
+  #[test]
+  fn test_inner_nul() {
+    let value = from_bytes::<BE, FixedStr<4>>(&[b'a', 0x00, b'b', 0x00]).unwrap();
+    assert_eq!(value.as_str(), "a\0b");
+  }
+  #[test]
+  fn test_invalid_utf8() {
+    assert!(from_bytes::<BE, FixedStr<2>>(&[0xFF, 0x00]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod nul {
+  use byteorder::BE;
+  use de::{from_bytes, Deserializer};
+  use ser::to_vec;
+  use serde::Deserialize;
+  use super::NulString;
+
+  #[test]
+  fn test_empty() {
+    assert_eq!(to_vec::<BE, _>(&NulString::default()).unwrap(), [0x00]);
+    assert_eq!(from_bytes::<BE, NulString>(&[0x00]).unwrap().as_str(), "");
+  }
+  #[test]
+  fn test_multibyte() {
+    let data = [0x4B, 0x61, 0x65, 0x72, 0x20, 0x4D, 0x6F, 0x72, 0x68, 0x65, 0x6E, 0x20, 0xE2, 0x80, 0x94, 0x20, 0xF0, 0x9F, 0x97, 0xA1, 0x00];
+    let value = NulString::new("Kaer Morhen — 🗡");
+    assert_eq!(to_vec::<BE, _>(&value).unwrap(), &data[..]);
+    assert_eq!(from_bytes::<BE, NulString>(&data).unwrap(), value);
+  }
+  /// Чтение останавливается на нулевом байте, а не продолжается до конца потока
+  #[test]
+  fn test_stops_at_nul() {
+    let data: &[u8] = &[0x61, 0x00,   0x62, 0x63, 0x00,   0xFF];
+    let mut de: Deserializer<BE, _> = Deserializer::new(data);
+    assert_eq!(NulString::deserialize(&mut de).unwrap().as_str(), "a");
+    assert_eq!(NulString::deserialize(&mut de).unwrap().as_str(), "bc");
+    assert_eq!(u8::deserialize(&mut de).unwrap(), 0xFF);
+  }
+  #[test]
+  fn test_no_terminator() {
+    assert!(from_bytes::<BE, NulString>(&[0x61, 0x62]).is_err());
+    assert!(from_bytes::<BE, NulString>(&[]).is_err());
+  }
+  #[test]
+  fn test_embedded_nul() {
+    assert!(to_vec::<BE, _>(&NulString::new("a\0b")).is_err());
+  }
+  #[test]
+  fn test_invalid_utf8() {
+    assert!(from_bytes::<BE, NulString>(&[0xFF, 0x00]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod utf32 {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::Utf32Char;
+
+  #[test]
+  fn test_roundtrip() {
+    for &(c, be) in &[('A', [0x00, 0x00, 0x00, 0x41]), ('ж', [0x00, 0x00, 0x04, 0x36]), ('🗡', [0x00, 0x01, 0xF5, 0xE1])] {
+      let mut le = be;
+      le.reverse();
+      assert_eq!(to_vec::<BE, _>(&Utf32Char(c)).unwrap(), be);
+      assert_eq!(to_vec::<LE, _>(&Utf32Char(c)).unwrap(), le);
+      assert_eq!(from_bytes::<BE, Utf32Char>(&be).unwrap(), Utf32Char(c));
+      assert_eq!(from_bytes::<LE, Utf32Char>(&le).unwrap(), Utf32Char(c));
+    }
+  }
+  #[test]
+  fn test_invalid() {
+    // Суррогат UTF-16
+    assert!(from_bytes::<BE, Utf32Char>(&[0x00, 0x00, 0xD8, 0x00]).is_err());
+    // Больше максимального кода символа
+    assert!(from_bytes::<BE, Utf32Char>(&[0x00, 0x11, 0x00, 0x00]).is_err());
+  }
+}
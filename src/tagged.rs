@@ -0,0 +1,242 @@
+//! Содержит функции для чтения и записи потоков полей с тегами, аналогичных Protocol Buffers.
+//!
+//! Каждому полю предшествует тег -- число в кодировке LEB128 (см. модуль [`varint`]), в
+//! старших битах которого хранится номер поля, а в младших 3-х битах -- [тип представления]
+//! значения поля. Тип представления позволяет пропустить поле, не зная его типа, поэтому
+//! неизвестные поля можно игнорировать. Поля могут повторяться и следуют до конца потока.
+//!
+//! Фиксированные 32-х и 64-х битовые значения читаются и записываются в порядке байт
+//! сериализатора, что позволяет использовать формат с любым порядком байт.
+//!
+//! # Пример
+//! ```rust
+//! # extern crate byteorder;
+//! # extern crate serde_pod;
+//! # use byteorder::LE;
+//! # use serde_pod::de::Deserializer;
+//! # use serde_pod::ser::Serializer;
+//! # use serde_pod::tagged::{read_fields, write_tag, WireType};
+//! # use serde_pod::varint::{read_varint, write_varint};
+//! # fn main() -> serde_pod::Result<()> {
+//! let mut buf = Vec::new();
+//! {
+//!   let mut ser = Serializer::<LE, _>::new(&mut buf);
+//!   write_tag(&mut ser, 1, WireType::Varint)?;
+//!   write_varint(&mut ser, 150)?;
+//! }
+//! assert_eq!(buf, [0x08, 0x96, 0x01]);
+//!
+//! let mut id = None;
+//! read_fields(&mut Deserializer::<LE, _>::new(&buf[..]), |de, tag| {
+//!   match tag.field {
+//!     1 => { id = Some(read_varint(de)?); Ok(true) }
+//!     _ => Ok(false),
+//!   }
+//! })?;
+//! assert_eq!(id, Some(150));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`varint`]: ../varint/index.html
+//! [тип представления]: enum.WireType.html
+
+use std::convert::TryFrom;
+use std::io::{BufRead, Write};
+use byteorder::ByteOrder;
+use serde::Deserialize;
+use serde::de::DeserializeSeed;
+
+use de::Deserializer;
+use error::{Error, Result};
+use raw::RawBuf;
+use ser::Serializer;
+use varint::{read_varint, write_varint};
+
+/// Тип представления значения поля, определяющий, сколько байт оно занимает в потоке
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WireType {
+  /// Число в кодировке LEB128
+  Varint,
+  /// 8 байт, например, `u64` или `f64`
+  Fixed64,
+  /// Число в кодировке LEB128, задающее длину, и затем указанное количество байт
+  LengthDelimited,
+  /// 4 байта, например, `u32` или `f32`
+  Fixed32,
+}
+
+impl WireType {
+  /// Возвращает тип представления, записываемый в младшие 3 бита тега
+  #[inline]
+  pub fn to_bits(self) -> u8 {
+    match self {
+      WireType::Varint => 0,
+      WireType::Fixed64 => 1,
+      WireType::LengthDelimited => 2,
+      WireType::Fixed32 => 5,
+    }
+  }
+  /// Возвращает тип представления по младшим 3-м битам тега или `None`, если он не поддерживается
+  #[inline]
+  pub fn from_bits(bits: u8) -> Option<Self> {
+    match bits {
+      0 => Some(WireType::Varint),
+      1 => Some(WireType::Fixed64),
+      2 => Some(WireType::LengthDelimited),
+      5 => Some(WireType::Fixed32),
+      _ => None,
+    }
+  }
+}
+
+/// Тег поля: его номер и тип представления значения
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Tag {
+  /// Номер поля
+  pub field: u64,
+  /// Тип представления значения поля
+  pub wire: WireType,
+}
+
+/// Записывает тег поля `(field << 3) | wire`
+///
+/// # Ошибки
+/// [`Error::InvalidLength`]: номер поля не помещается в тег
+///
+/// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+pub fn write_tag<BO, W>(ser: &mut Serializer<BO, W>, field: u64, wire: WireType) -> Result<()>
+  where BO: ByteOrder,
+        W: Write,
+{
+  if field >> 61 != 0 {
+    return Err(Error::InvalidLength(format!("field number {} does not fit into a tag", field)));
+  }
+  write_varint(ser, field << 3 | u64::from(wire.to_bits()))
+}
+
+/// Читает тег поля. Возвращает `None`, если поток закончился перед тегом
+///
+/// # Ошибки
+/// - [`Error::Unsupported`]: тип представления не поддерживается
+/// - ошибки чтения числа в кодировке LEB128
+///
+/// [`Error::Unsupported`]: ../error/enum.Error.html#variant.Unsupported
+pub fn read_tag<BO, R>(de: &mut Deserializer<BO, R>) -> Result<Option<Tag>>
+  where BO: ByteOrder,
+        R: BufRead,
+{
+  if de.is_eof()? {
+    return Ok(None);
+  }
+  let tag = read_varint(de)?;
+  let wire = WireType::from_bits((tag & 7) as u8).ok_or(Error::Unsupported("unknown wire type"))?;
+  Ok(Some(Tag { field: tag >> 3, wire }))
+}
+
+/// Пропускает значение поля с указанным типом представления
+pub fn skip_field<BO, R>(de: &mut Deserializer<BO, R>, wire: WireType) -> Result<()>
+  where BO: ByteOrder,
+        R: BufRead,
+{
+  match wire {
+    WireType::Varint => read_varint(de).map(drop),
+    WireType::Fixed64 => u64::deserialize(de).map(drop),
+    WireType::Fixed32 => u32::deserialize(de).map(drop),
+    WireType::LengthDelimited => {
+      let len = read_varint(de)?;
+      let len = usize::try_from(len).map_err(|_| Error::Overflow(len))?;
+      RawBuf(len).deserialize(de).map(drop)
+    }
+  }
+}
+
+/// Читает поля до конца потока, передавая тег каждого из них в функцию `f`. Функция должна
+/// прочитать значение поля и вернуть `true`, либо, если поле ей неизвестно, ничего не читать и
+/// вернуть `false` -- тогда значение поля будет пропущено.
+///
+/// # Ошибки
+/// Ошибка чтения тега, пропуска поля или первая ошибка, возвращенная функцией `f`
+pub fn read_fields<BO, R, F>(de: &mut Deserializer<BO, R>, mut f: F) -> Result<()>
+  where BO: ByteOrder,
+        R: BufRead,
+        F: FnMut(&mut Deserializer<BO, R>, Tag) -> Result<bool>,
+{
+  while let Some(tag) = read_tag(de)? {
+    if !f(de, tag)? {
+      skip_field(de, tag.wire)?;
+    }
+  }
+  Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use serde::{Deserialize, Serialize};
+  use de::Deserializer;
+  use ser::Serializer;
+  use varint::{read_varint, write_varint};
+  use super::{read_fields, read_tag, write_tag, Tag, WireType};
+
+  /// Два поля: число и строка
+  #[test]
+  fn test_two_fields() {
+    let mut buf = Vec::new();
+    {
+      let mut ser = Serializer::<LE, _>::new(&mut buf);
+      write_tag(&mut ser, 1, WireType::Varint).unwrap();
+      write_varint(&mut ser, 150).unwrap();
+      write_tag(&mut ser, 2, WireType::LengthDelimited).unwrap();
+      write_varint(&mut ser, 3).unwrap();
+      b"abc".serialize(&mut ser).unwrap();
+    }
+    assert_eq!(buf, [0x08, 0x96, 0x01,   0x12, 0x03, b'a', b'b', b'c']);
+
+    let mut id = None;
+    let mut name = None;
+    read_fields(&mut Deserializer::<LE, _>::new(&buf[..]), |de, tag| {
+      match (tag.field, tag.wire) {
+        (1, WireType::Varint) => id = Some(read_varint(de)?),
+        (2, WireType::LengthDelimited) => {
+          let len = read_varint(de)?;
+          assert_eq!(len, 3);
+          name = Some(<[u8; 3]>::deserialize(de)?);
+        }
+        _ => return Ok(false),
+      }
+      Ok(true)
+    }).unwrap();
+    assert_eq!(id, Some(150));
+    assert_eq!(name, Some(*b"abc"));
+  }
+  /// Неизвестные поля пропускаются, фиксированные поля записываются в порядке байт сериализатора
+  #[test]
+  fn test_skip_unknown() {
+    let data = [
+      0x09,   0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+      0x15,   0x12, 0x34, 0x56, 0x78,
+      0x1A, 0x02, 0xFF, 0xFF,
+      0x20, 0xFF, 0x01,
+      0x2D,   0x00, 0x00, 0x00, 0x2A,
+    ];
+    let mut fields = Vec::new();
+    read_fields(&mut Deserializer::<BE, _>::new(&data[..]), |de, tag| {
+      if tag.field == 5 {
+        fields.push(u32::deserialize(de)?);
+        return Ok(true);
+      }
+      Ok(false)
+    }).unwrap();
+    assert_eq!(fields, [42]);
+  }
+  #[test]
+  fn test_read_tag() {
+    let mut de = Deserializer::<BE, _>::new(&[0x90, 0x01,   0x0B][..]);
+    assert_eq!(read_tag(&mut de).unwrap(), Some(Tag { field: 18, wire: WireType::Varint }));
+    assert!(read_tag(&mut de).is_err());
+    assert_eq!(read_tag(&mut Deserializer::<BE, _>::new(&[][..])).unwrap(), None);
+  }
+}
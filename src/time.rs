@@ -0,0 +1,212 @@
+//! Содержит типы-обертки для длительностей и меток времени, записываемых в поток, как целое
+//! число секунд, миллисекунд, микросекунд или наносекунд в порядке байт сериализатора.
+//!
+//! При записи дробная часть длительности, не представимая в единицах типа, отбрасывается,
+//! а значение, не помещающееся в число, приводит к ошибке.
+//!
+//! # Пример
+//! ```rust
+//! # extern crate byteorder;
+//! # #[macro_use]
+//! # extern crate serde_derive;
+//! # extern crate serde_pod;
+//! # use std::time::Duration;
+//! # use byteorder::LE;
+//! # use serde_pod::{from_bytes, to_vec};
+//! # use serde_pod::time::{Millis64, Seconds32};
+//! #[derive(Debug, Deserialize, PartialEq, Serialize)]
+//! struct Timeouts {
+//!   idle: Seconds32,
+//!   retry: Millis64,
+//! }
+//!
+//! # fn main() -> serde_pod::Result<()> {
+//! let timeouts = Timeouts {
+//!   idle: Seconds32(Duration::from_secs(300)),
+//!   retry: Millis64(Duration::from_millis(1500)),
+//! };
+//! let data = [0x2C, 0x01, 0x00, 0x00,   0xDC, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+//! assert_eq!(to_vec::<LE, _>(&timeouts)?, data);
+//! assert_eq!(from_bytes::<LE, Timeouts>(&data)?, timeouts);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{self, Serialize, Serializer};
+
+/// Объявляет обертку над `Duration`, записываемую, как целое число указанных единиц
+macro_rules! duration {
+  ($(#[$meta:meta])* $name:ident: $type:ty, $unit:literal, $as:ident, $from:ident) => {
+    $(#[$meta])*
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct $name(pub Duration);
+
+    impl From<Duration> for $name {
+      #[inline]
+      fn from(value: Duration) -> Self { $name(value) }
+    }
+    impl From<$name> for Duration {
+      #[inline]
+      fn from(value: $name) -> Self { value.0 }
+    }
+
+    impl Serialize for $name {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = <$type>::try_from(self.0.$as()).map_err(|_| ser::Error::custom(format_args!(
+          "duration {:?} does not fit into {} {}", self.0, stringify!($type), $unit
+        )))?;
+        value.serialize(serializer)
+      }
+    }
+    impl<'de> Deserialize<'de> for $name {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <$type>::deserialize(deserializer).map(|value| $name(Duration::$from(value.into())))
+      }
+    }
+  };
+}
+
+duration!(
+  /// Длительность, записываемая, как `u32` целых секунд
+  Seconds32: u32, "seconds", as_secs, from_secs
+);
+duration!(
+  /// Длительность, записываемая, как `u64` целых секунд
+  Seconds64: u64, "seconds", as_secs, from_secs
+);
+duration!(
+  /// Длительность, записываемая, как `u32` целых миллисекунд
+  Millis32: u32, "milliseconds", as_millis, from_millis
+);
+duration!(
+  /// Длительность, записываемая, как `u64` целых миллисекунд
+  Millis64: u64, "milliseconds", as_millis, from_millis
+);
+duration!(
+  /// Длительность, записываемая, как `u64` целых микросекунд
+  Micros64: u64, "microseconds", as_micros, from_micros
+);
+duration!(
+  /// Длительность, записываемая, как `u64` наносекунд
+  Nanos64: u64, "nanoseconds", as_nanos, from_nanos
+);
+
+/// Объявляет обертку над `SystemTime`, записываемую, как целое число секунд от начала эпохи Unix
+macro_rules! timestamp {
+  ($(#[$meta:meta])* $name:ident: $type:ty) => {
+    $(#[$meta])*
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct $name(pub SystemTime);
+
+    impl From<SystemTime> for $name {
+      #[inline]
+      fn from(value: SystemTime) -> Self { $name(value) }
+    }
+    impl From<$name> for SystemTime {
+      #[inline]
+      fn from(value: $name) -> Self { value.0 }
+    }
+
+    impl Serialize for $name {
+      fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let since = self.0.duration_since(UNIX_EPOCH).map_err(|_| ser::Error::custom(format_args!(
+          "time {:?} is before the Unix epoch", self.0
+        )))?;
+        let value = <$type>::try_from(since.as_secs()).map_err(|_| ser::Error::custom(format_args!(
+          "time {:?} does not fit into {} seconds since the Unix epoch", self.0, stringify!($type)
+        )))?;
+        value.serialize(serializer)
+      }
+    }
+    impl<'de> Deserialize<'de> for $name {
+      fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <$type>::deserialize(deserializer)?;
+        UNIX_EPOCH.checked_add(Duration::from_secs(value.into()))
+          .map($name)
+          .ok_or_else(|| de::Error::custom(format_args!(
+            "{} seconds since the Unix epoch is out of the supported time range", value
+          )))
+      }
+    }
+  };
+}
+
+timestamp!(
+  /// Момент времени, записываемый, как `u32` целых секунд от начала эпохи Unix (`time_t`
+  /// в 32-х битных форматах). Моменты до начала эпохи и после 2106 года не могут быть записаны
+  UnixSeconds32: u32
+);
+timestamp!(
+  /// Момент времени, записываемый, как `u64` целых секунд от начала эпохи Unix.
+  /// Моменты до начала эпохи не могут быть записаны
+  UnixSeconds64: u64
+);
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use std::time::{Duration, UNIX_EPOCH};
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::{Micros64, Millis32, Millis64, Nanos64, Seconds32, Seconds64, UnixSeconds32, UnixSeconds64};
+
+  #[test]
+  fn test_seconds() {
+    let test = Seconds32(Duration::from_secs(0x0102_0304));
+    assert_eq!(to_vec::<BE, _>(&test).unwrap(), [0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(to_vec::<LE, _>(&test).unwrap(), [0x04, 0x03, 0x02, 0x01]);
+    assert_eq!(from_bytes::<LE, Seconds32>(&[0x04, 0x03, 0x02, 0x01]).unwrap(), test);
+
+    let test = Seconds64(Duration::from_secs(u64::MAX));
+    assert_eq!(from_bytes::<BE, Seconds64>(&to_vec::<BE, _>(&test).unwrap()).unwrap(), test);
+  }
+  #[test]
+  fn test_millis() {
+    let test = Millis64(Duration::from_millis(1500));
+    assert_eq!(to_vec::<BE, _>(&test).unwrap(), [0, 0, 0, 0, 0, 0, 0x05, 0xDC]);
+    assert_eq!(from_bytes::<BE, Millis64>(&[0, 0, 0, 0, 0, 0, 0x05, 0xDC]).unwrap(), test);
+
+    let test = Millis32(Duration::from_millis(u32::MAX.into()));
+    assert_eq!(to_vec::<LE, _>(&test).unwrap(), [0xFF; 4]);
+    assert_eq!(from_bytes::<LE, Millis32>(&[0xFF; 4]).unwrap(), test);
+  }
+  #[test]
+  fn test_micros_nanos() {
+    let test = Micros64(Duration::new(3, 250_000));
+    assert_eq!(from_bytes::<LE, Micros64>(&to_vec::<LE, _>(&test).unwrap()).unwrap(), test);
+    let test = Nanos64(Duration::new(3, 1));
+    assert_eq!(to_vec::<BE, _>(&test).unwrap(), 3_000_000_001u64.to_be_bytes());
+    assert_eq!(from_bytes::<BE, Nanos64>(&3_000_000_001u64.to_be_bytes()).unwrap(), test);
+  }
+  /// Дробная часть, не представимая в единицах типа, отбрасывается
+  #[test]
+  fn test_truncate() {
+    assert_eq!(to_vec::<BE, _>(&Seconds32(Duration::from_millis(2999))).unwrap(), [0, 0, 0, 2]);
+    assert_eq!(to_vec::<BE, _>(&Millis32(Duration::from_micros(1999))).unwrap(), [0, 0, 0, 1]);
+  }
+  #[test]
+  fn test_overflow() {
+    assert!(to_vec::<BE, _>(&Seconds32(Duration::from_secs(u64::from(u32::MAX) + 1))).is_err());
+    assert!(to_vec::<BE, _>(&Millis32(Duration::from_secs(50 * 24 * 60 * 60))).is_err());
+    assert!(to_vec::<BE, _>(&Millis64(Duration::MAX)).is_err());
+    assert!(to_vec::<BE, _>(&Nanos64(Duration::from_secs(600 * 365 * 24 * 60 * 60))).is_err());
+  }
+  #[test]
+  fn test_unix_seconds() {
+    let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let test = UnixSeconds32(time);
+    assert_eq!(to_vec::<BE, _>(&test).unwrap(), [0x65, 0x53, 0xF1, 0x00]);
+    assert_eq!(from_bytes::<BE, UnixSeconds32>(&[0x65, 0x53, 0xF1, 0x00]).unwrap(), test);
+
+    let test = UnixSeconds64(time);
+    assert_eq!(from_bytes::<LE, UnixSeconds64>(&to_vec::<LE, _>(&test).unwrap()).unwrap(), test);
+
+    assert!(to_vec::<BE, _>(&UnixSeconds32(UNIX_EPOCH - Duration::from_secs(1))).is_err());
+    assert!(to_vec::<BE, _>(&UnixSeconds32(UNIX_EPOCH + Duration::from_secs(1 << 32))).is_err());
+  }
+}
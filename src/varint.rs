@@ -0,0 +1,350 @@
+//! Содержит функции для чтения и записи целых чисел переменной длины в кодировке LEB128,
+//! используемой, например, в Protocol Buffers и WebAssembly.
+//!
+//! Число разбивается на группы по 7 бит, начиная с младших; каждая группа записывается в
+//! отдельный байт, старший бит которого установлен, если за ним следуют другие байты числа.
+//! Представление не зависит от порядка байт сериализатора.
+//!
+//! Помимо функций, модуль содержит типы-обертки [`Varint`] и [`Zigzag`], позволяющие хранить
+//! числа переменной длины в полях структур. Знаковые числа в [`Zigzag`] предварительно
+//! кодируются так, чтобы числа с небольшим модулем занимали мало байт независимо от знака.
+//!
+//! [`Varint`]: struct.Varint.html
+//! [`Zigzag`]: struct.Zigzag.html
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::result;
+use byteorder::ByteOrder;
+use serde::de::{self, SeqAccess, Unexpected, Visitor};
+use serde::{Deserialize, Serialize};
+
+use de::Deserializer;
+use error::{Error, Result};
+use raw::Raw;
+use ser::Serializer;
+
+/// Максимальное количество байт в представлении 64-битного числа
+const MAX_LEN: usize = 10;
+
+/// Кодирует число в LEB128. Возвращает буфер и количество занятых в нем байт
+fn encode(mut value: u64) -> ([u8; MAX_LEN], usize) {
+  let mut buf = [0; MAX_LEN];
+  let mut len = 0;
+  loop {
+    let byte = (value & 0x7F) as u8;
+    value >>= 7;
+    if value == 0 {
+      buf[len] = byte;
+      return (buf, len + 1);
+    }
+    buf[len] = byte | 0x80;
+    len += 1;
+  }
+}
+
+/// Записывает беззнаковое число в кодировке LEB128, занимающее от 1 до 10 байт
+///
+/// # Параметры
+/// - `ser`: Сериализатор, в который записывается число
+/// - `value`: Записываемое число
+pub fn write_varint<BO, W>(ser: &mut Serializer<BO, W>, value: u64) -> Result<()>
+  where BO: ByteOrder,
+        W: Write,
+{
+  let (buf, len) = encode(value);
+  Raw(&buf[..len]).serialize(ser)
+}
+
+/// Читает беззнаковое число в кодировке LEB128
+///
+/// # Параметры
+/// - `de`: Десериализатор, из которого читается число
+///
+/// # Ошибки
+/// - [`Error::InvalidLength`]: представление числа длиннее 10 байт или не помещается в 64 бита
+/// - ошибки чтения из потока, в том числе, если поток закончился в середине числа
+///
+/// [`Error::InvalidLength`]: ../error/enum.Error.html#variant.InvalidLength
+pub fn read_varint<BO, R>(de: &mut Deserializer<BO, R>) -> Result<u64>
+  where BO: ByteOrder,
+        R: BufRead,
+{
+  let mut value = 0u64;
+  for i in 0..MAX_LEN {
+    let byte = u8::deserialize(&mut *de)?;
+    // В последнем байте 64-битного числа значим только младший бит
+    if i == MAX_LEN - 1 && byte > 0x01 {
+      return Err(Error::InvalidLength("varint does not fit into 64 bits".into()));
+    }
+    value |= u64::from(byte & 0x7F) << (i * 7);
+    if byte & 0x80 == 0 {
+      return Ok(value);
+    }
+  }
+  Err(Error::InvalidLength(format!("varint is longer than {} bytes", MAX_LEN)))
+}
+
+/// Беззнаковый целочисленный тип, который может храниться в виде [`Varint`]
+///
+/// [`Varint`]: struct.Varint.html
+pub trait VarintInt: Copy {
+  /// Количество бит в типе
+  const BITS: u32;
+  /// Расширяет значение до 64 бит
+  fn to_u64(self) -> u64;
+  /// Преобразует 64-битное значение в значение данного типа. Возвращает `None`, если значение
+  /// не может быть представлено данным типом
+  fn from_u64(value: u64) -> Option<Self>;
+}
+/// Знаковый целочисленный тип, который может храниться в виде [`Zigzag`]
+///
+/// [`Zigzag`]: struct.Zigzag.html
+pub trait ZigzagInt: Copy {
+  /// Количество бит в типе
+  const BITS: u32;
+  /// Расширяет значение до 64 бит
+  fn to_i64(self) -> i64;
+  /// Преобразует 64-битное значение в значение данного типа. Возвращает `None`, если значение
+  /// не может быть представлено данным типом
+  fn from_i64(value: i64) -> Option<Self>;
+}
+
+/// Макрос, реализующий типажи `VarintInt` и `ZigzagInt` для целочисленных типов
+macro_rules! impl_int {
+  ($trait:ident, $to:ident, $from:ident, $wide:ty: $($type:ty),*) => {
+    $(
+      impl $trait for $type {
+        const BITS: u32 = <$type>::BITS;
+        #[inline]
+        fn $to(self) -> $wide { self.into() }
+        #[inline]
+        fn $from(value: $wide) -> Option<Self> { <$type>::try_from(value).ok() }
+      }
+    )*
+  }
+}
+impl_int!(VarintInt, to_u64, from_u64, u64: u8, u16, u32, u64);
+impl_int!(ZigzagInt, to_i64, from_i64, i64: i8, i16, i32, i64);
+
+/// Беззнаковое число, записываемое в кодировке LEB128 и занимающее от 1 до
+/// `(T::BITS + 6) / 7` байт.
+///
+/// При десериализации отвергаются представления, длиннее необходимого для типа `T`, значения,
+/// не помещающиеся в `T`, и неканонические представления, заканчивающиеся нулевой группой бит
+/// (например, `[0x80, 0x00]` вместо `[0x00]`). Благодаря этому каждое значение имеет
+/// единственное представление в потоке.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::varint::Varint;
+/// # fn main() -> serde_pod::Result<()> {
+/// assert_eq!(to_vec::<LE, _>(&Varint(300u32))?, [0xAC, 0x02]);
+/// assert_eq!(from_bytes::<LE, Varint<u32>>(&[0xAC, 0x02])?, Varint(300));
+/// assert!(from_bytes::<LE, Varint<u8>>(&[0xAC, 0x02]).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Varint<T>(pub T);
+
+/// Знаковое число, преобразуемое в беззнаковое кодированием ZigZag (`0 -> 0`, `-1 -> 1`,
+/// `1 -> 2`, `-2 -> 3` и т.д.) и записываемое в кодировке LEB128, как [`Varint`]. Десериализация
+/// выполняет те же проверки, что и для [`Varint`].
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::varint::Zigzag;
+/// # fn main() -> serde_pod::Result<()> {
+/// assert_eq!(to_vec::<LE, _>(&Zigzag(-65i32))?, [0x81, 0x01]);
+/// assert_eq!(from_bytes::<LE, Zigzag<i32>>(&[0x81, 0x01])?, Zigzag(-65));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Varint`]: struct.Varint.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Zigzag<T>(pub T);
+
+impl<T> From<T> for Varint<T> {
+  #[inline]
+  fn from(value: T) -> Self { Varint(value) }
+}
+impl<T> From<T> for Zigzag<T> {
+  #[inline]
+  fn from(value: T) -> Self { Zigzag(value) }
+}
+
+impl<T: VarintInt> Serialize for Varint<T> {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+    let (buf, len) = encode(self.0.to_u64());
+    Raw(&buf[..len]).serialize(serializer)
+  }
+}
+impl<'de, T: VarintInt> Deserialize<'de> for Varint<T> {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> result::Result<Self, D::Error> {
+    let value = deserializer.deserialize_tuple(MAX_LEN, Leb128(T::BITS))?;
+    T::from_u64(value).map(Varint).ok_or_else(|| de::Error::invalid_value(
+      Unexpected::Unsigned(value), &"a varint that fits into the target type"
+    ))
+  }
+}
+
+impl<T: ZigzagInt> Serialize for Zigzag<T> {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+    let value = self.0.to_i64();
+    let (buf, len) = encode(((value << 1) ^ (value >> 63)) as u64);
+    Raw(&buf[..len]).serialize(serializer)
+  }
+}
+impl<'de, T: ZigzagInt> Deserialize<'de> for Zigzag<T> {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> result::Result<Self, D::Error> {
+    let value = deserializer.deserialize_tuple(MAX_LEN, Leb128(T::BITS))?;
+    let value = (value >> 1) as i64 ^ -((value & 1) as i64);
+    T::from_i64(value).map(Zigzag).ok_or_else(|| de::Error::invalid_value(
+      Unexpected::Signed(value), &"a zigzag varint that fits into the target type"
+    ))
+  }
+}
+
+/// Посетитель, читающий по одному байту числа в кодировке LEB128 шириной не более указанного
+/// количества бит
+struct Leb128(u32);
+impl<'de> Visitor<'de> for Leb128 {
+  type Value = u64;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "a canonical LEB128 varint of at most {} bits", self.0)
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> result::Result<Self::Value, A::Error> {
+    let max_len = (self.0 as usize).div_ceil(7);
+    let mut value = 0u64;
+    for i in 0..max_len {
+      let byte: u8 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+      let shift = 7 * i as u32;
+      // Биты последнего байта, не помещающиеся в тип
+      if i == max_len - 1 && u32::from(byte) >> (self.0 - shift) != 0 {
+        return Err(de::Error::invalid_value(Unexpected::Unsigned(byte.into()), &self));
+      }
+      value |= u64::from(byte & 0x7F) << shift;
+      if byte & 0x80 == 0 {
+        if byte == 0 && i > 0 {
+          return Err(de::Error::custom("overlong varint encoding"));
+        }
+        return Ok(value);
+      }
+    }
+    Err(de::Error::invalid_length(max_len + 1, &self))
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use byteorder::{BE, LE};
+  use de::Deserializer;
+  use ser::Serializer;
+  use super::{read_varint, write_varint};
+
+  fn write(value: u64) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_varint(&mut Serializer::<BE, _>::new(&mut buf), value).unwrap();
+    buf
+  }
+
+  #[test]
+  fn test_encode() {
+    assert_eq!(write(0), [0x00]);
+    assert_eq!(write(1), [0x01]);
+    assert_eq!(write(127), [0x7F]);
+    assert_eq!(write(128), [0x80, 0x01]);
+    assert_eq!(write(300), [0xAC, 0x02]);
+    assert_eq!(write(u64::MAX), [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+  }
+  #[test]
+  fn test_decode() {
+    for &value in &[0, 1, 127, 128, 300, 0x0123_4567_89AB_CDEF, u64::MAX] {
+      let buf = write(value);
+      assert_eq!(read_varint(&mut Deserializer::<LE, _>::new(&buf[..])).unwrap(), value);
+    }
+  }
+  /// Слишком длинные представления и переполнение обнаруживаются
+  #[test]
+  fn test_invalid() {
+    let overflow = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+    assert!(read_varint(&mut Deserializer::<BE, _>::new(&overflow[..])).is_err());
+    let overlong = [0x80; 11];
+    assert!(read_varint(&mut Deserializer::<BE, _>::new(&overlong[..])).is_err());
+    assert!(read_varint(&mut Deserializer::<BE, _>::new(&[0x80][..])).is_err());
+  }
+}
+
+#[cfg(test)]
+mod wrappers {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::{Varint, Zigzag};
+
+  quickcheck! {
+    fn test_u32(value: u32) -> bool {
+      let data = to_vec::<BE, _>(&Varint(value)).unwrap();
+      data == to_vec::<LE, _>(&Varint(value)).unwrap()
+        && data.len() <= 5
+        && from_bytes::<LE, Varint<u32>>(&data).unwrap() == Varint(value)
+    }
+    fn test_u64(value: u64) -> bool {
+      let data = to_vec::<BE, _>(&Varint(value)).unwrap();
+      data.len() <= 10 && from_bytes::<BE, Varint<u64>>(&data).unwrap() == Varint(value)
+    }
+    fn test_i32(value: i32) -> bool {
+      let data = to_vec::<BE, _>(&Zigzag(value)).unwrap();
+      data.len() <= 5 && from_bytes::<BE, Zigzag<i32>>(&data).unwrap() == Zigzag(value)
+    }
+    fn test_i64(value: i64) -> bool {
+      let data = to_vec::<LE, _>(&Zigzag(value)).unwrap();
+      data.len() <= 10 && from_bytes::<LE, Zigzag<i64>>(&data).unwrap() == Zigzag(value)
+    }
+  }
+
+  /// Числа с небольшим модулем занимают мало байт независимо от знака
+  #[test]
+  fn test_zigzag() {
+    assert_eq!(to_vec::<BE, _>(&Zigzag(0i32)).unwrap(), [0x00]);
+    assert_eq!(to_vec::<BE, _>(&Zigzag(-1i32)).unwrap(), [0x01]);
+    assert_eq!(to_vec::<BE, _>(&Zigzag(1i32)).unwrap(), [0x02]);
+    assert_eq!(to_vec::<BE, _>(&Zigzag(-64i8)).unwrap(), [0x7F]);
+    assert_eq!(to_vec::<BE, _>(&Zigzag(64i8)).unwrap(), [0x80, 0x01]);
+    assert_eq!(to_vec::<BE, _>(&Zigzag(i64::MIN)).unwrap(), [0xFF; 9].iter().chain(&[0x01]).cloned().collect::<Vec<_>>());
+    assert_eq!(from_bytes::<BE, Zigzag<i8>>(&[0xFF, 0x01]).unwrap(), Zigzag(i8::MIN));
+  }
+  /// Значения, не помещающиеся в тип, и лишние байты отвергаются
+  #[test]
+  fn test_overflow() {
+    assert_eq!(from_bytes::<BE, Varint<u8>>(&[0xFF, 0x01]).unwrap(), Varint(0xFF));
+    assert!(from_bytes::<BE, Varint<u8>>(&[0x80, 0x02]).is_err());
+    assert!(from_bytes::<BE, Varint<u8>>(&[0x80, 0x80, 0x01]).is_err());
+    assert_eq!(from_bytes::<BE, Varint<u32>>(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]).unwrap(), Varint(u32::MAX));
+    assert!(from_bytes::<BE, Varint<u32>>(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F]).is_err());
+    assert!(from_bytes::<BE, Varint<u64>>(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02]).is_err());
+    assert!(from_bytes::<BE, Zigzag<i16>>(&[0xFF, 0xFF, 0x04]).is_err());
+  }
+  /// Неканонические представления с лишними нулевыми группами отвергаются
+  #[test]
+  fn test_overlong() {
+    assert!(from_bytes::<BE, Varint<u32>>(&[0x80, 0x00]).is_err());
+    assert!(from_bytes::<BE, Varint<u32>>(&[0x81, 0x80, 0x00]).is_err());
+    assert!(from_bytes::<BE, Varint<u64>>(&[0x80; 11]).is_err());
+    assert!(from_bytes::<BE, Varint<u64>>(&[0x80]).is_err());
+  }
+}
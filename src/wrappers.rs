@@ -0,0 +1,1483 @@
+//! Содержит типы-обертки, изменяющие представление значений в потоке по сравнению с
+//! представлением оборачиваемых ими типов.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeTuple, Serializer};
+
+use magic;
+use raw::{Raw, RawBuf};
+use TagWidth;
+
+/// Беззнаковое целое, занимающее в потоке ровно `BYTES` байт (от 1 до 8), например, 24-х или
+/// 40-битное число. Байты записываются и читаются в порядке байт сериализатора.
+///
+/// При сериализации старшие байты значения отбрасываются; если они не нулевые, т.е. значение
+/// не помещается в `BYTES` байт, возвращается ошибка. При десериализации прочитанное значение
+/// дополняется нулями до 64 бит.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UIntN<const BYTES: usize>(pub u64);
+
+/// Знаковое целое, занимающее в потоке ровно `BYTES` байт (от 1 до 8), например, 24-х или
+/// 40-битное число. Байты записываются и читаются в порядке байт сериализатора.
+///
+/// При сериализации старшие байты значения отбрасываются; если они не являются расширением
+/// знака, т.е. значение не помещается в `BYTES` байт, возвращается ошибка. При десериализации
+/// прочитанное значение расширяется до 64 бит с учетом знака.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IntN<const BYTES: usize>(pub i64);
+
+/// Проверяет, что ширина целого числа находится в допустимых пределах (от 1 до 8 байт)
+fn check_width(bytes: usize) -> Result<(), String> {
+  if bytes == 0 || bytes > 8 {
+    return Err(format!("integer width must be from 1 to 8 bytes, but {} requested", bytes));
+  }
+  Ok(())
+}
+
+impl<const BYTES: usize> Serialize for UIntN<BYTES> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    check_width(BYTES).map_err(ser::Error::custom)?;
+    if BYTES < 8 && self.0 >> (BYTES * 8) != 0 {
+      return Err(ser::Error::custom(format_args!("value {} does not fit into {} bytes", self.0, BYTES)));
+    }
+    magic::serialize_be_bytes(serializer, &self.0.to_be_bytes()[8 - BYTES..])
+  }
+}
+impl<const BYTES: usize> Serialize for IntN<BYTES> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    check_width(BYTES).map_err(ser::Error::custom)?;
+    // Все отбрасываемые биты, включая знаковый бит результата, должны совпадать
+    let rest = self.0 >> (BYTES * 8 - 1);
+    if rest != 0 && rest != -1 {
+      return Err(ser::Error::custom(format_args!("value {} does not fit into {} bytes", self.0, BYTES)));
+    }
+    magic::serialize_be_bytes(serializer, &self.0.to_be_bytes()[8 - BYTES..])
+  }
+}
+
+impl<'de, const BYTES: usize> Deserialize<'de> for UIntN<BYTES> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    check_width(BYTES).map_err(de::Error::custom)?;
+    deserializer.deserialize_tuple_struct(magic::BE_BYTES, BYTES, BeBytesVisitor(BYTES)).map(UIntN)
+  }
+}
+impl<'de, const BYTES: usize> Deserialize<'de> for IntN<BYTES> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    check_width(BYTES).map_err(de::Error::custom)?;
+    let value = deserializer.deserialize_tuple_struct(magic::BE_BYTES, BYTES, BeBytesVisitor(BYTES))?;
+    let shift = 64 - BYTES * 8;
+    Ok(IntN(((value << shift) as i64) >> shift))
+  }
+}
+
+/// Посетитель, собирающий беззнаковое число из указанного количества байт, идущих в порядке `Big-Endian`
+struct BeBytesVisitor(usize);
+impl<'de> Visitor<'de> for BeBytesVisitor {
+  type Value = u64;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "{} bytes of an integer", self.0)
+  }
+  fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+    if v.len() != self.0 {
+      return Err(de::Error::invalid_length(v.len(), &self));
+    }
+    Ok(v.iter().fold(0, |acc, &b| (acc << 8) | u64::from(b)))
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut value = 0;
+    for i in 0..self.0 {
+      let b: u8 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+      value = (value << 8) | u64::from(b);
+    }
+    Ok(value)
+  }
+}
+
+/// Массив фиксированной длины `N`, не ограниченный размерами массивов, для которых serde
+/// реализует сериализацию и десериализацию. Элементы записываются и читаются подряд, без
+/// разделителей и длины массива.
+///
+/// Если элементы массива десериализуются, как примитивные числа (`u8`, `i16`, `u32`, `f64` и т.п.),
+/// то все `N` элементов читаются из потока за одну операцию чтения, иначе элементы читаются
+/// по одному.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Array<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> Array<T, N> {
+  /// Возвращает обернутый массив
+  #[inline]
+  pub fn into_inner(self) -> [T; N] { self.0 }
+}
+impl<T, const N: usize> From<[T; N]> for Array<T, N> {
+  #[inline]
+  fn from(array: [T; N]) -> Self { Array(array) }
+}
+
+impl<T: Serialize, const N: usize> Serialize for Array<T, N> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(N)?;
+    for elem in self.0.iter() {
+      tuple.serialize_element(elem)?;
+    }
+    tuple.end()
+  }
+}
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for Array<T, N> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let visitor = ArrayVisitor(PhantomData);
+    match magic::primitive_width::<T>().and_then(magic::bulk_array_name) {
+      Some(name) => deserializer.deserialize_tuple_struct(name, N, visitor),
+      None => deserializer.deserialize_tuple(N, visitor),
+    }
+  }
+}
+
+/// Посетитель, собирающий массив из `N` элементов
+struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for ArrayVisitor<T, N> {
+  type Value = Array<T, N>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    write!(fmt, "an array of length {}", N)
+  }
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+    let mut vec = Vec::with_capacity(N);
+    for i in 0..N {
+      vec.push(seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?);
+    }
+    // Длина вектора всегда равна N, поэтому преобразование не может завершиться неудачей
+    <[T; N]>::try_from(vec).map(Array).map_err(|_| de::Error::invalid_length(N, &self))
+  }
+}
+
+/// Массив из `N` байт, записываемый и читаемый как есть, например, сигнатура формата или
+/// зарезервированная область. В отличие от `[u8; N]` и [`Array<u8, N>`], всегда читается из
+/// потока за одну операцию чтения, а в отличие от `Vec<u8>` -- не до конца потока, поэтому
+/// может располагаться в середине структуры.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::wrappers::Bytes;
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Header {
+///   signature: Bytes<4>,
+///   version: u16,
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let data = *b"RIFF\x02\x00";
+/// let header = Header { signature: Bytes(*b"RIFF"), version: 2 };
+/// assert_eq!(to_vec::<LE, _>(&header)?, data);
+/// assert_eq!(from_bytes::<LE, Header>(&data)?, header);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Array<u8, N>`]: struct.Array.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Bytes<N> {
+  /// Возвращает обернутый массив
+  #[inline]
+  pub fn into_inner(self) -> [u8; N] { self.0 }
+}
+impl<const N: usize> From<[u8; N]> for Bytes<N> {
+  #[inline]
+  fn from(array: [u8; N]) -> Self { Bytes(array) }
+}
+
+impl<const N: usize> Serialize for Bytes<N> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    Raw(&self.0).serialize(serializer)
+  }
+}
+impl<'de, const N: usize> Deserialize<'de> for Bytes<N> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let buf = RawBuf(N).deserialize(deserializer)?;
+    let len = buf.len();
+    <[u8; N]>::try_from(buf).map(Bytes).map_err(|_| de::Error::invalid_length(len, &RawBuf(N)))
+  }
+}
+
+/// Массив из `N` структур или кортежей, записываемый по столбцам (struct-of-arrays): сначала
+/// первые поля всех элементов, затем вторые и т.д. Каждое поле записывается в своем обычном
+/// представлении в порядке байт сериализатора.
+///
+/// Десериализатор этого крейта определяет размеры полей при чтении первого элемента, поэтому
+/// размер каждого поля должен быть одинаковым во всех элементах (строки и последовательности
+/// без фиксированной длины не поддерживаются). Другие форматы видят этот тип, как обычный
+/// массив, записанный по строкам.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::wrappers::Columnar;
+/// #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+/// struct Point { x: u8, y: u16 }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let points = Columnar([Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]);
+/// let data = [0x01, 0x03,   0x00, 0x02, 0x00, 0x04];
+/// assert_eq!(to_vec::<BE, _>(&points)?, data);
+/// assert_eq!(from_bytes::<BE, Columnar<Point, 2>>(&data)?, points);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Columnar<T, const N: usize>(pub [T; N]);
+
+impl<T, const N: usize> Columnar<T, N> {
+  /// Возвращает обернутый массив
+  #[inline]
+  pub fn into_inner(self) -> [T; N] { self.0 }
+}
+impl<T, const N: usize> From<[T; N]> for Columnar<T, N> {
+  #[inline]
+  fn from(array: [T; N]) -> Self { Columnar(array) }
+}
+
+impl<T: Serialize, const N: usize> Serialize for Columnar<T, N> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(magic::COLUMNAR, &self.0[..])
+  }
+}
+impl<'de, T: Deserialize<'de>, const N: usize> Deserialize<'de> for Columnar<T, N> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let array = deserializer.deserialize_tuple_struct(magic::COLUMNAR, N, ArrayVisitor(PhantomData))?;
+    Ok(Columnar(array.0))
+  }
+}
+
+/// Значение, расположенное в другом месте потока, на которое ссылается смещение, как в форматах
+/// наподобие FlatBuffers. В потоке на месте значения записывается смещение типа `u32` от позиции
+/// самого смещения до значения в порядке байт сериализатора.
+///
+/// Запись и чтение таких значений поддерживаются только сериализатором и десериализатором,
+/// созданными методами [`Serializer::seekable`] и [`Deserializer::seekable`]. Сериализатор
+/// записывает вместо значения заглушку и запоминает значение; значения записываются, а заглушки
+/// заполняются вызовом [`Serializer::write_offset_targets`]. Десериализатор после чтения
+/// значения продолжает чтение сразу за смещением.
+///
+/// [`Serializer::seekable`]: ../ser/struct.Serializer.html#method.seekable
+/// [`Serializer::write_offset_targets`]: ../ser/struct.Serializer.html#method.write_offset_targets
+/// [`Deserializer::seekable`]: ../de/struct.Deserializer.html#method.seekable
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Offset<T>(pub T);
+
+impl<T> Offset<T> {
+  /// Возвращает обернутое значение
+  #[inline]
+  pub fn into_inner(self) -> T { self.0 }
+}
+impl<T: Serialize> Serialize for Offset<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(magic::OFFSET, &self.0)
+  }
+}
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Offset<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_newtype_struct(magic::OFFSET, OffsetVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий значение, на которое ссылается смещение
+struct OffsetVisitor<T>(PhantomData<T>);
+impl<'de, T: Deserialize<'de>> Visitor<'de> for OffsetVisitor<T> {
+  type Value = Offset<T>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a value at offset")
+  }
+  fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    T::deserialize(deserializer).map(Offset)
+  }
+}
+
+/// Целочисленный тип, в котором [`TaggedEnum`] записывает метку варианта перечисления
+///
+/// [`TaggedEnum`]: struct.TaggedEnum.html
+pub trait EnumTagType {
+  /// Ширина метки
+  const WIDTH: TagWidth;
+}
+impl EnumTagType for u8  { const WIDTH: TagWidth = TagWidth::U8; }
+impl EnumTagType for u16 { const WIDTH: TagWidth = TagWidth::U16; }
+impl EnumTagType for u32 { const WIDTH: TagWidth = TagWidth::U32; }
+
+/// Перечисление, каждое значение которого записывается, как индекс варианта (метка) типа `Tag`
+/// в порядке байт сериализатора, за которым следуют данные варианта: ничего для unit-вариантов,
+/// значение для newtype-вариантов и поля по порядку для tuple- и struct-вариантов.
+///
+/// Обертка позволяет записывать и читать перечисления без настройки сериализатора и
+/// десериализатора методами `with_enum_tag`, а также использовать в одной структуре метки
+/// разной ширины. Внутри обертки перечисления, вложенные в данные варианта, также записываются
+/// с меткой типа `Tag`. Размер данных варианта не записывается.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::wrappers::TaggedEnum;
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// enum Shape {
+///   Circle(u16),
+///   Rect { width: u8, height: u8 },
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// let shape = TaggedEnum::<u16, _>::new(Shape::Rect { width: 3, height: 4 });
+/// let data = [0x01, 0x00,   0x03, 0x04];
+/// assert_eq!(to_vec::<LE, _>(&shape)?, data);
+/// assert_eq!(from_bytes::<LE, TaggedEnum<u16, Shape>>(&data)?, shape);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaggedEnum<Tag, E> {
+  /// Обернутое перечисление
+  value: E,
+  /// Тип метки варианта
+  _marker: PhantomData<Tag>,
+}
+
+impl<Tag, E> TaggedEnum<Tag, E> {
+  /// Оборачивает указанное значение перечисления
+  #[inline]
+  pub fn new(value: E) -> Self {
+    TaggedEnum { value, _marker: PhantomData }
+  }
+  /// Возвращает ссылку на обернутое значение
+  #[inline]
+  pub fn get(&self) -> &E { &self.value }
+  /// Возвращает обернутое значение
+  #[inline]
+  pub fn into_inner(self) -> E { self.value }
+}
+impl<Tag: EnumTagType, E: Serialize> Serialize for TaggedEnum<Tag, E> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(magic::tagged_enum_name(Tag::WIDTH), &self.value)
+  }
+}
+impl<'de, Tag: EnumTagType, E: Deserialize<'de>> Deserialize<'de> for TaggedEnum<Tag, E> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_newtype_struct(magic::tagged_enum_name(Tag::WIDTH), TaggedEnumVisitor(PhantomData))
+  }
+}
+
+/// Посетитель, читающий перечисление с меткой варианта
+struct TaggedEnumVisitor<Tag, E>(PhantomData<(Tag, E)>);
+impl<'de, Tag, E: Deserialize<'de>> Visitor<'de> for TaggedEnumVisitor<Tag, E> {
+  type Value = TaggedEnum<Tag, E>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("an enum with a variant tag")
+  }
+  fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+    E::deserialize(deserializer).map(TaggedEnum::new)
+  }
+}
+
+/// Момент времени в формате `FILETIME` Windows: количество 100-наносекундных интервалов,
+/// прошедших с 1 января 1601 года (UTC). Записывается и читается, как `u64` в порядке байт
+/// сериализатора.
+///
+/// При сериализации дробная часть интервала отбрасывается. Моменты времени ранее 1601 года
+/// или слишком далекие, чтобы быть представленными 64-битным числом, не сериализуются; при
+/// десериализации ошибкой является значение, непредставимое типом [`SystemTime`] на данной
+/// платформе.
+///
+/// [`SystemTime`]: https://doc.rust-lang.org/std/time/struct.SystemTime.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileTime(pub SystemTime);
+
+/// Количество секунд между 1 января 1601 года и 1 января 1970 года
+const FILETIME_UNIX_EPOCH_SECS: u64 = 11_644_473_600;
+/// Количество интервалов `FILETIME` в секунде
+const FILETIME_TICKS_PER_SEC: u64 = 10_000_000;
+
+impl FileTime {
+  /// Возвращает обернутый момент времени
+  #[inline]
+  pub fn into_inner(self) -> SystemTime { self.0 }
+  /// Возвращает момент времени, соответствующий началу отсчета `FILETIME` (1 января 1601 года)
+  pub fn epoch() -> Option<SystemTime> {
+    UNIX_EPOCH.checked_sub(Duration::from_secs(FILETIME_UNIX_EPOCH_SECS))
+  }
+  /// Преобразует момент времени в количество 100-наносекундных интервалов с 1601 года
+  fn to_ticks(self) -> Option<u64> {
+    let since = self.0.duration_since(FileTime::epoch()?).ok()?;
+    since.as_secs()
+      .checked_mul(FILETIME_TICKS_PER_SEC)?
+      .checked_add(u64::from(since.subsec_nanos() / 100))
+  }
+  /// Преобразует количество 100-наносекундных интервалов с 1601 года в момент времени
+  fn from_ticks(ticks: u64) -> Option<Self> {
+    let since = Duration::new(
+      ticks / FILETIME_TICKS_PER_SEC,
+      (ticks % FILETIME_TICKS_PER_SEC) as u32 * 100,
+    );
+    FileTime::epoch()?.checked_add(since).map(FileTime)
+  }
+}
+impl From<SystemTime> for FileTime {
+  #[inline]
+  fn from(time: SystemTime) -> Self { FileTime(time) }
+}
+
+impl Serialize for FileTime {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let ticks = self.to_ticks().ok_or_else(|| ser::Error::custom(format_args!(
+      "{:?} is out of FILETIME range", self.0
+    )))?;
+    serializer.serialize_u64(ticks)
+  }
+}
+impl<'de> Deserialize<'de> for FileTime {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let ticks = u64::deserialize(deserializer)?;
+    FileTime::from_ticks(ticks).ok_or_else(|| de::Error::custom(format_args!(
+      "FILETIME {} is not representable as SystemTime", ticks
+    )))
+  }
+}
+
+/// Физическая величина, записываемая в поток как целое число `raw` типа `T` (например, отсчет
+/// АЦП) и связанная с ним формулой `value = raw * SCALE + BIAS`. Чтобы масштаб и смещение можно
+/// было задать константными параметрами, они указываются в миллионных долях: `SCALE_MICRO` и
+/// `BIAS_MICRO`.
+///
+/// При сериализации значение пересчитывается обратно по формуле `raw = (value - BIAS) / SCALE`
+/// и округляется до ближайшего целого (половины округляются от нуля). Если результат не
+/// помещается в `T`, не является конечным числом или масштаб равен нулю, возвращается ошибка.
+/// Поэтому после сериализации и десериализации значение совпадает с исходным с точностью до
+/// половины масштаба.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::wrappers::Scaled;
+/// // Температура в сотых долях градуса со смещением -40 градусов
+/// type Temperature = Scaled<u16, 10_000, -40_000_000>;
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// assert_eq!(to_vec::<BE, _>(&Temperature::new(21.5))?, [0x18, 0x06]);
+/// assert!((from_bytes::<BE, Temperature>(&[0x18, 0x06])?.value() - 21.5).abs() < 1e-9);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Scaled<T, const SCALE_MICRO: i64, const BIAS_MICRO: i64> {
+  /// Значение физической величины
+  value: f64,
+  /// Тип, в котором величина записывается в поток
+  _marker: PhantomData<T>,
+}
+
+/// Целочисленный тип, в котором в поток записывается масштабированная величина [`Scaled`]
+///
+/// [`Scaled`]: struct.Scaled.html
+pub trait ScaledRaw: Copy + Serialize + for<'de> Deserialize<'de> {
+  /// Преобразует значение в число с плавающей точкой
+  fn to_f64(self) -> f64;
+  /// Округляет число до ближайшего целого и преобразует его в значение данного типа. Возвращает
+  /// `None`, если число не является конечным или не может быть представлено данным типом
+  fn from_f64(value: f64) -> Option<Self>;
+  /// Округляет число до ближайшего целого и преобразует его в значение данного типа, заменяя
+  /// числа, не помещающиеся в тип, его минимальным или максимальным значением. Возвращает
+  /// `None`, если число является NaN
+  fn from_f64_saturating(value: f64) -> Option<Self>;
+}
+
+/// Макрос, реализующий типаж `ScaledRaw` для целочисленных типов
+macro_rules! impl_scaled_raw {
+  ($($type:ty),*) => {
+    $(
+      impl ScaledRaw for $type {
+        #[inline]
+        fn to_f64(self) -> f64 { self as f64 }
+        fn from_f64(value: f64) -> Option<Self> {
+          let value = value.round();
+          // Границы типа точно представимы степенями двойки, в отличие от `MAX as f64`
+          let min = <$type>::MIN as f64;
+          let end = if min == 0.0 { 2f64.powi(<$type>::BITS as i32) } else { -min };
+          if value >= min && value < end { Some(value as $type) } else { None }
+        }
+        #[inline]
+        fn from_f64_saturating(value: f64) -> Option<Self> {
+          // Приведение `as` насыщает результат границами типа, но превращает NaN в 0
+          if value.is_nan() { None } else { Some(value.round() as $type) }
+        }
+      }
+    )*
+  }
+}
+impl_scaled_raw!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl<T, const SCALE_MICRO: i64, const BIAS_MICRO: i64> Scaled<T, SCALE_MICRO, BIAS_MICRO> {
+  /// Масштаб величины
+  const SCALE: f64 = SCALE_MICRO as f64 / 1_000_000.0;
+  /// Смещение величины
+  const BIAS: f64 = BIAS_MICRO as f64 / 1_000_000.0;
+
+  /// Оборачивает значение физической величины
+  #[inline]
+  pub fn new(value: f64) -> Self {
+    Scaled { value, _marker: PhantomData }
+  }
+  /// Возвращает значение физической величины
+  #[inline]
+  pub fn value(&self) -> f64 { self.value }
+}
+impl<T: ScaledRaw, const SCALE_MICRO: i64, const BIAS_MICRO: i64> Scaled<T, SCALE_MICRO, BIAS_MICRO> {
+  /// Преобразует записанное в поток число в значение физической величины
+  #[inline]
+  pub fn from_raw(raw: T) -> Self {
+    Self::new(raw.to_f64() * Self::SCALE + Self::BIAS)
+  }
+  /// Преобразует значение физической величины в число, записываемое в поток. Возвращает `None`,
+  /// если масштаб равен нулю или результат не может быть представлен типом `T`
+  pub fn to_raw(&self) -> Option<T> {
+    if SCALE_MICRO == 0 {
+      return None;
+    }
+    T::from_f64((self.value - Self::BIAS) / Self::SCALE)
+  }
+}
+
+impl<T: ScaledRaw, const SCALE_MICRO: i64, const BIAS_MICRO: i64> Serialize for Scaled<T, SCALE_MICRO, BIAS_MICRO> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let raw = self.to_raw().ok_or_else(|| ser::Error::custom(format_args!(
+      "value {} cannot be represented with scale {} and bias {}", self.value, Self::SCALE, Self::BIAS
+    )))?;
+    raw.serialize(serializer)
+  }
+}
+impl<'de, T: ScaledRaw, const SCALE_MICRO: i64, const BIAS_MICRO: i64> Deserialize<'de> for Scaled<T, SCALE_MICRO, BIAS_MICRO> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    T::deserialize(deserializer).map(Self::from_raw)
+  }
+}
+
+/// Число с фиксированной точкой в дополнительном коде, записываемое в поток как целое число `raw`
+/// типа `T`, младшие `FRAC` бит которого -- дробная часть: `value = raw / 2^FRAC`. Например,
+/// формат Q15 соответствует типу `SatFixed<i16, 15>`.
+///
+/// В отличие от [`Scaled`], значения, выходящие за диапазон `T`, не считаются ошибкой, а
+/// насыщаются, как это принято в цифровой обработке сигналов: при сериализации значение
+/// умножается на `2^FRAC`, округляется до ближайшего целого (половины округляются от нуля) и
+/// ограничивается минимальным и максимальным значениями `T`. Бесконечности также насыщаются,
+/// а сериализация NaN является ошибкой.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::wrappers::SatFixed;
+/// type Q15 = SatFixed<i16, 15>;
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// assert_eq!(to_vec::<BE, _>(&Q15::new(-0.5))?, [0xC0, 0x00]);
+/// // 1.0 не представимо в Q15 и насыщается до 0x7FFF
+/// assert_eq!(to_vec::<BE, _>(&Q15::new(1.0))?, [0x7F, 0xFF]);
+/// assert_eq!(from_bytes::<BE, Q15>(&[0xC0, 0x00])?.value(), -0.5);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Scaled`]: struct.Scaled.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct SatFixed<T, const FRAC: u32> {
+  /// Значение числа
+  value: f64,
+  /// Тип, в котором число записывается в поток
+  _marker: PhantomData<T>,
+}
+
+impl<T, const FRAC: u32> SatFixed<T, FRAC> {
+  /// Вес младшего бита числа, `2^-FRAC`
+  const ULP: f64 = 1.0 / (1u128 << FRAC) as f64;
+
+  /// Оборачивает значение числа
+  #[inline]
+  pub fn new(value: f64) -> Self {
+    SatFixed { value, _marker: PhantomData }
+  }
+  /// Возвращает значение числа
+  #[inline]
+  pub fn value(&self) -> f64 { self.value }
+}
+impl<T: ScaledRaw, const FRAC: u32> SatFixed<T, FRAC> {
+  /// Преобразует записанное в поток число в значение числа с фиксированной точкой
+  #[inline]
+  pub fn from_raw(raw: T) -> Self {
+    Self::new(raw.to_f64() * Self::ULP)
+  }
+  /// Преобразует значение в число, записываемое в поток, насыщая его границами типа `T`.
+  /// Возвращает `None`, если значение является NaN
+  #[inline]
+  pub fn to_raw(&self) -> Option<T> {
+    T::from_f64_saturating(self.value / Self::ULP)
+  }
+}
+
+impl<T: ScaledRaw, const FRAC: u32> Serialize for SatFixed<T, FRAC> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let raw = self.to_raw().ok_or_else(|| ser::Error::custom("NaN cannot be represented as a fixed-point number"))?;
+    raw.serialize(serializer)
+  }
+}
+impl<'de, T: ScaledRaw, const FRAC: u32> Deserialize<'de> for SatFixed<T, FRAC> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    T::deserialize(deserializer).map(Self::from_raw)
+  }
+}
+
+/// Необязательное целое число, отсутствие которого обозначается не отдельным флагом, а особым
+/// значением `SENTINEL` (например, `0xFFFFFFFF` для необязательных смещений и индексов).
+/// Записывается и читается, как число типа `T` в порядке байт сериализатора.
+///
+/// При десериализации прочитанное значение, равное `SENTINEL`, превращается в `None`, остальные
+/// -- в `Some`. При сериализации `None` записывается, как `SENTINEL`; значение `Some(SENTINEL)`
+/// неотличимо от `None`, поэтому его сериализация, как и использование `SENTINEL`, не
+/// представимого типом `T`, является ошибкой.
+///
+/// # Пример
+/// ```rust
+/// # extern crate byteorder;
+/// # extern crate serde_pod;
+/// # use byteorder::LE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// # use serde_pod::wrappers::OptionSentinel;
+/// type Index = OptionSentinel<u32, 0xFFFF_FFFF>;
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// assert_eq!(to_vec::<LE, _>(&Index::from(None))?, [0xFF, 0xFF, 0xFF, 0xFF]);
+/// assert_eq!(from_bytes::<LE, Index>(&[0x01, 0x00, 0x00, 0x00])?, Index::from(Some(1)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OptionSentinel<T, const SENTINEL: i128>(pub Option<T>);
+
+impl<T, const SENTINEL: i128> OptionSentinel<T, SENTINEL> {
+  /// Возвращает обернутое значение
+  #[inline]
+  pub fn into_inner(self) -> Option<T> { self.0 }
+}
+impl<T, const SENTINEL: i128> From<Option<T>> for OptionSentinel<T, SENTINEL> {
+  #[inline]
+  fn from(value: Option<T>) -> Self { OptionSentinel(value) }
+}
+
+/// Возвращает особое значение `sentinel`, преобразованное в тип `T`, или описание ошибки, если
+/// оно не представимо этим типом
+fn sentinel<T: TryFrom<i128>>(sentinel: i128) -> Result<T, String> {
+  T::try_from(sentinel).map_err(|_| format!("sentinel {} does not fit into the value type", sentinel))
+}
+
+impl<T, const SENTINEL: i128> Serialize for OptionSentinel<T, SENTINEL>
+  where T: Serialize + TryFrom<i128> + PartialEq,
+{
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let none: T = sentinel(SENTINEL).map_err(ser::Error::custom)?;
+    match self.0 {
+      Some(ref value) if *value == none => Err(ser::Error::custom(format_args!(
+        "value {} is reserved as a sentinel for None", SENTINEL
+      ))),
+      Some(ref value) => value.serialize(serializer),
+      None => none.serialize(serializer),
+    }
+  }
+}
+impl<'de, T, const SENTINEL: i128> Deserialize<'de> for OptionSentinel<T, SENTINEL>
+  where T: Deserialize<'de> + TryFrom<i128> + PartialEq,
+{
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let none: T = sentinel(SENTINEL).map_err(de::Error::custom)?;
+    let value = T::deserialize(deserializer)?;
+    Ok(OptionSentinel(if value == none { None } else { Some(value) }))
+  }
+}
+
+/// Число с плавающей точкой, в котором значение NaN обозначает отсутствие значения
+pub trait NanFloat: Copy + Serialize + for<'de> Deserialize<'de> {
+  /// Каноническое тихое NaN, записываемое вместо отсутствующего значения
+  const CANONICAL_NAN: Self;
+  /// Проверяет, является ли число NaN
+  fn is_nan(self) -> bool;
+}
+impl NanFloat for f32 {
+  const CANONICAL_NAN: Self = f32::from_bits(0x7FC0_0000);
+  #[inline]
+  fn is_nan(self) -> bool { f32::is_nan(self) }
+}
+impl NanFloat for f64 {
+  const CANONICAL_NAN: Self = f64::from_bits(0x7FF8_0000_0000_0000);
+  #[inline]
+  fn is_nan(self) -> bool { f64::is_nan(self) }
+}
+
+/// Функции сериализации и десериализации необязательных чисел с плавающей точкой, отсутствие
+/// которых обозначается значением NaN, для использования в атрибуте `with` полей типа
+/// `Option<f32>` или `Option<f64>`. Тип числа задается параметром типа:
+///
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Sample {
+///   #[serde(with = "serde_pod::NanOption::<f32>")]
+///   temperature: Option<f32>,
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// assert_eq!(to_vec::<BE, _>(&Sample { temperature: None })?, [0x7F, 0xC0, 0x00, 0x00]);
+/// assert_eq!(from_bytes::<BE, Sample>(&[0x7F, 0xC0, 0x00, 0x00])?, Sample { temperature: None });
+/// # Ok(())
+/// # }
+/// ```
+///
+/// При десериализации любое NaN, независимо от его битового представления, превращается в `None`.
+/// При сериализации `None` записывается, как каноническое тихое NaN (`0x7FC00000` для `f32` и
+/// `0x7FF8000000000000` для `f64`); значение `Some(NaN)` неотличимо от `None`, поэтому его
+/// сериализация является ошибкой.
+pub struct NanOption<F>(PhantomData<F>);
+
+impl<F: NanFloat> NanOption<F> {
+  /// Записывает число или каноническое NaN, если оно отсутствует
+  pub fn serialize<S: Serializer>(value: &Option<F>, serializer: S) -> Result<S::Ok, S::Error> {
+    match *value {
+      Some(value) if value.is_nan() => Err(ser::Error::custom("NaN is reserved for None")),
+      Some(value) => value.serialize(serializer),
+      None => F::CANONICAL_NAN.serialize(serializer),
+    }
+  }
+  /// Читает число, превращая NaN в `None`
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<F>, D::Error> {
+    let value = F::deserialize(deserializer)?;
+    Ok(if value.is_nan() { None } else { Some(value) })
+  }
+}
+
+/// Функции сериализации и десериализации значений [`Ordering`] в виде одного байта для
+/// использования в атрибуте `with` полей типа `Ordering`: `Less` записывается, как `0`,
+/// `Equal` -- как `1`, а `Greater` -- как `2`. При десериализации другие значения байта
+/// считаются ошибкой.
+///
+/// ```rust
+/// # extern crate byteorder;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_pod;
+/// # use std::cmp::Ordering;
+/// # use byteorder::BE;
+/// # use serde_pod::{from_bytes, to_vec};
+/// #[derive(Debug, Deserialize, PartialEq, Serialize)]
+/// struct Comparison {
+///   #[serde(with = "serde_pod::ordering_byte")]
+///   result: Ordering,
+/// }
+///
+/// # fn main() -> serde_pod::Result<()> {
+/// assert_eq!(to_vec::<BE, _>(&Comparison { result: Ordering::Greater })?, [0x02]);
+/// assert_eq!(from_bytes::<BE, Comparison>(&[0x00])?, Comparison { result: Ordering::Less });
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Ordering`]: https://doc.rust-lang.org/std/cmp/enum.Ordering.html
+pub mod ordering_byte {
+  use std::cmp::Ordering;
+  use serde::de::{self, Deserialize, Deserializer, Unexpected};
+  use serde::ser::{Serialize, Serializer};
+
+  /// Записывает результат сравнения, как байт `0`, `1` или `2`
+  pub fn serialize<S: Serializer>(value: &Ordering, serializer: S) -> Result<S::Ok, S::Error> {
+    let byte: u8 = match *value {
+      Ordering::Less    => 0,
+      Ordering::Equal   => 1,
+      Ordering::Greater => 2,
+    };
+    byte.serialize(serializer)
+  }
+  /// Читает результат сравнения из байта `0`, `1` или `2`
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Ordering, D::Error> {
+    match u8::deserialize(deserializer)? {
+      0 => Ok(Ordering::Less),
+      1 => Ok(Ordering::Equal),
+      2 => Ok(Ordering::Greater),
+      byte => Err(de::Error::invalid_value(Unexpected::Unsigned(byte.into()), &"0, 1 or 2")),
+    }
+  }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod int_n {
+  use super::{IntN, UIntN};
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+
+  #[test]
+  fn test_uint5() {
+    let test = UIntN::<5>(0x0012_3456_7890);
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x12, 0x34, 0x56, 0x78, 0x90]);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), [0x90, 0x78, 0x56, 0x34, 0x12]);
+    assert_eq!(from_bytes::<BE, UIntN<5>>(&[0x12, 0x34, 0x56, 0x78, 0x90]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, UIntN<5>>(&[0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+  #[test]
+  fn test_uint7() {
+    let test = UIntN::<7>(0x00FE_DCBA_9876_5432);
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0xFE, 0xDC, 0xBA, 0x98, 0x76, 0x54, 0x32]);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), [0x32, 0x54, 0x76, 0x98, 0xBA, 0xDC, 0xFE]);
+    assert_eq!(from_bytes::<BE, UIntN<7>>(&[0xFE, 0xDC, 0xBA, 0x98, 0x76, 0x54, 0x32]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, UIntN<7>>(&[0x32, 0x54, 0x76, 0x98, 0xBA, 0xDC, 0xFE]).unwrap(), test);
+  }
+  /// Значение, не помещающееся в указанное количество байт, не сериализуется
+  #[test]
+  fn test_uint_overflow() {
+    assert!(to_vec::<BE,_>(&UIntN::<5>(0x0100_0000_0000)).is_err());
+    assert!(to_vec::<LE,_>(&UIntN::<5>(0x0100_0000_0000)).is_err());
+  }
+
+  #[test]
+  fn test_int5() {
+    let test = IntN::<5>(-2);
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0xFF, 0xFF, 0xFF, 0xFF, 0xFE]);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), [0xFE, 0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(from_bytes::<BE, IntN<5>>(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFE]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, IntN<5>>(&[0xFE, 0xFF, 0xFF, 0xFF, 0xFF]).unwrap(), test);
+  }
+  #[test]
+  fn test_int7() {
+    let test = IntN::<7>(0x0012_3456_7890_ABCD);
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD]);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), [0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]);
+    assert_eq!(from_bytes::<BE, IntN<7>>(&[0x12, 0x34, 0x56, 0x78, 0x90, 0xAB, 0xCD]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, IntN<7>>(&[0xCD, 0xAB, 0x90, 0x78, 0x56, 0x34, 0x12]).unwrap(), test);
+  }
+  /// Старший бит прочитанного значения расширяется на все 64 бита
+  #[test]
+  fn test_int7_sign_extension() {
+    assert_eq!(from_bytes::<BE, IntN<7>>(&[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]).unwrap(), IntN(-0x0080_0000_0000_0000));
+    assert_eq!(from_bytes::<LE, IntN<7>>(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80]).unwrap(), IntN(-0x0080_0000_0000_0000));
+  }
+  /// Значение, не помещающееся в указанное количество байт, не сериализуется
+  #[test]
+  fn test_int_overflow() {
+    assert!(to_vec::<BE,_>(&IntN::<5>(0x0080_0000_0000)).is_err());
+    assert!(to_vec::<LE,_>(&IntN::<5>(-0x0080_0000_0001)).is_err());
+  }
+  /// Ширина должна быть от 1 до 8 байт
+  #[test]
+  fn test_invalid_width() {
+    assert!(to_vec::<BE,_>(&UIntN::<9>(0)).is_err());
+    assert!(from_bytes::<BE, IntN<0>>(&[]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod array {
+  use serde::Deserialize;
+  use super::Array;
+  use byteorder::{ByteOrder, BE, LE};
+  use de::{from_bytes, Deserializer};
+  use ser::to_vec;
+
+  #[test]
+  fn test_array_u8() {
+    let mut test = [0u8; 1024];
+    for (i, b) in test.iter_mut().enumerate() {
+      *b = i as u8;
+    }
+    assert_eq!(to_vec::<BE,_>(&Array(test)).unwrap(), &test[..]);
+    assert_eq!(to_vec::<LE,_>(&Array(test)).unwrap(), &test[..]);
+    assert_eq!(from_bytes::<BE, Array<u8, 1024>>(&test).unwrap().into_inner()[..], test[..]);
+    assert_eq!(from_bytes::<LE, Array<u8, 1024>>(&test).unwrap().into_inner()[..], test[..]);
+  }
+  #[test]
+  fn test_array_u32() {
+    let mut test = [0u32; 1024];
+    for (i, v) in test.iter_mut().enumerate() {
+      *v = 0x12345600 + i as u32;
+    }
+    let mut be = vec![0; 4096];
+    let mut le = vec![0; 4096];
+    BE::write_u32_into(&test, &mut be);
+    LE::write_u32_into(&test, &mut le);
+
+    assert_eq!(to_vec::<BE,_>(&Array(test)).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&Array(test)).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Array<u32, 1024>>(&be).unwrap().into_inner()[..], test[..]);
+    assert_eq!(from_bytes::<LE, Array<u32, 1024>>(&le).unwrap().into_inner()[..], test[..]);
+  }
+  /// Элементы, не являющиеся примитивными числами, читаются по одному
+  #[test]
+  fn test_array_struct() {
+    #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+    struct Test(u16, u8);
+
+    let test = Array([Test(0x1234, 0x56), Test(0x789A, 0xBC)]);
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0x12, 0x34, 0x56,   0x78, 0x9A, 0xBC]);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), [0x34, 0x12, 0x56,   0x9A, 0x78, 0xBC]);
+    assert_eq!(from_bytes::<BE, Array<Test, 2>>(&[0x12, 0x34, 0x56,   0x78, 0x9A, 0xBC]).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Array<Test, 2>>(&[0x34, 0x12, 0x56,   0x9A, 0x78, 0xBC]).unwrap(), test);
+  }
+  /// Массивы длиннее 32 элементов в середине структуры
+  #[test]
+  fn test_array_in_struct() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Test {
+      hash: Array<u8, 64>,
+      values: Array<u16, 100>,
+      tail: u8,
+    }
+    let mut hash = [0u8; 64];
+    for (i, b) in hash.iter_mut().enumerate() {
+      *b = 0xFF - i as u8;
+    }
+    let mut values = [0u16; 100];
+    for (i, v) in values.iter_mut().enumerate() {
+      *v = 0x0100 * i as u16 + 1;
+    }
+    let test = Test { hash: Array(hash), values: Array(values), tail: 0xAA };
+
+    let mut be = hash.to_vec();
+    be.extend(values.iter().flat_map(|v| v.to_be_bytes()));
+    be.push(0xAA);
+    let mut le = hash.to_vec();
+    le.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+    le.push(0xAA);
+
+    assert_eq!(be.len(), 64 + 200 + 1);
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Test>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Test>(&le).unwrap(), test);
+  }
+  /// Массив примитивов, прочитанный за одну операцию, не сбрасывает настройки десериализатора
+  #[test]
+  fn test_array_options() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+      values: Array<u16, 40>,
+      flag: bool,
+      extra: Option<u8>,
+    }
+    let mut data: Vec<u8> = (0..40u16).flat_map(|v| v.to_le_bytes()).collect();
+    data.extend_from_slice(&[0x01,   0x01, 0x2A]);
+
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_strict_bool().with_option_marker();
+    let test = Test::deserialize(&mut de).unwrap();
+    assert_eq!(test.values.into_inner()[39], 39);
+    assert_eq!((test.flag, test.extra), (true, Some(0x2A)));
+
+    data[80] = 0x02;
+    let mut de = Deserializer::<LE, _>::new(&data[..]).with_strict_bool().with_option_marker();
+    assert!(Test::deserialize(&mut de).is_err());
+  }
+  #[test]
+  #[should_panic]
+  fn test_array_no_data() {
+    from_bytes::<BE, Array<u32, 1024>>(&[0; 4095]).unwrap();
+  }
+}
+
+#[cfg(test)]
+mod bytes {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::Bytes;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Header {
+    signature: Bytes<4>,
+    version: u16,
+    size: u32,
+  }
+
+  #[test]
+  fn test_header() {
+    let header = Header { signature: Bytes(*b"GFF "), version: 0x0102, size: 0x03040506 };
+    let be = [b'G', b'F', b'F', b' ',   0x01, 0x02,   0x03, 0x04, 0x05, 0x06];
+    let le = [b'G', b'F', b'F', b' ',   0x02, 0x01,   0x06, 0x05, 0x04, 0x03];
+    assert_eq!(to_vec::<BE, _>(&header).unwrap(), be);
+    assert_eq!(to_vec::<LE, _>(&header).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Header>(&be).unwrap(), header);
+    assert_eq!(from_bytes::<LE, Header>(&le).unwrap(), header);
+  }
+  #[test]
+  fn test_no_data() {
+    assert!(from_bytes::<BE, Header>(b"GFF").is_err());
+  }
+}
+
+#[cfg(test)]
+mod offset {
+  use std::io::Cursor;
+  use byteorder::{BE, LE};
+  use de::{from_bytes, Deserializer};
+  use ser::{to_vec, Serializer};
+  use serde::{Deserialize, Serialize};
+  use super::Offset;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Test {
+    name: Offset<String>,
+    value: u16,
+  }
+
+  /// Строка записывается после основной структуры, смещение отсчитывается от самого смещения
+  #[test]
+  fn test_string() {
+    let test = Test { name: Offset("abc".into()), value: 0x1234 };
+    let be = [0x00, 0x00, 0x00, 0x06,   0x12, 0x34,   0x61, 0x62, 0x63];
+    let le = [0x06, 0x00, 0x00, 0x00,   0x34, 0x12,   0x61, 0x62, 0x63];
+
+    let mut buf = Cursor::new(Vec::new());
+    let mut ser = Serializer::<BE, _>::seekable(&mut buf);
+    test.serialize(&mut ser).unwrap();
+    ser.write_offset_targets().unwrap();
+    assert_eq!(buf.into_inner(), be);
+
+    let mut buf = Cursor::new(Vec::new());
+    let mut ser = Serializer::<LE, _>::seekable(&mut buf);
+    test.serialize(&mut ser).unwrap();
+    ser.write_offset_targets().unwrap();
+    assert_eq!(buf.into_inner(), le);
+
+    let mut de = Deserializer::<BE, _>::seekable(Cursor::new(&be[..]));
+    assert_eq!(Test::deserialize(&mut de).unwrap(), test);
+    let mut de = Deserializer::<LE, _>::seekable(Cursor::new(&le[..]));
+    assert_eq!(Test::deserialize(&mut de).unwrap(), test);
+  }
+  /// Значения, на которые ссылаются смещения внутри значения по смещению, записываются вместе с ним
+  #[test]
+  fn test_nested() {
+    let test = (Offset((0xAAu8, Offset(0xBBCCu16))), Offset(0xDDu8));
+    let be = [
+      0x00, 0x00, 0x00, 0x08,   0x00, 0x00, 0x00, 0x0B,
+      0xAA,   0x00, 0x00, 0x00, 0x04,   0xBB, 0xCC,
+      0xDD,
+    ];
+    let mut buf = Cursor::new(Vec::new());
+    let mut ser = Serializer::<BE, _>::seekable(&mut buf);
+    test.serialize(&mut ser).unwrap();
+    ser.write_offset_targets().unwrap();
+    assert_eq!(buf.into_inner(), be);
+
+    let mut de = Deserializer::<BE, _>::seekable(Cursor::new(&be[..]));
+    assert_eq!(<(Offset<(u8, Offset<u16>)>, Offset<u8>)>::deserialize(&mut de).unwrap(), test);
+  }
+  /// Без возможности перемещения по потоку смещения не поддерживаются
+  #[test]
+  fn test_not_seekable() {
+    assert!(to_vec::<BE,_>(&Offset(1u8)).is_err());
+    assert!(from_bytes::<BE, Offset<u8>>(&[0x00, 0x00, 0x00, 0x04,   0x01]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod tagged_enum {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::TaggedEnum;
+
+  /// То же перечисление, что и в тестах сериализатора перечислений
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  enum E {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, u16),
+    Struct { int1: u32, int2: u16 },
+  }
+
+  #[test]
+  fn test_unit() {
+    let u = TaggedEnum::<u8, _>::new(E::Unit);
+    assert_eq!(to_vec::<BE,_>(&u).unwrap(), [0x00]);
+    assert_eq!(from_bytes::<BE, TaggedEnum<u8, E>>(&[0x00]).unwrap(), u);
+  }
+  #[test]
+  fn test_newtype() {
+    let n = TaggedEnum::<u16, _>::new(E::Newtype(0x12345678));
+    let be = [0x00, 0x01,   0x12, 0x34, 0x56, 0x78];
+    let le = [0x01, 0x00,   0x78, 0x56, 0x34, 0x12];
+    assert_eq!(to_vec::<BE,_>(&n).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&n).unwrap(), le);
+    assert_eq!(from_bytes::<BE, TaggedEnum<u16, E>>(&be).unwrap(), n);
+    assert_eq!(from_bytes::<LE, TaggedEnum<u16, E>>(&le).unwrap(), n);
+  }
+  #[test]
+  fn test_tuple() {
+    let t = TaggedEnum::<u32, _>::new(E::Tuple(0x12345678, 0xABCD));
+    let be = [0x00, 0x00, 0x00, 0x02,   0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD];
+    let le = [0x02, 0x00, 0x00, 0x00,   0x78, 0x56, 0x34, 0x12,   0xCD, 0xAB];
+    assert_eq!(to_vec::<BE,_>(&t).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&t).unwrap(), le);
+    assert_eq!(from_bytes::<BE, TaggedEnum<u32, E>>(&be).unwrap(), t);
+    assert_eq!(from_bytes::<LE, TaggedEnum<u32, E>>(&le).unwrap(), t);
+  }
+  #[test]
+  fn test_struct() {
+    let s = TaggedEnum::<u8, _>::new(E::Struct { int1: 0x12345678, int2: 0xABCD });
+    let be = [0x03,   0x12, 0x34, 0x56, 0x78,   0xAB, 0xCD];
+    assert_eq!(to_vec::<BE,_>(&s).unwrap(), be);
+    assert_eq!(from_bytes::<BE, TaggedEnum<u8, E>>(&be).unwrap(), s);
+  }
+  #[test]
+  fn test_unknown_tag() {
+    assert!(from_bytes::<BE, TaggedEnum<u8, E>>(&[0x04]).is_err());
+  }
+  /// Метка задается только для обернутого перечисления, а не для соседних полей
+  #[test]
+  fn test_scope() {
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+      first: TaggedEnum<u8, E>,
+      second: TaggedEnum<u16, E>,
+    }
+    let record = Record { first: TaggedEnum::new(E::Unit), second: TaggedEnum::new(E::Newtype(1)) };
+    let data = [0x00,   0x00, 0x01,   0x00, 0x00, 0x00, 0x01];
+    assert_eq!(to_vec::<BE,_>(&record).unwrap(), data);
+    assert_eq!(from_bytes::<BE, Record>(&data).unwrap(), record);
+    assert!(to_vec::<BE,_>(&E::Unit).unwrap().is_empty());
+  }
+}
+
+#[cfg(test)]
+mod file_time {
+  use std::time::{Duration, UNIX_EPOCH};
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::FileTime;
+
+  /// Начало отсчета FILETIME -- 0
+  #[test]
+  fn test_epoch() {
+    let test = FileTime(FileTime::epoch().unwrap());
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), [0; 8]);
+    assert_eq!(from_bytes::<LE, FileTime>(&[0; 8]).unwrap(), test);
+  }
+  /// 1970-01-01 00:00:00 UTC
+  #[test]
+  fn test_unix_epoch() {
+    let test = FileTime(UNIX_EPOCH);
+    let be = [0x01, 0x9D, 0xB1, 0xDE, 0xD5, 0x3E, 0x80, 0x00];
+    let le = [0x00, 0x80, 0x3E, 0xD5, 0xDE, 0xB1, 0x9D, 0x01];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, FileTime>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, FileTime>(&le).unwrap(), test);
+  }
+  /// 2000-01-01 00:00:00.1234567 UTC
+  #[test]
+  fn test_y2k() {
+    let test = FileTime(UNIX_EPOCH + Duration::new(946_684_800, 123_456_700));
+    let be = [0x01, 0xBF, 0x53, 0xEB, 0x25, 0x80, 0x16, 0x87];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(from_bytes::<BE, FileTime>(&be).unwrap(), test);
+  }
+  /// Доли интервала в 100 нс отбрасываются
+  #[test]
+  fn test_truncate() {
+    let test = FileTime(UNIX_EPOCH + Duration::new(946_684_800, 123_456_789));
+    let be = [0x01, 0xBF, 0x53, 0xEB, 0x25, 0x80, 0x16, 0x87];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+  }
+  /// Моменты времени до 1601 года не представимы
+  #[test]
+  fn test_before_epoch() {
+    let test = FileTime(FileTime::epoch().unwrap() - Duration::from_secs(1));
+    assert!(to_vec::<BE,_>(&test).is_err());
+  }
+}
+
+#[cfg(test)]
+mod scaled {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::Scaled;
+
+  /// Отсчет 12-битного АЦП с опорным напряжением 3.3 В: 3.3 / 4096 = 0.000805664 В на отсчет
+  type Voltage = Scaled<u16, 806, 0>;
+  /// Температура в десятых долях градуса со смещением -273.15 градусов
+  type Temperature = Scaled<i16, 100_000, -273_150_000>;
+
+  #[test]
+  fn test_roundtrip() {
+    for &value in &[0.0, 0.5, 1.65, 3.2] {
+      let data = to_vec::<BE,_>(&Voltage::new(value)).unwrap();
+      let result = from_bytes::<BE, Voltage>(&data).unwrap().value();
+      assert!((result - value).abs() <= 0.000403, "{} != {}", result, value);
+    }
+    for &value in &[-273.15, -40.0, 0.0, 21.37, 1000.0] {
+      let data = to_vec::<LE,_>(&Temperature::new(value)).unwrap();
+      let result = from_bytes::<LE, Temperature>(&data).unwrap().value();
+      assert!((result - value).abs() <= 0.05 + 1e-9, "{} != {}", result, value);
+    }
+  }
+  /// Значение округляется до ближайшего отсчета
+  #[test]
+  fn test_rounding() {
+    type Half = Scaled<i8, 500_000, 0>;
+
+    assert_eq!(to_vec::<BE,_>(&Half::new(1.2)).unwrap(), [2]);
+    assert_eq!(to_vec::<BE,_>(&Half::new(1.3)).unwrap(), [3]);
+    assert_eq!(to_vec::<BE,_>(&Half::new(1.25)).unwrap(), [3]);
+    assert_eq!(to_vec::<BE,_>(&Half::new(-1.25)).unwrap(), [0xFD]);
+    assert_eq!(from_bytes::<BE, Half>(&[0xFD]).unwrap().value(), -1.5);
+  }
+  /// Значения, не помещающиеся в тип числа, не сериализуются
+  #[test]
+  fn test_overflow() {
+    assert!(to_vec::<BE,_>(&Voltage::new(-1.0)).is_err());
+    assert!(to_vec::<BE,_>(&Voltage::new(100.0)).is_err());
+    assert!(to_vec::<BE,_>(&Voltage::new(f64::NAN)).is_err());
+    assert!(to_vec::<BE,_>(&Scaled::<u8, 1_000_000, 0>::new(255.0)).is_ok());
+    assert!(to_vec::<BE,_>(&Scaled::<u8, 1_000_000, 0>::new(256.0)).is_err());
+    assert!(to_vec::<BE,_>(&Scaled::<i64, 1_000_000, 0>::new(9.3e18)).is_err());
+    assert!(to_vec::<BE,_>(&Scaled::<u8, 0, 0>::new(0.0)).is_err());
+  }
+}
+
+#[cfg(test)]
+mod sat_fixed {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::SatFixed;
+
+  type Q15 = SatFixed<i16, 15>;
+  type UQ8 = SatFixed<u8, 4>;
+
+  #[test]
+  fn test_roundtrip() {
+    for &value in &[-1.0, -0.5, 0.0, 0.25, 0.999969482421875] {
+      let data = to_vec::<LE,_>(&Q15::new(value)).unwrap();
+      assert_eq!(from_bytes::<LE, Q15>(&data).unwrap().value(), value);
+    }
+    assert_eq!(to_vec::<BE,_>(&UQ8::new(2.5)).unwrap(), [0x28]);
+    assert_eq!(from_bytes::<BE, UQ8>(&[0x28]).unwrap().value(), 2.5);
+  }
+  /// Значения вне диапазона типа насыщаются до его границ
+  #[test]
+  fn test_saturation() {
+    assert_eq!(to_vec::<BE,_>(&Q15::new(1.0)).unwrap(), [0x7F, 0xFF]);
+    assert_eq!(to_vec::<BE,_>(&Q15::new(100.0)).unwrap(), [0x7F, 0xFF]);
+    assert_eq!(to_vec::<BE,_>(&Q15::new(f64::INFINITY)).unwrap(), [0x7F, 0xFF]);
+    assert_eq!(to_vec::<BE,_>(&Q15::new(-1.5)).unwrap(), [0x80, 0x00]);
+    assert_eq!(to_vec::<BE,_>(&Q15::new(f64::NEG_INFINITY)).unwrap(), [0x80, 0x00]);
+    assert_eq!(to_vec::<BE,_>(&UQ8::new(16.0)).unwrap(), [0xFF]);
+    assert_eq!(to_vec::<BE,_>(&UQ8::new(-1.0)).unwrap(), [0x00]);
+    assert_eq!(from_bytes::<BE, UQ8>(&[0xFF]).unwrap().value(), 15.9375);
+  }
+  #[test]
+  fn test_nan() {
+    assert!(to_vec::<BE,_>(&Q15::new(f64::NAN)).is_err());
+  }
+}
+
+#[cfg(test)]
+mod option_sentinel {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::OptionSentinel;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Entry {
+    id: u8,
+    parent: OptionSentinel<u16, 0xFFFF>,
+  }
+
+  #[test]
+  fn test_u16() {
+    let none = Entry { id: 1, parent: OptionSentinel(None) };
+    let some = Entry { id: 2, parent: OptionSentinel(Some(0x1234)) };
+    assert_eq!(to_vec::<BE,_>(&none).unwrap(), [0x01,   0xFF, 0xFF]);
+    assert_eq!(to_vec::<BE,_>(&some).unwrap(), [0x02,   0x12, 0x34]);
+    assert_eq!(to_vec::<LE,_>(&some).unwrap(), [0x02,   0x34, 0x12]);
+    assert_eq!(from_bytes::<BE, Entry>(&[0x01,   0xFF, 0xFF]).unwrap(), none);
+    assert_eq!(from_bytes::<BE, Entry>(&[0x02,   0x12, 0x34]).unwrap(), some);
+    assert_eq!(from_bytes::<LE, Entry>(&[0x02,   0x34, 0x12]).unwrap(), some);
+  }
+  #[test]
+  fn test_signed() {
+    type Index = OptionSentinel<i32, -1>;
+    assert_eq!(to_vec::<BE,_>(&Index::from(None)).unwrap(), [0xFF; 4]);
+    assert_eq!(from_bytes::<BE, Index>(&[0xFF; 4]).unwrap(), Index::from(None));
+    assert_eq!(from_bytes::<BE, Index>(&[0x00, 0x00, 0x00, 0x00]).unwrap(), Index::from(Some(0)));
+  }
+  /// Значение, совпадающее с особым, и непредставимое особое значение не сериализуются
+  #[test]
+  fn test_invalid() {
+    assert!(to_vec::<BE,_>(&OptionSentinel::<u16, 0xFFFF>(Some(0xFFFF))).is_err());
+    assert!(to_vec::<BE,_>(&OptionSentinel::<u8, 0x100>(None)).is_err());
+    assert!(from_bytes::<BE, OptionSentinel<u8, -1>>(&[0xFF]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod nan {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Sample {
+    #[serde(with = "::wrappers::NanOption::<f64>")]
+    value: Option<f64>,
+    #[serde(with = "::wrappers::NanOption::<f32>")]
+    error: Option<f32>,
+  }
+
+  #[test]
+  fn test_some() {
+    let test = Sample { value: Some(1.5), error: Some(-2.0) };
+    let be = [0x3F, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,   0xC0, 0x00, 0x00, 0x00];
+    let le = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0x3F,   0x00, 0x00, 0x00, 0xC0];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&test).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Sample>(&be).unwrap(), test);
+    assert_eq!(from_bytes::<LE, Sample>(&le).unwrap(), test);
+  }
+  /// Отсутствующее значение записывается, как каноническое тихое NaN
+  #[test]
+  fn test_none() {
+    let test = Sample { value: None, error: None };
+    let be = [0x7F, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,   0x7F, 0xC0, 0x00, 0x00];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), be);
+    assert_eq!(from_bytes::<BE, Sample>(&be).unwrap(), test);
+  }
+  /// Любое NaN читается, как отсутствующее значение
+  #[test]
+  fn test_any_nan() {
+    let be = [0xFF, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,   0x7F, 0x80, 0x00, 0x01];
+    assert_eq!(from_bytes::<BE, Sample>(&be).unwrap(), Sample { value: None, error: None });
+    assert!(to_vec::<BE,_>(&Sample { value: Some(f64::NAN), error: None }).is_err());
+  }
+}
+
+#[cfg(test)]
+mod columnar {
+  use byteorder::{BE, LE};
+  use de::from_bytes;
+  use ser::to_vec;
+  use super::Columnar;
+
+  #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+  struct Sample {
+    id: u16,
+    value: u32,
+  }
+
+  fn samples() -> Columnar<Sample, 3> {
+    Columnar([
+      Sample { id: 0x0102, value: 0x1112_1314 },
+      Sample { id: 0x0304, value: 0x2122_2324 },
+      Sample { id: 0x0506, value: 0x3132_3334 },
+    ])
+  }
+
+  /// Сначала записываются все идентификаторы, затем все значения
+  #[test]
+  fn test_layout() {
+    let be = [
+      0x01, 0x02,   0x03, 0x04,   0x05, 0x06,
+      0x11, 0x12, 0x13, 0x14,   0x21, 0x22, 0x23, 0x24,   0x31, 0x32, 0x33, 0x34,
+    ];
+    let le = [
+      0x02, 0x01,   0x04, 0x03,   0x06, 0x05,
+      0x14, 0x13, 0x12, 0x11,   0x24, 0x23, 0x22, 0x21,   0x34, 0x33, 0x32, 0x31,
+    ];
+    assert_eq!(to_vec::<BE,_>(&samples()).unwrap(), be);
+    assert_eq!(to_vec::<LE,_>(&samples()).unwrap(), le);
+    assert_eq!(from_bytes::<BE, Columnar<Sample, 3>>(&be).unwrap(), samples());
+    assert_eq!(from_bytes::<LE, Columnar<Sample, 3>>(&le).unwrap(), samples());
+  }
+  /// Элементы-кортежи и вложенные структуры в качестве полей
+  #[test]
+  fn test_tuples() {
+    let test = Columnar([(0x01u8, (0x02u8, 0x03u8)), (0x04, (0x05, 0x06))]);
+    let data = [0x01, 0x04,   0x02, 0x03, 0x05, 0x06];
+    assert_eq!(to_vec::<BE,_>(&test).unwrap(), data);
+    assert_eq!(from_bytes::<BE, Columnar<(u8, (u8, u8)), 2>>(&data).unwrap(), test);
+  }
+  #[test]
+  fn test_empty() {
+    assert_eq!(to_vec::<BE,_>(&Columnar::<Sample, 0>([])).unwrap(), []);
+    assert_eq!(from_bytes::<BE, Columnar<Sample, 0>>(&[]).unwrap(), Columnar([]));
+  }
+  #[test]
+  fn test_invalid() {
+    assert!(to_vec::<BE,_>(&Columnar([1u8, 2u8])).is_err());
+    assert!(from_bytes::<BE, Columnar<u8, 2>>(&[1, 2]).is_err());
+    assert!(from_bytes::<BE, Columnar<Sample, 3>>(&[0; 17]).is_err());
+  }
+}
+
+#[cfg(test)]
+mod ordering {
+  use std::cmp::Ordering;
+  use byteorder::LE;
+  use de::from_bytes;
+  use ser::to_vec;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Comparison {
+    #[serde(with = "::wrappers::ordering_byte")]
+    result: Ordering,
+  }
+
+  #[test]
+  fn test_all() {
+    for &(result, byte) in &[(Ordering::Less, 0u8), (Ordering::Equal, 1), (Ordering::Greater, 2)] {
+      let test = Comparison { result };
+      assert_eq!(to_vec::<LE,_>(&test).unwrap(), [byte]);
+      assert_eq!(from_bytes::<LE, Comparison>(&[byte]).unwrap(), test);
+    }
+  }
+  #[test]
+  fn test_invalid() {
+    assert!(from_bytes::<LE, Comparison>(&[0x03]).is_err());
+    assert!(from_bytes::<LE, Comparison>(&[0xFF]).is_err());
+  }
+}
@@ -0,0 +1,248 @@
+//! Содержит потоки, применяющие к проходящим через них данным операцию XOR с повторяющимся
+//! ключом. Такое легкое запутывание данных используется во многих играх. Потоки встраиваются
+//! в цепочку ввода-вывода, поэтому сериализатор и десериализатор работают с ними как с любыми
+//! другими потоками.
+//!
+//! # Пример
+//! ```rust
+//! # extern crate byteorder;
+//! # extern crate serde;
+//! # extern crate serde_pod;
+//! # use byteorder::LE;
+//! # use serde::{Deserialize, Serialize};
+//! # use serde_pod::de::Deserializer;
+//! # use serde_pod::ser::Serializer;
+//! # use serde_pod::xor::{XorReader, XorWriter};
+//! # fn main() -> serde_pod::Result<()> {
+//! let mut ser = Serializer::<LE, _>::new(XorWriter::new(Vec::new(), b"\xAA\x55"));
+//! 0x1234_5678u32.serialize(&mut ser)?;
+//! let data = ser.into_inner().into_inner();
+//! assert_eq!(data, [0x78 ^ 0xAA, 0x56 ^ 0x55, 0x34 ^ 0xAA, 0x12 ^ 0x55]);
+//!
+//! let mut de = Deserializer::<LE, _>::new(XorReader::new(&data[..], b"\xAA\x55"));
+//! assert_eq!(u32::deserialize(&mut de)?, 0x1234_5678);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{self, BufRead, Read, Write};
+
+/// Размер буфера [`XorReader`] по умолчанию
+///
+/// [`XorReader`]: struct.XorReader.html
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Повторяющийся ключ и позиция в нем очередного байта данных
+#[derive(Clone, Debug)]
+struct Key {
+  /// Байты ключа
+  bytes: Vec<u8>,
+  /// Индекс байта ключа, применяемого к очередному байту данных
+  pos: usize,
+}
+impl Key {
+  /// Создает ключ, применение которого начинается с его первого байта
+  fn new(bytes: &[u8]) -> Self {
+    Key { bytes: bytes.to_vec(), pos: 0 }
+  }
+  /// Применяет ключ к данным, начиная с текущей позиции ключа, и сдвигает позицию
+  fn apply(&mut self, data: &mut [u8]) {
+    if self.bytes.is_empty() {
+      return;
+    }
+    for b in data {
+      *b ^= self.bytes[self.pos];
+      self.pos = (self.pos + 1) % self.bytes.len();
+    }
+  }
+  /// Сдвигает позицию ключа на `len` байт
+  fn skip(&mut self, len: usize) {
+    if !self.bytes.is_empty() {
+      self.pos = (self.pos + len % self.bytes.len()) % self.bytes.len();
+    }
+  }
+}
+
+/// Поток, читающий данные из нижележащего потока и применяющий к ним операцию XOR с повторяющимся
+/// ключом: к каждому байту применяется следующий байт ключа, после последнего байта ключа снова
+/// используется первый. Пустой ключ оставляет данные без изменений.
+///
+/// Поток буферизован, поэтому может использоваться десериализатором непосредственно.
+#[derive(Debug)]
+pub struct XorReader<R> {
+  /// Нижележащий поток
+  inner: R,
+  /// Ключ
+  key: Key,
+  /// Прочитанные и уже расшифрованные данные
+  buf: Box<[u8]>,
+  /// Позиция первого непрочитанного байта в буфере
+  pos: usize,
+  /// Количество данных в буфере
+  filled: usize,
+}
+impl<R> XorReader<R> {
+  /// Оборачивает поток
+  ///
+  /// # Параметры
+  /// - `inner`: Поток с запутанными данными
+  /// - `key`: Ключ, применяемый к данным, начиная с его первого байта
+  pub fn new(inner: R, key: &[u8]) -> Self {
+    XorReader {
+      inner,
+      key: Key::new(key),
+      buf: vec![0; BUFFER_SIZE].into_boxed_slice(),
+      pos: 0,
+      filled: 0,
+    }
+  }
+  /// Возвращает ссылку на нижележащий поток
+  #[inline]
+  pub fn get_ref(&self) -> &R { &self.inner }
+  /// Возвращает нижележащий поток. Данные, прочитанные из него в буфер, теряются
+  #[inline]
+  pub fn into_inner(self) -> R { self.inner }
+}
+impl<R: Read> Read for XorReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    // Большие чтения при пустом буфере выполняются в обход буфера
+    if self.pos == self.filled && buf.len() >= self.buf.len() {
+      let len = self.inner.read(buf)?;
+      self.key.apply(&mut buf[..len]);
+      return Ok(len);
+    }
+    let len = {
+      let data = self.fill_buf()?;
+      let len = data.len().min(buf.len());
+      buf[..len].copy_from_slice(&data[..len]);
+      len
+    };
+    self.consume(len);
+    Ok(len)
+  }
+}
+impl<R: Read> BufRead for XorReader<R> {
+  fn fill_buf(&mut self) -> io::Result<&[u8]> {
+    if self.pos == self.filled {
+      let len = self.inner.read(&mut self.buf)?;
+      self.key.apply(&mut self.buf[..len]);
+      self.pos = 0;
+      self.filled = len;
+    }
+    Ok(&self.buf[self.pos..self.filled])
+  }
+  #[inline]
+  fn consume(&mut self, amt: usize) {
+    self.pos = (self.pos + amt).min(self.filled);
+  }
+}
+
+/// Поток, применяющий к записываемым данным операцию XOR с повторяющимся ключом и записывающий
+/// результат в нижележащий поток: к каждому байту применяется следующий байт ключа, после
+/// последнего байта ключа снова используется первый. Пустой ключ оставляет данные без изменений.
+#[derive(Debug)]
+pub struct XorWriter<W> {
+  /// Нижележащий поток
+  inner: W,
+  /// Ключ
+  key: Key,
+}
+impl<W> XorWriter<W> {
+  /// Оборачивает поток
+  ///
+  /// # Параметры
+  /// - `inner`: Поток, в который записываются запутанные данные
+  /// - `key`: Ключ, применяемый к данным, начиная с его первого байта
+  pub fn new(inner: W, key: &[u8]) -> Self {
+    XorWriter { inner, key: Key::new(key) }
+  }
+  /// Возвращает ссылку на нижележащий поток
+  #[inline]
+  pub fn get_ref(&self) -> &W { &self.inner }
+  /// Возвращает нижележащий поток
+  #[inline]
+  pub fn into_inner(self) -> W { self.inner }
+}
+impl<W: Write> Write for XorWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let mut data = buf.to_vec();
+    // Позиция ключа сдвигается только на действительно записанные байты
+    let mut key = self.key.clone();
+    key.apply(&mut data);
+    let len = self.inner.write(&data)?;
+    self.key.skip(len);
+    Ok(len)
+  }
+  #[inline]
+  fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use std::io::{BufReader, Read, Write};
+  use byteorder::BE;
+  use serde::{Deserialize, Serialize};
+  use de::Deserializer;
+  use ser::Serializer;
+  use super::{XorReader, XorWriter};
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Save {
+    level: u16,
+    gold: u32,
+    name: [u8; 5],
+  }
+
+  const KEY: &[u8] = &[0x5A, 0xC3, 0x0F];
+
+  /// Длина данных не кратна длине ключа, и ключ повторяется несколько раз
+  #[test]
+  fn test_round_trip() {
+    let save = Save { level: 0x0107, gold: 0x1001_E240, name: *b"Rivia" };
+    let mut ser = Serializer::<BE, _>::new(XorWriter::new(Vec::new(), KEY));
+    save.serialize(&mut ser).unwrap();
+    let data = ser.into_inner().into_inner();
+    assert_eq!(data, [
+      0x01 ^ 0x5A, 0x07 ^ 0xC3,
+      0x10 ^ 0x0F, 0x01 ^ 0x5A, 0xE2 ^ 0xC3, 0x40 ^ 0x0F,
+      b'R' ^ 0x5A, b'i' ^ 0xC3, b'v' ^ 0x0F, b'i' ^ 0x5A, b'a' ^ 0xC3,
+    ]);
+
+    let mut de = Deserializer::<BE, _>::new(XorReader::new(&data[..], KEY));
+    assert_eq!(Save::deserialize(&mut de).unwrap(), save);
+  }
+  /// Позиция ключа сохраняется между частичными чтениями и записями
+  #[test]
+  fn test_chunks() {
+    let plain: Vec<u8> = (0..100).collect();
+    let mut writer = XorWriter::new(Vec::new(), KEY);
+    for chunk in plain.chunks(7) {
+      writer.write_all(chunk).unwrap();
+    }
+    let data = writer.into_inner();
+
+    let mut reader = XorReader::new(BufReader::with_capacity(5, &data[..]), KEY);
+    let mut read = Vec::new();
+    let mut buf = [0; 3];
+    loop {
+      let len = reader.read(&mut buf).unwrap();
+      if len == 0 {
+        break;
+      }
+      read.extend_from_slice(&buf[..len]);
+    }
+    assert_eq!(read, plain);
+  }
+  #[test]
+  fn test_empty_key() {
+    let mut writer = XorWriter::new(Vec::new(), &[]);
+    writer.write_all(b"data").unwrap();
+    assert_eq!(writer.get_ref(), b"data");
+
+    let mut read = Vec::new();
+    XorReader::new(&b"data"[..], &[]).read_to_end(&mut read).unwrap();
+    assert_eq!(read, b"data");
+  }
+}
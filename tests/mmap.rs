@@ -0,0 +1,40 @@
+//! Проверяет десериализацию из файла, отображенного в память
+#![cfg(feature = "memmap2")]
+extern crate byteorder;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_pod;
+
+use std::env;
+use std::fs;
+use std::process;
+use byteorder::{BE, LE};
+use serde_pod::{from_mmap, Error};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Section {
+  offset: u32,
+  count: u32,
+}
+
+#[test]
+fn test_from_mmap() {
+  let path = env::temp_dir().join(format!("serde-pod-mmap-{}.bin", process::id()));
+  fs::write(&path, [0x00, 0x00, 0x00, 0x38,   0x00, 0x00, 0x00, 0x0F]).unwrap();
+
+  let be = from_mmap::<BE, Section>(&path);
+  let le = from_mmap::<LE, Section>(&path);
+  fs::remove_file(&path).unwrap();
+
+  assert_eq!(be.unwrap(), Section { offset: 0x38, count: 0x0F });
+  assert_eq!(le.unwrap(), Section { offset: 0x38000000, count: 0x0F000000 });
+}
+
+#[test]
+fn test_from_mmap_missing_file() {
+  let path = env::temp_dir().join("serde-pod-mmap-missing.bin");
+  match from_mmap::<BE, Section>(&path) {
+    Err(Error::Io(_)) => {},
+    other => panic!("expected Error::Io, got {:?}", other),
+  }
+}
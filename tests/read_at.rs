@@ -0,0 +1,62 @@
+//! Проверяет чтение объектов по абсолютным смещениям из заголовка
+extern crate byteorder;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_pod;
+
+use std::io::Cursor;
+use byteorder::LE;
+use serde::Deserialize;
+use serde_pod::de::Deserializer;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Header {
+  signature: [u8; 4],
+  structs: u32,
+  labels: u32,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Struct {
+  kind: u32,
+  fields: u16,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Label([u8; 4]);
+
+#[test]
+fn test_header_with_two_objects() {
+  let data: &[u8] = &[
+    // Header
+    0x47, 0x46, 0x46, 0x20,   0x12, 0x00, 0x00, 0x00,   0x0C, 0x00, 0x00, 0x00,
+    // Label
+    0x4E, 0x61, 0x6D, 0x65,
+    // Padding
+    0x00, 0x00,
+    // Struct
+    0xFF, 0xFF, 0xFF, 0xFF,   0x03, 0x00,
+  ];
+  let mut de: Deserializer<LE, _> = Deserializer::seekable(Cursor::new(data));
+  let header = Header::deserialize(&mut de).unwrap();
+  assert_eq!(header, Header { signature: *b"GFF ", structs: 0x12, labels: 0x0C });
+
+  // Объекты читаются не в порядке их расположения в потоке
+  let root: Struct = de.read_at(header.structs.into()).unwrap();
+  let label: Label = de.read_at(header.labels.into()).unwrap();
+  assert_eq!(root, Struct { kind: 0xFFFF_FFFF, fields: 3 });
+  assert_eq!(label, Label(*b"Name"));
+
+  // Позиция в потоке восстанавливается после каждого чтения
+  assert_eq!(<[u8; 4]>::deserialize(&mut de).unwrap(), *b"Name");
+}
+
+#[test]
+fn test_out_of_bounds() {
+  let data: &[u8] = &[0x01, 0x02, 0x03, 0x04];
+  let mut de: Deserializer<LE, _> = Deserializer::seekable(Cursor::new(data));
+  assert!(de.read_at::<u32>(2).is_err());
+  // Позиция восстанавливается и после ошибки
+  assert_eq!(u32::deserialize(&mut de).unwrap(), 0x0403_0201);
+}
@@ -0,0 +1,64 @@
+//! Проверяет запись и чтение записей, ссылающихся на общую таблицу строк
+extern crate byteorder;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+extern crate serde_pod;
+
+use byteorder::LE;
+use serde::{Deserialize, Serialize};
+use serde_pod::de::Deserializer;
+use serde_pod::ser::Serializer;
+use serde_pod::string_table::StringTable;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Header {
+  records: u32,
+  labels: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Record {
+  id: u16,
+  /// Смещение метки в таблице строк
+  label: u32,
+}
+
+#[test]
+fn test_shared_label() {
+  let mut labels = StringTable::new();
+  let records = [
+    Record { id: 1, label: labels.intern("Name").unwrap() },
+    Record { id: 2, label: labels.intern("Name").unwrap() },
+  ];
+  let header = Header { records: records.len() as u32, labels: labels.len() as u32 };
+
+  let mut buf = Vec::new();
+  {
+    let mut ser = Serializer::<LE, _>::new(&mut buf);
+    header.serialize(&mut ser).unwrap();
+    for record in &records {
+      record.serialize(&mut ser).unwrap();
+    }
+    labels.write(&mut ser).unwrap();
+  }
+  assert_eq!(buf, [
+    // Header
+    0x02, 0x00, 0x00, 0x00,   0x05, 0x00, 0x00, 0x00,
+    // Records
+    0x01, 0x00,   0x00, 0x00, 0x00, 0x00,
+    0x02, 0x00,   0x00, 0x00, 0x00, 0x00,
+    // Labels
+    0x4E, 0x61, 0x6D, 0x65, 0x00,
+  ]);
+
+  let mut de = Deserializer::<LE, _>::new(&buf[..]);
+  let header = Header::deserialize(&mut de).unwrap();
+  let records: Vec<Record> = de.read_counted_by(header.records as usize).unwrap();
+  let labels = StringTable::read(&mut de, header.labels as usize).unwrap();
+
+  assert_eq!(records.len(), 2);
+  for record in &records {
+    assert_eq!(labels.resolve(record.label).unwrap(), "Name");
+  }
+}